@@ -0,0 +1,74 @@
+//! Reading and parsing a superbuild sized cache: `cargo bench -p cmake-cache`.
+//! A plain timing loop, so it needs no benchmark framework.
+
+use std::{
+    env, fs,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use cmake_cache::{Cache, parse_cache_content};
+
+const ENTRIES: usize = 50_000;
+const RUNS: usize = 20;
+
+/// A cache with `count` entries of mixed types, help strings and the
+/// properties cmake writes for some of them
+fn generate(count: usize) -> String {
+    let mut external = String::from("# This is the CMakeCache file.\n\n########################\n# EXTERNAL cache entries\n########################\n\n");
+    let mut internal = String::from("\n########################\n# INTERNAL cache entries\n########################\n\n");
+    for i in 0..count {
+        let name = format!("SUBPROJECT_{}_OPTION_{}", i / 100, i);
+        let (typ, value) = match i % 5 {
+            0 => ("BOOL", "ON".to_string()),
+            1 => ("PATH", format!("/opt/superbuild/install/sub{}/lib/cmake", i)),
+            2 => ("FILEPATH", format!("/usr/lib/x86_64-linux-gnu/libdep{}.so", i)),
+            3 => ("STRING", "Release".to_string()),
+            _ => ("STRING", format!("-O2 -g -DSUB_{}=1", i)),
+        };
+        external.push_str(&format!("//Help string of option {} of its subproject,\n// on two lines\n{}:{}={}\n\n", i, name, typ, value));
+        if i % 3 == 0 {
+            internal.push_str(&format!("//ADVANCED property for variable: {}\n{}-ADVANCED:INTERNAL=1\n", name, name));
+        }
+        if i % 5 == 3 {
+            internal.push_str(&format!("//STRINGS property for variable: {}\n{}-STRINGS:INTERNAL=Debug;Release;RelWithDebInfo\n", name, name));
+        }
+    }
+    external + &internal
+}
+
+fn measure(what: &str, mut run: impl FnMut()) {
+    let mut times: Vec<Duration> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .collect();
+    times.sort();
+    println!("{:<28} median {:>8.2} ms, fastest {:>8.2} ms", what, ms(times[RUNS / 2]), ms(times[0]));
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn main() {
+    let content = generate(ENTRIES);
+    let dir = env::temp_dir().join(format!("cmake-cache-bench-{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("create the bench dir");
+    fs::write(dir.join("CMakeCache.txt"), &content).expect("write the bench cache");
+    println!("{} entries, {:.1} MiB", ENTRIES, content.len() as f64 / (1024.0 * 1024.0));
+
+    measure("read the file", || {
+        black_box(fs::read_to_string(dir.join("CMakeCache.txt")).unwrap());
+    });
+    measure("parse from memory", || {
+        black_box(parse_cache_content(black_box(&content)));
+    });
+    measure("Cache::load (read + parse)", || {
+        black_box(Cache::load(&dir).unwrap());
+    });
+
+    let _ = fs::remove_dir_all(&dir);
+}
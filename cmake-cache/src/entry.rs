@@ -0,0 +1,182 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum VarType {
+    Bool,
+    Str,
+    Enum,
+    Filepath,
+    Dirpath,
+    // Int,
+    // Entries cmake keeps for itself, like CMAKE_HOME_DIRECTORY
+    Internal,
+    // Set with -D on the command line without a type, before the project
+    // declared one
+    Uninitialized,
+    Static,
+}
+
+impl VarType{
+    /// Type from its name in CMakeCache.txt or a -D definition
+    pub fn from_cache_name(s: &str) -> Option<VarType> {
+        match s {
+            "BOOL" => Some(VarType::Bool),
+            "FILEPATH" => Some(VarType::Filepath),
+            "STRING" => Some(VarType::Str),
+            "STATIC" => Some(VarType::Static),
+            "INTERNAL" => Some(VarType::Internal),
+            "UNINITIALIZED" => Some(VarType::Uninitialized),
+            "PATH" => Some(VarType::Dirpath),
+            _ => None,
+        }
+    }
+}
+
+impl VarType {
+    /// Type as written in CMakeCache.txt
+    pub fn cache_name(&self) -> &'static str {
+        match self {
+            VarType::Bool => "BOOL",
+            VarType::Str | VarType::Enum => "STRING",
+            VarType::Filepath => "FILEPATH",
+            VarType::Dirpath => "PATH",
+            VarType::Internal => "INTERNAL",
+            VarType::Uninitialized => "UNINITIALIZED",
+            VarType::Static => "STATIC",
+        }
+    }
+}
+
+impl fmt::Display for VarType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// How CMake's `if()` reads a BOOL value
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BoolValue {
+    /// ON/OFF, TRUE/FALSE, YES/NO, Y/N or 1/0, in any case
+    Plain(bool),
+    /// A constant read as expected by few: other numbers, IGNORE, NOTFOUND,
+    /// `<name>-NOTFOUND` and the empty string
+    Unusual(bool),
+    /// Not a constant at all, `if()` reads it as a variable name instead
+    Unknown,
+}
+
+impl BoolValue {
+    pub fn parse(val: &str) -> Self {
+        let upper = val.to_uppercase();
+        match upper.as_str() {
+            "ON" | "TRUE" | "YES" | "Y" | "1" => BoolValue::Plain(true),
+            "OFF" | "FALSE" | "NO" | "N" | "0" => BoolValue::Plain(false),
+            "" | "IGNORE" | "NOTFOUND" => BoolValue::Unusual(false),
+            _ if upper.ends_with("-NOTFOUND") => BoolValue::Unusual(false),
+            _ => match val.parse::<f64>() {
+                Ok(n) => BoolValue::Unusual(n != 0.0),
+                Err(_) => BoolValue::Unknown,
+            },
+        }
+    }
+
+    /// The value CMake reads, None when it depends on other variables
+    pub fn value(self) -> Option<bool> {
+        match self {
+            BoolValue::Plain(b) | BoolValue::Unusual(b) => Some(b),
+            BoolValue::Unknown => None,
+        }
+    }
+}
+
+/// One entry of the cache, with the properties cmake keeps for it in
+/// internal entries (`-ADVANCED`, `-STRINGS`, `-MODIFIED`) folded in
+#[derive(Clone)]
+pub struct CacheVar {
+    pub name: String,
+    pub typ: VarType,
+    // HELPSTRING, the `//` comment lines above the entry
+    pub desc: String,
+    pub value: String,
+    // Allowed values of an enum (STRINGS property)
+    pub values: Vec<String>,
+    pub advanced: bool,
+    // Set by the user in ccmake, cmake-gui or cmake-tui rather than by
+    // the project (MODIFIED property)
+    pub modified: bool,
+}
+
+impl CacheVar {
+    pub fn new(name: String, typ: VarType, desc: String, value: String) -> Self {
+        Self {
+            name,
+            typ,
+            desc,
+            value,
+            values: Vec::new(),
+            advanced: false,
+            modified: false,
+        }
+    }
+
+    pub(crate) fn set_enum_values(&mut self, values_str: &str) {
+        self.values = values_str.split(';').map(|s| s.to_string()).collect();
+    }
+
+    /// Turn a plain STRING variable into an enum with the given allowed values
+    pub fn make_enum(&mut self, values: &[String]) {
+        self.typ = VarType::Enum;
+        self.values = values.to_vec();
+    }
+
+    pub fn cycle_enum(&self, val: &str) -> String {
+        if self.values.is_empty() {
+            return val.to_string(); // nothing to cycle
+        }
+
+        // Find the current index of `self.value` in `self.values`
+        let current_index = self
+            .values
+            .iter()
+            .position(|v| v == val)
+            .unwrap_or(0); // default to 0 if not found
+
+        // Compute the next index, wrapping around
+        let next_index = (current_index + 1) % self.values.len();
+
+        // Update `self.value`
+        self.values[next_index].clone()
+    }
+
+    pub fn toggle_bool(val: &str) -> String {
+        let new_value = match val.to_lowercase().as_str() {
+            "on" => Some("OFF".to_string()),
+            "true" => Some("FALSE".to_string()),
+            "yes" => Some("NO".to_string()),
+            "y" => Some("N".to_string()),
+            "1" => Some("0".to_string()),
+            "off" => Some("ON".to_string()),
+            "false" => Some("TRUE".to_string()),
+            "no" => Some("YES".to_string()),
+            "n" => Some("Y".to_string()),
+            "ignore" => Some("ON".to_string()),
+            "notfound" => Some("ON".to_string()),
+            "" => Some("ON".to_string()),
+            _ => None
+        };
+        // self.value = new_value.unwrap_or(self.value.to_string());
+        new_value.unwrap_or(val.to_string())
+    }
+
+}
+
+
+impl fmt::Display for CacheVar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CacheVar {{ name: {}, type: {}, desc: {}, value: {}, values: {:?} }}",
+            self.name, self.typ, self.desc, self.value, self.values
+        )
+    }
+}
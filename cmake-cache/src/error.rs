@@ -0,0 +1,56 @@
+use std::{fmt, io, path::PathBuf};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file failed
+    Io { path: PathBuf, source: io::Error },
+    /// The file was read but holds no cache entries, it's probably not a CMakeCache.txt
+    NoEntries { path: PathBuf },
+    /// Another process holds the lock on the cache, see [`crate::CacheLock`]
+    /// `pid` is None when the lock was found before its holder wrote it
+    Locked { path: PathBuf, pid: Option<u32> },
+}
+
+impl Error {
+    pub(crate) fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        Error::Io { path: path.into(), source }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Error::NoEntries { path } => write!(f, "{}: no cache entries found", path.display()),
+            Error::Locked { path, pid: Some(pid) } => {
+                write!(f, "the cache is locked by another process (pid {}), see {}", pid, path.display())
+            }
+            Error::Locked { path, pid: None } => {
+                write!(f, "the cache is locked by another process, see {}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// For callers working with `io::Result`, keeps the message and a fitting kind
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        let kind = match &e {
+            Error::Io { source, .. } => source.kind(),
+            Error::NoEntries { .. } => io::ErrorKind::InvalidData,
+            Error::Locked { .. } => io::ErrorKind::WouldBlock,
+        };
+        io::Error::new(kind, e.to_string())
+    }
+}
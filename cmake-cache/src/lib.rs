@@ -0,0 +1,98 @@
+//! Reading and writing CMake's `CMakeCache.txt`, the file cmake keeps the
+//! cache variables of a build directory in.
+//!
+//! ```no_run
+//! let cache = cmake_cache::Cache::load("build")?;
+//! for var in cache.iter().filter(|v| !v.advanced) {
+//!     println!("{}:{}={}", var.name, var.typ.cache_name(), var.value);
+//! }
+//! if let Some(build_type) = cache.get("CMAKE_BUILD_TYPE") {
+//!     println!("building {}", build_type.value);
+//! }
+//! # Ok::<(), cmake_cache::Error>(())
+//! ```
+//!
+//! Values are changed in place with [`write_cache_changes`], which keeps
+//! everything else in the file as cmake wrote it, and entries are dropped
+//! with [`remove_cache_entries`].
+
+mod entry;
+mod error;
+mod lock;
+mod parse;
+mod write;
+
+use std::path::Path;
+
+pub use entry::{BoolValue, CacheVar, VarType};
+pub use error::{Error, Result};
+pub use lock::CacheLock;
+pub use parse::{ParseWarning, parse_cache_content, parse_cache_content_with_warnings, parse_cmake_cache, parse_source_dir};
+pub use write::{remove_cache_entries, serialize, write_cache_changes};
+
+/// The entries of a cache, sorted by name
+pub struct Cache {
+    entries: Vec<CacheVar>,
+    warnings: Vec<ParseWarning>,
+}
+
+impl Cache {
+    /// Read the cache of a build dir, `path` may also be the CMakeCache.txt itself
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let (entries, warnings) = parse::read_cache(path.as_ref())?;
+        Ok(Self { entries, warnings })
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let (entries, warnings) = parse_cache_content_with_warnings(content);
+        Self { entries, warnings }
+    }
+
+    /// Lines that were skipped or read differently than written
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CacheVar> {
+        let i = self.entries.binary_search_by(|v| v.name.as_str().cmp(name)).ok()?;
+        self.entries.get(i)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, CacheVar> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn into_entries(self) -> Vec<CacheVar> {
+        self.entries
+    }
+
+    pub fn into_parts(self) -> (Vec<CacheVar>, Vec<ParseWarning>) {
+        (self.entries, self.warnings)
+    }
+}
+
+impl IntoIterator for Cache {
+    type Item = CacheVar;
+    type IntoIter = std::vec::IntoIter<CacheVar>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Cache {
+    type Item = &'a CacheVar;
+    type IntoIter = std::slice::Iter<'a, CacheVar>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::error::{Error, Result};
+
+const LOCK_FILE: &str = "CMakeCache.txt.lock";
+
+// Writing the cache takes milliseconds, a lock this old was left behind by a
+// crashed process
+const STALE_LOCK_AGE: Duration = Duration::from_secs(60);
+
+/// Advisory lock on a build dir's cache, held while writing it so two
+/// writers don't interleave their writes. Released when dropped.
+pub struct CacheLock {
+    path: PathBuf,
+}
+
+impl CacheLock {
+    pub fn acquire(build_dir: &Path) -> Result<Self> {
+        let path = build_dir.join(LOCK_FILE);
+        if create_lock(&path)? {
+            return Ok(Self { path });
+        }
+        // Someone else made it, it's only cleared away when found stale
+        if let Some(lock) = read_lock(&path)
+            && (!lock.is_stale() || !remove_stale(&path, &lock))
+        {
+            return Err(Error::Locked { path, pid: lock.pid });
+        }
+        if create_lock(&path)? {
+            return Ok(Self { path });
+        }
+        let pid = read_lock(&path).and_then(|lock| lock.pid);
+        Err(Error::Locked { path, pid })
+    }
+}
+
+impl Drop for CacheLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Lock file as read from disk
+#[derive(PartialEq)]
+struct LockInfo {
+    /// None while the holder hasn't written it yet
+    pid: Option<u32>,
+    modified: SystemTime,
+}
+
+impl LockInfo {
+    fn is_stale(&self) -> bool {
+        let age = SystemTime::now().duration_since(self.modified).unwrap_or_default();
+        let alive = match self.pid {
+            Some(pid) if Path::new("/proc/self").exists() => Path::new("/proc").join(pid.to_string()).exists(),
+            _ => true,
+        };
+        !alive || age >= STALE_LOCK_AGE
+    }
+}
+
+/// Create the lock file with our pid in it, false when it already exists
+fn create_lock(path: &Path) -> Result<bool> {
+    let mut file = match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Ok(false),
+        Err(e) => return Err(Error::io(path, e)),
+    };
+    writeln!(file, "{}", std::process::id()).map_err(|e| Error::io(path, e))?;
+    Ok(true)
+}
+
+fn read_lock(path: &Path) -> Option<LockInfo> {
+    let content = fs::read_to_string(path).ok()?;
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(LockInfo { pid: content.trim().parse().ok(), modified })
+}
+
+/// Remove the lock at `path` if it's still the stale one read as `stale`.
+/// It's renamed aside first, so a lock another process made in the
+/// meantime is noticed and put back instead of being deleted.
+fn remove_stale(path: &Path, stale: &LockInfo) -> bool {
+    let aside = path.with_extension(format!("lock.stale-{}", std::process::id()));
+    if fs::rename(path, &aside).is_err() {
+        // Gone already, someone else cleared it
+        return true;
+    }
+    if read_lock(&aside).as_ref() == Some(stale) {
+        let _ = fs::remove_file(&aside);
+        return true;
+    }
+    // Not the one we read. Put it back unless yet another lock took its
+    // place, then it's left aside rather than deleted
+    if fs::hard_link(&aside, path).is_ok() {
+        let _ = fs::remove_file(&aside);
+    }
+    false
+}
@@ -0,0 +1,312 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::Cache;
+use crate::entry::{BoolValue, CacheVar, VarType};
+use crate::error::{Error, Result};
+
+/// A line of the cache the parser couldn't make full sense of
+#[derive(Debug, Clone)]
+pub struct ParseWarning {
+    // 1-based
+    pub line: usize,
+    pub content: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}: {}", self.line, self.reason, self.content)
+    }
+}
+
+/// `NAME:TYPE=VALUE` split into its parts, None for any other line. Names
+/// are C identifiers, properties like NAME-ADVANCED aren't entries.
+fn split_entry(line: &str) -> Option<(&str, &str, &str)> {
+    let (name, rest) = line.split_once(':')?;
+    let mut name_chars = name.bytes();
+    if !name_chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == b'_')
+        || !name_chars.all(|c| c.is_ascii_alphanumeric() || c == b'_')
+    {
+        return None;
+    }
+    let type_len = rest.bytes().take_while(u8::is_ascii_uppercase).count();
+    let value = rest[type_len..].strip_prefix('=')?;
+    (type_len > 0).then_some((name, &rest[..type_len], value))
+}
+
+/// The name and value of a `NAME-PROPERTY:INTERNAL=VALUE` line
+fn split_property<'a>(line: &'a str, property: &str) -> Option<(&'a str, &'a str)> {
+    let (name, rest) = line.split_once('-')?;
+    let value = rest.strip_prefix(property)?.strip_prefix(":INTERNAL=")?;
+    (!name.is_empty()).then_some((name, value))
+}
+
+/// The entries of the external section sorted by name, where a name set
+/// twice has the later value
+fn parse_external_section(external: &str, warnings: &mut Vec<ParseWarning>) -> Vec<CacheVar> {
+    // With the line each was read from, to point at overridden duplicates
+    let mut read: Vec<(usize, &str, CacheVar)> = Vec::new();
+    let mut current_desc = String::new();
+    let mut warn = |line: usize, content: &str, reason: String| {
+        warnings.push(ParseWarning { line, content: content.to_string(), reason });
+    };
+
+    for (i, line) in external.lines().enumerate() {
+        if let Some(desc) = line.strip_prefix("//") {
+            current_desc.push_str(desc);
+            continue;
+        }
+
+        if let Some((name, typ_name, value)) = split_entry(line) {
+            let typ = match VarType::from_cache_name(typ_name) {
+                Some(t) => t,
+                None => {
+                    warn(i + 1, line, format!("unknown type {}, read as STRING", typ_name));
+                    VarType::Str
+                }
+            };
+            let desc = std::mem::take(&mut current_desc);
+            read.push((i + 1, line, CacheVar::new(name.to_string(), typ, desc, value.to_string())));
+        } else if !line.trim().is_empty() && !line.starts_with('#') {
+            warn(i + 1, line, "not a cache entry, skipped".to_string());
+        }
+    }
+
+    // Stable, so a name's entries stay in the order they were read
+    read.sort_by(|a, b| a.2.name.cmp(&b.2.name));
+    let mut entries: Vec<CacheVar> = Vec::with_capacity(read.len());
+    let mut last_line = 0;
+    for (line, content, var) in read {
+        if let Some(earlier) = entries.last_mut().filter(|e| e.name == var.name) {
+            warn(line, content, format!("{} is set again, replacing line {}", var.name, last_line));
+            *earlier = var;
+        } else {
+            entries.push(var);
+        }
+        last_line = line;
+    }
+    entries.retain(|var| var.typ != VarType::Static);
+    warnings.sort_by_key(|w| w.line);
+    entries
+}
+
+/// Add cmake's own entries and the properties of the internal section to
+/// the `entries` sorted by name, keeping them sorted
+fn parse_internal_section(internal: &str, entries: &mut Vec<CacheVar>) {
+    // cmake's own bookkeeping. Properties of other entries (NAME-ADVANCED,
+    // NAME-STRINGS, NAME-MODIFIED) have a dash in the name and don't split as entries.
+    let mut added = Vec::new();
+    let mut current_desc = String::new();
+    for line in internal.lines() {
+        if let Some(desc) = line.strip_prefix("//") {
+            current_desc.push_str(desc);
+            continue;
+        }
+        if let Some((name, typ_name, value)) = split_entry(line)
+            && let Some(typ) = VarType::from_cache_name(typ_name).filter(|t| *t != VarType::Static)
+            && entries.binary_search_by(|v| v.name.as_str().cmp(name)).is_err()
+        {
+            let desc = std::mem::take(&mut current_desc);
+            added.push(CacheVar::new(name.to_string(), typ, desc, value.to_string()));
+        }
+        current_desc.clear();
+    }
+    if !added.is_empty() {
+        // The first one read counts, like for a name already set
+        added.sort_by(|a, b| a.name.cmp(&b.name));
+        added.dedup_by(|later, first| later.name == first.name);
+        entries.append(&mut added);
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    for line in internal.lines() {
+        let find = |name: &str| entries.binary_search_by(|v| v.name.as_str().cmp(name)).ok();
+        if let Some((name, values)) = split_property(line, "STRINGS").filter(|(_, v)| !v.is_empty()) {
+            if let Some(i) = find(name) {
+                entries[i].typ = VarType::Enum;
+                entries[i].set_enum_values(values);
+            }
+        } else if let Some((name, "1")) = split_property(line, "ADVANCED")
+            && let Some(i) = find(name)
+        {
+            entries[i].advanced = true;
+        } else if let Some((name, value)) = split_property(line, "MODIFIED")
+            && BoolValue::parse(value).value() == Some(true)
+            && let Some(i) = find(name)
+        {
+            entries[i].modified = true;
+        }
+    }
+}
+
+/// The entries of a cache sorted by name
+fn parse_cache(content: &str, warnings: &mut Vec<ParseWarning>) -> Vec<CacheVar> {
+    let (external, internal) = content.split_once("# INTERNAL cache entries").unwrap_or((content, ""));
+    let mut entries = parse_external_section(external, warnings);
+    parse_internal_section(internal, &mut entries);
+    entries
+}
+
+/// Parse the CMakeCache.txt of a build dir, `path` may also be the cache file itself
+pub fn parse_cmake_cache(path: impl AsRef<Path>) -> Result<Vec<CacheVar>> {
+    Cache::load(path).map(Cache::into_entries)
+}
+
+/// Read the cache of a build dir or the cache file at `path`, with the
+/// warnings about lines that were skipped
+pub(crate) fn read_cache(path: &Path) -> Result<(Vec<CacheVar>, Vec<ParseWarning>)> {
+    let cmake_cache_path = if path.is_file() { path.to_path_buf() } else { path.join("CMakeCache.txt") };
+
+    let cache_content = fs::read_to_string(&cmake_cache_path).map_err(|e| Error::io(&cmake_cache_path, e))?;
+    let (entries, warnings) = parse_cache_content_with_warnings(&cache_content);
+    if entries.is_empty() {
+        return Err(Error::NoEntries { path: cmake_cache_path });
+    }
+    Ok((entries, warnings))
+}
+
+/// Parse the text of a CMakeCache.txt, e.g. one kept in a snapshot. Lines
+/// that aren't cache entries are skipped, so this can't fail.
+pub fn parse_cache_content(cache_content: &str) -> Vec<CacheVar> {
+    parse_cache_content_with_warnings(cache_content).0
+}
+
+/// [`parse_cache_content`], also returning what was skipped or read in a
+/// way other than written
+pub fn parse_cache_content_with_warnings(cache_content: &str) -> (Vec<CacheVar>, Vec<ParseWarning>) {
+    let mut warnings = Vec::new();
+    // Left by Windows editors that save UTF-8 with a byte order mark.
+    // CRLF line endings need nothing, lines() drops the \r.
+    let cache_content = cache_content.strip_prefix('\u{feff}').unwrap_or(cache_content);
+
+    let entries = parse_cache(cache_content, &mut warnings);
+    (entries, warnings)
+}
+
+
+/// Read the project's source directory (CMAKE_HOME_DIRECTORY) from the cache
+pub fn parse_source_dir(build_dir: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(build_dir.join("CMakeCache.txt")).ok()?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("CMAKE_HOME_DIRECTORY:INTERNAL="))
+        .map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Everything the parser reads of an entry, to compare entries by
+    fn summary(entries: &[CacheVar]) -> Vec<String> {
+        entries
+            .iter()
+            .map(|v| format!("{}:{:?}={} {:?} {:?} {} {}", v.name, v.typ, v.value, v.desc, v.values, v.advanced, v.modified))
+            .collect()
+    }
+
+    const CACHE: &str = "\
+# This is the CMakeCache file.
+
+########################
+# EXTERNAL cache entries
+########################
+
+//Build the tests
+BUILD_TESTING:BOOL=ON
+
+//Backend to use
+DEMO_BACKEND:STRING=cpu
+
+demo_BINARY_DIR:STATIC=/src/build
+
+########################
+# INTERNAL cache entries
+########################
+
+//ADVANCED property for variable: BUILD_TESTING
+BUILD_TESTING-ADVANCED:INTERNAL=1
+//STRINGS property for variable: DEMO_BACKEND
+DEMO_BACKEND-STRINGS:INTERNAL=cpu;cuda;hip
+//MODIFIED property for variable: DEMO_BACKEND
+DEMO_BACKEND-MODIFIED:INTERNAL=ON
+//Source directory
+CMAKE_HOME_DIRECTORY:INTERNAL=/src
+";
+
+    #[test]
+    fn reads_entries_and_properties() {
+        let (entries, warnings) = parse_cache_content_with_warnings(CACHE);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        let names: Vec<&str> = entries.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["BUILD_TESTING", "CMAKE_HOME_DIRECTORY", "DEMO_BACKEND"]);
+
+        let testing = &entries[0];
+        assert_eq!((&testing.typ, testing.value.as_str(), testing.desc.as_str()), (&VarType::Bool, "ON", "Build the tests"));
+        assert!(testing.advanced && !testing.modified);
+
+        let backend = &entries[2];
+        assert_eq!(backend.typ, VarType::Enum);
+        assert_eq!(backend.values, ["cpu", "cuda", "hip"]);
+        assert!(backend.modified && !backend.advanced);
+    }
+
+    #[test]
+    fn reads_internal_entries() {
+        let entries = parse_cache_content(CACHE);
+        let home = entries.iter().find(|v| v.name == "CMAKE_HOME_DIRECTORY").unwrap();
+        assert_eq!((&home.typ, home.value.as_str(), home.desc.as_str()), (&VarType::Internal, "/src", "Source directory"));
+        // Properties and STATIC entries aren't entries of their own
+        assert!(entries.iter().all(|v| !v.name.contains('-') && v.typ != VarType::Static));
+    }
+
+    #[test]
+    fn an_external_entry_wins_over_an_internal_one() {
+        let content = CACHE.replace("CMAKE_HOME_DIRECTORY:INTERNAL=/src", "DEMO_BACKEND:INTERNAL=other");
+        let entries = parse_cache_content(&content);
+        let backend = entries.iter().find(|v| v.name == "DEMO_BACKEND").unwrap();
+        assert_eq!(backend.value, "cpu");
+    }
+
+    #[test]
+    fn ignores_crlf_and_bom() {
+        let content = format!("\u{feff}{}", CACHE.replace('\n', "\r\n"));
+        let (entries, warnings) = parse_cache_content_with_warnings(&content);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(summary(&entries), summary(&parse_cache_content(CACHE)));
+    }
+
+    #[test]
+    fn a_bom_before_the_first_entry_is_not_part_of_its_name() {
+        let entries = parse_cache_content("\u{feff}FIRST:STRING=a\r\n");
+        assert_eq!(entries[0].name, "FIRST");
+        assert_eq!(entries[0].value, "a");
+    }
+
+    #[test]
+    fn warns_about_lines_it_cant_read() {
+        let content = CACHE.replace(
+            "DEMO_BACKEND:STRING=cpu\n",
+            "DEMO_BACKEND:STRING=cpu\nnot an entry\nODD:WEIRD=1\nDEMO_BACKEND:STRING=cuda\n",
+        );
+        let (entries, warnings) = parse_cache_content_with_warnings(&content);
+        let found: Vec<(usize, &str)> = warnings.iter().map(|w| (w.line, w.content.as_str())).collect();
+        assert_eq!(found, [(12, "not an entry"), (13, "ODD:WEIRD=1"), (14, "DEMO_BACKEND:STRING=cuda")]);
+        assert!(warnings[2].reason.contains("replacing line 11"), "{}", warnings[2]);
+
+        // Unknown types are still read, as strings, and the later value wins
+        let odd = entries.iter().find(|v| v.name == "ODD").unwrap();
+        assert_eq!((&odd.typ, odd.value.as_str()), (&VarType::Str, "1"));
+        let backend = entries.iter().find(|v| v.name == "DEMO_BACKEND").unwrap();
+        assert_eq!(backend.value, "cuda");
+    }
+
+    #[test]
+    fn values_keep_equals_signs_and_colons() {
+        let entries = parse_cache_content("FLAGS:STRING=-DA=1 -I C:/inc\n");
+        assert_eq!(entries[0].value, "-DA=1 -I C:/inc");
+    }
+}
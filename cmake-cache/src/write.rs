@@ -0,0 +1,328 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use crate::entry::{CacheVar, VarType};
+use crate::error::{Error, Result};
+use crate::lock::CacheLock;
+
+/// Write new values for the given variables into the build dir's
+/// CMakeCache.txt. Only the value part of matching entries is replaced,
+/// everything else (comments, types, properties, line endings) is kept as
+/// is. Entries in `added` are appended to the end of the external section.
+/// Properties (`NAME-STRINGS`, ...) in `changes` the cache doesn't have yet
+/// are appended to the internal section, the way cmake writes them. The
+/// file is replaced atomically, under a [`CacheLock`] against other writers.
+pub fn write_cache_changes(
+    build_dir: &Path,
+    changes: &HashMap<String, String>,
+    added: &[CacheVar],
+) -> Result<()> {
+    let cache_path = build_dir.join("CMakeCache.txt");
+    let _lock = CacheLock::acquire(build_dir)?;
+    let content = fs::read_to_string(&cache_path).map_err(|e| Error::io(&cache_path, e))?;
+
+    let mut out = String::with_capacity(content.len());
+    let mut in_internal = false;
+    let mut replaced_names: HashSet<&str> = HashSet::new();
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let added_block: String = added
+        .iter()
+        .map(|var| {
+            let desc: String = var.desc.lines().map(|l| format!("//{}{}", l, newline)).collect();
+            format!("{}{}:{}={}{}{}", desc, var.name, var.typ.cache_name(), var.value, newline, newline)
+        })
+        .collect();
+
+    for line in content.split_inclusive('\n') {
+        if line.starts_with("# INTERNAL cache entries") {
+            in_internal = true;
+            // Above the `####` banner line that opens the internal section
+            let banner_start = out.trim_end_matches(['\n', '\r']).rfind('\n').map_or(0, |i| i + 1);
+            if out[banner_start..].starts_with('#') {
+                out.insert_str(banner_start, &added_block);
+            } else {
+                out.push_str(&added_block);
+            }
+        }
+
+        let body = line.trim_end_matches(['\n', '\r']);
+        let ending = &line[body.len()..];
+
+        // Internal entries are changed in place too, property entries like
+        // NAME-ADVANCED never match a variable's name
+        let replaced = if body.starts_with("//") || body.starts_with('#') {
+            None
+        } else {
+            body.split_once('=').and_then(|(key, _)| {
+                let (name, _typ) = key.split_once(':')?;
+                let (name, value) = changes.get_key_value(name.trim_start_matches('\u{feff}'))?;
+                replaced_names.insert(name);
+                Some(format!("{}={}", key, value))
+            })
+        };
+
+        match replaced {
+            Some(new_body) => {
+                out.push_str(&new_body);
+                out.push_str(ending);
+            }
+            None => out.push_str(line),
+        }
+    }
+
+    if !in_internal {
+        out.push_str(&added_block);
+    }
+
+    let mut new_properties: Vec<(&str, &str, &String)> = changes
+        .iter()
+        .filter(|(name, _)| !replaced_names.contains(name.as_str()))
+        .filter_map(|(name, value)| {
+            let (var, property) = name.rsplit_once('-')?;
+            let is_property = !property.is_empty() && property.chars().all(|c| c.is_ascii_uppercase());
+            is_property.then_some((var, property, value))
+        })
+        .collect();
+    new_properties.sort();
+    if !new_properties.is_empty() && !out.is_empty() && !out.ends_with('\n') {
+        out.push_str(newline);
+    }
+    for (var, property, value) in new_properties {
+        out.push_str(&format!("//{} property for variable: {}{}", property, var, newline));
+        out.push_str(&format!("{}-{}:INTERNAL={}{}", var, property, value, newline));
+    }
+
+    let tmp_path = build_dir.join("CMakeCache.txt.tmp");
+    fs::write(&tmp_path, out).map_err(|e| Error::io(&tmp_path, e))?;
+    fs::rename(&tmp_path, &cache_path).map_err(|e| Error::io(&cache_path, e))
+}
+
+/// Remove the entries named in `names` from the build dir's CMakeCache.txt,
+/// together with their help strings and properties (`NAME-ADVANCED`,
+/// `NAME-STRINGS`, ...). Like [`write_cache_changes`] the rest of the file
+/// is kept as is and it's replaced atomically under a [`CacheLock`].
+pub fn remove_cache_entries(build_dir: &Path, names: &[String]) -> Result<()> {
+    let cache_path = build_dir.join("CMakeCache.txt");
+    let _lock = CacheLock::acquire(build_dir)?;
+    let content = fs::read_to_string(&cache_path).map_err(|e| Error::io(&cache_path, e))?;
+
+    let removed = |key: &str| {
+        names.iter().any(|name| {
+            key == name
+                || key
+                    .strip_prefix(name.as_str())
+                    .and_then(|rest| rest.strip_prefix('-'))
+                    .is_some_and(|property| !property.is_empty() && property.chars().all(|c| c.is_ascii_uppercase()))
+        })
+    };
+
+    let mut out = String::with_capacity(content.len());
+    // Help string lines above an entry, dropped with it
+    let mut comments = String::new();
+    let mut skip_blank = false;
+    for line in content.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\n', '\r']);
+        if body.starts_with("//") {
+            comments.push_str(line);
+            continue;
+        }
+        let key = body.split_once('=').and_then(|(key, _)| key.split_once(':')).map(|(name, _)| name);
+        if key.is_some_and(removed) {
+            comments.clear();
+            skip_blank = true;
+            continue;
+        }
+        // The blank line separating a removed entry from the next one
+        if skip_blank && body.is_empty() && comments.is_empty() {
+            skip_blank = false;
+            continue;
+        }
+        skip_blank = false;
+        out.push_str(&std::mem::take(&mut comments));
+        out.push_str(line);
+    }
+    out.push_str(&comments);
+
+    let tmp_path = build_dir.join("CMakeCache.txt.tmp");
+    fs::write(&tmp_path, out).map_err(|e| Error::io(&tmp_path, e))?;
+    fs::rename(&tmp_path, &cache_path).map_err(|e| Error::io(&cache_path, e))
+}
+
+/// A complete CMakeCache.txt holding `entries`, in the layout cmake writes:
+/// the entries with their help strings, then the internal section with
+/// their ADVANCED, STRINGS and MODIFIED properties and the INTERNAL entries.
+pub fn serialize(entries: &[CacheVar]) -> String {
+    let internal = |var: &&CacheVar| var.typ == VarType::Internal;
+    let mut out = String::from(
+        "# This is the CMakeCache file.\n\
+         # You can edit this file to change values found and used by cmake.\n\n\
+         ########################\n\
+         # EXTERNAL cache entries\n\
+         ########################\n\n",
+    );
+    for var in entries.iter().filter(|v| !internal(v)) {
+        for line in var.desc.lines() {
+            out.push_str(&format!("//{}\n", line));
+        }
+        out.push_str(&format!("{}:{}={}\n\n", var.name, var.typ.cache_name(), var.value));
+    }
+
+    out.push_str(
+        "\n########################\n\
+         # INTERNAL cache entries\n\
+         ########################\n\n",
+    );
+    for var in entries {
+        if var.advanced {
+            out.push_str(&format!("//ADVANCED property for variable: {}\n", var.name));
+            out.push_str(&format!("{}-ADVANCED:INTERNAL=1\n", var.name));
+        }
+        if !var.values.is_empty() {
+            out.push_str(&format!("//STRINGS property for variable: {}\n", var.name));
+            out.push_str(&format!("{}-STRINGS:INTERNAL={}\n", var.name, var.values.join(";")));
+        }
+        if var.modified {
+            out.push_str(&format!("//MODIFIED property for variable: {}\n", var.name));
+            out.push_str(&format!("{}-MODIFIED:INTERNAL=ON\n", var.name));
+        }
+    }
+    for var in entries.iter().filter(internal) {
+        for line in var.desc.lines() {
+            out.push_str(&format!("//{}\n", line));
+        }
+        out.push_str(&format!("{}:INTERNAL={}\n", var.name, var.value));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::parse::{parse_cache_content, parse_cache_content_with_warnings};
+
+    const CACHE: &str = "\
+# This is the CMakeCache file.
+
+########################
+# EXTERNAL cache entries
+########################
+
+//Build the tests
+BUILD_TESTING:BOOL=ON
+
+//Backend to use
+DEMO_BACKEND:STRING=cpu
+
+
+########################
+# INTERNAL cache entries
+########################
+
+//ADVANCED property for variable: BUILD_TESTING
+BUILD_TESTING-ADVANCED:INTERNAL=1
+//Source directory
+CMAKE_HOME_DIRECTORY:INTERNAL=/src
+";
+
+    /// A build dir of its own for each test, holding `content` as its cache
+    fn build_dir(test: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cmake-cache-{}-{}", std::process::id(), test));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("CMakeCache.txt"), content).unwrap();
+        dir
+    }
+
+    fn changes(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    fn read(dir: &Path) -> String {
+        let content = fs::read_to_string(dir.join("CMakeCache.txt")).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+        content
+    }
+
+    #[test]
+    fn replaces_only_the_values() {
+        let dir = build_dir("values", CACHE);
+        write_cache_changes(&dir, &changes(&[("DEMO_BACKEND", "cuda"), ("CMAKE_HOME_DIRECTORY", "/other")]), &[]).unwrap();
+        let expected = CACHE.replace("STRING=cpu", "STRING=cuda").replace("INTERNAL=/src", "INTERNAL=/other");
+        assert_eq!(read(&dir), expected);
+    }
+
+    #[test]
+    fn round_trips_with_new_properties_and_entries() {
+        let dir = build_dir("round-trip", CACHE);
+        let added = CacheVar::new("NEW_OPT".to_string(), VarType::Bool, "Added by hand".to_string(), "OFF".to_string());
+        let new_values = changes(&[
+            ("DEMO_BACKEND", "hip"),
+            ("DEMO_BACKEND-STRINGS", "cpu;cuda;hip"),
+            ("DEMO_BACKEND-MODIFIED", "ON"),
+            ("BUILD_TESTING-ADVANCED", "0"),
+        ]);
+        write_cache_changes(&dir, &new_values, &[added]).unwrap();
+        let content = read(&dir);
+
+        let (entries, warnings) = parse_cache_content_with_warnings(&content);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        let names: Vec<&str> = entries.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["BUILD_TESTING", "CMAKE_HOME_DIRECTORY", "DEMO_BACKEND", "NEW_OPT"]);
+
+        let backend = &entries[2];
+        assert_eq!((&backend.typ, backend.value.as_str()), (&VarType::Enum, "hip"));
+        assert_eq!(backend.values, ["cpu", "cuda", "hip"]);
+        assert!(backend.modified);
+        // An existing property is changed in place, not added again
+        assert!(!entries[0].advanced);
+        assert_eq!(content.matches("BUILD_TESTING-ADVANCED").count(), 1);
+
+        // Added entries go in the external section, new properties in the internal one
+        let new_opt = &entries[3];
+        assert_eq!((&new_opt.typ, new_opt.value.as_str(), new_opt.desc.as_str()), (&VarType::Bool, "OFF", "Added by hand"));
+        let internal = content.find("# INTERNAL cache entries").unwrap();
+        assert!(content.find("NEW_OPT:BOOL=OFF").unwrap() < internal);
+        assert!(content.find("DEMO_BACKEND-STRINGS:INTERNAL=").unwrap() > internal);
+        assert!(content.contains("//MODIFIED property for variable: DEMO_BACKEND\nDEMO_BACKEND-MODIFIED:INTERNAL=ON\n"));
+    }
+
+    #[test]
+    fn keeps_crlf_and_bom() {
+        let original = format!("\u{feff}{}", CACHE.replace('\n', "\r\n"));
+        let dir = build_dir("crlf", &original);
+        let added = CacheVar::new("NEW_OPT".to_string(), VarType::Str, String::new(), "x".to_string());
+        write_cache_changes(&dir, &changes(&[("BUILD_TESTING", "OFF"), ("DEMO_BACKEND-MODIFIED", "ON")]), &[added]).unwrap();
+        let content = read(&dir);
+
+        assert!(content.starts_with('\u{feff}'));
+        assert!(!content.replace("\r\n", "").contains('\n'), "a bare \\n in {:?}", content);
+        let entries = parse_cache_content(&content);
+        let values: Vec<(&str, &str)> = entries.iter().map(|v| (v.name.as_str(), v.value.as_str())).collect();
+        assert_eq!(values, [("BUILD_TESTING", "OFF"), ("CMAKE_HOME_DIRECTORY", "/src"), ("DEMO_BACKEND", "cpu"), ("NEW_OPT", "x")]);
+        assert!(entries[2].modified);
+    }
+
+    #[test]
+    fn removes_entries_with_their_help_and_properties() {
+        let dir = build_dir("remove", CACHE);
+        remove_cache_entries(&dir, &["BUILD_TESTING".to_string()]).unwrap();
+        let content = read(&dir);
+        assert!(!content.contains("BUILD_TESTING") && !content.contains("Build the tests"), "{}", content);
+        let names: Vec<String> = parse_cache_content(&content).into_iter().map(|v| v.name).collect();
+        assert_eq!(names, ["CMAKE_HOME_DIRECTORY", "DEMO_BACKEND"]);
+    }
+
+    #[test]
+    fn serialized_entries_parse_back() {
+        let entries = parse_cache_content(CACHE);
+        let again = parse_cache_content(&serialize(&entries));
+        let summary = |entries: &[CacheVar]| -> Vec<String> {
+            entries.iter().map(|v| format!("{}:{:?}={} {:?} {}", v.name, v.typ, v.value, v.desc, v.advanced)).collect()
+        };
+        assert_eq!(summary(&again), summary(&entries));
+    }
+}
@@ -0,0 +1,508 @@
+use std::{fmt, str::FromStr};
+
+use cmake_cache::VarType;
+
+use crate::screen::Screen;
+
+/// Restricts the table to a subset of entries
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Filter {
+    Bool,
+    Path,
+    String,
+    Enum,
+    Modified,
+    // Values that look wrong for their entry
+    Problems,
+    // Entries of vcpkg or Conan
+    Packages,
+    // Entries that weren't in the cache the previous session
+    New,
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bool" => Ok(Filter::Bool),
+            "path" | "filepath" => Ok(Filter::Path),
+            "string" | "str" => Ok(Filter::String),
+            "enum" => Ok(Filter::Enum),
+            "modified" | "mod" => Ok(Filter::Modified),
+            "problems" => Ok(Filter::Problems),
+            "packages" | "vcpkg" | "conan" => Ok(Filter::Packages),
+            "new" => Ok(Filter::New),
+            _ => Err(format!(
+                "unknown filter '{}', expected bool, path, string, enum, modified, problems, packages, new or all",
+                s
+            )),
+        }
+    }
+}
+
+impl Filter {
+
+    pub fn matches_type(&self, typ: &VarType) -> bool {
+        match self {
+            Filter::Bool => *typ == VarType::Bool,
+            Filter::Path => matches!(typ, VarType::Filepath | VarType::Dirpath),
+            Filter::String => *typ == VarType::Str,
+            Filter::Enum => *typ == VarType::Enum,
+            // Checked against the value by the caller
+            Filter::Modified | Filter::Problems | Filter::Packages | Filter::New => true,
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Filter::Bool => "bool",
+            Filter::Path => "path",
+            Filter::String => "string",
+            Filter::Enum => "enum",
+            Filter::Modified => "modified",
+            Filter::Problems => "problems",
+            Filter::Packages => "packages",
+            Filter::New => "new",
+        };
+        f.pad(s)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SortOrder {
+    Name,
+    Type,
+    Value,
+    Modified,
+    // Most recently changed first, by the change journal
+    Recent,
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(SortOrder::Name),
+            "type" => Ok(SortOrder::Type),
+            "value" => Ok(SortOrder::Value),
+            "modified" | "mod" => Ok(SortOrder::Modified),
+            "recent" => Ok(SortOrder::Recent),
+            _ => Err(format!("unknown sort '{}', expected name, type, value, modified or recent", s)),
+        }
+    }
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            SortOrder::Name => "name",
+            SortOrder::Type => "type",
+            SortOrder::Value => "value",
+            SortOrder::Modified => "modified",
+            SortOrder::Recent => "recent",
+        };
+        f.pad(s)
+    }
+}
+
+/// A column of the cache table, besides the row numbers
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Column {
+    Name,
+    Type,
+    Value,
+    // Whether the project marked the entry advanced
+    Advanced,
+    // Whether the entry has a pending edit
+    Modified,
+}
+
+impl Column {
+    pub const DEFAULT: &[Column] = &[Column::Name, Column::Type, Column::Value];
+
+    /// Columns from a comma separated list, e.g. `name,value,modified`
+    pub fn parse_list(s: &str) -> Result<Vec<Column>, String> {
+        let columns = s.split(',').map(|c| c.trim().parse()).collect::<Result<Vec<Column>, _>>()?;
+        if !columns.contains(&Column::Name) {
+            return Err("the name column can't be left out".to_string());
+        }
+        Ok(columns)
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(Column::Name),
+            "type" => Ok(Column::Type),
+            "value" => Ok(Column::Value),
+            "advanced" => Ok(Column::Advanced),
+            "modified" => Ok(Column::Modified),
+            _ => Err(format!("unknown column '{}', expected name, type, value, advanced or modified", s)),
+        }
+    }
+}
+
+/// What happens to values too long for the value column
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LongValues {
+    // Cut short with an ellipsis
+    Truncate,
+    // Continued on more lines, making the row taller
+    Wrap,
+}
+
+impl FromStr for LongValues {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "truncate" => Ok(LongValues::Truncate),
+            "wrap" => Ok(LongValues::Wrap),
+            _ => Err(format!("unknown long value handling '{}', expected truncate or wrap", s)),
+        }
+    }
+}
+
+/// When pending edits are written to CMakeCache.txt without a `:w`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AutoSave {
+    // Only `:w` writes them
+    Off,
+    // Before each configure, so cmake reads them from the cache instead of -D
+    Configure,
+    // When quitting with `q` or `:q`, `:q!` still drops them
+    Exit,
+    Always,
+}
+
+impl AutoSave {
+    pub fn before_configure(self) -> bool {
+        matches!(self, AutoSave::Configure | AutoSave::Always)
+    }
+
+    pub fn on_exit(self) -> bool {
+        matches!(self, AutoSave::Exit | AutoSave::Always)
+    }
+}
+
+impl fmt::Display for AutoSave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AutoSave::Off => "off",
+            AutoSave::Configure => "configure",
+            AutoSave::Exit => "exit",
+            AutoSave::Always => "always",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for AutoSave {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(AutoSave::Off),
+            "configure" => Ok(AutoSave::Configure),
+            "exit" => Ok(AutoSave::Exit),
+            "always" => Ok(AutoSave::Always),
+            _ => Err(format!("unknown autosave '{}', expected off, configure, exit or always", s)),
+        }
+    }
+}
+
+/// Everything the user can do from scroll mode. Keybindings and `:` commands
+/// both resolve to an action, which `App::dispatch` carries out.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Action {
+    Quit,
+    // Quit even with unsaved edits
+    ForceQuit,
+    SelectNext(usize),
+    SelectPrevious(usize),
+    SelectFirst,
+    SelectLast,
+    // 1-based row number
+    SelectRow(usize),
+    ToggleAdvanced,
+    ToggleInternal,
+    // Show the entries the view.ignore patterns hide
+    ToggleIgnored,
+    // Show the selected value with its references expanded
+    ToggleExpanded,
+    CycleRowNumbers,
+    SetColumns(Vec<Column>),
+    // Switch between truncating and wrapping long values
+    ToggleWrap,
+    EditValue,
+    // Set the selected entry to this value, what `.` repeats after an edit
+    SetValue(String),
+    CycleValue,
+    // Back to the default the project declares for the selected variable
+    ResetToDefault,
+    // Lines to scroll the description of the selected variable, up when negative
+    ScrollDescription(i16),
+    // Scroll the selected value sideways, by characters
+    ScrollValue(isize),
+    // Show the whole selected value in a popup
+    ShowValue,
+    StartSearch,
+    NextSearchResult,
+    PreviousSearchResult,
+    StartCommand,
+    Configure,
+    // Configure even when another cmake seems to be using the build dir
+    ForceConfigure,
+    // Configure with cmake's profiling on, then show the slowest steps
+    ProfileConfigure,
+    // Wipe the cache and configure from scratch, keeping the chosen edits
+    FreshConfigure,
+    ShowProfile,
+    Build,
+    // Ask for the prefix, then run cmake --install
+    Install,
+    ShowPackage,
+    ToggleLog,
+    // Frame time, entry counts and the last event over the UI
+    ToggleDebugOverlay,
+    FocusLog,
+    ShowProblems,
+    // Cache lines the parser skipped
+    ShowDiagnostics,
+    ShowBuildTypes,
+    ShowTriplets,
+    ShowSubBuilds,
+    ShowUsages,
+    // Trace a scratch configure for where the selected variable is set and read
+    Explain,
+    ShowLauncher,
+    ShowFlagBundles,
+    ShowToolchain,
+    PickToolchain,
+    PickGenerator,
+    // Toggle CMAKE_EXPORT_COMPILE_COMMANDS
+    ToggleCompileCommands,
+    ShowDuplicates,
+    OpenEditor,
+    OpenListEditor,
+    // Edit the allowed values (STRINGS property) of a STRING entry
+    EditAllowedValues,
+    // Mark the selected entry as advanced or not (ADVANCED property)
+    ToggleAdvancedFlag,
+    NewEntry,
+    // New entry pre-filled from the selected one
+    DuplicateEntry,
+    // Remove the selected entry from the cache, after confirming
+    DeleteEntry,
+    // Drop all pending edits, after confirming
+    DiscardEdits,
+    // Pin the selected entry above the table, or unpin it
+    TogglePin,
+    ShowAbout,
+    // $SHELL in the build dir, with the TUI suspended until it exits
+    OpenShell,
+    // A shell command line run in the build dir with the cache exported
+    RunCommand(String),
+    // The environment of cmake runs, with overrides
+    ShowEnv,
+    // Set an environment variable for the next cmake runs, unset it when None
+    SetEnv(String, Option<String>),
+    ShowQuickSettings,
+    Set(String, String),
+    // Do the last change again, on the entry selected now
+    Repeat,
+    // `m{a-z}`, remember the selected entry under the letter
+    SetMark(char),
+    // `'{a-z}`, select the entry of the mark
+    JumpToMark(char),
+    ListMarks,
+    // Ctrl-o and Ctrl-i, through the entries jumped away from
+    JumpBack,
+    JumpForward,
+    Save,
+    // Save even while a configure or build is running
+    ForceSave,
+    SaveAndQuit,
+    // None to show the current setting
+    SetAutoSave(Option<AutoSave>),
+    SetFilter(Option<Filter>),
+    SetSort(SortOrder),
+    ShowSidecar,
+    ShowSnapshots,
+    ShowDiskUsage,
+    TakeSnapshot(String),
+    // Write the pending edits as a configure preset of this name into CMakeUserPresets.json
+    ExportPreset(String),
+    ShowHistory,
+    // Switch to a tab, or open a screen on top (close it when it's open already)
+    ShowScreen(Screen),
+    NextTab,
+    CloseScreen,
+    // Back from the log to the table, from the fullscreen log or the focused pane
+    LeaveLog,
+    PageDown,
+    PageUp,
+    NextError,
+    PreviousError,
+    // Select the entry of the diff screen in the cache table
+    ShowEntry,
+    BuildSelectedTarget,
+    RunSelectedTest,
+    RunTests,
+}
+
+impl Action {
+    /// The action with the count typed before its key, e.g. the 15 in `15j`
+    pub fn with_count(self, count: Option<usize>) -> Self {
+        match (self, count) {
+            (Action::SelectNext(_), Some(n)) => Action::SelectNext(n),
+            (Action::SelectPrevious(_), Some(n)) => Action::SelectPrevious(n),
+            (Action::SelectFirst | Action::SelectLast, Some(row)) => Action::SelectRow(row),
+            (action, _) => action,
+        }
+    }
+
+    /// Whether `.` can do the action again, on another entry: the changes
+    /// made to the selected entry
+    pub fn is_repeatable(&self) -> bool {
+        matches!(
+            self,
+            Action::SetValue(_) | Action::CycleValue | Action::ResetToDefault | Action::TogglePin | Action::DeleteEntry
+        )
+    }
+}
+
+/// Parse a `:` command line (without the colon) into an action
+pub fn parse_command(line: &str) -> Result<Action, String> {
+    let line = line.trim();
+    let (cmd, args) = match line.split_once(char::is_whitespace) {
+        Some((cmd, args)) => (cmd, args.trim()),
+        None => (line, ""),
+    };
+
+    // `:!make` like in vim, the rest of the line is the command
+    if let Some(command) = line.strip_prefix('!') {
+        return Ok(match command.trim() {
+            "" => Action::OpenShell,
+            command => Action::RunCommand(command.to_string()),
+        });
+    }
+
+    // `:42` goes to that row, like in vim
+    if let Ok(row) = cmd.parse::<usize>()
+        && args.is_empty()
+    {
+        return Ok(Action::SelectRow(row));
+    }
+
+    let action = match cmd {
+        "q" | "quit" => Action::Quit,
+        "q!" | "quit!" => Action::ForceQuit,
+        "w" | "write" => Action::Save,
+        "marks" => Action::ListMarks,
+        "sh" | "shell" => Action::OpenShell,
+        "env" => Action::ShowEnv,
+        "setenv" => {
+            let Some((name, value)) = args.split_once('=') else {
+                return Err("usage: setenv NAME=VALUE".to_string());
+            };
+            Action::SetEnv(name.trim().to_string(), Some(value.to_string()))
+        }
+        "unsetenv" if !args.is_empty() => Action::SetEnv(args.to_string(), None),
+        "unsetenv" => return Err("usage: unsetenv NAME".to_string()),
+        "run" if args.is_empty() => Action::OpenShell,
+        "run" => Action::RunCommand(args.to_string()),
+        "w!" | "write!" => Action::ForceSave,
+        "wq" | "x" => Action::SaveAndQuit,
+        "autosave" => Action::SetAutoSave(if args.is_empty() { None } else { Some(args.parse()?) }),
+        "set" => {
+            let Some((name, value)) = args.split_once('=') else {
+                return Err("usage: set VAR=VALUE".to_string());
+            };
+            Action::Set(name.trim().to_string(), value.to_string())
+        }
+        "filter" => match args {
+            "" | "all" | "none" => Action::SetFilter(None),
+            _ => Action::SetFilter(Some(args.parse()?)),
+        },
+        "sort" => Action::SetSort(if args.is_empty() { SortOrder::Name } else { args.parse()? }),
+        "advanced" => Action::ToggleAdvanced,
+        "internal" => Action::ToggleInternal,
+        "ignored" => Action::ToggleIgnored,
+        "expand" => Action::ToggleExpanded,
+        "columns" => Action::SetColumns(match args {
+            "" => Column::DEFAULT.to_vec(),
+            _ => Column::parse_list(args)?,
+        }),
+        "wrap" => Action::ToggleWrap,
+        "number" | "nu" => Action::CycleRowNumbers,
+        "value" => Action::ShowValue,
+        "configure" => Action::Configure,
+        "configure!" => Action::ForceConfigure,
+        "fresh" => Action::FreshConfigure,
+        "profile" => match args {
+            "" => Action::ProfileConfigure,
+            "show" => Action::ShowProfile,
+            _ => return Err("usage: profile [show]".to_string()),
+        },
+        "build" => Action::Build,
+        "install" => Action::Install,
+        "package" | "cpack" => Action::ShowPackage,
+        "view" | "screen" => match args {
+            "" => Action::NextTab,
+            _ => Action::ShowScreen(args.parse()?),
+        },
+        "cache" => Action::ShowScreen(Screen::Cache),
+        "targets" => Action::ShowScreen(Screen::Targets),
+        "tests" => Action::ShowScreen(Screen::Tests),
+        "diff" => Action::ShowScreen(Screen::Diff),
+        "help" => Action::ShowScreen(Screen::Help),
+        "test" | "ctest" => Action::RunTests,
+        "log" => Action::ToggleLog,
+        "debug" => Action::ToggleDebugOverlay,
+        "problems" => Action::ShowProblems,
+        "diagnostics" => Action::ShowDiagnostics,
+        "buildtype" | "build-type" => Action::ShowBuildTypes,
+        "triplet" => Action::ShowTriplets,
+        "deps" | "subbuilds" => Action::ShowSubBuilds,
+        "usages" | "grep" => Action::ShowUsages,
+        "explain" => Action::Explain,
+        "compile-commands" => Action::ToggleCompileCommands,
+        "launcher" | "ccache" => Action::ShowLauncher,
+        "sanitizers" | "flags" => Action::ShowFlagBundles,
+        "toolchain" => Action::ShowToolchain,
+        "toolchain-file" => Action::PickToolchain,
+        "generator" => Action::PickGenerator,
+        "duplicates" => Action::ShowDuplicates,
+        "edit" => Action::OpenEditor,
+        "reset-default" => Action::ResetToDefault,
+        "list" => Action::OpenListEditor,
+        "strings" | "allowed" => Action::EditAllowedValues,
+        "mark-advanced" => Action::ToggleAdvancedFlag,
+        "new" => Action::NewEntry,
+        "dup" | "duplicate" => Action::DuplicateEntry,
+        "delete" => Action::DeleteEntry,
+        "discard" | "e!" => Action::DiscardEdits,
+        "pin" | "unpin" => Action::TogglePin,
+        "version" | "about" => Action::ShowAbout,
+        "quick" => Action::ShowQuickSettings,
+        "sidecar" => Action::ShowSidecar,
+        "snapshots" => Action::ShowSnapshots,
+        "du" | "disk-usage" => Action::ShowDiskUsage,
+        "history" => Action::ShowHistory,
+        "snapshot" if args.is_empty() => return Err("usage: snapshot NAME".to_string()),
+        "snapshot" => Action::TakeSnapshot(args.to_string()),
+        "preset" if args.is_empty() => return Err("usage: preset NAME".to_string()),
+        "preset" => Action::ExportPreset(args.to_string()),
+        "" => return Err(String::new()),
+        _ => return Err(format!("unknown command '{}'", cmd)),
+    };
+    Ok(action)
+}
@@ -1,186 +1,3289 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
 
 use color_eyre::Result;
 use ratatui::{
     DefaultTerminal,
     buffer::Buffer,
-    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-    layout::{Constraint, Layout, Rect, Flex},
-    style::{
-        Color, Modifier, Style, Stylize,
-        palette::tailwind::{BLUE, SLATE},
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+        execute,
+        terminal::{EnterAlternateScreen, enable_raw_mode},
     },
+    layout::{Constraint, Layout, Rect, Flex},
+    style::{Color, Style, Stylize, palette::tailwind::{AMBER, BLUE}},
     symbols,
-    text::Line,
-    widgets::{
-        Block, Borders, HighlightSpacing, Padding, Paragraph, StatefulWidget, 
-        Widget, Wrap, Table, Row, Cell, TableState, Clear
-    },
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, StatefulWidget, Widget, Wrap},
 };
 
-use crate::cache_parser::{CacheVar, VarType, parse_cmake_cache};
-
-const TODO_HEADER_STYLE: Style = Style::new().fg(SLATE.c100).bg(BLUE.c800);
-const NORMAL_ROW_BG: Color = SLATE.c950;
-const ALT_ROW_BG_COLOR: Color = SLATE.c900;
-const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
-const TEXT_FG_COLOR: Color = SLATE.c200;
-// const COMPLETED_TEXT_FG_COLOR: Color = GREEN.c500;
-
-#[derive(PartialEq)]
+use crate::cache_editor::{CacheEditorState, CacheEditorWidget, NORMAL_ROW_BG, TEXT_FG_COLOR, TODO_HEADER_STYLE, VALUE_SCROLL_STEP};
+use cmake_cache::{BoolValue, CacheVar, VarType, parse_source_dir};
+use crate::action::{Action, AutoSave, LongValues, parse_command};
+use crate::build_info;
+use crate::busy::{find_configure_in_progress, find_external_cmake};
+use crate::config::{Config, VcsIgnore};
+use crate::cmake;
+use crate::build_dirs::find_build_dirs;
+use cmake_cache::{remove_cache_entries, write_cache_changes};
+use crate::build_type::{BuildTypePane, is_multi_config};
+use crate::toolchain::ToolchainPane;
+use crate::value_pane::ValuePane;
+use crate::toolchain_picker::{PickerResult, ToolchainPicker, remember_toolchain};
+use crate::capabilities::{Capabilities, GeneratorChoice};
+use crate::generator_picker::{GeneratorPicker, GeneratorPickerResult};
+use crate::fresh::{FreshPane, FreshResult};
+use crate::disk_usage::{DiskUsagePane, format_size};
+use crate::stale::changed_project_files;
+use crate::pins;
+use crate::project_config::{PROJECT_CONFIG_FILE, ProjectConfig};
+use crate::flag_bundles::FlagBundlesPane;
+use crate::launcher::{self, LauncherChoice, LauncherPane, launcher_var};
+use crate::package_manager::{self, PackageManager, TRIPLET_VAR, TripletPane};
+use crate::sub_builds::{SubBuildChoice, SubBuildsPane};
+use crate::targets::TargetsView;
+use crate::usages::UsagesPane;
+use crate::compile_commands::{COMPILE_COMMANDS, is_linked, link_into_source};
+use crate::ctest::TestsView;
+use crate::diagnostics::DiagnosticsPane;
+use crate::dialog::{Dialog, DialogResult};
+use crate::diff::DiffView;
+use crate::duplicates::{DuplicatesPane, find_duplicate_paths};
+use crate::editor;
+use crate::environment::EnvPane;
+use crate::help::HelpView;
+use crate::journal::{self, JournalChange, JournalEntry, JournalPane};
+use crate::jumps::JumpList;
+use crate::list_editor::{ListEditor, ListEditorResult, is_list, split_list};
+use crate::new_entry::{NewEntryForm, NewEntryResult};
+use crate::text_input::TextInput;
+#[cfg(feature = "time-travel")]
+use crate::time_travel::TimeTravel;
+use crate::crash;
+use crate::debug_overlay::DebugOverlay;
+use crate::vcs::Vcs;
+use crate::wsl::local_source_dir;
+use crate::log_pane::{LogPane, LogView};
+use crate::monochrome::strip_colors;
+use crate::missing_cache::{MissingCachePrompt, MissingCacheScreen, guess_source_dir};
+use crate::json::JsonValue;
+use crate::presets::{export_configure_preset, has_configure_preset};
+use crate::problems::{ProblemsPane, Severity, parse_problems};
+use crate::profiling::{ProfilePane, parse_trace};
+use crate::packaging::{CPACK_CONFIG, PackagePane};
+use crate::explain::{ExplainPane, ExplainRun, parse_trace_hits, write_initial_cache};
+use crate::remote;
+use crate::quick_settings::{QuickRow, QuickSettings, QuickSettingsPane};
+use crate::recording::{RecordedKey, Recorder, Replay, checksum};
+use crate::screen::{Screen, ScreenStack, key_label};
+use crate::run;
+use crate::shell;
+use crate::rpc::{INVALID_PARAMS, METHOD_NOT_FOUND, RpcCall, RpcError, RpcServer, SERVER_ERROR, var_json};
+use crate::session::Session;
+use crate::sidecar::Sidecar;
+use crate::snapshots::{Snapshot, SnapshotsPane};
+use crate::task::{Stream, Task, TaskEvent, TaskKind, TaskRecord, format_summary};
+
+#[derive(Debug, PartialEq)]
 enum AppMode {
     Scroll,
     ValueEdit,
     SearchInput,
+    CommandInput,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Focus {
+    Table,
+    Log,
+}
+
+/// What answering a dialog with one of its choices does
+enum Confirmed {
+    // A choice that only closes the dialog
+    Nothing,
+    IgnoreSidecar(Box<dyn Vcs>),
+    MoveSidecar,
+    // Toolchain file, None for native
+    SwitchToolchain(Option<PathBuf>),
+    // Generator, None for cmake's default
+    SwitchGenerator(Option<GeneratorChoice>),
+    // Definitions to carry over into the fresh cache
+    FreshConfigure(Vec<(String, String)>),
+    // Directory in the build dir to delete
+    RemoveArtifacts(PathBuf),
+    RunCleanTarget,
+    // Source dir to link compile_commands.json into
+    LinkCompileCommands(PathBuf),
+    // Edit of an entry cmake manages itself
+    Edit(String, Action),
+    DeleteEntry(String),
+    DiscardEdits,
+    DeleteSnapshot(String),
+    // Configure preset to overwrite in CMakeUserPresets.json
+    ReplacePreset(String),
+}
+
+/// Why pending edits weren't written
+enum SaveError {
+    // A cmake process works on the build dir, the message says which
+    Busy(String),
+    Failed(cmake_cache::Error),
+    // Written to the local copy of a remote cache only
+    Upload(String),
+}
+
+/// What's left to do once the cache read again in the background is in
+enum AfterReload {
+    // A configure succeeded: journal the edits it applied, `before` being
+    // the values it started from, and what cmake changed itself
+    Configure { before: HashMap<String, String>, edits: Vec<JournalChange> },
+    // The cache was rewritten under the UI, the pending edits stay
+    External,
+}
+
+/// The cache being read again on a worker thread, so a big one doesn't
+/// freeze the UI. The fetch of a remote cache happens there too.
+struct PendingReload {
+    receiver: Receiver<(std::result::Result<(), String>, std::result::Result<CacheEditorState, String>)>,
+    then: AfterReload,
 }
 
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Busy(message) => f.write_str(message),
+            SaveError::Failed(e) => write!(f, "Failed to save CMakeCache.txt: {}", e),
+            SaveError::Upload(e) => f.write_str(e),
+        }
+    }
+}
+
+// How long to wait for input before checking on running tasks
+const TICK: Duration = Duration::from_millis(50);
+
+// How long the first key of a chord waits for the second before doing what
+// it does alone
+const CHORD_TIMEOUT: Duration = Duration::from_millis(500);
+// Upper bound for count prefixes, so holding a digit key can't overflow
+const MAX_COUNT: usize = 99_999;
+// Searches kept per project, the oldest are dropped first
+const MAX_SEARCH_HISTORY: usize = 100;
+// Badge of the mode in the status bar, shown reversed in monochrome
+const STATUS_MODE_BG: Color = BLUE.c700;
+
 pub struct App {
     should_exit: bool,
-    var_list: CacheVarList,
+    build_dir: PathBuf,
+    config: Config,
+    sidecar: Sidecar,
+    // Confirmation or error on top of everything else
+    dialog: Option<Dialog<Confirmed>>,
+    // Prefix to install to, while asking for it
+    install_prompt: Option<TextInput>,
+    package: PackagePane,
+    // Link compile_commands.json into this source dir after the next configure
+    link_compile_commands: Option<PathBuf>,
+    // Entries the user confirmed editing, not asked about again
+    confirmed_edits: HashSet<String>,
+    // Quit once the dialog raised while saving the session is answered
+    exit_after_prompt: bool,
+    editor: CacheEditorState,
+    screens: ScreenStack,
+    targets: TargetsView,
+    tests: TestsView,
+    diff: DiffView,
+    help: HelpView,
+    // Shown instead of the table while the cache couldn't be read
+    missing_cache: Option<MissingCacheScreen>,
     mode: AppMode,
-    show_advanced: bool,
-
-    search_input: String,
-    cursor_pos: usize,
+    // Pending vim-style count typed before a motion (e.g. the 15 in `15j`)
+    count: Option<usize>,
+    // First key of a chord, waiting for the second since then
+    pending_key: Option<(KeyCode, Instant)>,
+    // What `.` repeats
+    last_change: Option<Action>,
+    marks: BTreeMap<char, String>,
+    jumps: JumpList,
+
+    search_input: TextInput,
+    // Previous `/` queries, oldest first, and the one Up/Down is on with
+    // what was typed before going through them
+    search_history: Vec<String>,
+    search_history_pos: Option<(usize, String)>,
+    // The row selected when the cache search started, the match shown while
+    // typing is looked up from it and Esc goes back to it
+    search_origin: Option<usize>,
+    command_input: TextInput,
+    // New value of the selected variable while editing it as text
+    value_input: TextInput,
+
+    focus: Focus,
+    log: LogPane,
+    task: Option<Task>,
+    task_started: Instant,
+    // Edits passed to the running task, journaled once it succeeds
+    task_edits: Vec<JournalChange>,
+    reload: Option<PendingReload>,
+    task_history: Vec<TaskRecord>,
+    // Edits saved or configured with in this session, for the exit summary
+    applied_changes: Vec<JournalChange>,
+    problems: ProblemsPane,
+    diagnostics: DiagnosticsPane,
+    build_type: BuildTypePane,
+    // Configuration builds, tests and installs use with a multi-config
+    // generator, cmake's default until one is picked
+    build_config: Option<String>,
+    triplets: TripletPane,
+    usages: UsagesPane,
+    sub_builds: SubBuildsPane,
+    // Build dirs the current one was opened from as a sub-build, innermost last
+    parent_builds: Vec<PathBuf>,
+    launcher: LauncherPane,
+    flag_bundles: FlagBundlesPane,
+    toolchain: ToolchainPane,
+    value_pane: ValuePane,
+    toolchain_picker: Option<ToolchainPicker>,
+    // With the source dir when picking for an initial configure, without
+    // when switching the generator of the build dir
+    generator_picker: Option<(GeneratorPicker, Option<PathBuf>)>,
+    fresh: Option<FreshPane>,
+    disk_usage: DiskUsagePane,
+    // Project files newer than the cache, relative to the source dir, shown in
+    // a banner until the next configure
+    stale_files: Vec<PathBuf>,
+    // Pinned by the project's .cmake-tui.toml, not the user's to unpin
+    project_pins: Vec<String>,
+    profile: ProfilePane,
+    // From the project's cmake-tui.json, None when it ships none
+    quick_settings: Option<QuickSettingsPane>,
+    // Trace the running configure writes, when started with :profile
+    profile_output: Option<PathBuf>,
+    explain: ExplainPane,
+    // The traced configure running for the explain pane
+    explain_run: Option<ExplainRun>,
+    duplicates: DuplicatesPane,
+    env: EnvPane,
+    journal: JournalPane,
+    snapshots: SnapshotsPane,
+    list_editor: Option<ListEditor>,
+    // Whether the list editor has the allowed values of the entry rather
+    // than its value
+    list_editor_strings: bool,
+    new_entry: Option<NewEntryForm>,
+    // Build info lines shown in the about popup, None while it's closed
+    about: Option<Vec<String>>,
+
+    // Set by a key handler when the selected value should be opened in $EDITOR,
+    // which needs the terminal and so is done by the run loop
+    open_editor: bool,
+    // File and line to open in $EDITOR, set by the usages pane like open_editor
+    open_usage: Option<(PathBuf, usize)>,
+    // Ctrl-z and the shell in the build dir, also done by the run loop
+    suspend: bool,
+    open_shell: bool,
+    run_command: Option<String>,
+    // One-off message shown in the status bar in place of the key hints until the next key press
+    message: Option<String>,
+    // Control socket for editor plugins, with --listen or --embed
+    rpc: Option<RpcServer>,
+    // Key presses written to a file, with --record
+    recorder: Option<Recorder>,
+    // Recording driving the UI, with --replay, until a key is pressed
+    replay: Option<Replay>,
+    #[cfg(feature = "time-travel")]
+    time_travel: TimeTravel,
+    debug: DebugOverlay,
+    // Something a crash report shows may have changed since it was last handed
+    // to the panic hook: an event came in, a task started or a reload finished
+    crash_state_changed: bool,
 }
 
-struct CacheVarTui {
-    var: CacheVar,
-    new_val: String,
-}
+impl App {
+    pub fn new(build_dir: PathBuf) -> Self {
+        let (editor, missing_cache) = match CacheEditorState::try_load(&build_dir) {
+            Ok(editor) => (editor, None),
+            Err(e) => (CacheEditorState::new(Vec::new()), Some(MissingCacheScreen::new(e.to_string()))),
+        };
+        let config = Config::load();
+        let sidecar = Sidecar::resolve(&build_dir, &config);
+        let mut message = config.error.as_ref().map(|e| format!("Config error: {}", e));
+        if message.is_none() && cmake::version().is_none() {
+            message = Some(format!("Couldn't run {}, configuring won't work", cmake::program().display()));
+        }
+
+        // Shown right away, the project ships them for users new to its options
+        let quick_settings = match local_source_dir(&build_dir).and_then(|dir| QuickSettings::load(&dir)) {
+            Some(Ok(settings)) => {
+                let mut pane = QuickSettingsPane::new(settings);
+                pane.visible = missing_cache.is_none();
+                Some(pane)
+            }
+            Some(Err(e)) => {
+                message = Some(format!("Quick settings error: {}", e));
+                None
+            }
+            None => None,
+        };
+
+        #[cfg(feature = "time-travel")]
+        let time_travel = TimeTravel::new(&editor);
+
+        let mut app = Self {
+            should_exit: false,
+            build_dir,
+            config,
+            sidecar,
+            dialog: None,
+            install_prompt: None,
+            package: PackagePane::new(),
+            link_compile_commands: None,
+            confirmed_edits: HashSet::new(),
+            exit_after_prompt: false,
+            editor,
+            screens: ScreenStack::new(),
+            targets: TargetsView::new(),
+            tests: TestsView::new(),
+            diff: DiffView::new(),
+            help: HelpView::new(),
+            missing_cache,
+            mode: AppMode::Scroll,
+            count: None,
+            pending_key: None,
+            last_change: None,
+            marks: BTreeMap::new(),
+            jumps: JumpList::default(),
+
+            search_input: TextInput::default(),
+            search_history: Vec::new(),
+            search_history_pos: None,
+            search_origin: None,
+            command_input: TextInput::default(),
+            value_input: TextInput::default(),
+
+            focus: Focus::Table,
+            log: LogPane::new(),
+            task: None,
+            task_started: Instant::now(),
+            task_edits: Vec::new(),
+            reload: None,
+            task_history: Vec::new(),
+            applied_changes: Vec::new(),
+            problems: ProblemsPane::new(),
+            diagnostics: DiagnosticsPane::new(),
+            build_type: BuildTypePane::new(),
+            build_config: None,
+            triplets: TripletPane::new(),
+            usages: UsagesPane::new(),
+            sub_builds: SubBuildsPane::new(),
+            parent_builds: Vec::new(),
+            launcher: LauncherPane::new(),
+            flag_bundles: FlagBundlesPane::new(),
+            toolchain: ToolchainPane::new(),
+            value_pane: ValuePane::new(),
+            toolchain_picker: None,
+            generator_picker: None,
+            fresh: None,
+            disk_usage: DiskUsagePane::new(),
+            stale_files: Vec::new(),
+            project_pins: Vec::new(),
+            profile: ProfilePane::new(),
+            quick_settings,
+            profile_output: None,
+            explain: ExplainPane::new(),
+            explain_run: None,
+            duplicates: DuplicatesPane::new(),
+            env: EnvPane::new(),
+            journal: JournalPane::new(),
+            snapshots: SnapshotsPane::new(),
+            list_editor: None,
+            list_editor_strings: false,
+            new_entry: None,
+            about: None,
+
+            open_editor: false,
+            open_usage: None,
+            suspend: false,
+            open_shell: false,
+            run_command: None,
+            message,
+            rpc: None,
+            recorder: None,
+            replay: None,
+            #[cfg(feature = "time-travel")]
+            time_travel,
+            debug: DebugOverlay::new(),
+            crash_state_changed: true,
+        };
+        app.apply_view_config();
+        if let Some(e) = app.load_project_config()
+            && app.message.is_none()
+        {
+            app.message = Some(e);
+        }
+        app.restore_session();
+        app.refresh_last_changes();
+        app.stale_files = changed_project_files(&app.build_dir);
+        // A config error is more pressing
+        if app.message.is_none() {
+            app.report_parse_warnings();
+        }
+        app
+    }
+
+    /// Point out cache lines that were dropped, so a broken entry doesn't
+    /// just go missing from the table
+    fn report_parse_warnings(&mut self) {
+        let count = self.editor.parse_warnings.len();
+        if count > 0 {
+            self.message = Some(format!("{} cache line(s) not read as written, :diagnostics lists them", count));
+        }
+    }
+
+    /// Bring back the view from the last time this build dir was open
+    fn restore_session(&mut self) {
+        let Some(session) = Session::load(&self.sidecar.dir) else { return };
+        self.editor.filter = session.filter;
+        self.editor.sort = session.sort;
+        self.editor.show_advanced = session.show_advanced;
+        self.editor.show_internal = session.show_internal;
+        self.search_history = session.search_history;
+        self.marks = session.marks;
+        if let Some(previous) = session.entries {
+            self.compare_entries(&previous);
+        }
+        self.editor.rebuild();
+        if let Some(name) = session.selected {
+            self.editor.select_by_name(&name);
+        }
+    }
+
+    /// Mark the entries added since the previous session and report the
+    /// ones that are gone, e.g. after a dependency bump
+    fn compare_entries(&mut self, previous: &[String]) {
+        let previous: HashSet<&str> = previous.iter().map(String::as_str).collect();
+        let current: HashSet<&str> = self.editor.vars.iter().map(|v| v.var.name.as_str()).collect();
+        self.editor.new_since_session =
+            current.iter().filter(|name| !previous.contains(*name)).map(|name| name.to_string()).collect();
+
+        let mut removed: Vec<&str> = previous.difference(&current).copied().collect();
+        removed.sort();
+        if !removed.is_empty() {
+            self.log.push_info(&format!("Gone from the cache since the last session: {}", removed.join(", ")));
+        }
+        let added = self.editor.new_since_session.len();
+        if (added > 0 || !removed.is_empty()) && self.message.is_none() {
+            self.message = Some(match removed.len() {
+                0 => format!("{} new entries since the last session, :filter new to list them", added),
+                n => format!(
+                    "{} new and {} removed entries since the last session (L for the removed ones)",
+                    added, n
+                ),
+            });
+        }
+    }
+
+    fn save_session(&mut self) {
+        let session = Session {
+            selected: self.editor.selected().map(|v| v.var.name.clone()),
+            filter: self.editor.filter,
+            sort: self.editor.sort,
+            show_advanced: self.editor.show_advanced,
+            show_internal: self.editor.show_internal,
+            search_history: self.search_history.clone(),
+            entries: Some(self.editor.vars.iter().filter(|v| !v.is_new).map(|v| v.var.name.clone()).collect()),
+            marks: self.marks.clone(),
+        };
+        let Some(dir) = self.ensure_sidecar() else { return };
+        if let Err(e) = session.save(&dir) {
+            self.message = Some(format!("Failed to save session: {}", e));
+        }
+    }
+
+    /// Leave the run loop, remembering the view for next time
+    fn quit(&mut self) {
+        self.save_session();
+        // Creating the sidecar dir may need an answer from the user first
+        if self.dialog.is_some() {
+            self.exit_after_prompt = true;
+        } else {
+            self.should_exit = true;
+        }
+    }
+
+    /// Take requests from `server` while the UI runs
+    pub fn serve_rpc(&mut self, server: RpcServer) {
+        if let Some(path) = server.path() {
+            self.message = Some(format!("Listening on {}", path.display()));
+        }
+        self.rpc = Some(server);
+    }
+
+    /// Tell connected clients about a change they didn't ask for
+    fn notify(&self, method: &str, params: JsonValue) {
+        if let Some(server) = &self.rpc {
+            server.notify(method, params);
+        }
+    }
+
+    fn notify_vars(&self) {
+        self.notify("vars", JsonValue::Array(self.editor.vars.iter().map(var_json).collect()));
+    }
+
+    fn poll_rpc(&mut self) {
+        let Some(server) = self.rpc.as_ref() else { return };
+        for call in server.poll() {
+            self.handle_rpc(call);
+        }
+    }
+
+    fn handle_rpc(&mut self, call: RpcCall) {
+        self.trace_event(format!("rpc {}", call.method));
+        let result = match call.method.as_str() {
+            "list" => Ok(JsonValue::Array(self.editor.vars.iter().map(var_json).collect())),
+            "get" => call.param_str("name").and_then(|name| {
+                self.editor
+                    .get(name)
+                    .map(var_json)
+                    .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("no cache variable named {}", name)))
+            }),
+            "set" => call.param_str("name").and_then(|name| {
+                let value = call.param_str("value")?.to_string();
+                if let Err(problem) = self.editor.check_value(name, &value) {
+                    return Err(RpcError::new(INVALID_PARAMS, problem));
+                }
+                match self.editor.set_value(name, value) {
+                    true => {
+                        if let Some(var) = self.editor.get(name) {
+                            self.notify("changed", var_json(var));
+                        }
+                        Ok(JsonValue::Bool(true))
+                    }
+                    false => Err(RpcError::new(INVALID_PARAMS, format!("no cache variable named {}", name))),
+                }
+            }),
+            "save" => self
+                .write_changes(false)
+                .map(|count| JsonValue::Number(count as f64))
+                .map_err(|e| RpcError::new(SERVER_ERROR, e.to_string())),
+            "configure" => self
+                .try_configure(false)
+                .map(|()| JsonValue::Bool(true))
+                .map_err(|e| RpcError::new(SERVER_ERROR, e)),
+            "quit" => {
+                self.quit();
+                Ok(JsonValue::Bool(true))
+            }
+            other => Err(RpcError::new(METHOD_NOT_FOUND, format!("unknown method '{}'", other))),
+        };
+        call.respond(result);
+    }
+
+    /// Write every key press, with a checksum of the state it led to, to `path`
+    pub fn record_to(&mut self, path: &Path) -> std::io::Result<()> {
+        self.recorder = Some(Recorder::create(path, self.cache_checksum())?);
+        Ok(())
+    }
+
+    /// Drive the UI with the key presses recorded in `path`
+    pub fn replay_from(&mut self, path: &Path) -> std::io::Result<()> {
+        let mut replay = Replay::load(path)?;
+        if replay.cache_checksum != self.cache_checksum() {
+            replay.other_cache = true;
+            self.message = Some("Replaying against a different CMakeCache.txt than was recorded".to_string());
+        }
+        self.replay = Some(replay);
+        Ok(())
+    }
+
+    fn cache_checksum(&self) -> u64 {
+        std::fs::read(self.build_dir.join("CMakeCache.txt")).map_or(0, |content| checksum(&content))
+    }
+
+    /// Checksum of everything key presses can change, compared while replaying
+    fn state_checksum(&self) -> u64 {
+        let mut state = format!(
+            "{:?} {:?} {:?} {:?} {:?} {:?} {:?} {} {} {} {:?} {:?}\n",
+            self.mode,
+            self.screens.current(),
+            self.focus,
+            self.count,
+            self.editor.selected().map(|v| &v.var.name),
+            self.editor.filter,
+            self.editor.sort,
+            self.editor.show_advanced,
+            self.editor.show_internal,
+            self.editor.visible_count(),
+            self.search_input.text(),
+            self.command_input.text(),
+        );
+        let popups = [
+            self.dialog.is_some(),
+            self.install_prompt.is_some(),
+            self.package.visible,
+            self.about.is_some(),
+            self.new_entry.is_some(),
+            self.list_editor.is_some(),
+            self.journal.visible,
+            self.snapshots.visible,
+            self.duplicates.visible,
+            self.env.visible,
+            self.problems.visible,
+            self.diagnostics.visible,
+            self.build_type.visible,
+            self.triplets.visible,
+            self.sub_builds.visible,
+            self.usages.visible,
+            self.launcher.visible,
+            self.flag_bundles.visible,
+            self.toolchain.visible,
+            self.value_pane.visible,
+            self.toolchain_picker.is_some(),
+            self.generator_picker.is_some(),
+            self.fresh.is_some(),
+            self.disk_usage.visible,
+            self.profile.visible,
+            self.explain.visible,
+            self.quick_settings.as_ref().is_some_and(|p| p.visible),
+            self.log.is_visible(),
+            self.should_exit,
+        ];
+        state.push_str(&format!("{:?}\n", popups));
+        for var in self.editor.vars.iter().filter(|v| v.is_modified()) {
+            state.push_str(&format!("{}={}\n", var.var.name, var.new_val));
+        }
+        checksum(state.as_bytes())
+    }
+
+    /// Handle a key press, recording it when asked to
+    fn handle_input(&mut self, key: KeyEvent) {
+        self.handle_key(key);
+        // The checksum walks the pending edits, only worth it when recording
+        if key.kind != KeyEventKind::Press || self.recorder.is_none() {
+            return;
+        }
+        let state = self.state_checksum();
+        if let Some(recorder) = self.recorder.as_mut()
+            && let Err(e) = recorder.record(&key, state)
+        {
+            self.message = Some(format!("Stopped recording: {}", e));
+            self.recorder = None;
+        }
+    }
+
+    fn replay_key(&mut self, recorded: RecordedKey) {
+        self.handle_input(recorded.key);
+        let Some(replay) = self.replay.as_ref() else { return };
+        if self.state_checksum() != recorded.state {
+            self.message = Some(format!(
+                "Replay diverged from the recording at key {} of {}{}",
+                replay.position,
+                replay.total(),
+                if replay.other_cache { ", the cache differs from the recorded one" } else { "" }
+            ));
+            self.replay = None;
+        } else if replay.is_finished() {
+            self.message = Some(format!("Replay finished, {} keys", replay.total()));
+            self.replay = None;
+        }
+    }
+
+    pub fn run(&mut self, mut terminal: DefaultTerminal) -> Result<()> {
+        while !self.should_exit {
+            if std::mem::take(&mut self.crash_state_changed) {
+                crash::set_state(self.crash_state());
+            }
+            let frame_start = Instant::now();
+            terminal.draw(|frame| frame.render_widget(&mut *self, frame.area()))?;
+            self.debug.record_frame(frame_start.elapsed());
+            // Don't block on input while a task streams output
+            if event::poll(TICK)?
+                && let Event::Key(key) = event::read()?
+            {
+                if key.kind == KeyEventKind::Press && self.replay.take().is_some() {
+                    self.message = Some("Replay stopped".to_string());
+                } else {
+                    self.handle_input(key);
+                }
+            }
+            if let Some(recorded) = self.replay.as_mut().and_then(|r| r.next_due()) {
+                self.replay_key(recorded);
+            }
+            if self.open_editor {
+                self.open_editor = false;
+                self.edit_in_external_editor(&mut terminal)?;
+            }
+            if let Some((path, line)) = self.open_usage.take() {
+                self.open_file_in_external_editor(&mut terminal, &path, line)?;
+            }
+            if std::mem::take(&mut self.suspend)
+                && let Err(e) = self.release_terminal(&mut terminal, shell::suspend)?
+            {
+                self.message = Some(format!("Can't suspend: {}", e));
+            }
+            if std::mem::take(&mut self.open_shell) {
+                let build_dir = self.build_dir.clone();
+                if let Err(e) = self.release_terminal(&mut terminal, || shell::run_shell(&build_dir))? {
+                    self.message = Some(format!("Failed to run {}: {}", shell::shell_command(), e));
+                }
+            }
+            if let Some(line) = self.run_command.take() {
+                // The values as the table shows them, pending edits included
+                let vars: Vec<CacheVar> =
+                    self.editor.vars.iter().map(|v| CacheVar { value: v.new_val.clone(), ..v.var.clone() }).collect();
+                let build_dir = self.build_dir.clone();
+                match self.release_terminal(&mut terminal, || run::run_in_shell(&build_dir, &vars, &line))? {
+                    Ok(status) => self.message = Some(format!("{}: {}", line, status)),
+                    Err(e) => self.message = Some(format!("Failed to run {}: {}", line, e)),
+                }
+            }
+            self.flush_pending_key();
+            self.poll_task();
+            self.poll_reload();
+            self.poll_rpc();
+        }
+        Ok(())
+    }
+
+    /// Headless loop for --embed: requests come from the server, no terminal
+    /// is touched and state updates go out as notifications. Ends on "quit"
+    /// or once the client closes its end.
+    pub fn run_embedded(&mut self) -> Result<()> {
+        for warning in &self.editor.parse_warnings {
+            eprintln!("{}: {}", self.build_dir.join("CMakeCache.txt").display(), warning);
+        }
+        self.notify_vars();
+        // Nobody can answer the VCS ignore prompt here, so don't wait for it
+        while !self.should_exit && !self.exit_after_prompt {
+            let Some(server) = self.rpc.as_ref() else { break };
+            match server.recv_timeout(TICK) {
+                Ok(call) => self.handle_rpc(call),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            self.poll_task();
+            self.poll_reload();
+            if let Some(message) = self.message.take() {
+                self.notify("message", JsonValue::String(message));
+            }
+        }
+        Ok(())
+    }
+
+    /// Summary of the tasks run and the changes applied in this session,
+    /// printed after the TUI exits. With `command`, it ends with the cmake
+    /// command that applies the same changes to another build dir.
+    pub fn exit_summary(&self, command: bool) -> Option<String> {
+        if self.task_history.is_empty() && self.applied_changes.is_empty() {
+            return None;
+        }
+        let mut out = String::new();
+        if !self.task_history.is_empty() {
+            out.push_str(&format_summary(&self.build_dir, &self.task_history));
+        }
+        if !self.applied_changes.is_empty() {
+            out.push_str(&journal::format_applied(&self.build_dir.join("CMakeCache.txt"), &self.applied_changes));
+        }
+        if command && !self.applied_changes.is_empty() {
+            let mut args = vec![remote::quote(&cmake::program().to_string_lossy())];
+            for change in &self.applied_changes {
+                let typ = self.editor.vars.iter().find(|v| v.var.name == change.name).map(|v| v.var.typ.cache_name());
+                let define = match typ {
+                    Some(typ) => format!("-D{}:{}={}", change.name, typ, change.new),
+                    None => format!("-D{}={}", change.name, change.new),
+                };
+                args.push(remote::quote(&define));
+            }
+            args.push(remote::quote(&self.build_dir.to_string_lossy()));
+            out.push_str(&args.join(" "));
+            out.push('\n');
+        }
+        Some(out)
+    }
+
+    /// Suspend the TUI and edit the selected value in the user's editor
+    fn edit_in_external_editor(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        let Some(var) = self.editor.selected() else { return Ok(()) };
+        let name = var.var.name.clone();
+        let value = var.new_val.clone();
+
+        match self.release_terminal(terminal, || editor::edit_value(&name, &value))? {
+            // Checked, and confirmed for entries cmake manages, like a value typed into the table
+            Ok(Some(new_value)) if new_value != value => self.dispatch(Action::Set(name, new_value)),
+            Ok(Some(_)) => {}
+            Ok(None) => self.message = Some(format!("{} exited with an error, value unchanged", editor::editor_command())),
+            Err(e) => self.message = Some(format!("Failed to run {}: {}", editor::editor_command(), e)),
+        }
+        Ok(())
+    }
+
+    /// Suspend the TUI and show `path` at `line` in the user's editor
+    fn open_file_in_external_editor(&mut self, terminal: &mut DefaultTerminal, path: &Path, line: usize) -> Result<()> {
+        match self.release_terminal(terminal, || editor::open_file(path, line))? {
+            Ok(true) => {}
+            Ok(false) => self.message = Some(format!("{} exited with an error", editor::editor_command())),
+            Err(e) => self.message = Some(format!("Failed to run {}: {}", editor::editor_command(), e)),
+        }
+        Ok(())
+    }
+
+    /// Give the terminal to `run` with the TUI suspended, then take it back.
+    /// A cache rewritten meanwhile, e.g. by a configure from the shell, is
+    /// read again with the pending edits kept.
+    fn release_terminal<T>(&mut self, terminal: &mut DefaultTerminal, run: impl FnOnce() -> T) -> Result<T> {
+        let cache = self.build_dir.join("CMakeCache.txt");
+        let written = fs::metadata(&cache).and_then(|m| m.modified()).ok();
+
+        ratatui::restore();
+        let result = run();
+        enable_raw_mode()?;
+        execute!(std::io::stdout(), EnterAlternateScreen)?;
+        terminal.clear()?;
+
+        if self.missing_cache.is_none() && fs::metadata(&cache).and_then(|m| m.modified()).ok() != written {
+            self.start_reload(false, AfterReload::External);
+        }
+        self.stale_files = changed_project_files(&self.build_dir);
+        Ok(result)
+    }
+
+    /// Create the sidecar directory if needed and return it. When it was just
+    /// created inside a VCS working tree that doesn't ignore it, the user is
+    /// asked (depending on config) whether to add it to the ignore file.
+    fn ensure_sidecar(&mut self) -> Option<PathBuf> {
+        match self.sidecar.create() {
+            Ok(Some(vcs)) => match self.config.vcs_ignore {
+                VcsIgnore::Ask => self.ask_ignore_sidecar(vcs),
+                VcsIgnore::Always => self.add_sidecar_to_ignore(vcs.as_ref()),
+                VcsIgnore::Never => {}
+            },
+            Ok(None) => {}
+            Err(e) => {
+                self.message = Some(format!("Failed to create {}: {}", self.sidecar.dir.display(), e));
+                return None;
+            }
+        }
+        Some(self.sidecar.dir.clone())
+    }
+
+    fn add_sidecar_to_ignore(&mut self, vcs: &dyn Vcs) {
+        let ignore_file = vcs.ignore_file();
+        self.message = Some(match vcs.add_ignore(&self.sidecar.dir) {
+            Ok(()) => format!("Added {} to {}", self.sidecar.dir.display(), ignore_file.display()),
+            Err(e) => format!("Failed to update {}: {}", ignore_file.display(), e),
+        });
+    }
+
+    fn ask_ignore_sidecar(&mut self, vcs: Box<dyn Vcs>) {
+        let ignore_file = vcs.ignore_file();
+        let ignore_name = ignore_file.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let message = format!(
+            "cmake-tui keeps its files in {}, which is inside the {} working tree at {} and not ignored.",
+            self.sidecar.dir.display(),
+            vcs.name(),
+            vcs.root().display()
+        );
+        self.dialog = Some(
+            Dialog::new("Tool state inside a working tree", message)
+                .choice('y', &format!("add it to {}", ignore_name), Confirmed::IgnoreSidecar(vcs))
+                .choice('n', "leave it as is", Confirmed::Nothing)
+                .choice('o', "move it outside the tree (state directory)", Confirmed::MoveSidecar),
+        );
+    }
+
+    fn handle_dialog_key(&mut self, key: KeyEvent) {
+        let Some(dialog) = self.dialog.take() else { return };
+        match dialog.handle_key(key) {
+            DialogResult::Open(dialog) => self.dialog = Some(dialog),
+            DialogResult::Cancelled => {}
+            DialogResult::Picked(confirmed) => self.confirmed(confirmed),
+        }
+        if self.dialog.is_none() && self.exit_after_prompt {
+            self.should_exit = true;
+        }
+    }
+
+    /// Carry out what the user picked in a dialog
+    fn confirmed(&mut self, confirmed: Confirmed) {
+        match confirmed {
+            Confirmed::Nothing => {}
+            Confirmed::IgnoreSidecar(vcs) => self.add_sidecar_to_ignore(vcs.as_ref()),
+            Confirmed::MoveSidecar => {
+                self.message = Some(match self.sidecar.move_out_of_tree(&self.build_dir) {
+                    Ok(()) => format!("Tool state moved to {}", self.sidecar.dir.display()),
+                    Err(e) => format!("Failed to move tool state: {}", e),
+                });
+            }
+            Confirmed::SwitchToolchain(toolchain) => {
+                if let Err(e) = self.switch_toolchain(toolchain) {
+                    self.dialog = Some(Dialog::error("Failed to switch the toolchain", e));
+                }
+            }
+            Confirmed::FreshConfigure(defines) => {
+                let generator = GeneratorChoice::from_cache(&self.editor);
+                if let Err(e) = self.configure_from_scratch("configuring from a fresh cache", generator, defines) {
+                    self.dialog = Some(Dialog::error("Failed to start the fresh configure", e));
+                }
+            }
+            Confirmed::RemoveArtifacts(path) => {
+                self.message = Some(match fs::remove_dir_all(&path) {
+                    Ok(()) => format!("Removed {}", path.display()),
+                    Err(e) => format!("Failed to remove {}: {}", path.display(), e),
+                });
+                self.disk_usage.open(&self.build_dir);
+            }
+            Confirmed::RunCleanTarget => {
+                self.disk_usage.visible = false;
+                let task = Task::build_target(&self.build_dir, "clean", self.build_config().as_deref());
+                self.track_task(TaskKind::Build, task);
+            }
+            Confirmed::SwitchGenerator(generator) => {
+                let defines = self.editor.configure_defines();
+                if let Err(e) = self.configure_from_scratch("switching the generator", generator, defines) {
+                    self.dialog = Some(Dialog::error("Failed to switch the generator", e));
+                }
+            }
+            Confirmed::LinkCompileCommands(source_dir) => {
+                self.message = Some(format!(
+                    "{} is linked after the next configure, c to configure",
+                    source_dir.join(COMPILE_COMMANDS).display()
+                ));
+                self.link_compile_commands = Some(source_dir);
+            }
+            Confirmed::Edit(name, action) => {
+                self.confirmed_edits.insert(name);
+                self.dispatch(action);
+            }
+            Confirmed::DeleteEntry(name) => self.delete_entry(&name),
+            Confirmed::DiscardEdits => {
+                let count = self.modified_count();
+                self.editor.discard_edits();
+                self.message = Some(format!("Discarded {} pending edits", count));
+            }
+            Confirmed::ReplacePreset(name) => self.write_preset(&name),
+            Confirmed::DeleteSnapshot(name) => {
+                let snapshots = Snapshot::list(&self.sidecar.dir);
+                if let Some(snapshot) = snapshots.iter().find(|s| s.name == name)
+                    && let Err(e) = snapshot.delete()
+                {
+                    self.dialog = Some(Dialog::error("Failed to delete the snapshot", e.to_string()));
+                }
+                self.snapshots.set_snapshots(Snapshot::list(&self.sidecar.dir));
+            }
+        }
+    }
+
+    fn ask_delete_entry(&mut self) {
+        let Some(var) = self.editor.selected() else { return };
+        let name = var.var.name.clone();
+        let message = match var.is_new {
+            true => format!("{} was added here and isn't in CMakeCache.txt yet, it's dropped.", name),
+            false => format!(
+                "Removes {} from CMakeCache.txt with its help string and properties. \
+                 If the project declares it, the next configure adds it back with its default.",
+                name
+            ),
+        };
+        self.dialog = Some(
+            Dialog::new("Delete the entry?", message)
+                .choice('y', "delete it", Confirmed::DeleteEntry(name))
+                .choice('n', "keep it", Confirmed::Nothing),
+        );
+    }
+
+    /// Where the pins are kept, shared by the build dirs of a project
+    fn project_dir(&self) -> PathBuf {
+        local_source_dir(&self.build_dir).unwrap_or_else(|| self.build_dir.clone())
+    }
+
+    /// Table layout from the user config, for a newly loaded cache
+    fn apply_view_config(&mut self) {
+        self.editor.columns = self.config.columns.clone();
+        self.editor.long_values = self.config.long_values;
+        self.editor.name_width = self.config.name_width;
+        self.editor.row_numbers = self.config.row_numbers;
+        self.editor.type_colors = self.config.type_colors.clone();
+        self.editor.monochrome = self.config.monochrome;
+    }
+
+    /// Apply the project's .cmake-tui.toml over the user config: its pins
+    /// come before the user's, its ignore patterns add to the user's. Returns
+    /// the problem with the file, if any.
+    fn load_project_config(&mut self) -> Option<String> {
+        let project = ProjectConfig::load(local_source_dir(&self.build_dir).as_deref(), &self.build_dir);
+        let mut pinned = project.pinned.clone();
+        pinned.extend(pins::load(&self.project_dir()).into_iter().filter(|name| !project.pinned.contains(name)));
+        self.editor.pinned = pinned;
+        self.editor.set_ignore_patterns(self.config.ignore.iter().chain(&project.ignore).cloned().collect());
+        self.editor.set_project_hints(project.enums, project.descriptions);
+        self.project_pins = project.pinned;
+        project.error
+    }
+
+    fn toggle_pin(&mut self) {
+        let Some(name) = self.editor.selected().map(|v| v.var.name.clone()) else { return };
+        if self.project_pins.contains(&name) {
+            self.message = Some(format!("{} is pinned by the project's {}", name, PROJECT_CONFIG_FILE));
+            return;
+        }
+        let pinned = self.editor.toggle_pin(&name);
+        let user_pins: Vec<String> =
+            self.editor.pinned.iter().filter(|name| !self.project_pins.contains(name)).cloned().collect();
+        self.message = Some(match pins::save(&self.project_dir(), &user_pins) {
+            Ok(()) if pinned => format!("Pinned {}", name),
+            Ok(()) => format!("Unpinned {}", name),
+            Err(e) => format!("Failed to save the pins: {}", e),
+        });
+    }
+
+    /// Remove an entry from the cache file, or from the pending edits when
+    /// it was only added here. The other pending edits are kept.
+    fn delete_entry(&mut self, name: &str) {
+        if self.editor.remove_new_var(name) {
+            self.message = Some(format!("Dropped the new entry {}", name));
+            return;
+        }
+        if let Some(reason) = self.busy_reason() {
+            self.message = Some(format!("Not deleting, {}", reason));
+            return;
+        }
+        match remove_cache_entries(&self.build_dir, &[name.to_string()]) {
+            Ok(()) => {
+                self.editor.reload_keeping_edits(&self.build_dir);
+                self.notify_vars();
+                self.message = Some(match upload_cache() {
+                    Ok(()) => format!("Deleted {} from CMakeCache.txt", name),
+                    Err(e) => format!("Deleted {} from the local copy only: {}", name, e),
+                });
+            }
+            Err(e) => {
+                self.dialog = Some(
+                    Dialog::error("Failed to delete the entry", format!("{} is still in CMakeCache.txt.", name))
+                        .details(e.to_string()),
+                );
+            }
+        }
+    }
+
+    fn export_preset(&mut self, name: &str) {
+        if self.modified_count() == 0 {
+            self.message = Some("No pending edits to put into a preset".to_string());
+            return;
+        }
+        let Some(source_dir) = local_source_dir(&self.build_dir) else {
+            self.message = Some("The cache doesn't say where the source directory is".to_string());
+            return;
+        };
+        if has_configure_preset(&source_dir, name) {
+            self.dialog = Some(
+                Dialog::new(
+                    "Replace the preset?",
+                    format!("CMakeUserPresets.json already has a configure preset named '{}'.", name),
+                )
+                .choice('y', "replace it", Confirmed::ReplacePreset(name.to_string()))
+                .choice('n', "keep it", Confirmed::Nothing),
+            );
+        } else {
+            self.write_preset(name);
+        }
+    }
+
+    fn write_preset(&mut self, name: &str) {
+        let Some(source_dir) = local_source_dir(&self.build_dir) else { return };
+        let defines = self.editor.configure_defines();
+        let generator = self.editor.get("CMAKE_GENERATOR").map(|v| v.var.value.clone());
+        match export_configure_preset(&source_dir, &self.build_dir, name, generator.as_deref(), &defines) {
+            Ok(path) => {
+                self.message = Some(format!(
+                    "Wrote the preset '{}' with {} cache variables to {}",
+                    name,
+                    defines.len(),
+                    path.display()
+                ));
+            }
+            Err(e) => self.dialog = Some(Dialog::error("Failed to export the preset", e)),
+        }
+    }
+
+    fn ask_discard_edits(&mut self) {
+        let count = self.modified_count();
+        if count == 0 {
+            self.message = Some("No pending edits".to_string());
+            return;
+        }
+        self.dialog = Some(
+            Dialog::new(
+                "Discard the pending edits?",
+                format!(
+                    "{} pending edits go back to the values in CMakeCache.txt, entries added here are dropped.",
+                    count
+                ),
+            )
+            .choice('y', "discard them", Confirmed::DiscardEdits)
+            .choice('n', "keep them", Confirmed::Nothing),
+        );
+    }
+
+    fn task_running(&self) -> bool {
+        self.task.as_ref().is_some_and(|t| t.is_running())
+    }
+
+    /// Why writing the cache right now is unsafe, if it is. Both our own tasks
+    /// and cmake processes started elsewhere (another terminal, an IDE) count.
+    fn busy_reason(&self) -> Option<String> {
+        if self.reload.is_some() {
+            return Some("CMakeCache.txt is still being read".to_string());
+        }
+        if let Some(task) = self.task.as_ref().filter(|t| t.is_running()) {
+            return Some(format!("{} is still running", task.kind));
+        }
+        find_external_cmake(&self.build_dir, self.task.as_ref().map(|t| t.pid()))
+            .or_else(|| find_configure_in_progress(&self.build_dir))
+    }
+
+    /// Start a configure run unless something else is using the build dir,
+    /// with `profile` one that records where cmake spends its time
+    fn try_configure(&mut self, profile: bool) -> Result<(), String> {
+        if self.task_running() {
+            return Err("A task is already running".to_string());
+        }
+        if let Some(reason) = self.busy_reason() {
+            return Err(format!("Not configuring, {}. Use :configure! to run anyway", reason));
+        }
+        if self.config.auto_save.before_configure() && self.modified_count() > 0 {
+            self.save(false);
+            // The message or dialog of the save says why
+            if self.modified_count() > 0 {
+                return Ok(());
+            }
+        }
+        if profile {
+            let output = self.ensure_sidecar().ok_or("No place to write the profile to")?.join("profile.json");
+            let task = Task::profiled_configure(&self.build_dir, &self.editor.configure_defines(), &output);
+            self.profile_output = Some(output);
+            self.track_task(TaskKind::Configure, task);
+        } else {
+            self.start_task(TaskKind::Configure);
+        }
+        Ok(())
+    }
+
+    fn handle_missing_cache_key(&mut self, key: KeyEvent) {
+        let configuring = self.task_running();
+        let Some(screen) = self.missing_cache.as_mut() else { return };
+        if let Some((prompt, input)) = screen.prompt.as_mut() {
+            match key.code {
+                KeyCode::Esc => screen.prompt = None,
+                KeyCode::Enter => {
+                    let (prompt, path) = (*prompt, PathBuf::from(input.text()));
+                    screen.prompt = None;
+                    let result = match prompt {
+                        MissingCachePrompt::OpenPath => self.open_build_dir(path),
+                        MissingCachePrompt::SourceDir => self.pick_initial_generator(path),
+                    };
+                    if let (Err(e), Some(screen)) = (result, self.missing_cache.as_mut()) {
+                        screen.problem = Some(e);
+                    }
+                }
+                _ => {
+                    input.handle_key(key);
+                }
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('o') => screen.prompt = Some((MissingCachePrompt::OpenPath, TextInput::default())),
+            KeyCode::Char('c') if !configuring => {
+                let source = guess_source_dir(&self.build_dir);
+                let input = TextInput::new(&source.to_string_lossy());
+                screen.prompt = Some((MissingCachePrompt::SourceDir, input));
+            }
+            KeyCode::Char('q') => self.quit(),
+            KeyCode::Esc => self.missing_cache = None,
+            KeyCode::Char('L') => self.log.toggle(),
+            KeyCode::Tab if self.log.is_visible() => self.focus = Focus::Log,
+            _ => {}
+        }
+    }
+
+    /// Switch to another build dir, given like on the command line
+    fn open_build_dir(&mut self, path: PathBuf) -> Result<(), String> {
+        let build_dir = if path.file_name().is_some_and(|name| name == "CMakeCache.txt") {
+            path.parent().unwrap_or(Path::new(".")).to_path_buf()
+        } else if path.is_dir() && !path.join("CMakeCache.txt").is_file() {
+            // A source dir, take its most recently configured build dir
+            find_build_dirs(&path).into_iter().next().map_or(path, |d| d.path)
+        } else {
+            path
+        };
+        let editor = CacheEditorState::try_load(&build_dir).map_err(|e| e.to_string())?;
+        self.sidecar = Sidecar::resolve(&build_dir, &self.config);
+        self.build_dir = build_dir;
+        self.editor = editor;
+        self.missing_cache = None;
+        self.apply_view_config();
+        let project_config_error = self.load_project_config();
+        self.restore_session();
+        self.refresh_last_changes();
+        self.stale_files = changed_project_files(&self.build_dir);
+        self.load_tab();
+        self.message = Some(project_config_error.unwrap_or_else(|| format!("Opened {}", self.build_dir.display())));
+        self.report_parse_warnings();
+        Ok(())
+    }
+
+    /// Offer the generators cmake supports before the first configure of
+    /// `source_dir`. Without a list from cmake it picks the generator itself.
+    fn pick_initial_generator(&mut self, source_dir: PathBuf) -> Result<(), String> {
+        if !source_dir.join("CMakeLists.txt").is_file() {
+            return Err(format!("No CMakeLists.txt in {}", source_dir.display()));
+        }
+        match Capabilities::load() {
+            Ok(capabilities) => {
+                self.generator_picker = Some((GeneratorPicker::new(capabilities, None), Some(source_dir)));
+                Ok(())
+            }
+            Err(e) => {
+                self.log.push_info(&format!("No generator list, leaving the choice to cmake: {}", e));
+                self.start_initial_configure(source_dir, None)
+            }
+        }
+    }
+
+    /// First configure of a build dir that has no cache yet. Started in a
+    /// source dir, the build dir becomes `build/` below it.
+    fn start_initial_configure(&mut self, source_dir: PathBuf, generator: Option<GeneratorChoice>) -> Result<(), String> {
+        if same_dir(&source_dir, &self.build_dir) {
+            self.build_dir = self.build_dir.join("build");
+            self.sidecar = Sidecar::resolve(&self.build_dir, &self.config);
+        }
+        let task = Task::initial_configure(&source_dir, &self.build_dir, generator.as_ref(), &[]);
+        self.track_task(TaskKind::Configure, task);
+        Ok(())
+    }
+
+    /// Pending edits are applied by passing them to the configure run,
+    /// new entries with their type so cmake creates them as such
+    fn start_task(&mut self, kind: TaskKind) {
+        if self.task_running() {
+            self.message = Some("A task is already running".to_string());
+            return;
+        }
+
+        let task = match kind {
+            TaskKind::Configure => Task::configure(&self.build_dir, &self.editor.configure_defines()),
+            TaskKind::Build => Task::build(&self.build_dir, self.build_config().as_deref()),
+            // Need more input first, started by their own actions
+            TaskKind::Explain | TaskKind::Install | TaskKind::Package | TaskKind::Test => return,
+        };
+        self.track_task(kind, task);
+    }
+
+    /// Show the output of a started task in the log
+    fn track_task(&mut self, kind: TaskKind, task: std::io::Result<Task>) {
+        self.crash_state_changed = true;
+        self.log.clear();
+        if self.log.view == LogView::Hidden {
+            self.log.view = LogView::Pane;
+        }
+        match task {
+            Ok(task) => {
+                self.log.title = format!("- {} (running)", kind);
+                self.log.push_info(&format!("Running {} in {}", kind, self.build_dir.display()));
+                if kind == TaskKind::Configure {
+                    for (name, value) in &self.env.overrides {
+                        match value {
+                            Some(value) => self.log.push_info(&format!("With {}={}", name, value)),
+                            None => self.log.push_info(&format!("With {} unset", name)),
+                        }
+                    }
+                    for warning in self.env.compiler_mismatches(&self.editor) {
+                        self.log.push_info(&format!("Warning: {}", warning));
+                    }
+                }
+                self.task = Some(task);
+                self.task_started = Instant::now();
+                self.task_edits = if kind == TaskKind::Configure { journal::pending_changes(&self.editor) } else { Vec::new() };
+            }
+            Err(e) => {
+                self.log.title = format!("- {} (failed to start)", kind);
+                self.log.push_info(&format!("Failed to run cmake: {}", e));
+            }
+        }
+    }
+
+    fn poll_task(&mut self) {
+        let Some(task) = self.task.as_mut() else { return };
+        let kind = task.kind;
+
+        for event in task.poll() {
+            match event {
+                TaskEvent::Line(stream, line) => {
+                    let name = if stream == Stream::Stderr { "stderr" } else { "stdout" };
+                    self.notify(
+                        "log",
+                        JsonValue::Object(vec![
+                            ("stream".to_string(), JsonValue::String(name.to_string())),
+                            ("line".to_string(), JsonValue::String(line.clone())),
+                        ]),
+                    );
+                    self.log.push(stream, &line);
+                }
+                TaskEvent::Finished(status) => {
+                    self.task_history.push(TaskRecord {
+                        kind,
+                        duration: self.task_started.elapsed(),
+                        exit_code: status.and_then(|s| s.code()),
+                        changes: self.task_edits.len(),
+                    });
+
+                    let elapsed = self.task_started.elapsed().as_secs_f32();
+                    let code = status
+                        .and_then(|s| s.code())
+                        .map_or("killed".to_string(), |c| c.to_string());
+                    self.log.title = format!("- {} (exit {}, {:.1}s)", kind, code, elapsed);
+                    self.log.push_info(&format!("{} finished with exit code {} in {:.1}s", kind, code, elapsed));
+                    self.trace_event(format!("{} finished with exit code {} in {:.1}s", kind, code, elapsed));
+                    self.notify(
+                        "finished",
+                        JsonValue::Object(vec![
+                            ("task".to_string(), JsonValue::String(kind.to_string())),
+                            (
+                                "exit_code".to_string(),
+                                status.and_then(|s| s.code()).map_or(JsonValue::Null, |c| JsonValue::Number(c as f64)),
+                            ),
+                        ]),
+                    );
+
+                    if let Some(run) = self.explain_run.take() {
+                        match parse_trace_hits(&run.trace, &run.name) {
+                            Ok(mut hits) => {
+                                hits.retain(|hit| hit.file != run.initial_cache);
+                                self.explain.set_hits(&run.name, &run.source_dir, hits);
+                                self.explain.visible = true;
+                            }
+                            Err(e) => self.log.push_info(&format!("Failed to read the trace: {}", e)),
+                        }
+                    }
+                    if let Some(output) = self.profile_output.take() {
+                        match parse_trace(&output) {
+                            Ok(profile) => {
+                                self.profile.set_profile(profile);
+                                self.profile.visible = true;
+                            }
+                            Err(e) => self.log.push_info(&format!("Failed to read the profile: {}", e)),
+                        }
+                    }
+                    if kind == TaskKind::Configure {
+                        self.problems.set_problems(parse_problems(self.log.plain_lines()));
+                        if !self.problems.problems.is_empty() {
+                            self.log.push_info(&format!(
+                                "{} errors, {} warnings, press P to list them",
+                                self.problems.count(Severity::Error),
+                                self.problems.count(Severity::Warning)
+                            ));
+                        }
+                        if status.is_some_and(|s| s.success()) {
+                            let edits = std::mem::take(&mut self.task_edits);
+                            let before: HashMap<String, String> =
+                                self.editor.vars.iter().map(|v| (v.var.name.clone(), v.var.value.clone())).collect();
+                            // The configure wrote the remote cache, not the copy
+                            self.start_reload(remote::current().is_some(), AfterReload::Configure { before, edits });
+                            self.stale_files = changed_project_files(&self.build_dir);
+                            if let Some(source_dir) = self.link_compile_commands.take() {
+                                match link_into_source(&self.build_dir, &source_dir) {
+                                    Ok(link) => self.log.push_info(&format!("Linked {}", link.display())),
+                                    Err(e) => self.log.push_info(&format!("Failed to link {}: {}", COMPILE_COMMANDS, e)),
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Read the cache again on a worker thread, fetching it first from a
+    /// remote build dir when `fetch`. The table stays as it is until
+    /// [`poll_reload`](Self::poll_reload) swaps the new one in.
+    fn start_reload(&mut self, fetch: bool, then: AfterReload) {
+        let (sender, receiver) = mpsc::channel();
+        let build_dir = self.build_dir.clone();
+        thread::spawn(move || {
+            let fetched = match remote::current() {
+                Some(remote) if fetch => remote.fetch(),
+                _ => Ok(()),
+            };
+            let _ = sender.send((fetched, CacheEditorState::try_load(&build_dir).map_err(|e| e.to_string())));
+        });
+        self.reload = Some(PendingReload { receiver, then });
+    }
+
+    /// Give the entries a configure applied edits to the MODIFIED property,
+    /// which cmake only writes itself for edits made in ccmake or cmake-gui
+    fn mark_user_modified<'a>(&mut self, names: impl Iterator<Item = &'a str>) {
+        if let Some(reason) = self.busy_reason() {
+            self.log.push_info(&format!("Not marking the edited entries as set by hand, {}", reason));
+            return;
+        }
+        let names: Vec<&str> = names.filter(|name| self.editor.get(name).is_some_and(|v| !v.var.modified)).collect();
+        if names.is_empty() {
+            return;
+        }
+        let changes: HashMap<_, _> = names.iter().map(|name| (format!("{}-MODIFIED", name), "ON".to_string())).collect();
+        if let Err(e) = write_cache_changes(&self.build_dir, &changes, &[]) {
+            self.log.push_info(&format!("Failed to mark the edited entries as set by hand: {}", e));
+            return;
+        }
+        // Only marked in the table once the cache says so too
+        for var in self.editor.vars.iter_mut().filter(|v| names.contains(&v.var.name.as_str())) {
+            var.var.modified = true;
+        }
+        if let Err(e) = upload_cache() {
+            self.log.push_info(&e);
+        }
+    }
+
+    fn poll_reload(&mut self) {
+        let Some(pending) = self.reload.as_ref() else { return };
+        let (fetched, fresh) = match pending.receiver.try_recv() {
+            Ok(loaded) => loaded,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.reload = None;
+                self.message = Some("Failed to read CMakeCache.txt again".to_string());
+                return;
+            }
+        };
+        let Some(PendingReload { then, .. }) = self.reload.take() else { return };
+        self.crash_state_changed = true;
+        if let Err(e) = fetched {
+            self.log.push_info(&e);
+        }
+        // An empty table would look like the cache lost its entries
+        let fresh = match fresh {
+            Ok(fresh) => fresh,
+            Err(e) => {
+                self.dialog = Some(
+                    Dialog::error(
+                        "Failed to read CMakeCache.txt again",
+                        "The table still shows the cache as it was read before.",
+                    )
+                    .details(e),
+                );
+                return;
+            }
+        };
+        match then {
+            AfterReload::Configure { before, edits } => {
+                // cmake takes no properties on the command line, those edits
+                // stay pending until saved
+                let property_edits = self.editor.property_edits();
+                self.editor.swap_in(fresh);
+                self.editor.restore_property_edits(property_edits);
+                self.mark_user_modified(edits.iter().map(|edit| edit.name.as_str()));
+                self.load_tab();
+                let cmake_changes = self.cmake_changes(&before, &edits);
+                self.record_journal("configure", edits);
+                self.record_journal("cmake", cmake_changes);
+                self.notify_vars();
+                // The first configure of the build dir wrote its cache
+                if self.missing_cache.is_some() && !self.editor.vars.is_empty() {
+                    self.missing_cache = None;
+                    self.restore_session();
+                }
+            }
+            AfterReload::External => {
+                self.editor.swap_in_keeping_edits(fresh);
+                self.load_tab();
+                self.notify_vars();
+                self.message = Some("CMakeCache.txt changed meanwhile, read it again".to_string());
+            }
+        }
+    }
+
+    fn handle_problems_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('P') | KeyCode::Char('q') => self.problems.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.problems.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.problems.select_previous(),
+            KeyCode::Enter => self.jump_to_problem_var(),
+            _ => {}
+        }
+    }
+
+    fn handle_quick_settings_key(&mut self, key: KeyEvent) {
+        let Some(pane) = self.quick_settings.as_mut() else { return };
+        let selected = pane.selected();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => pane.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => pane.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => pane.select_previous(),
+            KeyCode::Char(' ') | KeyCode::Enter => match selected {
+                Some(QuickRow::Setting(g, s)) => {
+                    let name = pane.setting(g, s).variable.clone();
+                    let cycled = self.editor.get(&name).and_then(|var| match var.var.typ {
+                        VarType::Bool => Some(CacheVar::toggle_bool(&var.new_val)),
+                        VarType::Enum => Some(var.var.cycle_enum(&var.new_val)),
+                        _ => None,
+                    });
+                    match cycled {
+                        Some(value) => {
+                            self.set_value(&name, value);
+                        }
+                        // Free form values are edited in the table
+                        None if key.code == KeyCode::Enter => {
+                            pane.visible = false;
+                            if !self.editor.select_by_name(&name) {
+                                self.message = Some(format!("{} is not shown in the table", name));
+                            }
+                        }
+                        None => {}
+                    }
+                }
+                Some(QuickRow::Recommendation(r)) if key.code == KeyCode::Enter => {
+                    let recommendation = &pane.settings.recommended[r];
+                    let name = recommendation.name.clone();
+                    let values = recommendation.values.clone();
+                    let missing: Vec<String> = values
+                        .into_iter()
+                        .filter(|(variable, value)| !self.editor.set_value(variable, value.clone()))
+                        .map(|(variable, _)| variable)
+                        .collect();
+                    self.message = Some(match missing.is_empty() {
+                        true => format!("Applied {}, w to save", name),
+                        false => format!("Applied {}, not in the cache: {}", name, missing.join(", ")),
+                    });
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn handle_diagnostics_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.diagnostics.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.diagnostics.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.diagnostics.select_previous(),
+            _ => {}
+        }
+    }
+
+    fn handle_build_type_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.build_type.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.build_type.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.build_type.select_previous(),
+            KeyCode::Enter | KeyCode::Char('c') if self.build_type.multi_config => {
+                let Some(config) = self.build_type.selected().map(str::to_string) else { return };
+                self.build_type.visible = false;
+                self.message = Some(format!("Building the {} configuration", config));
+                self.build_config = Some(config);
+                self.load_tab();
+                if key.code == KeyCode::Char('c') {
+                    self.start_task(TaskKind::Build);
+                }
+            }
+            KeyCode::Enter | KeyCode::Char('c') => {
+                let Some(build_type) = self.build_type.selected().map(str::to_string) else { return };
+                self.build_type.visible = false;
+                self.set_build_type(build_type);
+                if key.code == KeyCode::Char('c')
+                    && let Err(e) = self.try_configure(false)
+                {
+                    self.message = Some(e);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The configuration picked for builds, when the generator has several
+    fn build_config(&self) -> Option<String> {
+        self.build_config.clone().filter(|_| is_multi_config(&self.editor))
+    }
+
+    /// Set CMAKE_BUILD_TYPE, adding it when the cache has none yet (as with
+    /// multi-config generators, until the first `-DCMAKE_BUILD_TYPE`)
+    fn set_build_type(&mut self, build_type: String) {
+        if self.editor.get("CMAKE_BUILD_TYPE").is_some() {
+            self.set_value("CMAKE_BUILD_TYPE", build_type);
+        } else {
+            let desc = "Choose the type of build.".to_string();
+            self.editor.add_var(CacheVar::new("CMAKE_BUILD_TYPE".to_string(), VarType::Str, desc, build_type));
+        }
+    }
+
+    /// The screen keys go to, the log pane while it has the focus
+    fn active_screen(&self) -> Screen {
+        let current = self.screens.current();
+        match current.is_tab() && self.focus == Focus::Log && self.log.is_visible() {
+            true => Screen::Log,
+            false => current,
+        }
+    }
+
+    fn show_screen(&mut self, screen: Screen) {
+        // Opening a screen that's open already closes it, so its key toggles it
+        if !screen.is_tab() && self.screens.current() == screen {
+            self.screens.close();
+            return;
+        }
+        if screen == Screen::Help {
+            self.help.open(self.active_screen());
+        }
+        self.screens.open(screen);
+        self.load_tab();
+    }
+
+    /// Read what the current tab lists anew, after a configure or a switch
+    fn load_tab(&mut self) {
+        match self.screens.tab() {
+            Screen::Targets => {
+                let build_type = match self.build_config() {
+                    Some(config) => config,
+                    None => self.editor.get("CMAKE_BUILD_TYPE").map_or(String::new(), |v| v.var.value.clone()),
+                };
+                self.targets.load(&self.build_dir, &build_type);
+            }
+            Screen::Tests => self.tests.load(&self.build_dir),
+            _ => {}
+        }
+    }
+
+    fn handle_screen_key(&mut self, key: KeyEvent) {
+        let screen = self.active_screen();
+        // Typing into the filter of a list
+        if screen == Screen::Targets && self.targets.filtering() {
+            self.targets.handle_filter_key(key);
+            return;
+        }
+        if screen == Screen::Tests && self.tests.filtering() {
+            self.tests.handle_filter_key(key);
+            return;
+        }
+
+        // The second key of a chord, or else the first does what it does alone
+        if let Some((first, _)) = self.pending_key.take() {
+            if key.code == KeyCode::Esc {
+                self.count = None;
+                return;
+            }
+            if let Some(chord) = screen.chord(first, key.code) {
+                let count = self.count.take();
+                self.dispatch(chord.action.clone().with_count(count));
+                return;
+            }
+            if let Some(prefix) = screen.prefix(first) {
+                self.count = None;
+                if let KeyCode::Char(c) = key.code {
+                    self.dispatch((prefix.action)(c));
+                }
+                return;
+            }
+            self.run_binding(screen, first);
+        }
+
+        // A leading 0 is not a count, only digits following another one are
+        if let KeyCode::Char(c @ '0'..='9') = key.code
+            && (c != '0' || self.count.is_some())
+        {
+            self.push_count_digit(c);
+            return;
+        }
+
+        // Held modifiers make the arrows scroll the value sideways
+        if screen == Screen::Cache && key.modifiers.intersects(KeyModifiers::SHIFT | KeyModifiers::ALT) {
+            match key.code {
+                KeyCode::Left => return self.editor.scroll_value(-VALUE_SCROLL_STEP),
+                KeyCode::Right => return self.editor.scroll_value(VALUE_SCROLL_STEP),
+                _ => {}
+            }
+        }
+        if screen == Screen::Cache {
+            match (key.code, key.modifiers.contains(KeyModifiers::CONTROL)) {
+                (KeyCode::Char('o'), true) => return self.dispatch(Action::JumpBack),
+                (KeyCode::Char('i'), true) => return self.dispatch(Action::JumpForward),
+                // What most terminals send for Ctrl-i, when it's not needed for the log
+                (KeyCode::Tab, _) if !self.log.is_visible() => return self.dispatch(Action::JumpForward),
+                _ => {}
+            }
+        }
+
+        if screen.starts_chord(key.code) {
+            self.pending_key = Some((key.code, Instant::now()));
+            return;
+        }
+        self.run_binding(screen, key.code);
+    }
+
+    fn run_binding(&mut self, screen: Screen, key: KeyCode) {
+        let count = self.count.take();
+        if let Some(binding) = screen.binding(key) {
+            self.dispatch(binding.action.clone().with_count(count));
+        }
+    }
+
+    /// The first key of a chord nothing followed, on its own
+    fn flush_pending_key(&mut self) {
+        // A prefix waits for its letter however long it takes
+        if let Some((key, since)) = self.pending_key
+            && since.elapsed() >= CHORD_TIMEOUT
+            && self.active_screen().prefix(key).is_none()
+        {
+            self.pending_key = None;
+            self.run_binding(self.active_screen(), key);
+        }
+    }
+
+    /// Move the selection of the active screen, or scroll it
+    fn navigate(&mut self, action: Action) {
+        match (self.active_screen(), action) {
+            (Screen::Cache, Action::SelectNext(count)) => self.editor.select_next(count),
+            (Screen::Cache, Action::SelectPrevious(count)) => self.editor.select_previous(count),
+            (Screen::Cache, Action::SelectFirst) => self.jump(|app| app.editor.select_first()),
+            (Screen::Cache, Action::SelectLast) => self.jump(|app| app.editor.select_last()),
+            (Screen::Cache, Action::SelectRow(row)) => self.jump(|app| app.editor.select_row(row)),
+            (Screen::Targets, Action::SelectNext(count)) => self.targets.select_next(count),
+            (Screen::Targets, Action::SelectPrevious(count)) => self.targets.select_previous(count),
+            (Screen::Targets, Action::SelectFirst) => self.targets.select_first(),
+            (Screen::Targets, Action::SelectLast) => self.targets.select_last(),
+            (Screen::Tests, Action::SelectNext(count)) => self.tests.select_next(count),
+            (Screen::Tests, Action::SelectPrevious(count)) => self.tests.select_previous(count),
+            (Screen::Tests, Action::SelectFirst) => self.tests.select_first(),
+            (Screen::Tests, Action::SelectLast) => self.tests.select_last(),
+            (Screen::Log, Action::SelectNext(count)) => self.log.scroll_down(count),
+            (Screen::Log, Action::SelectPrevious(count)) => self.log.scroll_up(count),
+            (Screen::Log, Action::SelectFirst) => self.log.scroll_top(),
+            (Screen::Log, Action::SelectLast) => self.log.scroll_bottom(),
+            (Screen::Diff, Action::SelectNext(count)) => self.diff.select_next(count),
+            (Screen::Diff, Action::SelectPrevious(count)) => self.diff.select_previous(count),
+            (Screen::Diff, Action::SelectFirst) => self.diff.select_first(),
+            (Screen::Diff, Action::SelectLast) => self.diff.select_last(),
+            (Screen::Help, Action::SelectNext(count)) => self.help.scroll(count as i16),
+            (Screen::Help, Action::SelectPrevious(count)) => self.help.scroll(-(count as i16)),
+            (Screen::Help, Action::SelectFirst) => self.help.scroll_top(),
+            _ => {}
+        }
+    }
+
+    fn build_selected_target(&mut self) {
+        let Some(target) = self.targets.selected().map(|t| t.name.clone()) else { return };
+        if self.task_running() {
+            self.message = Some("A task is already running".to_string());
+            return;
+        }
+        let task = Task::build_target(&self.build_dir, &target, self.build_config().as_deref());
+        self.track_task(TaskKind::Build, task);
+    }
+
+    /// Run the tests with ctest, only the selected one with `selected`
+    fn run_tests(&mut self, selected: bool) {
+        let name = match selected {
+            true => match self.tests.selected() {
+                Some(test) => Some(test.name.clone()),
+                None => return,
+            },
+            false => None,
+        };
+        if self.task_running() {
+            self.message = Some("A task is already running".to_string());
+            return;
+        }
+        let task = Task::test(&self.build_dir, name.as_deref(), self.build_config().as_deref());
+        self.track_task(TaskKind::Test, task);
+    }
+
+    fn show_triplets(&mut self) {
+        if !self.triplets.open(&self.editor) {
+            self.message = Some("No vcpkg checkout with triplets found, set VCPKG_ROOT to point at it".to_string());
+        }
+    }
+
+    fn handle_triplets_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.triplets.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.triplets.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.triplets.select_previous(),
+            KeyCode::Enter | KeyCode::Char('c') => {
+                let Some(triplet) = self.triplets.selected().map(str::to_string) else { return };
+                self.triplets.visible = false;
+                if self.editor.get(TRIPLET_VAR).is_some() {
+                    self.set_value(TRIPLET_VAR, triplet);
+                } else {
+                    let desc = "Target triplet of the vcpkg dependencies".to_string();
+                    self.editor.add_var(CacheVar::new(TRIPLET_VAR.to_string(), VarType::Str, desc, triplet));
+                }
+                if key.code == KeyCode::Char('c')
+                    && let Err(e) = self.try_configure(false)
+                {
+                    self.message = Some(e);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn show_sub_builds(&mut self) {
+        self.sub_builds.open(&self.build_dir, self.parent_builds.last().map(PathBuf::as_path));
+        if self.sub_builds.is_empty() {
+            self.sub_builds.visible = false;
+            self.message = Some("No FetchContent or ExternalProject builds with a cache of their own".to_string());
+        }
+    }
+
+    fn handle_sub_builds_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.sub_builds.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.sub_builds.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.sub_builds.select_previous(),
+            KeyCode::Enter => {
+                let modified = self.editor.modified_count();
+                if modified > 0 {
+                    self.message = Some(format!("Save (w) or undo the {} pending edits first", modified));
+                    return;
+                }
+                let result = match self.sub_builds.selected() {
+                    Some(SubBuildChoice::Open(path)) => {
+                        let path = path.clone();
+                        let parent = self.build_dir.canonicalize().unwrap_or_else(|_| self.build_dir.clone());
+                        self.open_build_dir(path).map(|_| self.parent_builds.push(parent))
+                    }
+                    Some(SubBuildChoice::Back(path)) => {
+                        let path = path.clone();
+                        self.open_build_dir(path).map(|_| {
+                            self.parent_builds.pop();
+                        })
+                    }
+                    None => return,
+                };
+                self.sub_builds.visible = false;
+                if let Err(e) = result {
+                    self.message = Some(e);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn show_usages(&mut self) {
+        let Some(name) = self.editor.selected().map(|v| v.var.name.clone()) else { return };
+        match local_source_dir(&self.build_dir) {
+            Some(source_dir) => self.usages.open(&source_dir, &name),
+            None => self.message = Some("The cache doesn't say where the source directory is".to_string()),
+        }
+    }
+
+    fn handle_usages_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.usages.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.usages.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.usages.select_previous(),
+            KeyCode::Enter => self.open_usage = self.usages.selected().map(|u| (u.file.clone(), u.line)),
+            _ => {}
+        }
+    }
+
+    fn handle_launcher_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.launcher.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.launcher.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.launcher.select_previous(),
+            KeyCode::Enter => {
+                let values = match self.launcher.selected() {
+                    Some(LauncherChoice::Use(path)) => self.launcher_values(Some(path.display().to_string())),
+                    Some(LauncherChoice::Remove) => self.launcher_values(None),
+                    Some(LauncherChoice::Revert) => self.launcher.previous.take().unwrap_or_default(),
+                    None => return,
+                };
+                self.launcher.visible = false;
+                self.set_launcher_values(values);
+            }
+            _ => {}
+        }
+    }
+
+    /// The launcher entries of every language of the project set to `launcher`,
+    /// remembering their values from before the first change to revert to
+    fn launcher_values(&mut self, launcher: Option<String>) -> Vec<(String, Option<String>)> {
+        let vars: Vec<String> = launcher::languages(&self.editor).iter().map(|l| launcher_var(l)).collect();
+        if self.launcher.previous.is_none() {
+            let previous = vars.iter().map(|v| (v.clone(), self.editor.get(v).map(|var| var.new_val.clone())));
+            self.launcher.previous = Some(previous.collect());
+        }
+        vars.into_iter().map(|v| (v, Some(launcher.clone().unwrap_or_default()))).collect()
+    }
+
+    /// Set launcher entries, None for ones that shouldn't be in the cache
+    fn set_launcher_values(&mut self, values: Vec<(String, Option<String>)>) {
+        for (name, value) in values {
+            match (value, self.editor.get(&name).is_some()) {
+                (Some(value), true) => {
+                    self.editor.set_value(&name, value);
+                }
+                (Some(value), false) if !value.is_empty() => {
+                    let desc = "Program the compiler runs under, e.g. a compiler cache.".to_string();
+                    self.editor.add_var(CacheVar::new(name, VarType::Str, desc, value));
+                }
+                (None, true) if !self.editor.remove_new_var(&name) => {
+                    self.editor.set_value(&name, String::new());
+                }
+                _ => {}
+            }
+        }
+        self.message = Some(match launcher::current_launcher(&self.editor) {
+            Some(launcher) => format!("Compilers run under {} after the next configure, c to configure", launcher),
+            None => "No compiler launcher after the next configure, c to configure".to_string(),
+        });
+    }
+
+    fn handle_flag_bundles_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.flag_bundles.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.flag_bundles.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.flag_bundles.select_previous(),
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                let Some(bundle) = self.flag_bundles.selected() else { return };
+                let enabled = bundle.is_enabled(&self.editor);
+                let changes = bundle.toggle(&self.editor);
+                let count = changes.len();
+                for change in changes {
+                    if self.editor.get(&change.name).is_some() {
+                        self.editor.set_value(&change.name, change.after);
+                    } else {
+                        let desc = "Flags used by the current build type.".to_string();
+                        self.editor.add_var(CacheVar::new(change.name, VarType::Str, desc, change.after));
+                    }
+                }
+                self.message = Some(format!(
+                    "{} turned {} in {} flag entries, c to configure",
+                    bundle.name,
+                    if enabled { "off" } else { "on" },
+                    count
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_value_pane_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('o') => self.value_pane.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.value_pane.scroll(1),
+            KeyCode::Char('k') | KeyCode::Up => self.value_pane.scroll(-1),
+            KeyCode::Char('r') => self.value_pane.toggle_raw(),
+            KeyCode::Char('s') => {
+                self.value_pane.visible = false;
+                self.edit_allowed_values();
+            }
+            KeyCode::Char('a') => {
+                self.value_pane.visible = false;
+                self.toggle_advanced_flag();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_toolchain_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('T') => self.toolchain.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.toolchain.scroll(1),
+            KeyCode::Char('k') | KeyCode::Up => self.toolchain.scroll(-1),
+            KeyCode::Char('p') => {
+                self.toolchain.visible = false;
+                self.open_toolchain_picker();
+            }
+            _ => {}
+        }
+    }
+
+    fn open_toolchain_picker(&mut self) {
+        let current = self.editor.get("CMAKE_TOOLCHAIN_FILE").map(|v| v.var.value.as_str()).filter(|v| !v.is_empty());
+        self.toolchain_picker = Some(ToolchainPicker::new(parse_source_dir(&self.build_dir).as_deref(), current));
+    }
+
+    fn handle_toolchain_picker_key(&mut self, key: KeyEvent) {
+        let Some(picker) = self.toolchain_picker.as_mut() else { return };
+        match picker.handle_key(key) {
+            PickerResult::Continue => {}
+            PickerResult::Cancel => self.toolchain_picker = None,
+            PickerResult::Pick(toolchain) => {
+                self.toolchain_picker = None;
+                let current = self.editor.get("CMAKE_TOOLCHAIN_FILE").map(|v| PathBuf::from(&v.var.value));
+                if toolchain == current.filter(|c| !c.as_os_str().is_empty()) {
+                    self.message = Some("The build dir already uses that toolchain".to_string());
+                } else {
+                    self.ask_switch_toolchain(toolchain);
+                }
+            }
+        }
+    }
+
+    fn open_generator_picker(&mut self) {
+        match Capabilities::load() {
+            Ok(capabilities) => {
+                let current = GeneratorChoice::from_cache(&self.editor).map(|g| g.generator);
+                self.generator_picker = Some((GeneratorPicker::new(capabilities, current.as_deref()), None));
+            }
+            Err(e) => self.dialog = Some(Dialog::error("No generators to pick from", e)),
+        }
+    }
+
+    fn handle_generator_picker_key(&mut self, key: KeyEvent) {
+        let Some((picker, _)) = self.generator_picker.as_mut() else { return };
+        let generator = match picker.handle_key(key) {
+            GeneratorPickerResult::Continue => return,
+            GeneratorPickerResult::Cancel => {
+                self.generator_picker = None;
+                return;
+            }
+            GeneratorPickerResult::Pick(generator) => generator,
+        };
+        let Some((_, initial_source)) = self.generator_picker.take() else { return };
+        if let Some(source_dir) = initial_source {
+            if let Err(e) = self.start_initial_configure(source_dir, generator)
+                && let Some(screen) = self.missing_cache.as_mut()
+            {
+                screen.problem = Some(e);
+            }
+            return;
+        }
+
+        if generator.is_some() && generator == GeneratorChoice::from_cache(&self.editor) {
+            self.message = Some("The build dir already uses that generator".to_string());
+            return;
+        }
+        let target = generator.as_ref().map_or("with the generator cmake picks".to_string(), |g| format!("for {}", g));
+        let message = format!(
+            "A build directory keeps the generator of its first configure. Switching deletes \
+             CMakeCache.txt and CMakeFiles/ and configures again {}, keeping the pending edits. \
+             A snapshot of the cache is taken first (S to restore it).",
+            target
+        );
+        self.dialog = Some(
+            Dialog::new("Switch the generator?", message)
+                .choice('y', "wipe the cache and configure", Confirmed::SwitchGenerator(generator))
+                .choice('n', "cancel", Confirmed::Nothing),
+        );
+    }
+
+    /// Start over from a fresh cache, with the pending edits picked to keep
+    /// when there are any
+    fn open_fresh_configure(&mut self) {
+        let defines = self.editor.configure_defines();
+        if defines.is_empty() {
+            self.ask_fresh_configure(defines);
+        } else {
+            self.fresh = Some(FreshPane::new(defines));
+        }
+    }
+
+    fn handle_fresh_key(&mut self, key: KeyEvent) {
+        let Some(pane) = self.fresh.as_mut() else { return };
+        match pane.handle_key(key) {
+            FreshResult::Continue => {}
+            FreshResult::Cancel => self.fresh = None,
+            FreshResult::Pick(defines) => {
+                self.fresh = None;
+                self.ask_fresh_configure(defines);
+            }
+        }
+    }
+
+    fn ask_fresh_configure(&mut self, defines: Vec<(String, String)>) {
+        let dropped = self.modified_count() - defines.len();
+        let mut message = "Deletes CMakeCache.txt and CMakeFiles/ and configures again from the project's defaults, \
+                           keeping the generator. A snapshot of the cache is taken first (S to restore it)."
+            .to_string();
+        if dropped > 0 {
+            message.push_str(&format!(" {} pending edit(s) are dropped.", dropped));
+        }
+        let mut dialog = Dialog::new("Configure from a fresh cache?", message);
+        if !defines.is_empty() {
+            let lines: Vec<String> = defines.iter().map(|(name, value)| format!("-D{}={}", name, value)).collect();
+            dialog = dialog.details(lines.join("\n"));
+        }
+        self.dialog = Some(
+            dialog
+                .choice('y', "wipe the cache and configure", Confirmed::FreshConfigure(defines))
+                .choice('n', "cancel", Confirmed::Nothing),
+        );
+    }
+
+    fn handle_disk_usage_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('U') => self.disk_usage.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.disk_usage.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.disk_usage.select_previous(),
+            KeyCode::Char('r') => self.disk_usage.open(&self.build_dir),
+            KeyCode::Char('x') => {
+                let Some(entry) = self.disk_usage.selected() else { return };
+                if !entry.is_dir {
+                    self.message = Some(format!("{} is not a directory, only directories are removed", entry.name));
+                    return;
+                }
+                if let Some(reason) = self.task_running().then(|| "a task is running".to_string()).or_else(|| self.busy_reason()) {
+                    self.message = Some(format!("Not removing anything, {}", reason));
+                    return;
+                }
+                let mut message = format!("Deletes {} ({}).", entry.path.display(), format_size(entry.size));
+                if entry.name == "CMakeFiles" {
+                    message.push_str(" Everything is rebuilt and the next configure checks the compilers again.");
+                } else if entry.name == "_deps" {
+                    message.push_str(" The dependencies are downloaded and built again on the next configure.");
+                }
+                self.dialog = Some(
+                    Dialog::new("Remove the directory?", message)
+                        .choice('y', "remove it", Confirmed::RemoveArtifacts(entry.path.clone()))
+                        .choice('n', "keep it", Confirmed::Nothing),
+                );
+            }
+            KeyCode::Char('c') => {
+                if self.task_running() {
+                    self.message = Some("A task is already running".to_string());
+                    return;
+                }
+                self.dialog = Some(
+                    Dialog::new(
+                        "Run the clean target?",
+                        "Runs cmake --build with --target clean, deleting what the build produced. \
+                         The configure results are kept.",
+                    )
+                    .choice('y', "clean", Confirmed::RunCleanTarget)
+                    .choice('n', "cancel", Confirmed::Nothing),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn ask_switch_toolchain(&mut self, toolchain: Option<PathBuf>) {
+        let target = match &toolchain {
+            Some(path) => format!("with -DCMAKE_TOOLCHAIN_FILE={}", path.display()),
+            None => "without a toolchain file".to_string(),
+        };
+        let message = format!(
+            "CMake reads the toolchain only on the first configure of a build directory. \
+             Switching deletes CMakeCache.txt and CMakeFiles/ and configures again {}, \
+             keeping the pending edits. A snapshot of the cache is taken first (S to restore it).",
+            target
+        );
+        self.dialog = Some(
+            Dialog::new("Switch the toolchain?", message)
+                .choice('y', "wipe the cache and configure", Confirmed::SwitchToolchain(toolchain))
+                .choice('n', "cancel", Confirmed::Nothing),
+        );
+    }
+
+    /// CMake only reads the toolchain file on the first configure of a build
+    /// dir, so switching means starting over with the new toolchain
+    fn switch_toolchain(&mut self, toolchain: Option<PathBuf>) -> Result<(), String> {
+        let mut defines = self.editor.configure_defines();
+        defines.retain(|(name, _)| name.split(':').next() != Some("CMAKE_TOOLCHAIN_FILE"));
+        if let Some(path) = &toolchain {
+            defines.push(("CMAKE_TOOLCHAIN_FILE".to_string(), path.display().to_string()));
+        }
+        let generator = GeneratorChoice::from_cache(&self.editor);
+        self.configure_from_scratch("switching the toolchain", generator, defines)?;
+        if let Some(path) = &toolchain
+            && let Err(e) = remember_toolchain(path)
+        {
+            self.log.push_info(&format!("Failed to remember the toolchain: {}", e));
+        }
+        Ok(())
+    }
+
+    /// For what cmake only reads on the first configure of a build dir, and
+    /// for a cache gone bad: snapshot the cache, delete it with CMakeFiles/
+    /// and configure again with `generator` and `defines`
+    fn configure_from_scratch(
+        &mut self,
+        why: &str,
+        generator: Option<GeneratorChoice>,
+        defines: Vec<(String, String)>,
+    ) -> Result<(), String> {
+        if self.task_running() {
+            return Err("A task is already running".to_string());
+        }
+        if let Some(reason) = self.busy_reason() {
+            return Err(format!("Not {}, {}", why, reason));
+        }
+        let source_dir = parse_source_dir(&self.build_dir).ok_or("The cache doesn't say where the source directory is")?;
+        let dir = self.ensure_sidecar().ok_or("No place to keep a snapshot of the cache")?;
+        Snapshot::create(&dir, &self.build_dir, &format!("before {}", why))
+            .map_err(|e| format!("Not {}, failed to snapshot the cache: {}", why, e))?;
+
+        // cmake --fresh does the wiping itself since 3.24
+        let task = if cmake::version().is_some_and(|v| v.at_least(3, 24)) {
+            Task::fresh_configure(&source_dir, &self.build_dir, generator.as_ref(), &defines)
+        } else {
+            let remove = |result: std::io::Result<()>| match result {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to wipe the cache: {}", e)),
+                _ => Ok(()),
+            };
+            remove(fs::remove_file(self.build_dir.join("CMakeCache.txt")))?;
+            remove(fs::remove_dir_all(self.build_dir.join("CMakeFiles")))?;
+            Task::initial_configure(&source_dir, &self.build_dir, generator.as_ref(), &defines)
+        };
+        self.track_task(TaskKind::Configure, task);
+        Ok(())
+    }
+
+    /// Configure a scratch copy of the build dir with cmake's trace on, to
+    /// find where the selected variable is set and read
+    fn explain_selected(&mut self) -> Result<(), String> {
+        if self.task_running() {
+            return Err("A task is already running".to_string());
+        }
+        let name = self.editor.selected().map(|v| v.var.name.clone()).ok_or("Nothing selected")?;
+        let source_dir = parse_source_dir(&self.build_dir).ok_or("The cache doesn't say where the source directory is")?;
+        let dir = self.ensure_sidecar().ok_or("No place for the scratch build dir")?.join("explain");
+        let scratch_dir = dir.join("build");
+        let failed = |e: std::io::Error| format!("Failed to set up {}: {}", dir.display(), e);
+        match fs::remove_dir_all(&dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(failed(e)),
+            _ => {}
+        }
+        fs::create_dir_all(&scratch_dir).map_err(failed)?;
+        let initial_cache = dir.join("initial-cache.cmake");
+        write_initial_cache(&self.editor.vars, &initial_cache).map_err(failed)?;
+
+        let generator = self.editor.get("CMAKE_GENERATOR").map(|v| v.var.value.clone()).filter(|g| !g.is_empty());
+        let trace = dir.join("trace.json");
+        let task = Task::traced_configure(&source_dir, &scratch_dir, &initial_cache, generator.as_deref(), &trace);
+        self.track_task(TaskKind::Explain, task);
+        if self.task_running() {
+            self.message = Some(format!("Tracing a scratch configure for {}...", name));
+            self.explain_run = Some(ExplainRun { name, source_dir, initial_cache, trace });
+        }
+        Ok(())
+    }
+
+    fn handle_explain_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.explain.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.explain.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.explain.select_previous(),
+            KeyCode::Enter => self.open_usage = self.explain.selected().map(|h| (h.file.clone(), h.line)),
+            _ => {}
+        }
+    }
+
+    fn handle_profile_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.profile.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.profile.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.profile.select_previous(),
+            KeyCode::Tab => self.profile.toggle_grouping(),
+            _ => {}
+        }
+    }
+
+    fn show_duplicates(&mut self) {
+        let groups = find_duplicate_paths(
+            self.editor.vars.iter().map(|v| (v.var.name.as_str(), &v.var.typ, v.new_val.as_str())),
+        );
+        self.duplicates.set_groups(groups);
+        self.duplicates.visible = true;
+    }
+
+    fn handle_duplicates_key(&mut self, key: KeyEvent) {
+        if let Some(input) = self.duplicates.replace.as_mut() {
+            match key.code {
+                KeyCode::Esc => self.duplicates.replace = None,
+                KeyCode::Enter => self.replace_duplicate_group(),
+                _ => { input.handle_key(key); }
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('D') | KeyCode::Char('q') => self.duplicates.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.duplicates.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.duplicates.select_previous(),
+            KeyCode::Char('r') => self.duplicates.start_replace(),
+            KeyCode::Enter => {
+                let Some(name) = self.duplicates.selected().map(|g| g.names[0].clone()) else { return };
+                self.jump(|app| _ = app.editor.reveal(&name));
+                self.duplicates.visible = false;
+            }
+            _ => {}
+        }
+    }
+
+    /// Set every variable of the selected duplicate group to the typed value
+    fn replace_duplicate_group(&mut self) {
+        let Some(input) = self.duplicates.replace.take() else { return };
+        let Some(group) = self.duplicates.selected() else { return };
+        let names = group.names.clone();
+        let value = input.text().to_string();
+
+        // Only one entry cmake manages can be asked about at a time, the
+        // others are set by the next replace once it's confirmed
+        let (managed, names): (Vec<_>, Vec<_>) = names.into_iter().partition(|name| self.needs_edit_confirmation(name));
+        let mut problems = Vec::new();
+        for name in names {
+            self.dispatch(Action::Set(name.clone(), value.clone()));
+            if self.editor.get(&name).is_none_or(|v| v.new_val != value) {
+                problems.push(format!("{}: {}", name, self.message.take().unwrap_or_default()));
+            }
+        }
+        if managed.len() > 1 {
+            problems.push(format!("{} more entries cmake manages, replace again to set them", managed.len() - 1));
+        }
+        if !problems.is_empty() {
+            self.message = Some(format!("Not replaced in {}", problems.join("; ")));
+        }
+        if let Some(name) = managed.into_iter().next() {
+            self.dispatch(Action::Set(name, value));
+        }
+        self.show_duplicates();
+    }
+
+    fn handle_env_key(&mut self, key: KeyEvent) {
+        if let Some(input) = self.env.edit.as_mut() {
+            match key.code {
+                KeyCode::Esc => self.env.edit = None,
+                KeyCode::Enter => {
+                    let value = input.text().to_string();
+                    self.env.edit = None;
+                    if let Some(name) = self.env.selected().map(str::to_string) {
+                        self.set_env(name, Some(Some(value)));
+                    }
+                }
+                _ => _ = input.handle_key(key),
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('$') | KeyCode::Char('q') => self.env.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.env.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.env.select_previous(),
+            KeyCode::Enter => self.env.start_edit(),
+            KeyCode::Char('u') | KeyCode::Char('r') => {
+                if let Some(name) = self.env.selected().map(str::to_string) {
+                    let value = if key.code == KeyCode::Char('u') { Some(None) } else { None };
+                    self.set_env(name, value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Override an environment variable for the cmake runs from now on:
+    /// Some(Some) sets it, Some(None) unsets it, None goes back to the
+    /// value cmake-tui was started with
+    fn set_env(&mut self, name: String, value: Option<Option<String>>) {
+        match value {
+            Some(value) => _ = self.env.overrides.insert(name, value),
+            None => _ = self.env.overrides.remove(&name),
+        }
+        cmake::set_env_overrides(self.env.override_list());
+    }
+
+    fn handle_journal_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('H') | KeyCode::Char('q') => self.journal.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.journal.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.journal.select_previous(),
+            _ => {}
+        }
+    }
+
+    fn show_snapshots(&mut self) {
+        self.snapshots.set_snapshots(Snapshot::list(&self.sidecar.dir));
+        self.snapshots.visible = true;
+    }
+
+    fn take_snapshot(&mut self, name: &str) {
+        let Some(dir) = self.ensure_sidecar() else { return };
+        self.message = Some(match Snapshot::create(&dir, &self.build_dir, name) {
+            Ok(_) => format!("Snapshot '{}' taken", name),
+            Err(e) => format!("Failed to take snapshot: {}", e),
+        });
+        if self.snapshots.visible {
+            self.snapshots.set_snapshots(Snapshot::list(&dir));
+        }
+    }
+
+    /// Replace the cache with the selected snapshot. The current cache is
+    /// snapshotted first, so a restore can itself be undone.
+    fn restore_snapshot(&mut self) {
+        let Some(snapshot) = self.snapshots.selected() else { return };
+        let name = snapshot.name.clone();
+        if let Some(reason) = self.busy_reason() {
+            self.message = Some(format!("Not restoring, {}", reason));
+            return;
+        }
+        if let Err(e) = Snapshot::create(&self.sidecar.dir, &self.build_dir, &format!("before restoring '{}'", name)) {
+            self.message = Some(format!("Not restoring, failed to back up the current cache: {}", e));
+            return;
+        }
+        let Some(snapshot) = self.snapshots.selected() else { return };
+        self.message = Some(match snapshot.restore(&self.build_dir) {
+            Ok(()) => {
+                self.editor.reload(&self.build_dir);
+                match upload_cache() {
+                    Ok(()) => format!("Restored snapshot '{}', run configure to apply it", name),
+                    Err(e) => format!("Restored snapshot '{}' to the local copy only: {}", name, e),
+                }
+            }
+            Err(e) => format!("Failed to restore snapshot: {}", e),
+        });
+        self.snapshots.set_snapshots(Snapshot::list(&self.sidecar.dir));
+    }
+
+    fn handle_snapshots_key(&mut self, key: KeyEvent) {
+        if let Some(input) = self.snapshots.name_input.as_mut() {
+            match key.code {
+                KeyCode::Esc => self.snapshots.name_input = None,
+                KeyCode::Enter => {
+                    let name = input.text().trim().to_string();
+                    self.snapshots.name_input = None;
+                    if !name.is_empty() {
+                        self.take_snapshot(&name);
+                    }
+                }
+                _ => { input.handle_key(key); }
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('S') | KeyCode::Char('q') => self.snapshots.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.snapshots.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.snapshots.select_previous(),
+            KeyCode::Char('s') => self.snapshots.name_input = Some(TextInput::default()),
+            KeyCode::Char('d') => {
+                let Some(snapshot) = self.snapshots.selected() else { return };
+                match snapshot.diff(&self.build_dir) {
+                    Ok(changes) => self.snapshots.diff = Some(changes),
+                    Err(e) => self.message = Some(format!("Failed to compare snapshot: {}", e)),
+                }
+            }
+            KeyCode::Char('r') => self.restore_snapshot(),
+            KeyCode::Char('x') => {
+                let Some(snapshot) = self.snapshots.selected() else { return };
+                let name = snapshot.name.clone();
+                self.dialog = Some(
+                    Dialog::new("Delete the snapshot?", format!("The snapshot '{}' is deleted for good.", name))
+                        .choice('y', "delete it", Confirmed::DeleteSnapshot(name))
+                        .choice('n', "keep it", Confirmed::Nothing),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn open_list_editor(&mut self) {
+        let Some(var) = self.editor.selected() else { return };
+        if matches!(var.var.typ, VarType::Bool | VarType::Enum) {
+            return;
+        }
+        self.list_editor = Some(ListEditor::new(&var.var.name, &var.new_val));
+        self.list_editor_strings = false;
+    }
+
+    /// Edit the values a STRING entry offers to pick from, written to the
+    /// cache as its STRINGS property on save
+    fn edit_allowed_values(&mut self) {
+        let Some(var) = self.editor.selected() else { return };
+        if !matches!(var.var.typ, VarType::Str | VarType::Enum) {
+            self.message = Some(format!("{} is a {}, only STRING entries have allowed values", var.var.name, var.var.typ));
+            return;
+        }
+        let title = format!("{} allowed values", var.var.name);
+        self.list_editor = Some(ListEditor::new(&title, &var.var.values.join(";")));
+        self.list_editor_strings = true;
+    }
+
+    /// Mark the selected entry as advanced or not, written to the cache as
+    /// its ADVANCED property on save
+    fn toggle_advanced_flag(&mut self) {
+        let Some(var) = self.editor.selected() else { return };
+        let name = var.var.name.clone();
+        let advanced = !var.var.advanced;
+        self.editor.set_advanced(&name, advanced);
+        self.message = Some(match (advanced, self.editor.show_advanced) {
+            (true, true) => format!("Marked {} as advanced", name),
+            (true, false) => format!("Marked {} as advanced, t shows it again", name),
+            (false, _) => format!("{} is no longer advanced", name),
+        });
+    }
+
+    fn open_new_entry(&mut self, duplicate: bool) {
+        let existing = self.editor.vars.iter().map(|v| v.var.name.clone()).collect();
+        self.new_entry = match (duplicate, self.editor.selected()) {
+            (true, Some(var)) => Some(NewEntryForm::duplicate(&var.var, &var.new_val, existing)),
+            (true, None) => return,
+            (false, _) => Some(NewEntryForm::new(existing)),
+        };
+    }
+
+    fn handle_new_entry_key(&mut self, key: KeyEvent) {
+        let Some(form) = self.new_entry.as_mut() else { return };
+        match form.handle_key(key) {
+            NewEntryResult::Continue => {}
+            NewEntryResult::Cancel => self.new_entry = None,
+            NewEntryResult::Create(var) => {
+                self.new_entry = None;
+                let name = var.name.clone();
+                if !self.editor.add_var(var) {
+                    self.message = Some(format!("Added {}, hidden by the current filter", name));
+                }
+            }
+        }
+    }
+
+    fn handle_list_editor_key(&mut self, key: KeyEvent) {
+        let Some(list_editor) = self.list_editor.as_mut() else { return };
+        match list_editor.handle_key(key) {
+            ListEditorResult::Continue => {}
+            ListEditorResult::Cancel => self.list_editor = None,
+            ListEditorResult::Apply(value) => {
+                if self.list_editor_strings {
+                    if let Some(name) = self.editor.selected().map(|v| v.var.name.clone()) {
+                        self.editor.set_allowed_values(&name, split_list(&value));
+                    }
+                } else if let Some(name) = self.editor.selected().map(|v| v.var.name.clone()) {
+                    self.dispatch(Action::Set(name.clone(), value.clone()));
+                    // A rejected value stays in the editor to be fixed, the message says why
+                    if self.editor.get(&name).is_none_or(|v| v.new_val != value) {
+                        return;
+                    }
+                }
+                self.list_editor = None;
+            }
+        }
+    }
+
+    /// Select the first cache variable mentioned by the selected problem
+    fn jump_to_problem_var(&mut self) {
+        let Some(problem) = self.problems.selected() else { return };
+        let Some(name) = problem
+            .identifiers()
+            .find(|word| self.editor.get(word).is_some())
+            .map(|word| word.to_string())
+        else {
+            return;
+        };
+
+        // A hidden advanced variable is shown by revealing advanced entries
+        self.jump(|app| _ = app.editor.reveal(&name));
+        self.problems.visible = false;
+        self.focus = Focus::Table;
+    }
+
+    /// Variable an action changes the value of
+    fn edit_target(&self, action: &Action) -> Option<String> {
+        match action {
+            Action::EditValue
+            | Action::SetValue(_)
+            | Action::CycleValue
+            | Action::ResetToDefault
+            | Action::OpenEditor
+            | Action::OpenListEditor
+            | Action::EditAllowedValues
+            | Action::ToggleAdvancedFlag => {
+                self.editor.selected().map(|v| v.var.name.clone())
+            }
+            Action::Set(name, _) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Why there's no entry to act on, for the message line
+    fn nothing_selected(&self) -> String {
+        if self.editor.vars.is_empty() {
+            "The cache has no entries".to_string()
+        } else if self.editor.visible_count() == 0 {
+            "No entries shown, f clears the filter".to_string()
+        } else {
+            "No entry selected".to_string()
+        }
+    }
+
+    fn needs_edit_confirmation(&self, name: &str) -> bool {
+        !self.confirmed_edits.contains(name)
+            && self
+                .editor
+                .get(name)
+                .is_some_and(|v| matches!(v.var.typ, VarType::Internal | VarType::Uninitialized))
+    }
+
+    /// Turn CMAKE_EXPORT_COMPILE_COMMANDS on or off, offering to link the
+    /// result into the source dir when it's turned on
+    fn toggle_compile_commands(&mut self) {
+        const NAME: &str = "CMAKE_EXPORT_COMPILE_COMMANDS";
+        let enabled = match self.editor.get(NAME) {
+            Some(var) => {
+                let value = CacheVar::toggle_bool(&var.new_val);
+                let enabled = BoolValue::parse(&value).value() == Some(true);
+                self.set_value(NAME, value);
+                enabled
+            }
+            None => {
+                let desc = "Enable/Disable output of compile commands during generation.".to_string();
+                self.editor.add_var(CacheVar::new(NAME.to_string(), VarType::Bool, desc, "ON".to_string()));
+                true
+            }
+        };
+        if !enabled {
+            self.link_compile_commands = None;
+            self.message = Some(format!("{} turned off, c to configure", NAME));
+            return;
+        }
+        self.message = Some(format!("{} turned on, c to configure", NAME));
+        match local_source_dir(&self.build_dir) {
+            Some(source_dir) if !same_dir(&source_dir, &self.build_dir) && !is_linked(&self.build_dir, &source_dir) => {
+                let message = format!(
+                    "clangd and other tools look for compile_commands.json in the source tree. \
+                     After the next configure, {} can link to the one cmake writes into the build directory.",
+                    source_dir.join(COMPILE_COMMANDS).display()
+                );
+                self.dialog = Some(
+                    Dialog::new("Link compile_commands.json?", message)
+                        .choice('y', "link it after the next configure", Confirmed::LinkCompileCommands(source_dir))
+                        .choice('n', "don't link it", Confirmed::Nothing),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_install_prompt_key(&mut self, key: KeyEvent) {
+        let Some(mut input) = self.install_prompt.take() else { return };
+        match key.code {
+            KeyCode::Enter => {
+                if self.task_running() {
+                    self.message = Some("A task is already running".to_string());
+                    return;
+                }
+                let configured = self.editor.get("CMAKE_INSTALL_PREFIX").map(|v| v.var.value.as_str());
+                // Only an actual change is passed along, so the cache value stays in charge otherwise
+                let prefix = Some(input.text().trim()).filter(|p| !p.is_empty() && Some(*p) != configured);
+                let task = Task::install(&self.build_dir, prefix, self.build_config().as_deref());
+                self.track_task(TaskKind::Install, task);
+            }
+            KeyCode::Esc => {}
+            _ => {
+                input.handle_key(key);
+                self.install_prompt = Some(input);
+            }
+        }
+    }
+
+    fn show_package(&mut self) {
+        if !self.package.open(&self.build_dir) {
+            self.message = Some(format!("No {} in the build directory, the project doesn't include(CPack)", CPACK_CONFIG));
+        }
+    }
+
+    fn handle_package_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.package.visible = false,
+            KeyCode::Char('j') | KeyCode::Down => self.package.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.package.select_previous(),
+            KeyCode::Enter => {
+                let Some(generator) = self.package.selected() else { return };
+                if self.task_running() {
+                    self.message = Some("A task is already running".to_string());
+                    return;
+                }
+                self.package.visible = false;
+                let task = Task::package(&self.build_dir, Path::new(CPACK_CONFIG), generator);
+                self.track_task(TaskKind::Package, task);
+            }
+            _ => {}
+        }
+    }
+
+    fn ask_edit(&mut self, name: String, action: Action) {
+        let message = match self.editor.get(&name).map(|v| &v.var.typ) {
+            Some(VarType::Uninitialized) => format!(
+                "{} has no type yet (UNINITIALIZED): it was passed with -D before the project declared it. \
+                 The project's declaration decides its type and may replace the value on the next configure.",
+                name
+            ),
+            _ => format!(
+                "{} is an INTERNAL entry, bookkeeping cmake keeps for itself. \
+                 Changing it can confuse cmake or break the build directory.",
+                name
+            ),
+        };
+        self.dialog = Some(
+            Dialog::new("Edit an entry cmake manages?", message)
+                .choice('y', "edit it anyway, not asked again for it", Confirmed::Edit(name, action))
+                .choice('n', "leave it", Confirmed::Nothing),
+        );
+    }
+
+    fn dispatch(&mut self, action: Action) {
+        if acts_on_selected_entry(&action) && self.editor.selected().is_none() {
+            self.message = Some(self.nothing_selected());
+            return;
+        }
+        if let Some(name) = self.edit_target(&action)
+            && self.needs_edit_confirmation(&name)
+        {
+            self.ask_edit(name, action);
+            return;
+        }
+
+        if action.is_repeatable() {
+            self.last_change = Some(action.clone());
+        }
+
+        #[cfg(feature = "time-travel")]
+        let label = format!("{:?}", action);
+
+        match action {
+            Action::Quit if self.config.auto_save.on_exit() && self.modified_count() > 0 => {
+                self.save(false);
+                if self.modified_count() == 0 {
+                    self.quit();
+                }
+            }
+            Action::Quit | Action::ForceQuit => self.quit(),
+            Action::SelectNext(_)
+            | Action::SelectPrevious(_)
+            | Action::SelectFirst
+            | Action::SelectLast
+            | Action::SelectRow(_) => self.navigate(action),
+            Action::ToggleAdvanced => self.editor.toggle_show_advanced(),
+            Action::ToggleInternal => self.editor.toggle_show_internal(),
+            Action::SetColumns(columns) => self.editor.columns = columns,
+            Action::ToggleWrap => {
+                self.editor.long_values = match self.editor.long_values {
+                    LongValues::Truncate => LongValues::Wrap,
+                    LongValues::Wrap => LongValues::Truncate,
+                };
+            }
+            Action::ToggleIgnored => match self.editor.ignored_count() {
+                0 if !self.editor.show_ignored => {
+                    self.message = Some("Nothing to reveal, no entry matches the view.ignore patterns".to_string())
+                }
+                _ => self.editor.toggle_show_ignored(),
+            },
+            Action::ToggleExpanded => self.editor.show_expanded = !self.editor.show_expanded,
+            Action::CycleRowNumbers => self.editor.row_numbers = self.editor.row_numbers.next(),
+            Action::EditValue => self.edit_value(),
+            Action::SetValue(value) => {
+                if let Some(name) = self.editor.selected().map(|v| v.var.name.clone()) {
+                    self.set_value(&name, value);
+                }
+            }
+            Action::CycleValue => self.editor.cycle_value(),
+            Action::ResetToDefault => self.reset_to_default(),
+            Action::ScrollDescription(lines) => self.editor.scroll_description(lines),
+            Action::ScrollValue(columns) => self.editor.scroll_value(columns),
+            Action::ShowValue => {
+                if let Some(var) = self.editor.selected() {
+                    self.value_pane.open(&var.var.name, &var.new_val);
+                }
+            }
+            Action::StartSearch => match self.active_screen() {
+                Screen::Targets => self.targets.start_filter(),
+                Screen::Tests => self.tests.start_filter(),
+                _ => self.search_var(),
+            },
+            Action::NextSearchResult => match self.active_screen() {
+                Screen::Log => self.log.next_match(),
+                _ => self.select_next_search_result(),
+            },
+            Action::PreviousSearchResult => match self.active_screen() {
+                Screen::Log => self.log.previous_match(),
+                _ => self.select_previous_search_result(),
+            },
+            Action::StartCommand => {
+                self.command_input = TextInput::default();
+                self.mode = AppMode::CommandInput;
+            }
+            Action::Configure => {
+                if let Err(e) = self.try_configure(false) {
+                    self.message = Some(e);
+                }
+            }
+            Action::ProfileConfigure => {
+                if let Err(e) = self.try_configure(true) {
+                    self.message = Some(e);
+                }
+            }
+            Action::ShowProfile => match self.profile.has_profile() {
+                true => self.profile.visible = true,
+                false => self.message = Some("No profile yet, run :profile to configure with profiling".to_string()),
+            },
+            Action::ForceConfigure => self.start_task(TaskKind::Configure),
+            Action::ShowDiskUsage => self.disk_usage.open(&self.build_dir),
+            Action::FreshConfigure => self.open_fresh_configure(),
+            Action::Build => self.start_task(TaskKind::Build),
+            Action::Install => {
+                let prefix = self.editor.get("CMAKE_INSTALL_PREFIX").map_or("", |v| v.new_val.as_str());
+                self.install_prompt = Some(TextInput::new(prefix));
+            }
+            Action::ShowPackage => self.show_package(),
+            Action::ShowScreen(screen) => self.show_screen(screen),
+            Action::NextTab => self.show_screen(self.screens.next_tab()),
+            Action::CloseScreen => {
+                self.screens.close();
+            }
+            Action::LeaveLog => {
+                if self.screens.current() == Screen::Log {
+                    self.screens.close();
+                }
+                self.focus = Focus::Table;
+            }
+            Action::PageDown => self.log.page_down(),
+            Action::PageUp => self.log.page_up(),
+            Action::NextError => self.log.next_error(),
+            Action::PreviousError => self.log.previous_error(),
+            Action::ShowEntry => {
+                if let Some(name) = self.diff.selected(&self.editor).map(str::to_string) {
+                    self.screens.open(Screen::Cache);
+                    self.jump(|app| _ = app.editor.reveal(&name));
+                }
+            }
+            Action::BuildSelectedTarget => self.build_selected_target(),
+            Action::RunSelectedTest => self.run_tests(true),
+            Action::RunTests => self.run_tests(false),
+            Action::ToggleDebugOverlay => self.debug.visible = !self.debug.visible,
+            Action::ToggleLog => {
+                self.log.toggle();
+                if !self.log.is_visible() {
+                    self.focus = Focus::Table;
+                }
+            }
+            Action::FocusLog => {
+                if self.log.is_visible() {
+                    self.focus = Focus::Log;
+                }
+            }
+            Action::ShowProblems => self.problems.visible = true,
+            Action::ShowDiagnostics => self.diagnostics.visible = true,
+            Action::ShowBuildTypes => self.build_type.open(&self.editor, self.build_config.as_deref()),
+            Action::ShowTriplets => self.show_triplets(),
+            Action::ShowSubBuilds => self.show_sub_builds(),
+            Action::ShowUsages => self.show_usages(),
+            Action::Explain => {
+                if let Err(e) = self.explain_selected() {
+                    self.message = Some(e);
+                }
+            }
+            Action::ShowLauncher => self.launcher.open(),
+            Action::ShowFlagBundles => self.flag_bundles.visible = true,
+            Action::ShowToolchain => self.toolchain.open(&self.build_dir),
+            Action::PickToolchain => self.open_toolchain_picker(),
+            Action::PickGenerator => self.open_generator_picker(),
+            Action::ToggleCompileCommands => self.toggle_compile_commands(),
+            Action::ShowDuplicates => self.show_duplicates(),
+            Action::OpenEditor => self.open_editor = self.editor.selected().is_some(),
+            Action::OpenListEditor => self.open_list_editor(),
+            Action::EditAllowedValues => self.edit_allowed_values(),
+            Action::ToggleAdvancedFlag => self.toggle_advanced_flag(),
+            Action::NewEntry => self.open_new_entry(false),
+            Action::DuplicateEntry => self.open_new_entry(true),
+            Action::DeleteEntry => self.ask_delete_entry(),
+            Action::DiscardEdits => self.ask_discard_edits(),
+            Action::TogglePin => self.toggle_pin(),
+            Action::ShowQuickSettings => match self.quick_settings.as_mut() {
+                Some(pane) => pane.visible = true,
+                None => self.message = Some("The project has no cmake-tui.json with quick settings".to_string()),
+            },
+            Action::ShowAbout => {
+                let cmake_version = build_info::cmake_version();
+                self.about = Some(build_info::build_info_lines(cmake_version.as_deref()));
+            }
+            Action::Set(name, value) => {
+                self.set_value(&name, value);
+            }
+            Action::SetMark(letter) => match (letter, self.editor.selected().map(|v| v.var.name.clone())) {
+                ('a'..='z', Some(name)) => {
+                    self.message = Some(format!("Marked {} as '{}", name, letter));
+                    self.marks.insert(letter, name);
+                }
+                ('a'..='z', None) => self.message = Some(self.nothing_selected()),
+                _ => self.message = Some("Marks are the letters a-z".to_string()),
+            },
+            Action::JumpToMark('\'') => self.dispatch(Action::JumpBack),
+            Action::JumpToMark(letter) => match self.marks.get(&letter).cloned() {
+                Some(name) => {
+                    let from = self.editor.selected().map(|v| v.var.name.clone());
+                    if self.jump_to(&name)
+                        && let Some(from) = from
+                        && from != name
+                    {
+                        self.jumps.record(from);
+                    }
+                }
+                None => self.message = Some(format!("No mark '{}", letter)),
+            },
+            Action::ListMarks => {
+                self.message = Some(match self.marks.is_empty() {
+                    true => "No marks, m{a-z} marks the selected entry".to_string(),
+                    false => self.marks.iter().map(|(letter, name)| format!("'{} {}", letter, name)).collect::<Vec<_>>().join("  "),
+                });
+            }
+            Action::JumpBack => {
+                let current = self.editor.selected().map(|v| v.var.name.clone());
+                match self.jumps.back(current.as_deref()) {
+                    Some(name) => _ = self.jump_to(&name),
+                    None => self.message = Some("At the oldest jump".to_string()),
+                }
+            }
+            Action::JumpForward => match self.jumps.forward() {
+                Some(name) => _ = self.jump_to(&name),
+                None => self.message = Some("At the newest jump".to_string()),
+            },
+            Action::OpenShell => self.open_shell = true,
+            Action::ShowEnv => self.env.open(),
+            Action::SetEnv(name, value) => {
+                self.message = Some(match &value {
+                    Some(value) => format!("{}={} for the next configures", name, value),
+                    None => format!("{} unset for the next configures", name),
+                });
+                self.set_env(name, Some(value));
+            }
+            Action::RunCommand(line) => self.run_command = Some(line),
+            Action::Repeat => match self.last_change.clone() {
+                Some(change) => self.dispatch(change),
+                None => self.message = Some("Nothing to repeat yet".to_string()),
+            },
+            Action::Save => self.save(false),
+            Action::ForceSave => self.save(true),
+            Action::SaveAndQuit => {
+                self.save(false);
+                if self.modified_count() == 0 {
+                    self.quit();
+                }
+            }
+            Action::SetAutoSave(auto_save) => {
+                if let Some(auto_save) = auto_save {
+                    self.config.auto_save = auto_save;
+                }
+                self.message = Some(match self.config.auto_save {
+                    AutoSave::Off => "Edits are only saved with :w".to_string(),
+                    AutoSave::Configure => "Edits are saved before each configure".to_string(),
+                    AutoSave::Exit => "Edits are saved when quitting, :q! drops them".to_string(),
+                    AutoSave::Always => "Edits are saved before each configure and when quitting".to_string(),
+                });
+            }
+            Action::SetFilter(filter) => match (self.active_screen(), filter) {
+                (Screen::Targets, None) => self.targets.clear_filter(),
+                (Screen::Tests, None) => self.tests.clear_filter(),
+                (_, filter) => self.editor.set_filter(filter),
+            },
+            Action::SetSort(order) => self.editor.set_sort(order),
+            Action::ShowSnapshots => self.show_snapshots(),
+            Action::ShowHistory => {
+                self.journal.set_entries(journal::read(&self.sidecar.dir));
+                self.journal.visible = true;
+            }
+            Action::TakeSnapshot(name) => self.take_snapshot(&name),
+            Action::ExportPreset(name) => self.export_preset(&name),
+            Action::ShowSidecar => {
+                if let Some(dir) = self.ensure_sidecar()
+                    && self.dialog.is_none()
+                {
+                    self.message = Some(format!("Tool state is stored in {}", dir.display()));
+                }
+            }
+        }
+
+        #[cfg(feature = "time-travel")]
+        self.time_travel.record(label, &self.editor);
+    }
 
-impl From<CacheVar> for CacheVarTui {
-    fn from(var: CacheVar) -> Self {
-        CacheVarTui {
-            new_val: var.value.clone(),
-            var: var,
+    #[cfg(feature = "time-travel")]
+    fn handle_time_travel_key(&mut self, key: KeyEvent) {
+        let state = match key.code {
+            KeyCode::F(12) | KeyCode::Esc => {
+                self.time_travel.visible = false;
+                None
+            }
+            KeyCode::Char('h') | KeyCode::Left | KeyCode::Char('k') | KeyCode::Up => self.time_travel.step_back(),
+            KeyCode::Char('l') | KeyCode::Right | KeyCode::Char('j') | KeyCode::Down => self.time_travel.step_forward(),
+            _ => None,
+        };
+        if let Some(state) = state {
+            self.editor = state;
         }
     }
-}
-
-struct CacheVarList {
-    vars: Vec<CacheVarTui>,
-    row_idx_var_idx_map: HashMap<usize, usize>,
-    longest_name: usize,
-    state: TableState,
-}
-
-impl App {
-    pub fn new(build_dir: PathBuf) -> Self {
-        let vec: Vec<CacheVar> =
-            parse_cmake_cache(build_dir).unwrap_or_default();
 
-        let tui_vec: Vec<CacheVarTui> = vec
-                    .into_iter()
-                    .map(CacheVarTui::from) // Uses the impl From we just wrote
-                    .collect();
+    fn handle_command_input_mode_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.mode = AppMode::Scroll,
+            KeyCode::Enter => {
+                self.mode = AppMode::Scroll;
+                match parse_command(self.command_input.text()) {
+                    // `:q` refuses to drop unsaved edits, like vim
+                    Ok(Action::Quit) if self.modified_count() > 0 && !self.config.auto_save.on_exit() => {
+                        self.message = Some(format!(
+                            "{} unsaved changes, use :wq to save or :q! to discard them",
+                            self.modified_count()
+                        ));
+                    }
+                    Ok(action) => self.dispatch(action),
+                    Err(e) if e.is_empty() => {}
+                    Err(e) => self.message = Some(e),
+                }
+            }
+            KeyCode::Backspace if self.command_input.text().is_empty() => self.mode = AppMode::Scroll,
+            _ => { self.command_input.handle_key(key); }
+        }
+    }
 
-        let max_len = tui_vec
-            .iter()
-            .map(|i| i.var.name.chars().count())
-            .max()
-            .unwrap_or(100); // Default fallback width
-        
-        let var_list = CacheVarList {
-            vars: tui_vec,
-            row_idx_var_idx_map: HashMap::new(),
-            longest_name: max_len,
-            state: TableState::default(),
+    /// Set a value typed by the user. Returns false when it was refused.
+    fn set_value(&mut self, name: &str, value: String) -> bool {
+        let warning = match self.editor.check_value(name, &value) {
+            Ok(warning) => warning,
+            Err(problem) => {
+                self.message = Some(problem);
+                return false;
+            }
         };
+        if !self.editor.set_value(name, value) {
+            self.message = Some(format!("No cache variable named {}", name));
+            return false;
+        }
+        self.message = warning;
+        true
+    }
 
-        Self {
-            should_exit: false,
-            var_list: var_list,
-            mode: AppMode::Scroll,
-            show_advanced: false,
-
-            search_input: "".to_string(),
-            cursor_pos: 0,
+    fn record_journal(&mut self, action: &str, changes: Vec<JournalChange>) {
+        if changes.is_empty() {
+            return;
+        }
+        // Values cmake changed itself weren't asked for
+        if action != "cmake" {
+            journal::merge_changes(&mut self.applied_changes, &changes);
         }
+        let Some(dir) = self.ensure_sidecar() else { return };
+        if let Err(e) = journal::append(&dir, &JournalEntry::new(action, changes)) {
+            self.message = Some(format!("Failed to write the change journal: {}", e));
+        }
+        self.refresh_last_changes();
     }
 
-    pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
-        self.rebuild_idx_map();
-        while !self.should_exit {
-            terminal.draw(|frame| frame.render_widget(&mut self, frame.area()))?;
-            if let Event::Key(key) = event::read()? {
-                self.handle_key(key);
-            };
+    /// What goes into a crash report: where the app was and the pending
+    /// edits, which are lost with the crash
+    fn crash_state(&self) -> String {
+        let mut out = format!("build dir: {}\n", self.build_dir.display());
+        out.push_str(&format!("screen: {:?}, focus: {:?}\n", self.active_screen(), self.focus));
+        out.push_str(&format!(
+            "entries: {}, {} shown, {} modified\n",
+            self.editor.vars.len(),
+            self.editor.visible_count(),
+            self.editor.modified_count()
+        ));
+        if let Some(task) = &self.task {
+            out.push_str(&format!("running: {}\n", task.kind));
         }
-        Ok(())
+        out.push_str(&format!("last event: {}\n", self.debug.last_event()));
+        for change in journal::pending_changes(&self.editor) {
+            out.push_str(&format!("pending: {}={}\n", change.name, change.new));
+        }
+        out
     }
 
-    fn handle_scroll_mode_key(&mut self, key: KeyEvent){
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Esc  => self.should_exit = true,
-            // KeyCode::Char('h') | KeyCode::Left => self.select_none(),
-            KeyCode::Char('j') | KeyCode::Down => self.select_next(),
-            KeyCode::Char('k') | KeyCode::Up   => self.select_previous(),
-            KeyCode::Char('g') | KeyCode::Home => self.select_first(),
-            KeyCode::Char('G') | KeyCode::End  => self.select_last(),
-            KeyCode::Char('t')  => self.toggle_show_advanced(),
-            KeyCode::Enter => self.edit_value(),
-            KeyCode::Char(' ') => self.cycle_value(),
-            KeyCode::Char('/') => self.search_var(),
-            KeyCode::Char('n') => self.select_next_search_result(),
-            _ => {}
+    /// Log `event` to --log-file and show it as the last one in the debug overlay
+    fn trace_event(&mut self, event: String) {
+        tracing::debug!("{}", event);
+        self.debug.record_event(event);
+        self.crash_state_changed = true;
+    }
+
+    fn refresh_last_changes(&mut self) {
+        let entries = journal::read(&self.sidecar.dir);
+        self.editor.set_last_changes(journal::last_changes(&entries));
+    }
+
+    /// Values cmake changed itself during a configure, not the ones it was
+    /// passed, comparing the cache before and after
+    fn cmake_changes(&self, before: &HashMap<String, String>, passed: &[JournalChange]) -> Vec<JournalChange> {
+        // Everything is new after the first configure, nothing worth listing
+        if before.is_empty() {
+            return Vec::new();
         }
+        self.editor
+            .vars
+            .iter()
+            // cmake's own bookkeeping changes on every configure
+            .filter(|v| v.var.typ != VarType::Internal)
+            .filter(|v| before.get(&v.var.name) != Some(&v.var.value))
+            .filter(|v| !passed.iter().any(|p| p.name == v.var.name && p.new == v.var.value))
+            .map(|v| JournalChange {
+                name: v.var.name.clone(),
+                old: before.get(&v.var.name).cloned(),
+                new: v.var.value.clone(),
+            })
+            .collect()
+    }
+
+    fn modified_count(&self) -> usize {
+        self.editor.modified_count()
     }
 
-    fn rebuild_idx_map(&mut self){
-        self.var_list.row_idx_var_idx_map.clear();
-        for (original_idx, var) in self.var_list.vars.iter().enumerate(){
-            if self.show_advanced || !var.var.advanced {
-                let row_idx = self.var_list.row_idx_var_idx_map.len();
-                self.var_list.row_idx_var_idx_map.insert(row_idx, original_idx);
+    /// Write pending edits to CMakeCache.txt. Unless forced, refuses while
+    /// a cmake process works on the build dir, since it would overwrite the
+    /// cache (or read it half written).
+    fn save(&mut self, force: bool) {
+        match self.write_changes(force) {
+            Ok(0) => self.message = Some("No changes to save".to_string()),
+            Ok(count) => {
+                if self.message.is_none() {
+                    self.message = Some(format!("Saved {} changes to CMakeCache.txt", count));
+                }
+            }
+            Err(SaveError::Busy(e)) => self.message = Some(e),
+            Err(SaveError::Failed(e)) => {
+                tracing::warn!("saving failed: {}", e);
+                self.dialog = Some(
+                    Dialog::error("Failed to save CMakeCache.txt", "The pending edits are kept, nothing was written.")
+                        .details(e.to_string()),
+                );
+            }
+            Err(SaveError::Upload(e)) => {
+                tracing::warn!("uploading the cache failed: {}", e);
+                self.dialog = Some(
+                    Dialog::error(
+                        "Failed to write the remote CMakeCache.txt",
+                        "The pending edits are kept, :w sends them again.",
+                    )
+                    .details(e),
+                );
             }
         }
     }
 
-    // fn get_selected_var_idx(&self) -> Option<usize> {
-    //     self.var_list.state.selected()
-    //         .and_then(|row_idx| self.var_list.row_idx_var_idx_map.get(&row_idx))
-    //         .copied()
-    // }
+    /// Write pending edits, returning how many were written
+    fn write_changes(&mut self, force: bool) -> std::result::Result<usize, SaveError> {
+        let (changes, added) = self.editor.pending_changes();
+        if changes.is_empty() && added.is_empty() {
+            return Ok(0);
+        }
+        if !force && let Some(reason) = self.busy_reason() {
+            return Err(SaveError::Busy(format!("Not saving, {}. Use :w! to save anyway", reason)));
+        }
 
-    fn check_if_var_is_modified(&self, var: &CacheVarTui) -> bool {
-        var.new_val != var.var.value
+        let edits = journal::pending_changes(&self.editor);
+        match write_cache_changes(&self.build_dir, &changes, &added) {
+            Ok(()) => {
+                upload_cache().map_err(SaveError::Upload)?;
+                self.editor.mark_saved();
+                self.record_journal("save", edits);
+                self.notify_vars();
+                Ok(changes.len() + added.len())
+            }
+            Err(e) => Err(SaveError::Failed(e)),
+        }
     }
 
-
     fn handle_search_input_mode_key(&mut self, key: KeyEvent){
         match key.code {
-            KeyCode::Char(c) => {
-                self.search_input.insert(self.cursor_pos, c);
-                self.cursor_pos += 1;
-            }
             KeyCode::Esc  => {
-                self.cursor_pos = 0;
                 self.search_input.clear();
                 self.mode = AppMode::Scroll;
-            }
-            // KeyCode::Char('h') | KeyCode::Left => self.select_none(),
-            KeyCode::Backspace => {
-                if self.search_input.len() > 0 {
-                    self.search_input.pop();
-                    self.cursor_pos -= 1;
+                if let Some(origin) = self.search_origin.take() {
+                    self.editor.table.select(Some(origin));
                 }
             }
-            KeyCode::Left => {
-                if self.cursor_pos > 0{
-                    self.cursor_pos -= 1;
+            KeyCode::Enter => {
+                self.mode = AppMode::Scroll;
+                self.remember_search(self.search_input.text().to_string());
+                if self.active_screen() == Screen::Log {
+                    self.log.search(self.search_input.text());
+                } else {
+                    // From where the search started, so it counts as one jump
+                    if let Some(origin) = self.search_origin.take() {
+                        self.editor.table.select(Some(origin));
+                    }
+                    self.select_next_search_result();
                 }
             }
-            KeyCode::Right => {
-                if self.cursor_pos < self.search_input.len() {
-                    self.cursor_pos += 1;
-                }
+            KeyCode::Up => {
+                self.recall_search(true);
+                self.show_search_match();
+            }
+            KeyCode::Down => {
+                self.recall_search(false);
+                self.show_search_match();
+            }
+            _ => {
+                self.search_input.handle_key(key);
+                self.show_search_match();
+            }
+        }
+    }
+
+    /// Select the first match of what's typed so far, from the row the
+    /// search started on, or that row again when nothing matches
+    fn show_search_match(&mut self) {
+        let Some(origin) = self.search_origin else { return };
+        self.editor.table.select(Some(origin));
+        self.editor.select_next_match(self.search_input.text());
+    }
+
+    fn remember_search(&mut self, query: String) {
+        self.search_history_pos = None;
+        if query.is_empty() { return; }
+        self.search_history.retain(|q| *q != query);
+        self.search_history.push(query);
+        if self.search_history.len() > MAX_SEARCH_HISTORY {
+            self.search_history.remove(0);
+        }
+    }
+
+    /// Replace the search line with an older (or newer) query from the history,
+    /// going past the newest one brings back what was typed
+    fn recall_search(&mut self, older: bool) {
+        let pos = match (&self.search_history_pos, older) {
+            (None, true) if !self.search_history.is_empty() => {
+                self.search_history_pos = Some((self.search_history.len(), self.search_input.text().to_string()));
+                self.search_history.len() - 1
+            }
+            (Some((pos, _)), true) => pos.saturating_sub(1),
+            (Some((pos, _)), false) if pos + 1 < self.search_history.len() => pos + 1,
+            (Some(_), false) => {
+                let (_, typed) = self.search_history_pos.take().unwrap_or_default();
+                self.search_input = TextInput::new(&typed);
+                return;
             }
+            (None, _) => return,
+        };
+        if let Some((current, _)) = &mut self.search_history_pos {
+            *current = pos;
+        }
+        self.search_input = TextInput::new(&self.search_history[pos]);
+    }
+
+    fn handle_value_edit_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.mode = AppMode::Scroll,
             KeyCode::Enter => {
-                self.mode = AppMode::Scroll;
-                self.select_next_search_result();
+                let Some(name) = self.editor.selected().map(|v| v.var.name.clone()) else {
+                    self.mode = AppMode::Scroll;
+                    self.message = Some(format!("Value not set: {}", self.nothing_selected()));
+                    return;
+                };
+                // A refused value stays in the editor to be corrected
+                let value = self.value_input.text().to_string();
+                if self.set_value(&name, value.clone()) {
+                    self.mode = AppMode::Scroll;
+                    self.last_change = Some(Action::SetValue(value));
+                }
+            }
+            _ => {
+                self.value_input.handle_key(key);
             }
-            _ => {}
         }
     }
 
@@ -189,258 +3292,502 @@ impl App {
         if key.kind != KeyEventKind::Press {
             return;
         }
+        self.message = None;
+        match key.modifiers.is_empty() {
+            true => self.trace_event(format!("key {:?}", key.code)),
+            false => self.trace_event(format!("key {:?} {:?}", key.code, key.modifiers)),
+        }
+
+        // Above everything else, the overlay is for looking into any state
+        #[cfg(feature = "time-travel")]
+        if self.time_travel.visible {
+            self.handle_time_travel_key(key);
+            return;
+        } else if key.code == KeyCode::F(12) {
+            self.time_travel.visible = true;
+            return;
+        }
+
+        // Job control works whatever has the keys
+        if key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.suspend = true;
+            return;
+        }
 
-        if self.mode == AppMode::Scroll{
-            self.handle_scroll_mode_key(key);
+        if self.dialog.is_some() {
+            self.handle_dialog_key(key);
+        } else if self.install_prompt.is_some() {
+            self.handle_install_prompt_key(key);
+        } else if self.about.is_some() {
+            // Any key closes the about popup
+            self.about = None;
+        } else if self.mode == AppMode::Scroll && self.disk_usage.visible {
+            self.handle_disk_usage_key(key);
+        } else if self.mode == AppMode::Scroll && self.fresh.is_some() {
+            self.handle_fresh_key(key);
+        } else if self.mode == AppMode::Scroll && self.generator_picker.is_some() {
+            self.handle_generator_picker_key(key);
+        } else if self.mode == AppMode::Scroll && self.missing_cache.is_some() && self.active_screen() == Screen::Cache {
+            self.handle_missing_cache_key(key);
+        } else if self.mode == AppMode::Scroll && self.new_entry.is_some() {
+            self.handle_new_entry_key(key);
+        } else if self.mode == AppMode::Scroll && self.list_editor.is_some() {
+            self.handle_list_editor_key(key);
+        } else if self.mode == AppMode::Scroll && self.journal.visible {
+            self.handle_journal_key(key);
+        } else if self.mode == AppMode::Scroll && self.snapshots.visible {
+            self.handle_snapshots_key(key);
+        } else if self.mode == AppMode::Scroll && self.env.visible {
+            self.handle_env_key(key);
+        } else if self.mode == AppMode::Scroll && self.duplicates.visible {
+            self.handle_duplicates_key(key);
+        } else if self.mode == AppMode::Scroll && self.problems.visible {
+            self.handle_problems_key(key);
+        } else if self.mode == AppMode::Scroll && self.quick_settings.as_ref().is_some_and(|p| p.visible) {
+            self.handle_quick_settings_key(key);
+        } else if self.mode == AppMode::Scroll && self.diagnostics.visible {
+            self.handle_diagnostics_key(key);
+        } else if self.mode == AppMode::Scroll && self.build_type.visible {
+            self.handle_build_type_key(key);
+        } else if self.mode == AppMode::Scroll && self.package.visible {
+            self.handle_package_key(key);
+        } else if self.mode == AppMode::Scroll && self.triplets.visible {
+            self.handle_triplets_key(key);
+        } else if self.mode == AppMode::Scroll && self.sub_builds.visible {
+            self.handle_sub_builds_key(key);
+        } else if self.mode == AppMode::Scroll && self.usages.visible {
+            self.handle_usages_key(key);
+        } else if self.mode == AppMode::Scroll && self.launcher.visible {
+            self.handle_launcher_key(key);
+        } else if self.mode == AppMode::Scroll && self.flag_bundles.visible {
+            self.handle_flag_bundles_key(key);
+        } else if self.mode == AppMode::Scroll && self.toolchain.visible {
+            self.handle_toolchain_key(key);
+        } else if self.mode == AppMode::Scroll && self.value_pane.visible {
+            self.handle_value_pane_key(key);
+        } else if self.mode == AppMode::Scroll && self.toolchain_picker.is_some() {
+            self.handle_toolchain_picker_key(key);
+        } else if self.mode == AppMode::Scroll && self.profile.visible {
+            self.handle_profile_key(key);
+        } else if self.mode == AppMode::Scroll && self.explain.visible {
+            self.handle_explain_key(key);
+        } else if self.mode == AppMode::Scroll {
+            self.handle_screen_key(key);
         } else if self.mode == AppMode::SearchInput {
             self.handle_search_input_mode_key(key);
+        } else if self.mode == AppMode::CommandInput {
+            self.handle_command_input_mode_key(key);
+        } else if self.mode == AppMode::ValueEdit {
+            self.handle_value_edit_key(key);
         }
     }
 
     fn select_next_search_result(&mut self){
         if self.mode != AppMode::Scroll { return; }
-        if self.search_input.is_empty() { return; }
-
-        let query = self.search_input.to_lowercase();
-
-        let start_row = self.var_list.state.selected().unwrap_or(0);
-        let last_row = self
-            .var_list
-            .row_idx_var_idx_map
-            .len()-1;
-
-        // Search the list starting from the current row until the end.
-        // Once it wraps to the end search again from the begining of the list to the start row
-        let search_order = (start_row + 1..last_row).chain(0..=start_row);
-
-        for row in search_order {
-            let var_idx = *self.var_list.row_idx_var_idx_map.get(&row).unwrap();
-            let var = &self.var_list.vars.get(var_idx).unwrap();
-            if var.var.name.to_lowercase().starts_with(&query){
-                self.var_list.state.select(Some(row));
-                return
-            }
-        }
+        let query = self.search_input.text().to_string();
+        self.jump(|app| app.editor.select_next_match(&query));
     }
 
-    fn toggle_show_advanced(&mut self) {
-        self.show_advanced = !self.show_advanced;
-        self.rebuild_idx_map();
+    fn select_previous_search_result(&mut self) {
+        if self.mode != AppMode::Scroll { return; }
+        let query = self.search_input.text().to_string();
+        self.jump(|app| app.editor.select_previous_match(&query));
     }
 
-    fn select_next(&mut self) {
-        if self.mode != AppMode::Scroll {return}
-        self.var_list.state.select_next();
-    }
-    fn select_previous(&mut self) {
-        if self.mode != AppMode::Scroll {return}
-        self.var_list.state.select_previous();
+    /// Move the selection in a way that counts as a jump, remembering where
+    /// it was for Ctrl-o
+    fn jump(&mut self, move_selection: impl FnOnce(&mut Self)) {
+        let from = self.editor.selected().map(|v| v.var.name.clone());
+        move_selection(self);
+        if let Some(from) = from
+            && self.editor.selected().is_none_or(|v| v.var.name != from)
+        {
+            self.jumps.record(from);
+        }
     }
 
-    fn select_first(&mut self) {
-        if self.mode != AppMode::Scroll {return}
-        self.var_list.state.select_first();
+    /// Select an entry the jump list or a mark names, unless it's gone
+    fn jump_to(&mut self, name: &str) -> bool {
+        if self.editor.get(name).is_none() {
+            self.message = Some(format!("{} is no longer in the cache", name));
+            return false;
+        }
+        self.screens.open(Screen::Cache);
+        self.editor.reveal(name)
     }
 
-    fn select_last(&mut self) {
-        if self.mode != AppMode::Scroll {return}
-        self.var_list.state.select_last();
+    fn push_count_digit(&mut self, digit: char) {
+        let digit = digit.to_digit(10).unwrap_or(0) as usize;
+        let count = self.count.unwrap_or(0).saturating_mul(10).saturating_add(digit);
+        self.count = Some(count.min(MAX_COUNT));
     }
 
     fn search_var(&mut self) {
         if self.mode != AppMode::Scroll {return}
+        self.count = None;
         self.search_input.clear();
-        self.cursor_pos = 0;
+        self.search_history_pos = None;
+        self.search_origin = match self.active_screen() {
+            Screen::Cache => self.editor.table.selected(),
+            _ => None,
+        };
         self.mode = AppMode::SearchInput;
     }
 
-    fn cycle_value(&mut self) {
-        if self.mode != AppMode::Scroll {return}
-
-        let var: &mut CacheVarTui = self.get_selected_var_mut().unwrap(); 
-
-        if var.var.typ == VarType::Bool {
-            var.new_val = CacheVar::toggle_bool(&var.new_val);
-        } else if var.var.typ == VarType::Enum {
-            var.new_val = var.var.cycle_enum(&var.new_val);
+    /// Set the selected entry back to the default the project declares for it
+    fn reset_to_default(&mut self) {
+        let Some(var) = self.editor.selected() else { return };
+        let name = var.var.name.clone();
+        match self.editor.deviation(var).map(|d| d.value.clone()) {
+            Some(value) => {
+                if self.set_value(&name, value.clone()) {
+                    self.message = Some(format!("{} reset to the project default '{}'", name, value));
+                }
+            }
+            None if self.editor.defaults.contains_key(&name) => {
+                self.message = Some(format!("{} already has the project default", name));
+            }
+            None => self.message = Some(format!("The project doesn't declare a default for {}", name)),
         }
-
     }
 
+    /// Edit the selected value: lists in the list editor, other values as text
     fn edit_value(&mut self) {
-        if self.mode == AppMode::ValueEdit {
-            self.mode = AppMode::Scroll;
-
-        } else if self.mode == AppMode::Scroll {
-            if self.get_selected_var().unwrap().var.typ == VarType::Bool {
-                // self.mode = AppMode::ValueEdit
-            }
+        if self.mode != AppMode::Scroll {
+            return;
+        }
+        let Some(var) = self.editor.selected() else { return };
+        // The triplet is picked like an enum value, from the installed ones
+        if var.var.name == TRIPLET_VAR && self.triplets.open(&self.editor) {
+            return;
+        }
+        if var.var.typ != VarType::Bool && is_list(&var.new_val) {
+            self.open_list_editor();
+        } else {
+            self.value_input = TextInput::new(&var.new_val);
+            self.mode = AppMode::ValueEdit;
         }
     }
 }
 
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let [title_area, main_area, help_area] = Layout::vertical([
+        let [title_area, banner_area, main_area, status_area] = Layout::vertical([
             Constraint::Length(1),
+            Constraint::Length(if self.stale_files.is_empty() { 0 } else { 1 }),
             Constraint::Fill(1),
             Constraint::Length(1),
         ])
         .areas(area);
 
-        let [list_area, footer_area] =
-            Layout::vertical([Constraint::Fill(9), Constraint::Fill(1)]).areas(main_area);
+        let fullscreen_log = self.screens.current() == Screen::Log;
+        let (table_area, log_area) = match self.log.view {
+            _ if fullscreen_log => (Rect::default(), Some(main_area)),
+            LogView::Hidden => (main_area, None),
+            LogView::Pane => {
+                let [table_area, log_area] =
+                    Layout::vertical([Constraint::Fill(3), Constraint::Fill(2)]).areas(main_area);
+                (table_area, Some(log_area))
+            }
+        };
+
+        self.render_title_header(title_area, buf);
+        self.render_stale_banner(banner_area, buf);
+        self.render_status_bar(status_area, buf);
 
-        App::render_title_header(title_area, buf);
-        App::render_help_footer(help_area, buf);
-        self.render_var_table(list_area, buf);
+        if let Some(log_area) = log_area {
+            self.log.render(log_area, buf, fullscreen_log || self.focus == Focus::Log);
+        }
 
-        if self.mode != AppMode::SearchInput{
-            self.render_selected_var(footer_area, buf);
-        } else {
-            self.render_search_footer(footer_area, buf);
+        if !table_area.is_empty() {
+            match self.screens.current() {
+                Screen::Cache => match &self.missing_cache {
+                    Some(screen) => screen.render(&self.build_dir, self.task_running(), table_area, buf),
+                    None => CacheEditorWidget::new()
+                        .pending_count(self.count)
+                        .render(table_area, buf, &mut self.editor),
+                },
+                Screen::Targets => self.targets.render(table_area, buf),
+                Screen::Tests => self.tests.render(table_area, buf),
+                Screen::Diff => self.diff.render(&self.editor, table_area, buf),
+                Screen::Help => self.help.render(table_area, buf),
+                // Drawn over the whole main area above
+                Screen::Log => {}
+            }
+        }
+
+        if matches!(self.mode, AppMode::SearchInput | AppMode::CommandInput | AppMode::ValueEdit) {
+            // Takes the place of the description pane, or the bottom of the
+            // log in fullscreen log mode
+            let search_area = if table_area.is_empty() {
+                Rect { y: main_area.bottom().saturating_sub(2), height: 2, ..main_area }
+            } else {
+                CacheEditorWidget::layout(table_area)[1]
+            };
+            Clear.render(search_area, buf);
+            self.render_search_footer(search_area, buf);
+        }
+
+
+        if self.problems.visible {
+            self.problems.render(popup_area(area, 80, 70), buf);
+        }
+        if let Some(pane) = self.quick_settings.as_mut().filter(|p| p.visible) {
+            pane.render(&self.editor, popup_area(area, 80, 70), buf);
+        }
+        if self.diagnostics.visible {
+            self.diagnostics.render(&self.editor.parse_warnings, popup_area(area, 80, 70), buf);
+        }
+        if self.build_type.visible {
+            self.build_type.render(popup_area(area, 40, 30), buf);
+        }
+        if self.triplets.visible {
+            self.triplets.render(popup_area(area, 40, 60), buf);
+        }
+        if self.sub_builds.visible {
+            self.sub_builds.render(popup_area(area, 80, 50), buf);
+        }
+        if self.usages.visible {
+            self.usages.render(popup_area(area, 80, 70), buf);
+        }
+        if self.launcher.visible {
+            self.launcher.render(&self.editor, popup_area(area, 60, 40), buf);
+        }
+        if self.flag_bundles.visible {
+            self.flag_bundles.render(&self.editor, popup_area(area, 80, 70), buf);
+        }
+        if self.toolchain.visible {
+            self.toolchain.render(&self.editor, popup_area(area, 80, 70), buf);
+        }
+        if self.value_pane.visible {
+            self.value_pane.render(popup_area(area, 80, 60), buf);
+        }
+        if let Some(picker) = self.toolchain_picker.as_mut() {
+            picker.render(popup_area(area, 80, 50), buf);
+        }
+        if let Some((picker, _)) = self.generator_picker.as_mut() {
+            picker.render(popup_area(area, 80, 60), buf);
+        }
+        if let Some(pane) = self.fresh.as_mut() {
+            pane.render(popup_area(area, 80, 60), buf);
+        }
+        if self.disk_usage.visible {
+            self.disk_usage.render(popup_area(area, 80, 60), buf);
+        }
+        if self.profile.visible {
+            self.profile.render(popup_area(area, 80, 70), buf);
+        }
+        if self.explain.visible {
+            self.explain.render(popup_area(area, 80, 70), buf);
+        }
+        if self.env.visible {
+            self.env.render(&self.editor, popup_area(area, 80, 70), buf);
+        }
+        if self.duplicates.visible {
+            self.duplicates.render(popup_area(area, 80, 70), buf);
+        }
+        if self.snapshots.visible {
+            self.snapshots.render(popup_area(area, 80, 70), buf);
+        }
+        if self.journal.visible {
+            self.journal.render(popup_area(area, 80, 70), buf);
+        }
+        if let Some(list_editor) = self.list_editor.as_mut() {
+            list_editor.render(popup_area(area, 60, 60), buf);
+        }
+        if let Some(form) = &self.new_entry {
+            form.render(popup_area(area, 60, 30), buf);
+        }
+        if let Some(about) = &self.about {
+            render_about(about, popup_area(area, 40, 30), buf);
+        }
+        if let Some(input) = &self.install_prompt {
+            self.render_install_prompt(input, popup_area(area, 60, 30), buf);
+        }
+        if self.package.visible {
+            self.package.render(popup_area(area, 50, 50), buf);
+        }
+        if let Some(dialog) = &self.dialog {
+            dialog.render(popup_area(area, 60, 30), buf);
+        }
 
+        #[cfg(feature = "time-travel")]
+        if self.time_travel.visible {
+            self.time_travel.render(popup_area(area, 80, 70), buf);
+        }
+        if self.debug.visible {
+            self.debug.render(area, buf, &self.editor);
         }
 
-        self.render_popup(area, buf);
+        if self.config.monochrome {
+            strip_colors(buf);
+        }
     }
 }
 
 impl App {
-    fn render_title_header(area: Rect, buf: &mut Buffer) {
-        Paragraph::new("CMake-TUI")
-            .bold()
-            .centered()
-            .render(area, buf);
-    }
-
-    fn render_help_footer(area: Rect, buf: &mut Buffer) {
-        Paragraph::new("Use ↓↑ to move, <Space> to cycle value, <Enter> to edit value, / to search, n to cycle search results, t to toggle advanced, g/G to go top/bottom.")
-            .centered()
-            .render(area, buf);
+    fn render_title_header(&self, area: Rect, buf: &mut Buffer) {
+        // Inside a dependency build, say which so it's not mistaken for the project
+        let title = match self.parent_builds.is_empty() {
+            true if let Some(remote) = remote::current() => format!("CMake-TUI: {}", remote.url()),
+            true => "CMake-TUI".to_string(),
+            false => format!("CMake-TUI: {} (:deps to go back)", self.build_dir.display()),
+        };
+        // Tabs of the views, the current one highlighted
+        let mut spans = vec![Span::raw(format!("{}  ", title)).bold()];
+        for tab in Screen::TABS {
+            let label = Span::raw(format!(" {} ", tab));
+            spans.push(if tab == self.screens.tab() { label.reversed() } else { label.dim() });
+        }
+        if !self.screens.current().is_tab() {
+            spans.push(Span::raw(format!(" › {}", self.screens.current())).bold());
+        }
+        Line::from(spans).centered().render(area, buf);
+        if let Some(launcher) = launcher::current_launcher(&self.editor) {
+            Line::raw(format!("launcher: {} ", launcher)).right_aligned().dim().render(area, buf);
+        }
+        if let Some(manager) = package_manager::detect(&self.editor) {
+            let triplet = self.editor.get(TRIPLET_VAR).map(|v| v.new_val.as_str()).filter(|t| !t.is_empty());
+            let text = match (manager, triplet) {
+                (PackageManager::Vcpkg, Some(triplet)) => format!(" vcpkg: {}", triplet),
+                _ => format!(" {}", manager),
+            };
+            Line::raw(text).left_aligned().dim().render(area, buf);
+        }
     }
 
-    fn get_selected_var_mut(&mut self) -> Option<&mut CacheVarTui> {
-        let row_idx = self.var_list.state.selected()?;
-        let var_idx = *self.var_list.row_idx_var_idx_map.get(&row_idx)?;
-        self.var_list.vars.get_mut(var_idx)
+    fn render_stale_banner(&self, area: Rect, buf: &mut Buffer) {
+        let Some(name) = self.stale_files.first() else { return };
+        let more = match self.stale_files.len() {
+            1 => String::new(),
+            n => format!(" and {} more file(s)", n - 1),
+        };
+        Line::raw(format!(
+            " {}{} changed since the last configure, the cache may be out of date. c to configure ",
+            name.display(),
+            more
+        ))
+        .centered()
+        .fg(Color::Black)
+        .bg(AMBER.c400)
+        .render(area, buf);
     }
 
-    fn get_selected_var(&self) -> Option<&CacheVarTui> {
-        let row_idx = self.var_list.state.selected()?;
-        let var_idx = *self.var_list.row_idx_var_idx_map.get(&row_idx)?;
-        self.var_list.vars.get(var_idx)
+    /// The keys most likely to be wanted next, for the status bar
+    fn status_hints(&self) -> Vec<(String, &'static str)> {
+        match self.mode {
+            AppMode::SearchInput => return vec![("<Enter>".to_string(), "go to the match"), ("↑↓".to_string(), "earlier searches"), ("<Esc>".to_string(), "cancel")],
+            AppMode::CommandInput => return vec![("<Enter>".to_string(), "run"), ("<Esc>".to_string(), "cancel")],
+            AppMode::ValueEdit => return vec![("<Enter>".to_string(), "set the value"), ("<Esc>".to_string(), "cancel")],
+            AppMode::Scroll => {}
+        }
+        let mut hints = Vec::new();
+        match self.active_screen() {
+            Screen::Cache if self.missing_cache.is_none() => {
+                if let Some(var) = self.editor.selected() {
+                    match var.var.typ {
+                        VarType::Bool => hints.push(("<Space>".to_string(), "toggle")),
+                        VarType::Enum => hints.extend([("<Space>".to_string(), "cycle"), ("<Enter>".to_string(), "edit")]),
+                        _ => hints.push(("<Enter>".to_string(), "edit")),
+                    }
+                    if is_list(&var.new_val) {
+                        hints.push(("l".to_string(), "edit as a list"));
+                    }
+                    if var.is_modified() {
+                        hints.push(("R".to_string(), "reset"));
+                    }
+                }
+                match self.editor.modified_count() {
+                    0 => hints.push(("c".to_string(), "configure")),
+                    _ => hints.extend([("d".to_string(), "review"), ("w".to_string(), "save")]),
+                }
+                hints.push(("/".to_string(), "search"));
+            }
+            Screen::Log => hints.extend([("]".to_string(), "next error"), ("/".to_string(), "search"), ("<Esc>".to_string(), "back")]),
+            screen => hints.extend(
+                screen
+                    .bindings()
+                    .iter()
+                    .filter(|b| !matches!(b.action, Action::SelectNext(_) | Action::SelectPrevious(_) | Action::SelectFirst | Action::SelectLast))
+                    .map(|b| (key_label(&b.keys[0]), b.help)),
+            ),
+        }
+        hints.push(("?".to_string(), "all keys"));
+        hints
     }
 
-    fn render_popup(&self, area: Rect, buf: &mut Buffer) {
-        if self.mode != AppMode::ValueEdit {return};
-
-        let var = self.get_selected_var().unwrap(); // TODO fix unwrap
-
-        // Format the detailed content. Use Line::from(Vec<Span>) for rich text.
-        let content = vec![
-            Line::from(format!("Name: {}", var.var.name)).bold(),
-            Line::from(format!("Type: {}", var.var.typ)),
-            // Line::from(format!("Value: {}", var.value)),
-            // Line::from(vec![
-            //     "Description: ".bold(),
-            //     // Assuming 'desc' field exists on CacheVar based on your prior commented code
-            //     var.desc.clone().into(), 
-            // ]),
-        ];
-        // let content = vec![Line::from(format!("Name")).bold()];
-
-        // 2. Define the size and position of the popup
-        let popup_area = popup_area(area, 20, 10); // 70% width, 50% height
-        Clear.render(popup_area, buf);
-
-        // // 3. Define the Block
-        let block = Block::new()
-            .title(Line::raw("Full Cache Variable Details").centered().bold())
-            .borders(Borders::ALL)
-            .border_style(Style::new().fg(BLUE.c500))
-            .bg(NORMAL_ROW_BG); // Dark background
-
-        // 4. Render the Content Paragraph
-        Paragraph::new(content)
-            .block(block)
-            .fg(TEXT_FG_COLOR)
-            .wrap(Wrap { trim: false })
-            .render(popup_area, buf);
-    }
+    /// The bottom line: the mode, the filter and sort of the cache, how many
+    /// edits are pending, and hints for the next keys or the last message
+    fn render_status_bar(&self, area: Rect, buf: &mut Buffer) {
+        let mode = match self.mode {
+            AppMode::Scroll => "SCROLL",
+            AppMode::ValueEdit => "EDIT",
+            AppMode::SearchInput => "SEARCH",
+            AppMode::CommandInput => "COMMAND",
+        };
+        let mut left = vec![Span::raw(format!(" {} ", mode)).bold().fg(Color::White).bg(STATUS_MODE_BG)];
+        // What's typed of a count or a chord so far, like vim's showcmd
+        let count = self.count.map(|count| count.to_string()).unwrap_or_default();
+        let pending = self.pending_key.map(|(key, _)| key_label(&key)).unwrap_or_default();
+        if !count.is_empty() || !pending.is_empty() {
+            left.push(Span::raw(format!(" {}{}", count, pending)).bold());
+        }
+        if self.screens.tab() == Screen::Cache {
+            if let Some(filter) = self.editor.filter {
+                left.push(Span::raw(format!("  filter: {}", filter)));
+            }
+            left.push(Span::raw(format!("  sort: {}", self.editor.sort)).dim());
+        }
+        left.push(Span::raw(" "));
 
-    // --- NEW TABLE RENDERING LOGIC ---
-    fn render_var_table(&mut self, area: Rect, buf: &mut Buffer) {
-        // 1. Define the Container Block
-        let block = Block::new()
-            .title(Line::raw(" Cache Entries ").left_aligned())
-            .borders(Borders::TOP)
-            .border_set(symbols::border::EMPTY)
-            .border_style(TODO_HEADER_STYLE)
-            .bg(NORMAL_ROW_BG);
+        let mut right = match self.editor.modified_count() {
+            0 => Line::default(),
+            n => Line::from(Span::raw(format!(" {} modified ", n)).bold().fg(AMBER.c400)),
+        };
+        // Saving without a :w shouldn't come as a surprise
+        if self.config.auto_save != AutoSave::Off {
+            right.push_span(Span::raw(format!("autosave: {} ", self.config.auto_save)).dim());
+        }
 
-        // 2. Define the Header Row
-        let header = Row::new(vec![
-            Cell::from("Name"),
-            Cell::from("Type"),
-            Cell::from("Value")
+        let left = Line::from(left);
+        let [left_area, middle_area, right_area] = Layout::horizontal([
+            Constraint::Length(left.width() as u16),
+            Constraint::Fill(1),
+            Constraint::Length(right.width() as u16),
         ])
-        .style(TODO_HEADER_STYLE)
-        .height(1)
-        .bottom_margin(1); 
-
-
-        // 3. Define the Rows from tui_vars
-        let rows: Vec<Row> = self
-            .var_list
-            .vars
-            .iter()
-            .filter(|var| self.show_advanced || !var.var.advanced)
-            .enumerate()
-            .map(|(i, var)| {
-                let color = alternate_colors(i);
-
-                let name_label = if self.check_if_var_is_modified(var) {
-                    format!("*{}", var.var.name)
-                } else {
-                    format!(" {}", var.var.name)
-                };
-                
-                // Assuming var.var.name, var.var.typ, var.var.value implement Display
-                Row::new(vec![
-                    Cell::from(name_label),
-                    Cell::from(var.var.typ.to_string()), 
-                    Cell::from(var.new_val.to_string()),
-                ])
-                .style(Style::new().bg(color).fg(TEXT_FG_COLOR))
-            })
-            .collect();
-
-        // 4. Define Column Widths
-        // We use the calculated longest_name for the first column
-        let widths = [
-            Constraint::Length(self.var_list.longest_name as u16 + 4), // +4 for padding
-            Constraint::Length(20), // Fixed width for Type
-            Constraint::Min(10),    // Remaining space for Value
-        ];
-
-        // 5. Construct the Table
-        let table = Table::new(rows, widths)
-            .header(header)
-            .block(block)
-            .row_highlight_style(SELECTED_STYLE)
-            .highlight_symbol(">")
-            .highlight_spacing(HighlightSpacing::Always);
+        .areas(area);
+        left.render(left_area, buf);
+        right.render(right_area, buf);
 
-        // 6. Render with State
-        StatefulWidget::render(table, area, buf, &mut self.var_list.state);
+        if let Some(message) = &self.message {
+            Line::raw(message.as_str()).bold().centered().render(middle_area, buf);
+            return;
+        }
+        let mut spans = Vec::new();
+        for (key, what) in self.status_hints() {
+            // Only whole hints, the ones that don't fit are left out
+            let width = key.chars().count() + what.chars().count() + 3;
+            if spans.iter().map(Span::width).sum::<usize>() + width > middle_area.width as usize {
+                break;
+            }
+            spans.push(Span::raw(format!(" {}", key)).bold());
+            spans.push(Span::raw(format!(" {} ", what)).dim());
+        }
+        Line::from(spans).centered().render(middle_area, buf);
     }
 
     fn render_search_footer(&self, area: Rect, buf: &mut Buffer) {
 
-        let search_str = format!("Search: {}", self.search_input);
+        let title = match self.mode {
+            AppMode::CommandInput => self.command_input.line(":"),
+            AppMode::ValueEdit => {
+                let name = self.editor.selected().map_or("", |v| v.var.name.as_str());
+                self.value_input.line(&format!("{} = ", name))
+            }
+            _ => self.search_input.line("Search: "),
+        };
         let block = Block::new()
-            .title(Line::raw(search_str).left_aligned())
+            .title(title.left_aligned())
             .borders(Borders::TOP)
             .border_set(symbols::border::EMPTY)
             .border_style(TODO_HEADER_STYLE)
@@ -454,47 +3801,88 @@ impl App {
             .render(area, buf);
     }
 
-    fn render_selected_var(&self, area: Rect, buf: &mut Buffer) {
-
-        let (name, desc) = if let Some(var) = self.get_selected_var() {
-            let mut values: String = "".to_string();
-            if var.var.typ == VarType::Enum {
-                values = format!("\n\nPossible values: \n{}", var.var.values.join(", "));
-            }
-            (var.var.name.clone(), format!("{}{}", var.var.desc, values))
-        } else {
-            ("No var".to_string(), "Nothing selected...".to_string())
-        };
+}
 
+impl App {
+    fn render_install_prompt(&self, input: &TextInput, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
         let block = Block::new()
-            .title(Line::raw(name).left_aligned())
-            .borders(Borders::TOP)
-            .border_set(symbols::border::EMPTY)
-            .border_style(TODO_HEADER_STYLE)
+            .title(Line::raw(" Install ").centered().bold())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
             .bg(NORMAL_ROW_BG)
-            .padding(Padding::horizontal(1));
-
-        Paragraph::new(desc)
+            .padding(Padding::uniform(1));
+        let content = vec![
+            Line::raw(
+                "Runs cmake --install on what was last built. A prefix other than CMAKE_INSTALL_PREFIX \
+                 is passed as --prefix for this install only, the cache keeps its value.",
+            ),
+            Line::raw(""),
+            input.line("Prefix: "),
+            Line::raw(""),
+            Line::raw("[Enter] install"),
+            Line::raw("[Esc] cancel"),
+        ];
+        Paragraph::new(content)
             .block(block)
             .fg(TEXT_FG_COLOR)
             .wrap(Wrap { trim: false })
             .render(area, buf);
     }
+
 }
 
-const fn alternate_colors(i: usize) -> Color {
-    if i % 2 == 0 {
-        NORMAL_ROW_BG
-    } else {
-        ALT_ROW_BG_COLOR
-    }
+fn render_about(lines: &[String], area: Rect, buf: &mut Buffer) {
+    Clear.render(area, buf);
+    let block = Block::new()
+        .title(Line::raw(" About ").centered().bold())
+        .title_bottom(Line::raw(" press any key ").centered())
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(BLUE.c500))
+        .bg(NORMAL_ROW_BG)
+        .padding(Padding::uniform(1));
+    let content: Vec<Line> = lines.iter().map(|l| Line::raw(l.clone())).collect();
+    Paragraph::new(content)
+        .block(block)
+        .fg(TEXT_FG_COLOR)
+        .render(area, buf);
+}
+
+/// Actions on the entry selected in the cache table
+fn acts_on_selected_entry(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::EditValue
+            | Action::SetValue(_)
+            | Action::CycleValue
+            | Action::ResetToDefault
+            | Action::OpenEditor
+            | Action::OpenListEditor
+            | Action::EditAllowedValues
+            | Action::ToggleAdvancedFlag
+            | Action::DuplicateEntry
+            | Action::DeleteEntry
+            | Action::ShowUsages
+            | Action::Explain
+            | Action::TogglePin
+    )
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    a.canonicalize().ok().is_some_and(|a| b.canonicalize().is_ok_and(|b| a == b))
 }
 
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
-fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+pub(crate) fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
     let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
     let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
     let [area] = vertical.areas(area);
     let [area] = horizontal.areas(area);
     area
 }
+
+/// Send the cache written to the local copy on to the remote build dir,
+/// when editing one
+fn upload_cache() -> std::result::Result<(), String> {
+    remote::current().map_or(Ok(()), |remote| remote.upload())
+}
@@ -0,0 +1,188 @@
+use std::{fs, path::Path, str::FromStr};
+
+use cmake_cache::{CacheVar, VarType, write_cache_changes};
+
+use crate::busy::{find_configure_in_progress, find_external_cmake};
+use crate::cache_editor::CacheEditorState;
+use crate::config::Config;
+use crate::journal::{self, JournalEntry};
+use crate::remote;
+use crate::sidecar::Sidecar;
+use crate::task::configure_in_terminal;
+use crate::toml::{self, TomlValue};
+
+/// A `NAME=VALUE` or `NAME:TYPE=VALUE` definition, as given to cmake with -D
+#[derive(Debug, Clone)]
+pub struct Definition {
+    pub name: String,
+    // Needed to add an entry, checked against the type of one the cache has
+    pub typ: Option<VarType>,
+    pub value: String,
+}
+
+impl FromStr for Definition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected NAME=VALUE or NAME:TYPE=VALUE, got '{}'", s))?;
+        let (name, typ) = match key.split_once(':') {
+            Some((name, typ)) => {
+                let parsed = VarType::from_cache_name(&typ.to_uppercase())
+                    .filter(|t| !matches!(t, VarType::Internal | VarType::Static | VarType::Uninitialized))
+                    .ok_or_else(|| format!("unknown type '{}', expected BOOL, STRING, FILEPATH or PATH", typ))?;
+                (name, Some(parsed))
+            }
+            None => (key, None),
+        };
+        if name.is_empty() {
+            return Err(format!("no variable name in '{}'", s));
+        }
+        Ok(Self { name: name.to_string(), typ, value: value.to_string() })
+    }
+}
+
+/// Definitions from a changes file: a TOML file whose keys are entry names,
+/// or `"NAME:TYPE"` to add the entry when missing and check its type when
+/// not. Booleans are written as ON/OFF, arrays as `;` separated lists.
+pub fn read_changes_file(path: &Path) -> Result<Vec<Definition>, String> {
+    if path.extension().is_some_and(|ext| ext == "yaml" || ext == "yml") {
+        return Err(format!("{}: only TOML changes files are supported", path.display()));
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let table = toml::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let mut definitions = table
+        .iter()
+        .map(|(key, value)| {
+            if key.contains('.') {
+                return Err(format!("{}: '{}', tables are not supported, only NAME = VALUE", path.display(), key));
+            }
+            let value = cache_value(value).ok_or_else(|| format!("{}: '{}' has a nested array", path.display(), key))?;
+            format!("{}={}", key, value).parse::<Definition>().map_err(|e| format!("{}: {}", path.display(), e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    definitions.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(definitions)
+}
+
+fn cache_value(value: &TomlValue) -> Option<String> {
+    match value {
+        TomlValue::String(s) => Some(s.clone()),
+        TomlValue::Int(i) => Some(i.to_string()),
+        TomlValue::Bool(b) => Some(if *b { "ON" } else { "OFF" }.to_string()),
+        TomlValue::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                TomlValue::Array(_) => None,
+                item => cache_value(item),
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(|items| items.join(";")),
+    }
+}
+
+/// Apply `definitions` to the cache of `build_dir` without the TUI, for
+/// scripts. Every definition is checked first and nothing is written when
+/// one is refused. The changes are then written to the cache, or with
+/// `configure` passed to a configure run, and listed on stdout. Returns
+/// false when the configure failed.
+pub fn run(build_dir: &Path, definitions: &[Definition], configure: bool) -> Result<bool, String> {
+    let cache_path = build_dir.join("CMakeCache.txt");
+    let mut editor =
+        CacheEditorState::try_load(build_dir).map_err(|e| format!("Failed to read {}: {}", cache_path.display(), e))?;
+    // Entries on those lines aren't in the editor, so can't be set
+    for warning in &editor.parse_warnings {
+        eprintln!("{}: {}", cache_path.display(), warning);
+    }
+
+    let problems: Vec<String> = definitions
+        .iter()
+        .filter_map(|def| apply(&mut editor, def).err().map(|problem| format!("{}: {}", def.name, problem)))
+        .collect();
+    if !problems.is_empty() {
+        return Err(format!("Nothing applied:\n  {}", problems.join("\n  ")));
+    }
+
+    let changes = journal::pending_changes(&editor);
+    if changes.is_empty() {
+        println!("No changes, the cache already has these values");
+        return Ok(true);
+    }
+    if let Some(reason) = find_external_cmake(build_dir, None).or_else(|| find_configure_in_progress(build_dir)) {
+        return Err(format!("Nothing applied, {}", reason));
+    }
+
+    let longest_name = changes.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for change in &changes {
+        let old = change.old.as_deref().map_or("(new entry)".to_string(), |old| format!("\"{}\"", old));
+        println!("  {:<w$}  {} -> \"{}\"", change.name, old, change.new, w = longest_name);
+    }
+
+    if configure {
+        let status = configure_in_terminal(build_dir, &editor.configure_defines())
+            .map_err(|e| format!("Failed to run cmake: {}", e))?;
+        if !status.success() {
+            eprintln!("Configure failed ({})", status);
+            return Ok(false);
+        }
+        println!("{} change(s) passed to configure", changes.len());
+    } else {
+        let (values, added) = editor.pending_changes();
+        write_cache_changes(build_dir, &values, &added)
+            .map_err(|e| format!("Failed to save CMakeCache.txt: {}", e))?;
+        if let Some(remote) = remote::current() {
+            remote.upload()?;
+        }
+        println!("{} change(s) saved to {}", changes.len(), cache_path.display());
+    }
+
+    // Only into a sidecar dir that's already there, creating one may need
+    // an answer about ignoring it that a script can't give
+    let sidecar = Sidecar::resolve(build_dir, &Config::load());
+    if sidecar.dir.is_dir()
+        && let Err(e) = journal::append(&sidecar.dir, &JournalEntry::new("batch", changes))
+    {
+        eprintln!("Failed to write the change journal: {}", e);
+    }
+    Ok(true)
+}
+
+/// Set the pending value of one definition, refusing values cmake wouldn't
+/// take for the entry's type. Lesser problems are printed as warnings.
+fn apply(editor: &mut CacheEditorState, def: &Definition) -> Result<(), String> {
+    match editor.get(&def.name) {
+        Some(var) => {
+            // An entry passed with -D before the project declared it has no type to check against
+            if let Some(typ) = &def.typ
+                && var.var.typ != VarType::Uninitialized
+                && typ.cache_name() != var.var.typ.cache_name()
+            {
+                return Err(format!("is {} in the cache, not {}", var.var.typ.cache_name(), typ.cache_name()));
+            }
+            if var.var.typ == VarType::Enum && !var.var.values.contains(&def.value) {
+                return Err(format!("\"{}\" is not one of {}", def.value, var.var.values.join(", ")));
+            }
+            editor.check_value(&def.name, &def.value)?;
+            editor.set_value(&def.name, def.value.clone());
+        }
+        None => {
+            let typ = def
+                .typ
+                .clone()
+                .ok_or("not in the cache, give a type to add it, e.g. NAME:STRING=VALUE")?;
+            editor.add_var(CacheVar::new(def.name.clone(), typ, String::new(), def.value.clone()));
+            if let Err(problem) = editor.check_value(&def.name, &def.value) {
+                editor.remove_new_var(&def.name);
+                return Err(problem);
+            }
+        }
+    }
+    if let Some(var) = editor.get(&def.name)
+        && let Some(warning) = editor.issue(var)
+    {
+        eprintln!("warning: {}: {}", def.name, warning);
+    }
+    Ok(())
+}
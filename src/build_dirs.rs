@@ -0,0 +1,204 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use color_eyre::{Result, eyre::eyre};
+use ratatui::{
+    DefaultTerminal,
+    buffer::Buffer,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::Rect,
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::app::popup_area;
+use crate::timestamp;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+// Deep enough for out/build/<preset> as laid out by CMake presets
+const MAX_DEPTH: usize = 3;
+
+/// A directory holding a CMakeCache.txt, found under a source tree
+pub struct BuildDir {
+    pub path: PathBuf,
+    // When the cache was last written, the most recently used build dir
+    // is the likeliest pick
+    pub modified: Option<SystemTime>,
+    pub build_type: Option<String>,
+    pub generator: Option<String>,
+}
+
+impl BuildDir {
+    fn open(path: PathBuf) -> Option<Self> {
+        let cache = path.join("CMakeCache.txt");
+        let modified = fs::metadata(&cache).ok()?.modified().ok();
+        let content = fs::read_to_string(&cache).unwrap_or_default();
+        // Only two values are shown, no need for the full parser
+        let value = |name: &str| {
+            content
+                .lines()
+                .filter_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+                .find_map(|rest| rest.split_once('=').map(|(_, v)| v.to_string()))
+                .filter(|v| !v.is_empty())
+        };
+        Some(Self {
+            build_type: value("CMAKE_BUILD_TYPE"),
+            generator: value("CMAKE_GENERATOR"),
+            modified,
+            path,
+        })
+    }
+}
+
+/// Build dirs below `source_dir` (build/, out/, cmake-build-*, ...), most
+/// recently configured first
+pub fn find_build_dirs(source_dir: &Path) -> Vec<BuildDir> {
+    let mut found = Vec::new();
+    scan(source_dir, 0, &mut found);
+    found.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.path.cmp(&b.path)));
+    found
+}
+
+fn scan(dir: &Path, depth: usize, found: &mut Vec<BuildDir>) {
+    if depth >= MAX_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let mut subdirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|e| {
+            let name = e.file_name();
+            let name = name.to_string_lossy();
+            // Hidden dirs are VCS metadata and tool state, CMakeFiles is
+            // cmake's own and holds try_compile caches
+            !name.starts_with('.') && name != "CMakeFiles" && name != "node_modules"
+        })
+        .map(|e| e.path())
+        .collect();
+    subdirs.sort();
+
+    for subdir in subdirs {
+        if subdir.join("CMakeCache.txt").is_file() {
+            // Nested caches below a build dir belong to sub-builds
+            found.extend(BuildDir::open(subdir));
+        } else {
+            scan(&subdir, depth + 1, found);
+        }
+    }
+}
+
+/// Full screen list to choose one of several build dirs before the editor starts
+pub struct BuildDirPicker {
+    source_dir: PathBuf,
+    dirs: Vec<BuildDir>,
+    state: ListState,
+}
+
+impl BuildDirPicker {
+    pub fn new(source_dir: &Path, dirs: Vec<BuildDir>) -> Self {
+        Self {
+            source_dir: source_dir.to_path_buf(),
+            dirs,
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    /// Let the user pick, None if they quit instead
+    pub fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<Option<PathBuf>> {
+        loop {
+            terminal.draw(|frame| self.render(frame.area(), frame.buffer_mut()))?;
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('j') | KeyCode::Down => self.state.select_next(),
+                KeyCode::Char('k') | KeyCode::Up => self.state.select_previous(),
+                KeyCode::Enter => {
+                    let selected = self.state.selected().and_then(|i| self.dirs.get(i));
+                    return Ok(selected.map(|d| d.path.clone()));
+                }
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let area = popup_area(area, 80, 60);
+        Clear.render(area, buf);
+
+        let title = format!(" No CMakeCache.txt in {}, pick a build directory ", self.source_dir.display());
+        let block = Block::new()
+            .title(Line::raw(title).centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Enter> open, q quit ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+
+        let items: Vec<ListItem> = self
+            .dirs
+            .iter()
+            .map(|d| {
+                let path = d.path.strip_prefix(&self.source_dir).unwrap_or(&d.path);
+                let modified = d
+                    .modified
+                    .and_then(|m| m.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map_or(String::new(), |m| timestamp::format(m.as_secs()));
+                ListItem::new(format!(
+                    "{:<40} {:<16} {:<16} {}",
+                    path.display(),
+                    d.build_type.as_deref().unwrap_or("-"),
+                    modified,
+                    d.generator.as_deref().unwrap_or(""),
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
+
+/// Build dir to open for `path`: the dir of a CMakeCache.txt, `path` itself
+/// when it has a cache, else the only build dir below it, else one picked by
+/// the user. None when the user quit the picker.
+pub fn resolve_build_dir(path: PathBuf, pick: bool) -> Result<Option<PathBuf>> {
+    if path.is_file() {
+        if path.file_name().is_none_or(|name| name != "CMakeCache.txt") {
+            return Err(eyre!("{} is not a CMakeCache.txt", path.display()));
+        }
+        let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        return Ok(Some(dir.to_path_buf()));
+    }
+    if path.join("CMakeCache.txt").is_file() {
+        return Ok(Some(path));
+    }
+    let mut dirs = find_build_dirs(&path);
+    if dirs.len() == 1 {
+        return Ok(Some(dirs.remove(0).path));
+    }
+    // Nothing to choose from, the editor reports the missing cache
+    if dirs.is_empty() || !pick {
+        return Ok(Some(path));
+    }
+    let mut terminal = ratatui::init();
+    let picked = BuildDirPicker::new(&path, dirs).run(&mut terminal);
+    ratatui::restore();
+    Ok(picked?)
+}
@@ -0,0 +1,48 @@
+use std::path::Path;
+
+use crate::cmake::{self, CmakeVersion};
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const GIT_COMMIT: &str = env!("CMAKE_TUI_GIT_COMMIT");
+pub const BUILD_DATE: &str = env!("CMAKE_TUI_BUILD_DATE");
+pub const FEATURES: &str = env!("CMAKE_TUI_FEATURES");
+
+/// Version of the cmake in use, e.g. "3.28.1", with its path when it's not
+/// the one on PATH
+pub fn cmake_version() -> Option<String> {
+    cmake::version().map(|version| describe(cmake::program(), version))
+}
+
+fn describe(program: &Path, version: CmakeVersion) -> String {
+    if program == Path::new("cmake") {
+        version.to_string()
+    } else {
+        format!("{} ({})", version, program.display())
+    }
+}
+
+/// Multi-line build description used by `--version` and the about popup
+pub fn build_info_lines(cmake_version: Option<&str>) -> Vec<String> {
+    let mut lines = vec![
+        format!("cmake-tui {}", VERSION),
+        format!("commit:   {}", GIT_COMMIT),
+        format!("built:    {}", BUILD_DATE),
+    ];
+    if !FEATURES.is_empty() {
+        lines.push(format!("features: {}", FEATURES));
+    }
+    lines.push(format!("cmake:    {}", cmake_version.unwrap_or("not found")));
+    lines
+}
+
+/// What `--version` prints after the program name, for the cmake picked with
+/// --cmake, $CMAKE or the config file
+pub fn long_version() -> String {
+    let lines = build_info_lines(cmake_version().as_deref());
+    let mut out = format!("{} ({} {})", VERSION, GIT_COMMIT, BUILD_DATE);
+    for line in &lines[1..] {
+        out.push('\n');
+        out.push_str(line);
+    }
+    out
+}
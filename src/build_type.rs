@@ -0,0 +1,97 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::cache_editor::CacheEditorState;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+const DEFAULT_BUILD_TYPES: &[&str] = &["Debug", "Release", "RelWithDebInfo", "MinSizeRel"];
+
+/// Whether the build dir's generator builds several configurations side
+/// by side (Visual Studio, Xcode, Ninja Multi-Config). CMAKE_BUILD_TYPE is
+/// ignored by those, the configuration is picked when building.
+pub fn is_multi_config(editor: &CacheEditorState) -> bool {
+    let generator = editor.get("CMAKE_GENERATOR").map(|v| v.var.value.as_str()).unwrap_or_default();
+    generator.starts_with("Visual Studio") || generator == "Xcode" || generator.ends_with("Multi-Config")
+}
+
+/// Pick for CMAKE_BUILD_TYPE, the entry edited far more often than any
+/// other, or with a multi-config generator the configuration to build
+pub struct BuildTypePane {
+    pub visible: bool,
+    // Picking the configuration to build rather than CMAKE_BUILD_TYPE
+    pub multi_config: bool,
+    build_types: Vec<String>,
+    state: ListState,
+}
+
+impl BuildTypePane {
+    pub fn new() -> Self {
+        Self { visible: false, multi_config: false, build_types: Vec::new(), state: ListState::default() }
+    }
+
+    /// Show the build types of the project: the ones in CMAKE_CONFIGURATION_TYPES
+    /// when it's set, else CMake's defaults, with the current one selected.
+    /// `build_config` is the one builds use with a multi-config generator.
+    pub fn open(&mut self, editor: &CacheEditorState, build_config: Option<&str>) {
+        self.multi_config = is_multi_config(editor);
+        let configured = editor.get("CMAKE_CONFIGURATION_TYPES").map(|v| v.new_val.as_str()).unwrap_or_default();
+        self.build_types = match configured.is_empty() {
+            true => DEFAULT_BUILD_TYPES.iter().map(|t| t.to_string()).collect(),
+            false => configured.split(';').filter(|t| !t.is_empty()).map(str::to_string).collect(),
+        };
+        let current = match self.multi_config {
+            true => build_config,
+            false => editor.get("CMAKE_BUILD_TYPE").map(|v| v.new_val.as_str()),
+        };
+        let selected = self.build_types.iter().position(|t| Some(t.as_str()) == current);
+        self.state = ListState::default().with_selected(Some(selected.unwrap_or(0)));
+        self.visible = true;
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.state.selected().and_then(|i| self.build_types.get(i)).map(String::as_str)
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let (title, hint) = match self.multi_config {
+            true => (" Configuration to build ", " j/k select, <Enter> pick, c pick and build, <Esc> close "),
+            false => (" Build type ", " j/k select, <Enter> set, c set and configure, <Esc> close "),
+        };
+        let block = Block::new()
+            .title(Line::raw(title).centered().bold())
+            .title_bottom(Line::raw(hint).centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+
+        let items: Vec<ListItem> = self.build_types.iter().map(|t| ListItem::new(t.as_str())).collect();
+        let list = List::new(items)
+            .block(block)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+// Tools that read or rewrite the cache, or hold the build tree while running
+const CMAKE_TOOLS: &[&str] = &["cmake", "ccmake", "cmake-gui", "ctest", "cpack", "make", "gmake", "ninja"];
+
+/// Look for another process working on `build_dir`, returning a short
+/// description of it. This is a heuristic: on Linux it scans /proc for cmake
+/// and build tool processes whose working directory or arguments point at
+/// the build dir. `own_pid` is skipped so our own task isn't reported.
+pub fn find_external_cmake(build_dir: &Path, own_pid: Option<u32>) -> Option<String> {
+    let build_dir = fs::canonicalize(build_dir).ok()?;
+    let entries = fs::read_dir("/proc").ok()?;
+
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+        if Some(pid) == own_pid || pid == std::process::id() {
+            continue;
+        }
+
+        let proc_dir = entry.path();
+        let Ok(comm) = fs::read_to_string(proc_dir.join("comm")) else { continue };
+        let comm = comm.trim();
+        if !CMAKE_TOOLS.contains(&comm) {
+            continue;
+        }
+
+        if works_on(&proc_dir, &build_dir) {
+            return Some(format!("{} (pid {}) is running in {}", comm, pid, build_dir.display()));
+        }
+    }
+    None
+}
+
+/// Signs of a configure in progress left in the build tree itself, for
+/// when the process can't be seen (another machine on a shared file system,
+/// no /proc). cmake runs its try_compile checks in CMakeFiles/CMakeScratch
+/// (CMakeTmp before 3.24), which only have content while configuring.
+pub fn find_configure_in_progress(build_dir: &Path) -> Option<String> {
+    ["CMakeScratch", "CMakeTmp"].iter().find_map(|name| {
+        let dir = build_dir.join("CMakeFiles").join(name);
+        let mut entries = fs::read_dir(&dir).ok()?;
+        entries
+            .next()
+            .is_some()
+            .then(|| format!("a configure seems to be in progress ({} is not empty)", dir.display()))
+    })
+}
+
+fn works_on(proc_dir: &Path, build_dir: &Path) -> bool {
+    let in_dir = |p: &PathBuf| p.starts_with(build_dir);
+
+    let cwd = fs::read_link(proc_dir.join("cwd")).ok();
+    if cwd.as_ref().is_some_and(in_dir) {
+        return true;
+    }
+
+    // Arguments are NUL separated, e.g. `cmake --build /path/to/build`.
+    // Relative ones are resolved against the process' own working directory.
+    let Ok(cmdline) = fs::read(proc_dir.join("cmdline")) else { return false };
+    cmdline
+        .split(|&b| b == 0)
+        .skip(1)
+        .filter_map(|arg| std::str::from_utf8(arg).ok())
+        .map(|arg| arg.strip_prefix("-B").unwrap_or(arg))
+        .filter(|arg| !arg.is_empty() && !arg.starts_with('-'))
+        .map(|arg| match &cwd {
+            Some(cwd) => cwd.join(arg),
+            None => PathBuf::from(arg),
+        })
+        .filter_map(|p| fs::canonicalize(p).ok())
+        .any(|p| in_dir(&p))
+}
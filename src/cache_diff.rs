@@ -0,0 +1,307 @@
+//! `cmake-tui diff`: the build dir's cache against a reference, for CI
+//! checks that a build dir has the configuration it should.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use clap::ValueEnum;
+use cmake_cache::{BoolValue, Cache, VarType};
+
+use crate::json::{self, JsonValue};
+use crate::wsl::local_source_dir;
+
+const PRESET_FILES: [&str; 2] = ["CMakePresets.json", "CMakeUserPresets.json"];
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum DiffFormat {
+    /// `-`/`+` lines like diff -u, the reference first
+    Unified,
+    /// An object with the added, removed and changed entries
+    Json,
+}
+
+/// An entry as the reference has it. Presets may leave the type out.
+struct RefEntry {
+    name: String,
+    typ: Option<String>,
+    value: String,
+}
+
+/// What to compare against. A preset only names the entries it sets, the
+/// others in the cache aren't additions.
+struct Reference {
+    entries: Vec<RefEntry>,
+    partial: bool,
+}
+
+enum Difference {
+    Added { name: String, typ: String, value: String },
+    Removed(RefEntry),
+    Changed { name: String, typ: String, reference: String, value: String },
+}
+
+impl Difference {
+    fn name(&self) -> &str {
+        match self {
+            Difference::Added { name, .. } | Difference::Changed { name, .. } => name,
+            Difference::Removed(entry) => &entry.name,
+        }
+    }
+}
+
+fn is_internal(typ: &str) -> bool {
+    matches!(typ, "INTERNAL" | "STATIC")
+}
+
+/// Bools compare by what cmake reads them as, so a preset's `true` matches `ON`
+fn same_value(typ: &str, reference: &str, value: &str) -> bool {
+    if reference == value {
+        return true;
+    }
+    match (typ, BoolValue::parse(reference), BoolValue::parse(value)) {
+        ("BOOL", BoolValue::Plain(a), BoolValue::Plain(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// A value of the JSON, strings as they are and other scalars the way
+/// presets spell them for cmake
+fn json_scalar(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::String(s) => Some(s.clone()),
+        JsonValue::Bool(b) => Some(if *b { "TRUE" } else { "FALSE" }.to_string()),
+        JsonValue::Number(_) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// A `cacheVariables` object, or any object of the same shape: values as
+/// they are or `{"type": ..., "value": ...}`
+fn entries_of_object(fields: &[(String, JsonValue)]) -> Vec<RefEntry> {
+    fields
+        .iter()
+        .filter_map(|(name, value)| match value {
+            JsonValue::Object(_) => Some(RefEntry {
+                name: name.clone(),
+                typ: value.get("type").and_then(|t| t.as_str()).map(|t| t.to_string()),
+                value: value.get("value").and_then(json_scalar)?,
+            }),
+            // null unsets the variable a preset inherited
+            JsonValue::Null => None,
+            _ => Some(RefEntry { name: name.clone(), typ: None, value: json_scalar(value)? }),
+        })
+        .collect()
+}
+
+/// A JSON file: an object of values like a preset's `cacheVariables`, or an
+/// array of `{"name", "type", "value"}` objects like the `list` request answers
+fn read_json(path: &Path) -> Result<Reference, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let root = json::parse(&content).map_err(|e| format!("{} doesn't parse: {}", path.display(), e))?;
+    let entries = match &root {
+        JsonValue::Object(fields) => entries_of_object(fields),
+        JsonValue::Array(items) => items
+            .iter()
+            .filter_map(|item| {
+                Some(RefEntry {
+                    name: item.get("name")?.as_str()?.to_string(),
+                    typ: item.get("type").and_then(|t| t.as_str()).map(|t| t.to_string()),
+                    value: item.get("value").and_then(json_scalar)?,
+                })
+            })
+            .collect(),
+        _ => return Err(format!("{} holds neither an object nor an array", path.display())),
+    };
+    Ok(Reference { entries, partial: false })
+}
+
+/// The cache of a build dir, or a CMakeCache.txt, with the lines it couldn't
+/// read on stderr since their entries are left out of the comparison
+fn load_cache(path: &Path) -> Result<Cache, String> {
+    let cache = Cache::load(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let file = if path.is_dir() { path.join("CMakeCache.txt") } else { path.to_path_buf() };
+    for warning in cache.warnings() {
+        eprintln!("{}: {}", file.display(), warning);
+    }
+    Ok(cache)
+}
+
+fn read_cache(path: &Path) -> Result<Reference, String> {
+    let cache = load_cache(path)?;
+    let entries = cache
+        .into_entries()
+        .into_iter()
+        .map(|var| RefEntry { name: var.name, typ: Some(var.typ.cache_name().to_string()), value: var.value })
+        .collect();
+    Ok(Reference { entries, partial: false })
+}
+
+/// The cache variables of the configure preset `name` of the project, with
+/// the ones of the presets it inherits from
+fn read_preset(build_dir: &Path, name: &str) -> Result<Reference, String> {
+    let Some(source_dir) = local_source_dir(build_dir) else {
+        return Err(format!("{} is no file, and the cache names no source dir to look for presets in", name));
+    };
+    let mut presets = Vec::new();
+    for file in PRESET_FILES {
+        let path = source_dir.join(file);
+        let Ok(content) = fs::read_to_string(&path) else { continue };
+        let root = json::parse(&content).map_err(|e| format!("{} doesn't parse: {}", path.display(), e))?;
+        if let Some(configure) = root.get("configurePresets").and_then(|p| p.as_array()) {
+            presets.extend(configure.iter().cloned());
+        }
+    }
+    let find = |name: &str| presets.iter().find(|p| p.get("name").and_then(|n| n.as_str()) == Some(name));
+    if find(name).is_none() {
+        return Err(format!("{} is neither a file nor a configure preset of {}", name, source_dir.display()));
+    }
+
+    // Depth first, the preset itself last so its own variables win
+    let mut chain: Vec<&JsonValue> = Vec::new();
+    let mut pending = vec![(name.to_string(), false)];
+    while let Some((current, expanded)) = pending.pop() {
+        let Some(preset) = find(&current) else { continue };
+        if expanded {
+            if !chain.iter().any(|p| std::ptr::eq(*p, preset)) {
+                chain.push(preset);
+            }
+            continue;
+        }
+        pending.push((current, true));
+        // The first of several parents wins, so it's applied last
+        match preset.get("inherits") {
+            Some(JsonValue::String(parent)) => pending.push((parent.clone(), false)),
+            Some(JsonValue::Array(parents)) => {
+                pending.extend(parents.iter().filter_map(|p| p.as_str()).map(|p| (p.to_string(), false)))
+            }
+            _ => {}
+        }
+        // A preset inheriting from itself somewhere up the chain
+        if pending.len() > presets.len() * 2 + 2 {
+            return Err(format!("The configure preset {} inherits in a cycle", name));
+        }
+    }
+
+    let source = source_dir.to_string_lossy().replace('\\', "/");
+    let mut entries: Vec<RefEntry> = Vec::new();
+    for preset in chain {
+        let Some(JsonValue::Object(fields)) = preset.get("cacheVariables") else { continue };
+        for mut entry in entries_of_object(fields) {
+            entry.value = entry.value.replace("${sourceDir}", &source).replace("${presetName}", name);
+            entries.retain(|e| e.name != entry.name);
+            entries.push(entry);
+        }
+        // Unset again by a later preset
+        for (unset, _) in fields.iter().filter(|(_, v)| *v == JsonValue::Null) {
+            entries.retain(|e| e.name != *unset);
+        }
+    }
+    Ok(Reference { entries, partial: true })
+}
+
+/// `against` as a file, a JSON one by its extension, or else the name of a
+/// configure preset of the project
+fn read_reference(build_dir: &Path, against: &str) -> Result<Reference, String> {
+    let path = Path::new(against);
+    if !path.exists() {
+        return read_preset(build_dir, against);
+    }
+    match path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+        true => read_json(path),
+        false => read_cache(path),
+    }
+}
+
+fn differences(cache: Cache, reference: Reference) -> Vec<Difference> {
+    let partial = reference.partial;
+    let mut reference: HashMap<String, RefEntry> = reference
+        .entries
+        .into_iter()
+        .filter(|e| !e.typ.as_deref().is_some_and(is_internal))
+        .map(|e| (e.name.clone(), e))
+        .collect();
+
+    let mut differences = Vec::new();
+    for var in cache.into_entries().into_iter().filter(|v| !matches!(v.typ, VarType::Internal | VarType::Static)) {
+        let typ = var.typ.cache_name().to_string();
+        match reference.remove(&var.name) {
+            Some(entry) if !same_value(&typ, &entry.value, &var.value) => {
+                differences.push(Difference::Changed { name: var.name, typ, reference: entry.value, value: var.value })
+            }
+            Some(_) => {}
+            None if partial => {}
+            None => differences.push(Difference::Added { name: var.name, typ, value: var.value }),
+        }
+    }
+    differences.extend(reference.into_values().map(Difference::Removed));
+    differences.sort_by(|a, b| a.name().cmp(b.name()));
+    differences
+}
+
+fn entry_line(name: &str, typ: Option<&str>, value: &str) -> String {
+    match typ {
+        Some(typ) => format!("{}:{}={}", name, typ, value),
+        None => format!("{}={}", name, value),
+    }
+}
+
+fn unified(differences: &[Difference], against: &str, build_dir: &Path) -> String {
+    let mut out = format!("--- {}\n+++ {}\n", against, build_dir.join("CMakeCache.txt").display());
+    for difference in differences {
+        match difference {
+            Difference::Added { name, typ, value } => out.push_str(&format!("+{}\n", entry_line(name, Some(typ), value))),
+            Difference::Removed(entry) => {
+                out.push_str(&format!("-{}\n", entry_line(&entry.name, entry.typ.as_deref(), &entry.value)))
+            }
+            Difference::Changed { name, typ, reference, value } => {
+                out.push_str(&format!("-{}\n", entry_line(name, Some(typ), reference)));
+                out.push_str(&format!("+{}\n", entry_line(name, Some(typ), value)));
+            }
+        }
+    }
+    out
+}
+
+fn json_report(differences: &[Difference]) -> JsonValue {
+    let string = |s: &str| JsonValue::String(s.to_string());
+    let entry = |name: &str, typ: Option<&str>, value: &str| {
+        let mut fields = vec![("name".to_string(), string(name))];
+        if let Some(typ) = typ {
+            fields.push(("type".to_string(), string(typ)));
+        }
+        fields.push(("value".to_string(), string(value)));
+        JsonValue::Object(fields)
+    };
+    let (mut added, mut removed, mut changed) = (Vec::new(), Vec::new(), Vec::new());
+    for difference in differences {
+        match difference {
+            Difference::Added { name, typ, value } => added.push(entry(name, Some(typ), value)),
+            Difference::Removed(e) => removed.push(entry(&e.name, e.typ.as_deref(), &e.value)),
+            Difference::Changed { name, typ, reference, value } => changed.push(JsonValue::Object(vec![
+                ("name".to_string(), string(name)),
+                ("type".to_string(), string(typ)),
+                ("reference".to_string(), string(reference)),
+                ("value".to_string(), string(value)),
+            ])),
+        }
+    }
+    JsonValue::Object(vec![
+        ("added".to_string(), JsonValue::Array(added)),
+        ("removed".to_string(), JsonValue::Array(removed)),
+        ("changed".to_string(), JsonValue::Array(changed)),
+    ])
+}
+
+/// Print how the cache of `build_dir` differs from `against`: a cache file
+/// or build dir, a JSON file or a configure preset of the project. Returns
+/// whether they're the same.
+pub fn run(build_dir: &Path, against: &str, format: DiffFormat) -> Result<bool, String> {
+    let cache = load_cache(build_dir)?;
+    let reference = read_reference(build_dir, against)?;
+    let differences = differences(cache, reference);
+    match format {
+        DiffFormat::Unified if differences.is_empty() => {}
+        DiffFormat::Unified => print!("{}", unified(&differences, against, build_dir)),
+        DiffFormat::Json => println!("{}", json_report(&differences).pretty()),
+    }
+    Ok(differences.is_empty())
+}
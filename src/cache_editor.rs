@@ -0,0 +1,1517 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    io,
+    path::Path,
+    str::FromStr,
+};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, EMERALD, GREEN, ORANGE, RED, SKY, SLATE, VIOLET, YELLOW},
+    },
+    symbols,
+    text::{Line, Span, Text},
+    widgets::{
+        Block, Borders, Cell, HighlightSpacing, Padding, Paragraph, Row, StatefulWidget, Table,
+        TableState, Widget, Wrap,
+    },
+};
+
+use regex::Regex;
+
+use crate::action::{Column, Filter, LongValues, SortOrder};
+use cmake_cache::{BoolValue, Cache, CacheVar, ParseWarning, VarType};
+use crate::source_scan::{DefaultScanner, EnumScanner, ProjectDefault};
+use crate::expand::{expand, has_references};
+use crate::wsl::{Wsl, local_source_dir};
+use crate::highlight::{has_structure, highlight};
+use crate::path_status::{PathState, PathStatus};
+use crate::package_manager;
+use crate::search_index::NameIndex;
+use crate::timestamp;
+use crate::variable_docs::{Docs, VariableDocs, is_unhelpful};
+use crate::validation::{check_bool, validate};
+
+pub const TODO_HEADER_STYLE: Style = Style::new().fg(SLATE.c100).bg(BLUE.c800);
+pub const NORMAL_ROW_BG: Color = SLATE.c950;
+const ALT_ROW_BG_COLOR: Color = SLATE.c900;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+pub const TEXT_FG_COLOR: Color = SLATE.c200;
+const ROW_NUMBER_FG_COLOR: Color = SLATE.c500;
+// Rows whose value looks wrong
+const WARNING_FG_COLOR: Color = YELLOW.c400;
+const NEW_BADGE_FG_COLOR: Color = GREEN.c400;
+// Entries with the MODIFIED property, set by hand in an earlier session
+const USER_BADGE_FG_COLOR: Color = SKY.c400;
+// Characters a value scrolls sideways per key press
+pub const VALUE_SCROLL_STEP: isize = 8;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RowNumbers {
+    Off,
+    Absolute,
+    Relative,
+}
+
+impl FromStr for RowNumbers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(RowNumbers::Off),
+            "absolute" => Ok(RowNumbers::Absolute),
+            "relative" => Ok(RowNumbers::Relative),
+            _ => Err(format!("unknown row numbering '{}', expected off, absolute or relative", s)),
+        }
+    }
+}
+
+impl RowNumbers {
+    pub fn next(self) -> Self {
+        match self {
+            RowNumbers::Off => RowNumbers::Absolute,
+            RowNumbers::Absolute => RowNumbers::Relative,
+            RowNumbers::Relative => RowNumbers::Off,
+        }
+    }
+}
+
+/// Where the color of an entry's type shows
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TypeColoring {
+    Off,
+    // The Type cell only
+    Cell,
+    // The text of the whole row
+    Row,
+}
+
+impl FromStr for TypeColoring {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(TypeColoring::Off),
+            "cell" => Ok(TypeColoring::Cell),
+            "row" => Ok(TypeColoring::Row),
+            _ => Err(format!("unknown type coloring '{}', expected off, cell or row", s)),
+        }
+    }
+}
+
+/// Color for each entry type, so a mixed cache is easier to scan
+#[derive(Debug, Clone)]
+pub struct TypeColors {
+    pub coloring: TypeColoring,
+    pub bool: Color,
+    // FILEPATH and PATH
+    pub path: Color,
+    pub string: Color,
+    // STRING with a list of values to pick from
+    pub enumeration: Color,
+    pub internal: Color,
+    // UNINITIALIZED and STATIC
+    pub other: Color,
+}
+
+impl Default for TypeColors {
+    fn default() -> Self {
+        Self {
+            coloring: TypeColoring::Cell,
+            bool: EMERALD.c400,
+            path: SKY.c400,
+            string: ORANGE.c300,
+            enumeration: VIOLET.c400,
+            internal: SLATE.c500,
+            other: SLATE.c400,
+        }
+    }
+}
+
+impl TypeColors {
+    pub fn get(&self, typ: &VarType) -> Color {
+        match typ {
+            VarType::Bool => self.bool,
+            VarType::Filepath | VarType::Dirpath => self.path,
+            VarType::Str => self.string,
+            VarType::Enum => self.enumeration,
+            VarType::Internal => self.internal,
+            VarType::Uninitialized | VarType::Static => self.other,
+        }
+    }
+}
+
+/// A cache variable together with its edited, not yet saved, value
+#[derive(Clone)]
+pub struct CacheVarTui {
+    pub var: CacheVar,
+    pub new_val: String,
+    // Added in the UI and not written to the cache yet
+    pub is_new: bool,
+    // The allowed values (STRINGS property) as the cache has them,
+    // `var.values` being the edited ones
+    pub saved_values: Vec<String>,
+    // Same for the ADVANCED property
+    pub saved_advanced: bool,
+}
+
+impl From<CacheVar> for CacheVarTui {
+    fn from(var: CacheVar) -> Self {
+        CacheVarTui {
+            new_val: var.value.clone(),
+            saved_values: var.values.clone(),
+            saved_advanced: var.advanced,
+            var,
+            is_new: false,
+        }
+    }
+}
+
+impl CacheVarTui {
+    pub fn is_modified(&self) -> bool {
+        self.is_value_modified() || self.are_values_modified() || self.is_advanced_modified()
+    }
+
+    /// Whether the value itself has a pending edit, as opposed to only its
+    /// properties
+    pub fn is_value_modified(&self) -> bool {
+        self.is_new || self.new_val != self.var.value
+    }
+
+    pub fn are_values_modified(&self) -> bool {
+        self.var.values != self.saved_values
+    }
+
+    pub fn is_advanced_modified(&self) -> bool {
+        self.var.advanced != self.saved_advanced
+    }
+
+    /// Take over the property edits of `edit`, this entry before the cache
+    /// was read again
+    fn take_property_edits(&mut self, edit: CacheVarTui) {
+        if edit.is_advanced_modified() {
+            self.var.advanced = edit.var.advanced;
+        }
+        if edit.are_values_modified() {
+            self.set_allowed_values(edit.var.values);
+        }
+    }
+
+    /// Replace the allowed values. A STRING entry given some becomes an
+    /// enum, and one left without any a plain STRING again.
+    pub fn set_allowed_values(&mut self, values: Vec<String>) {
+        match values.is_empty() {
+            true if self.var.typ == VarType::Enum => {
+                self.var.typ = VarType::Str;
+                self.var.values.clear();
+            }
+            true => self.var.values.clear(),
+            false if self.var.typ == VarType::Str => self.var.make_enum(&values),
+            false => self.var.values = values,
+        }
+    }
+}
+
+/// When and how a variable's value last changed
+#[derive(Clone)]
+pub struct LastChange {
+    pub time: u64,
+    // e.g. "saved in cmake-tui by alice@host"
+    pub how: String,
+}
+
+/// State of a [`CacheEditorWidget`]: the variables with their pending edits
+/// and which of them are shown, in what order, and which one is selected.
+#[derive(Clone)]
+pub struct CacheEditorState {
+    pub vars: Vec<CacheVarTui>,
+    // Index into `vars` of each row shown
+    rows: Vec<usize>,
+    longest_name: usize,
+    pub table: TableState,
+    pub show_advanced: bool,
+    // INTERNAL entries are cmake's own bookkeeping, hidden separately from
+    // the advanced ones the project marked
+    pub show_internal: bool,
+    // Names matching these are noise hidden from the table, e.g. FETCHCONTENT_*
+    ignore: Vec<Regex>,
+    pub show_ignored: bool,
+    // Show the selected value with the references to other entries expanded
+    pub show_expanded: bool,
+    pub filter: Option<Filter>,
+    pub sort: SortOrder,
+    pub row_numbers: RowNumbers,
+    pub columns: Vec<Column>,
+    pub long_values: LongValues,
+    // Width of the name column, None to fit the longest name
+    pub name_width: Option<u16>,
+    pub type_colors: TypeColors,
+    // No colors to tell entries apart, markers say it instead
+    pub monochrome: bool,
+    // By variable name, kept across reloads
+    pub last_changes: HashMap<String, LastChange>,
+    // Description scroll offset and the variable it applies to, so moving
+    // the selection starts the next description at the top
+    description_scroll: Option<(String, u16)>,
+    // Same for how far the selected value is scrolled sideways, in characters
+    value_scroll: Option<(String, usize)>,
+    // Cache lines the parser skipped or read differently than written
+    pub parse_warnings: Vec<ParseWarning>,
+    // What the path values point at
+    paths: PathStatus,
+    // CMake's documentation, for entries with an unhelpful help string
+    docs: VariableDocs,
+    // Values and help text the project's .cmake-tui.toml gives its options
+    enum_hints: HashMap<String, Vec<String>>,
+    descriptions: HashMap<String, String>,
+    // Defaults declared by option() and set(CACHE) in the project, by variable name
+    pub defaults: HashMap<String, ProjectDefault>,
+    // Entries the cache didn't have the previous session, badged as NEW
+    pub new_since_session: HashSet<String>,
+    // Names shown above the table whatever the filters, in the order pinned
+    pub pinned: Vec<String>,
+    // How many of the first rows are the pinned ones
+    pinned_rows: usize,
+    // Value cells as last laid out, by index into `vars`, with the value and
+    // path check generation they show. Dropped when the rows change.
+    value_cells: HashMap<usize, (String, u64, Line<'static>)>,
+    // Names of `vars` for the search, kept in step with it
+    name_index: NameIndex,
+    // The row showing each of `vars`, None for the hidden ones
+    var_rows: Vec<Option<usize>>,
+}
+
+impl CacheEditorState {
+    pub fn new(vars: Vec<CacheVar>) -> Self {
+        let vars: Vec<CacheVarTui> = vars.into_iter().map(CacheVarTui::from).collect();
+
+        let max_len = vars
+            .iter()
+            .map(|i| i.var.name.chars().count())
+            .max()
+            .unwrap_or(100); // Default fallback width
+        let name_index = NameIndex::new(vars.iter().map(|v| v.var.name.as_str()));
+
+        let mut state = Self {
+            vars,
+            rows: Vec::new(),
+            longest_name: max_len,
+            table: TableState::default(),
+            show_advanced: false,
+            show_internal: false,
+            ignore: Vec::new(),
+            show_ignored: false,
+            show_expanded: false,
+            filter: None,
+            sort: SortOrder::Name,
+            row_numbers: RowNumbers::Off,
+            columns: Column::DEFAULT.to_vec(),
+            long_values: LongValues::Truncate,
+            name_width: None,
+            type_colors: TypeColors::default(),
+            monochrome: false,
+            last_changes: HashMap::new(),
+            description_scroll: None,
+            value_scroll: None,
+            parse_warnings: Vec::new(),
+            paths: PathStatus::new(),
+            docs: VariableDocs::new(),
+            enum_hints: HashMap::new(),
+            descriptions: HashMap::new(),
+            defaults: HashMap::new(),
+            new_since_session: HashSet::new(),
+            pinned: Vec::new(),
+            pinned_rows: 0,
+            value_cells: HashMap::new(),
+            name_index,
+            var_rows: Vec::new(),
+        };
+        state.lay_out_rows();
+        state
+    }
+
+    /// Variables of the build dir's cache, none if it can't be read
+    pub fn load(build_dir: &Path) -> Self {
+        Self::try_load(build_dir).unwrap_or_else(|_| Self::new(Vec::new()))
+    }
+
+    /// Read the cache of `build_dir`, with the project's enum values and
+    /// defaults the cache doesn't have yet
+    pub fn try_load(build_dir: &Path) -> io::Result<Self> {
+        let (mut vec, parse_warnings) = Cache::load(build_dir)?.into_parts();
+        for warning in &parse_warnings {
+            tracing::warn!("{}: {}", build_dir.join("CMakeCache.txt").display(), warning);
+        }
+
+        // Enums declared in the project but not yet written to the cache
+        // as -STRINGS properties (e.g. before the next configure)
+        let source_dir = local_source_dir(build_dir);
+        if let Some(source_dir) = &source_dir
+            && let Ok(scanner) = EnumScanner::new()
+        {
+            let enums = scanner.scan(source_dir);
+            for var in vec.iter_mut().filter(|v| v.typ == VarType::Str) {
+                if let Some(values) = enums.get(&var.name) {
+                    var.make_enum(values);
+                }
+            }
+        }
+
+        let mut state = Self::new(vec);
+        state.parse_warnings = parse_warnings;
+        state.paths.wsl = Wsl::detect(build_dir);
+        if let Some(source_dir) = &source_dir
+            && let Ok(scanner) = DefaultScanner::new()
+        {
+            state.defaults = scanner.scan(source_dir);
+        }
+        Ok(state)
+    }
+
+    /// Re-read the cache from disk, keeping the view settings and the
+    /// selection on the same variable
+    pub fn reload(&mut self, build_dir: &Path) {
+        self.swap_in(Self::load(build_dir));
+    }
+
+    /// Like [`reload`](Self::reload), but the pending edits and the entries
+    /// added in the UI carry over to the re-read variables
+    pub fn reload_keeping_edits(&mut self, build_dir: &Path) {
+        self.swap_in_keeping_edits(Self::load(build_dir));
+    }
+
+    /// Take the variables of `fresh`, the cache of the same build dir read
+    /// again (e.g. on a worker thread), keeping the view settings. The
+    /// selection and the scroll position stay on the same variables.
+    pub fn swap_in(&mut self, fresh: Self) {
+        let anchor = self.anchor();
+
+        self.vars = fresh.vars;
+        self.name_index = fresh.name_index;
+        self.longest_name = fresh.longest_name;
+        self.parse_warnings = fresh.parse_warnings;
+        self.defaults = fresh.defaults;
+        self.paths.clear();
+        self.paths.wsl = fresh.paths.wsl;
+        self.apply_enum_hints();
+        self.lay_out_rows();
+
+        self.restore_anchor(anchor);
+    }
+
+    /// Like [`swap_in`](Self::swap_in), with the pending edits and the
+    /// entries added in the UI carried over
+    pub fn swap_in_keeping_edits(&mut self, fresh: Self) {
+        let anchor = self.anchor();
+        let edits: Vec<CacheVarTui> = self.vars.iter().filter(|v| v.is_modified()).cloned().collect();
+        self.swap_in(fresh);
+        for edit in edits {
+            match self.vars.iter_mut().find(|v| v.var.name == edit.var.name) {
+                Some(var) => {
+                    var.new_val = edit.new_val.clone();
+                    var.take_property_edits(edit);
+                }
+                None if edit.is_new => {
+                    let pos = self.vars.partition_point(|v| v.var.name < edit.var.name);
+                    self.name_index.insert(&edit.var.name, pos);
+                    self.vars.insert(pos, edit);
+                }
+                // Gone from the cache, nothing left to edit
+                None => {}
+            }
+        }
+        self.lay_out_rows();
+        self.restore_anchor(anchor);
+    }
+
+    /// The selected variable, or the top one when nothing is selected, and
+    /// how many rows below the top of the view it is
+    fn anchor(&self) -> Option<(String, usize)> {
+        let offset = self.table.offset();
+        let row = self.table.selected().unwrap_or(offset);
+        let &var_idx = self.rows.get(row)?;
+        Some((self.vars[var_idx].var.name.clone(), row.saturating_sub(offset)))
+    }
+
+    /// Put the variable of [`anchor`](Self::anchor) back where it was in
+    /// the view, wherever its row is now
+    fn restore_anchor(&mut self, anchor: Option<(String, usize)>) {
+        let Some((name, below_top)) = anchor else { return };
+        let Some(row) = self.rows.iter().position(|&var_idx| self.vars[var_idx].var.name == name) else { return };
+        if self.table.selected().is_some() {
+            self.table.select(Some(row));
+        }
+        *self.table.offset_mut() = row.saturating_sub(below_top);
+    }
+
+    /// Drop all pending edits and the entries added in the UI
+    pub fn discard_edits(&mut self) {
+        let selected_name = self.selected().map(|v| v.var.name.clone());
+        self.vars.retain(|v| !v.is_new);
+        self.name_index = NameIndex::new(self.vars.iter().map(|v| v.var.name.as_str()));
+        for var in self.vars.iter_mut() {
+            var.new_val = var.var.value.clone();
+            var.var.advanced = var.saved_advanced;
+            if var.are_values_modified() {
+                var.set_allowed_values(var.saved_values.clone());
+            }
+        }
+        self.lay_out_rows();
+        if let Some(name) = selected_name {
+            self.select_by_name(&name);
+        }
+    }
+
+    /// Recompute which variables are shown and in which order, after the
+    /// view settings changed. The selection stays on the same variable, or
+    /// goes to the nearest one of the rows before that is still shown.
+    pub fn rebuild(&mut self) {
+        let selected_row = self.table.selected().filter(|&row| row < self.rows.len());
+        let previous_rows = std::mem::take(&mut self.rows);
+        self.lay_out_rows();
+        let Some(selected_row) = selected_row else { return };
+
+        // Outwards from the selected row, below first
+        let nearest = (0..previous_rows.len())
+            .flat_map(|distance| [selected_row.checked_add(distance), selected_row.checked_sub(distance).filter(|_| distance > 0)])
+            .flatten()
+            .filter_map(|row| previous_rows.get(row))
+            .find_map(|&var_idx| self.var_rows[var_idx]);
+        self.table.select(nearest.or((!self.rows.is_empty()).then_some(0)));
+    }
+
+    /// Lay out the rows afresh, after the variables themselves changed. The
+    /// selection is left to the caller.
+    fn lay_out_rows(&mut self) {
+        let mut visible: Vec<usize> = self
+            .vars
+            .iter()
+            .enumerate()
+            .filter(|(_, var)| self.show_advanced || !var.var.advanced)
+            .filter(|(_, var)| self.show_internal || var.var.typ != VarType::Internal)
+            .filter(|(_, var)| self.show_ignored || !self.is_ignored(&var.var.name))
+            .filter(|(_, var)| match self.filter {
+                Some(Filter::Modified) => var.is_modified(),
+                Some(Filter::Problems) => self.issue(var).is_some(),
+                Some(Filter::Packages) => package_manager::owner(&var.var.name).is_some(),
+                Some(Filter::New) => self.new_since_session.contains(&var.var.name),
+                Some(filter) => filter.matches_type(&var.var.typ),
+                None => true,
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        // Vars are kept sorted by name, so a stable sort keeps names ordered within a key
+        let vars = &self.vars;
+        match self.sort {
+            SortOrder::Name => {}
+            SortOrder::Type => visible.sort_by_key(|&i| vars[i].var.typ.to_string()),
+            SortOrder::Value => visible.sort_by(|&a, &b| vars[a].new_val.cmp(&vars[b].new_val)),
+            SortOrder::Modified => visible.sort_by_key(|&i| !vars[i].is_modified()),
+            // Variables that never changed go last
+            SortOrder::Recent => {
+                visible.sort_by_key(|&i| Reverse(self.last_changes.get(&vars[i].var.name).map(|c| c.time)))
+            }
+        }
+
+        // Pinned entries come first, even when the filters hide them
+        let pinned: Vec<usize> = self
+            .pinned
+            .iter()
+            .filter_map(|name| self.index_of(name))
+            .collect();
+        visible.retain(|i| !pinned.contains(i));
+        self.pinned_rows = pinned.len();
+
+        self.rows = pinned.into_iter().chain(visible).collect();
+        self.var_rows = vec![None; self.vars.len()];
+        for (row, &var_idx) in self.rows.iter().enumerate() {
+            self.var_rows[var_idx] = Some(row);
+        }
+        self.value_cells.clear();
+    }
+
+    /// The value cell of `vars[var_idx]`, formatted again only once the
+    /// value or what its paths point at changed
+    fn value_line(&mut self, var_idx: usize) -> Line<'static> {
+        let var = &self.vars[var_idx];
+        // Only path values show the stat results
+        let generation = match var.var.typ {
+            VarType::Filepath | VarType::Dirpath => self.paths.generation(),
+            _ => 0,
+        };
+        if let Some((value, made, line)) = self.value_cells.get(&var_idx)
+            && *value == var.new_val
+            && *made == generation
+        {
+            return line.clone();
+        }
+        let line = value_cell(var, self);
+        self.value_cells.insert(var_idx, (var.new_val.clone(), generation, line.clone()));
+        line
+    }
+
+    /// Number of rows currently shown
+    pub fn visible_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_pinned(&self, name: &str) -> bool {
+        self.pinned.iter().any(|p| p == name)
+    }
+
+    /// Pin `name` or unpin it when it already is. Returns whether it's
+    /// pinned now.
+    pub fn toggle_pin(&mut self, name: &str) -> bool {
+        let pinned = match self.pinned.iter().position(|p| p == name) {
+            Some(i) => {
+                self.pinned.remove(i);
+                false
+            }
+            None => {
+                self.pinned.push(name.to_string());
+                true
+            }
+        };
+        self.rebuild();
+        // Stays hidden when unpinning an entry the filters don't show
+        if !self.select_by_name(name) {
+            self.table.select(Some(0).filter(|_| self.visible_count() > 0));
+        }
+        pinned
+    }
+
+    pub fn modified_count(&self) -> usize {
+        self.vars.iter().filter(|v| v.is_modified()).count()
+    }
+
+    pub fn selected(&self) -> Option<&CacheVarTui> {
+        let var_idx = *self.rows.get(self.table.selected()?)?;
+        self.vars.get(var_idx)
+    }
+
+    pub fn selected_mut(&mut self) -> Option<&mut CacheVarTui> {
+        let var_idx = *self.rows.get(self.table.selected()?)?;
+        self.vars.get_mut(var_idx)
+    }
+
+    /// Where `name` is in `vars`, which are kept sorted by name
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.vars.binary_search_by(|v| v.var.name.as_str().cmp(name)).ok()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CacheVarTui> {
+        self.vars.get(self.index_of(name)?)
+    }
+
+    /// Set the pending value of `name`. Returns false if there's no such variable.
+    pub fn set_value(&mut self, name: &str, value: String) -> bool {
+        match self.index_of(name).map(|idx| &mut self.vars[idx]) {
+            Some(var) => {
+                var.new_val = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the pending allowed values of `name`. Returns false if there's no
+    /// such variable.
+    pub fn set_allowed_values(&mut self, name: &str, values: Vec<String>) -> bool {
+        let Some(idx) = self.index_of(name) else { return false };
+        self.vars[idx].set_allowed_values(values);
+        // An entry turned into an enum or back shows differently
+        self.rebuild();
+        true
+    }
+
+    /// Mark `name` as advanced or not, pending until saved. Returns false if
+    /// there's no such variable.
+    pub fn set_advanced(&mut self, name: &str, advanced: bool) -> bool {
+        let Some(idx) = self.index_of(name) else { return false };
+        self.vars[idx].var.advanced = advanced;
+        self.rebuild();
+        true
+    }
+
+    /// The entries with pending edits of their properties, which cmake
+    /// takes no options for, to carry over a configure with
+    /// [`restore_property_edits`](Self::restore_property_edits)
+    pub fn property_edits(&self) -> Vec<CacheVarTui> {
+        self.vars.iter().filter(|v| v.are_values_modified() || v.is_advanced_modified()).cloned().collect()
+    }
+
+    pub fn restore_property_edits(&mut self, edits: Vec<CacheVarTui>) {
+        for edit in edits {
+            if let Some(idx) = self.index_of(&edit.var.name) {
+                self.vars[idx].take_property_edits(edit);
+            }
+        }
+        self.rebuild();
+    }
+
+    /// `value` with the references to other entries replaced by their pending values
+    pub fn expand(&self, value: &str) -> String {
+        expand(value, &|name| self.get(name).map(|v| v.new_val.clone()))
+    }
+
+    /// What's wrong with the pending value of `var`, if anything
+    pub fn issue(&self, var: &CacheVarTui) -> Option<String> {
+        validate(&var.var, &var.new_val, &self.paths)
+    }
+
+    /// The project's default for `var` when the value differs from it
+    pub fn deviation(&self, var: &CacheVarTui) -> Option<&ProjectDefault> {
+        let default = self.defaults.get(&var.var.name)?;
+        let same = match var.var.typ {
+            VarType::Bool => BoolValue::parse(&var.new_val).value() == BoolValue::parse(&default.value).value(),
+            _ => var.new_val == default.value,
+        };
+        (!same).then_some(default)
+    }
+
+    /// Check `value` before it's set on `name`: Err when it can't be right for
+    /// the type, a warning when CMake will read it other than it looks
+    pub fn check_value(&self, name: &str, value: &str) -> Result<Option<String>, String> {
+        match self.get(name) {
+            Some(var) if var.var.typ == VarType::Bool => check_bool(value),
+            _ => Ok(None),
+        }
+    }
+
+    /// Add an entry created in the UI and select it. Returns false when the
+    /// view settings hide it.
+    pub fn add_var(&mut self, var: CacheVar) -> bool {
+        let name = var.name.clone();
+        let mut entry = CacheVarTui::from(var);
+        entry.is_new = true;
+
+        let pos = self.vars.partition_point(|v| v.var.name < name);
+        self.name_index.insert(&name, pos);
+        self.vars.insert(pos, entry);
+        self.longest_name = self.longest_name.max(name.chars().count());
+        self.lay_out_rows();
+        self.select_by_name(&name)
+    }
+
+    /// Drop an entry added in the UI that isn't in the cache yet. Returns
+    /// false for entries read from the cache, those stay.
+    pub fn remove_new_var(&mut self, name: &str) -> bool {
+        let Some(idx) = self.vars.iter().position(|v| v.var.name == name && v.is_new) else { return false };
+        self.vars.remove(idx);
+        self.name_index.remove(idx);
+        self.lay_out_rows();
+        true
+    }
+
+    /// Select the row showing `name`. Returns false if it isn't visible.
+    pub fn select_by_name(&mut self, name: &str) -> bool {
+        let row = self.rows.iter().position(|&var_idx| self.vars[var_idx].var.name == name);
+        if row.is_some() {
+            self.table.select(row);
+        }
+        row.is_some()
+    }
+
+    /// Select `name`, showing advanced or internal entries if that's what hides it
+    pub fn reveal(&mut self, name: &str) -> bool {
+        if self.select_by_name(name) {
+            return true;
+        }
+        self.show_advanced = true;
+        self.show_internal |= self.get(name).is_some_and(|v| v.var.typ == VarType::Internal);
+        self.show_ignored |= self.is_ignored(name);
+        self.rebuild();
+        self.select_by_name(name)
+    }
+
+    pub fn select_next(&mut self, count: usize) {
+        let Some(last_row) = self.rows.len().checked_sub(1) else {
+            return;
+        };
+        let row = match self.table.selected() {
+            Some(row) => row.saturating_add(count).min(last_row),
+            None => 0,
+        };
+        self.table.select(Some(row));
+    }
+
+    pub fn select_previous(&mut self, count: usize) {
+        let Some(last_row) = self.rows.len().checked_sub(1) else {
+            return;
+        };
+        let row = match self.table.selected() {
+            Some(row) => row.saturating_sub(count).min(last_row),
+            None => last_row,
+        };
+        self.table.select(Some(row));
+    }
+
+    /// Select a row by its 1-based number, as shown in the row number column
+    pub fn select_row(&mut self, number: usize) {
+        let Some(last_row) = self.rows.len().checked_sub(1) else {
+            return;
+        };
+        let row = number.saturating_sub(1).min(last_row);
+        self.table.select(Some(row));
+    }
+
+    pub fn select_first(&mut self) {
+        self.table.select_first();
+    }
+
+    pub fn select_last(&mut self) {
+        self.table.select_last();
+    }
+
+    /// Select the next variable after the current one whose name starts with
+    /// `query`, wrapping around
+    pub fn select_next_match(&mut self, query: &str) {
+        self.select_match(query, true);
+    }
+
+    pub fn select_previous_match(&mut self, query: &str) {
+        self.select_match(query, false);
+    }
+
+    fn select_match(&mut self, query: &str, forward: bool) {
+        if query.is_empty() || self.rows.is_empty() { return; }
+
+        let start_row = self.table.selected().unwrap_or(0);
+        let rows = self.rows.len();
+
+        // The shown match closest after the current row (or before it going
+        // backwards), wrapping around and coming back to the start row last
+        let distance = |row: usize| match forward {
+            true => (row + rows - start_row - 1) % rows,
+            false => (start_row + rows - row - 1) % rows,
+        };
+        let closest = self
+            .name_index
+            .with_prefix(query)
+            .filter_map(|var_idx| self.var_rows[var_idx])
+            .min_by_key(|&row| distance(row));
+        if let Some(row) = closest {
+            self.table.select(Some(row));
+        }
+    }
+
+    pub fn toggle_show_advanced(&mut self) {
+        self.show_advanced = !self.show_advanced;
+        self.rebuild();
+    }
+
+    pub fn toggle_show_internal(&mut self) {
+        self.show_internal = !self.show_internal;
+        self.rebuild();
+    }
+
+    /// Values to pick from for STRING entries and help text for entries
+    /// without one, from the project's .cmake-tui.toml. Kept across reloads.
+    pub fn set_project_hints(&mut self, enums: HashMap<String, Vec<String>>, descriptions: HashMap<String, String>) {
+        self.enum_hints = enums;
+        self.descriptions = descriptions;
+        self.apply_enum_hints();
+        self.rebuild();
+    }
+
+    fn apply_enum_hints(&mut self) {
+        for var in self.vars.iter_mut().filter(|v| v.var.typ == VarType::Str) {
+            if let Some(values) = self.enum_hints.get(&var.var.name) {
+                var.var.make_enum(values);
+                var.saved_values = values.clone();
+            }
+        }
+    }
+
+    pub fn toggle_show_ignored(&mut self) {
+        self.show_ignored = !self.show_ignored;
+        self.rebuild();
+    }
+
+    /// Hide the entries whose names match one of `patterns`
+    pub fn set_ignore_patterns(&mut self, patterns: Vec<Regex>) {
+        self.ignore = patterns;
+        self.rebuild();
+    }
+
+    pub fn is_ignored(&self, name: &str) -> bool {
+        self.ignore.iter().any(|pattern| pattern.is_match(name))
+    }
+
+    /// How many entries the ignore patterns hide or would hide
+    pub fn ignored_count(&self) -> usize {
+        self.vars.iter().filter(|v| self.is_ignored(&v.var.name)).count()
+    }
+
+    pub fn set_filter(&mut self, filter: Option<Filter>) {
+        self.filter = filter;
+        self.rebuild();
+    }
+
+    pub fn set_last_changes(&mut self, last_changes: HashMap<String, LastChange>) {
+        self.last_changes = last_changes;
+        if self.sort == SortOrder::Recent {
+            self.rebuild();
+        }
+    }
+
+    pub fn set_sort(&mut self, sort: SortOrder) {
+        self.sort = sort;
+        self.rebuild();
+    }
+
+    /// Toggle a bool or step an enum to its next value
+    pub fn cycle_value(&mut self) {
+        let Some(var) = self.selected_mut() else { return };
+
+        if var.var.typ == VarType::Bool {
+            var.new_val = CacheVar::toggle_bool(&var.new_val);
+        } else if var.var.typ == VarType::Enum {
+            var.new_val = var.var.cycle_enum(&var.new_val);
+        }
+    }
+
+    /// New values of entries in the cache and the entries added in the UI,
+    /// as [`cmake_cache::write_cache_changes`] takes them
+    pub fn pending_changes(&self) -> (HashMap<String, String>, Vec<CacheVar>) {
+        let values = self
+            .vars
+            .iter()
+            .filter(|v| !v.is_new && v.is_value_modified())
+            .map(|v| (v.var.name.clone(), v.new_val.clone()));
+        // STRINGS properties go in as entries of their own
+        let allowed_values = self
+            .vars
+            .iter()
+            .filter(|v| v.are_values_modified())
+            .map(|v| (format!("{}-STRINGS", v.var.name), v.var.values.join(";")));
+        // cmake reads 0 as not advanced, same as no property
+        let advanced = self
+            .vars
+            .iter()
+            .filter(|v| v.is_advanced_modified())
+            .map(|v| (format!("{}-ADVANCED", v.var.name), if v.var.advanced { "1" } else { "0" }.to_string()));
+        // Marked as set by the user, like ccmake and cmake-gui do
+        let user_modified = self
+            .vars
+            .iter()
+            .filter(|v| v.is_value_modified() && !v.var.modified)
+            .map(|v| (format!("{}-MODIFIED", v.var.name), "ON".to_string()));
+        let changes = values.chain(allowed_values).chain(advanced).chain(user_modified).collect();
+        let added = self
+            .vars
+            .iter()
+            .filter(|v| v.is_new)
+            .map(|v| CacheVar { value: v.new_val.clone(), ..v.var.clone() })
+            .collect();
+        (changes, added)
+    }
+
+    /// The pending edits as -D definitions for a configure, with the type
+    /// for entries the cache doesn't have yet
+    pub fn configure_defines(&self) -> Vec<(String, String)> {
+        self.vars
+            .iter()
+            .filter(|v| v.is_value_modified())
+            .map(|v| match v.is_new {
+                true => (format!("{}:{}", v.var.name, v.var.typ.cache_name()), v.new_val.clone()),
+                false => (v.var.name.clone(), v.new_val.clone()),
+            })
+            .collect()
+    }
+
+    /// Mark all pending values as saved
+    pub fn mark_saved(&mut self) {
+        for var in self.vars.iter_mut() {
+            var.var.modified |= var.is_value_modified();
+            var.var.value = var.new_val.clone();
+            var.saved_values = var.var.values.clone();
+            var.saved_advanced = var.var.advanced;
+            var.is_new = false;
+        }
+    }
+
+    /// Lines the selected variable's description is scrolled down by
+    fn description_offset(&self) -> u16 {
+        match (&self.description_scroll, self.selected()) {
+            (Some((name, offset)), Some(var)) if *name == var.var.name => *offset,
+            _ => 0,
+        }
+    }
+
+    /// Scroll the selected variable's description by `lines`, up when
+    /// negative. The end is clamped when rendering, once the wrapped
+    /// length is known.
+    pub fn scroll_description(&mut self, lines: i16) {
+        let Some(name) = self.selected().map(|v| v.var.name.clone()) else { return };
+        let offset = self.description_offset().saturating_add_signed(lines);
+        self.description_scroll = Some((name, offset));
+    }
+
+    /// Columns the selected value is scrolled sideways by
+    fn value_offset(&self) -> usize {
+        match (&self.value_scroll, self.selected()) {
+            (Some((name, offset)), Some(var)) if *name == var.var.name => *offset,
+            _ => 0,
+        }
+    }
+
+    /// Scroll the selected value sideways by `columns`, left when negative.
+    /// Like the description, the end is clamped when rendering.
+    pub fn scroll_value(&mut self, columns: isize) {
+        let Some(name) = self.selected().map(|v| v.var.name.clone()) else { return };
+        let offset = self.value_offset().saturating_add_signed(columns);
+        self.value_scroll = Some((name, offset));
+    }
+
+    /// Basic navigation and editing keys, for embedding the editor without
+    /// cmake-tui's own key handling. Returns false for keys it doesn't use.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char('<') => self.scroll_value(-VALUE_SCROLL_STEP),
+            KeyCode::Char('>') => self.scroll_value(VALUE_SCROLL_STEP),
+            KeyCode::Char('J') => self.scroll_description(1),
+            KeyCode::Char('K') => self.scroll_description(-1),
+            KeyCode::Char('j') | KeyCode::Down => self.select_next(1),
+            KeyCode::Char('k') | KeyCode::Up => self.select_previous(1),
+            KeyCode::Char('g') | KeyCode::Home => self.select_first(),
+            KeyCode::Char('G') | KeyCode::End => self.select_last(),
+            KeyCode::Char('t') => self.toggle_show_advanced(),
+            KeyCode::Char('i') => self.toggle_show_internal(),
+            KeyCode::Char('x') => self.show_expanded = !self.show_expanded,
+            KeyCode::Char('f') => self.set_filter(None),
+            KeyCode::Char('#') => self.row_numbers = self.row_numbers.next(),
+            KeyCode::Char(' ') => self.cycle_value(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Table of cache variables with the selected variable's description below
+#[derive(Default)]
+pub struct CacheEditorWidget {
+    pending_count: Option<usize>,
+}
+
+impl CacheEditorWidget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count prefix being typed, shown in the table title like vim's showcmd
+    pub fn pending_count(mut self, count: Option<usize>) -> Self {
+        self.pending_count = count;
+        self
+    }
+
+    /// Split `area` into the table and the description pane below it
+    pub fn layout(area: Rect) -> [Rect; 2] {
+        Layout::vertical([Constraint::Fill(9), Constraint::Fill(1)]).areas(area)
+    }
+
+    fn render_table(&self, area: Rect, buf: &mut Buffer, state: &mut CacheEditorState) {
+        let mut title = " Cache Entries ".to_string();
+        if state.sort != SortOrder::Name {
+            title.push_str(&format!("[sort: {}] ", state.sort));
+        }
+
+        // 1. Define the Container Block
+        let mut block = Block::new()
+            .title(Line::raw(title).left_aligned())
+            .borders(Borders::TOP)
+            .border_set(symbols::border::EMPTY)
+            .border_style(TODO_HEADER_STYLE)
+            .bg(NORMAL_ROW_BG);
+
+        // Show the pending count prefix, like vim's showcmd
+        if let Some(count) = self.pending_count {
+            block = block.title(Line::raw(format!(" {} ", count)).right_aligned());
+        }
+
+        // The table is rendered from a copy of its state, so clamp what
+        // select_last() and friends leave out of range here
+        let row_count = state.rows.len();
+        if state.table.selected().is_some_and(|sel| sel >= row_count) {
+            state.table.select(row_count.checked_sub(1));
+        }
+
+        let show_row_numbers = state.row_numbers != RowNumbers::Off;
+        let selected_row = state.table.selected();
+
+        // 2. Define the Column Widths
+        // The name column fits the longest name unless configured otherwise,
+        // with room for the NEW and USER badges when some row has them
+        let mut badge_width = if state.new_since_session.is_empty() { 0 } else { 4 };
+        if state.vars.iter().any(|v| v.var.modified) {
+            badge_width += 5;
+        }
+        if state.monochrome {
+            // The [A] and [!] markers
+            badge_width += 8;
+        }
+        let name_width = state.name_width.unwrap_or(state.longest_name as u16 + 4 + badge_width); // +4 for padding
+        let row_number_width = state.rows.len().max(1).to_string().len();
+        let mut widths: Vec<Constraint> = state
+            .columns
+            .iter()
+            .map(|column| match column {
+                Column::Name => Constraint::Length(name_width),
+                Column::Type => Constraint::Length(20), // Fixed width for Type
+                Column::Value => Constraint::Min(10),   // Remaining space for Value
+                Column::Advanced => Constraint::Length(8),
+                Column::Modified => Constraint::Length(8),
+            })
+            .collect();
+        if show_row_numbers {
+            widths.insert(0, Constraint::Length(row_number_width as u16));
+        }
+
+        // Width the value column ends up with, to fit long values into.
+        // Laid out like the table does, less the highlight symbol.
+        let value_width = state
+            .columns
+            .iter()
+            .position(|c| *c == Column::Value)
+            .map(|i| i + show_row_numbers as usize)
+            .and_then(|i| {
+                let inner = Rect { width: area.width.saturating_sub(1), height: 1, ..area };
+                Layout::horizontal(widths.clone()).spacing(1).split(inner).get(i).map(|r| r.width as usize)
+            })
+            .unwrap_or(0);
+
+        // The selected value scrolled sideways, as far as there's more of it
+        let value_offset = match selected_row.and_then(|row| state.rows.get(row)).copied() {
+            Some(var_idx) => {
+                let len = line_width(&state.value_line(var_idx));
+                state.value_offset().min(len.saturating_sub(value_width.saturating_sub(1)))
+            }
+            None => 0,
+        };
+        if let Some((_, stored)) = state.value_scroll.as_mut() {
+            *stored = value_offset;
+        }
+
+        // 3. Define the Header Row
+        let mut header_cells: Vec<Cell> = state
+            .columns
+            .iter()
+            .map(|column| match column {
+                Column::Name => Cell::from("Name"),
+                Column::Type => Cell::from("Type"),
+                Column::Value => Cell::from("Value"),
+                Column::Advanced => Cell::from("Advanced"),
+                Column::Modified => Cell::from("Modified"),
+            })
+            .collect();
+        if show_row_numbers {
+            header_cells.insert(0, Cell::from("#"));
+        }
+        let header = Row::new(header_cells)
+        .style(TODO_HEADER_STYLE)
+        .height(1)
+        .bottom_margin(1);
+
+        // 4. Lay out the values of the rows that can show, caches of 10k+
+        // entries have far more rows than fit on screen. The pinned rows
+        // have a section of their own above the rest, to stay in view.
+        let pinned = state.pinned_rows.min(row_count);
+        let mut values: HashMap<usize, Text<'static>> = HashMap::new();
+        let mut value_of = |state: &mut CacheEditorState, row: usize| -> Text<'static> {
+            values
+                .entry(row)
+                .or_insert_with(|| {
+                    let mut value = state.value_line(state.rows[row]);
+                    if selected_row == Some(row) && value_offset > 0 {
+                        value = scroll_line(value, value_offset);
+                    }
+                    fit_value(value, value_width, state.long_values)
+                })
+                .clone()
+        };
+
+        let mut table_area = area;
+        let mut pinned_area = None;
+        if pinned > 0 {
+            let height = (pinned as u16 + 1).min(area.height / 2);
+            let [top, rest_area] = Layout::vertical([Constraint::Length(height), Constraint::Fill(1)]).areas(area);
+            pinned_area = Some(top);
+            table_area = rest_area;
+        }
+
+        // The window of the other rows: from the offset of the last frame,
+        // moved as little as needed to show the selected row. Less the
+        // block's title and the header with its margin.
+        let room = table_area.height.saturating_sub(3) as usize;
+        let mut offset = (pinned + state.table.offset()).min(row_count.saturating_sub(1)).max(pinned);
+        if let Some(sel) = selected_row.filter(|&sel| sel >= pinned) {
+            if sel < offset {
+                offset = sel;
+            } else {
+                let mut first = sel;
+                let mut used = value_of(state, sel).lines.len().max(1);
+                while first > offset {
+                    let above = value_of(state, first - 1).lines.len().max(1);
+                    if used + above > room {
+                        break;
+                    }
+                    used += above;
+                    first -= 1;
+                }
+                offset = first;
+            }
+        }
+        let mut texts: Vec<(usize, Text<'static>)> = (0..pinned).map(|row| (row, value_of(state, row))).collect();
+        let mut used = 0;
+        for row in offset..row_count {
+            if used >= room.max(1) {
+                break;
+            }
+            let value = value_of(state, row);
+            used += value.lines.len().max(1);
+            texts.push((row, value));
+        }
+
+        let rows: Vec<Row> = {
+            let state = &*state;
+            let build_row = |i: usize, value: Text<'static>| {
+            let var = &state.vars[state.rows[i]];
+            let color = alternate_colors(i);
+            let height = value.lines.len().max(1) as u16;
+
+            let mut cells: Vec<Cell> = state
+                .columns
+                .iter()
+                .map(|column| match column {
+                    Column::Name => {
+                        let mut name_label = Line::raw(if var.is_modified() {
+                            format!("*{}", var.var.name)
+                        } else {
+                            format!(" {}", var.var.name)
+                        });
+                        if state.new_since_session.contains(&var.var.name) {
+                            name_label.push_span(Span::raw(" NEW").fg(NEW_BADGE_FG_COLOR).bold());
+                        }
+                        if var.var.modified {
+                            name_label.push_span(Span::raw(" USER").fg(USER_BADGE_FG_COLOR));
+                        }
+                        // What color tells otherwise
+                        if state.monochrome && var.var.advanced {
+                            name_label.push_span(Span::raw(" [A]"));
+                        }
+                        if state.monochrome && state.issue(var).is_some() {
+                            name_label.push_span(Span::raw(" [!]").bold());
+                        }
+                        Cell::from(name_label)
+                    }
+                    Column::Type => match state.type_colors.coloring {
+                        TypeColoring::Cell => Cell::from(var.var.typ.to_string()).fg(state.type_colors.get(&var.var.typ)),
+                        _ => Cell::from(var.var.typ.to_string()),
+                    },
+                    Column::Value => Cell::from(value.clone()),
+                    Column::Advanced => Cell::from(if var.var.advanced { "yes" } else { "" }),
+                    Column::Modified => Cell::from(if var.is_modified() { "yes" } else { "" }),
+                })
+                .collect();
+            if show_row_numbers {
+                // Relative numbering shows the distance to the selected row,
+                // and the absolute number on the selected row itself
+                let number = match (state.row_numbers, selected_row) {
+                    (RowNumbers::Relative, Some(sel)) if sel != i => sel.abs_diff(i),
+                    _ => i + 1,
+                };
+                cells.insert(0, Cell::from(format!("{:>w$}", number, w = row_number_width)).fg(ROW_NUMBER_FG_COLOR));
+            }
+            let fg = match (state.issue(var), state.type_colors.coloring) {
+                (Some(_), _) => WARNING_FG_COLOR,
+                (None, TypeColoring::Row) => state.type_colors.get(&var.var.typ),
+                (None, _) => TEXT_FG_COLOR,
+            };
+            Row::new(cells).height(height).style(Style::new().bg(color).fg(fg))
+            };
+            texts.into_iter().map(|(i, value)| build_row(i, value)).collect()
+        };
+        let mut rows = rows;
+        let rest = rows.split_off(pinned);
+
+        // 5. Construct the Tables
+        let table = |rows: Vec<Row<'static>>| {
+            Table::new(rows, widths.clone())
+                .row_highlight_style(SELECTED_STYLE)
+                .highlight_symbol(">")
+                .highlight_spacing(HighlightSpacing::Always)
+        };
+        if let Some(pinned_area) = pinned_area {
+            let pinned_block = Block::new()
+                .title(Line::raw(" Pinned ").left_aligned())
+                .borders(Borders::TOP)
+                .border_set(symbols::border::EMPTY)
+                .border_style(TODO_HEADER_STYLE)
+                .bg(NORMAL_ROW_BG);
+            let mut pinned_state = TableState::default().with_selected(selected_row.filter(|&sel| sel < pinned));
+            StatefulWidget::render(table(rows).block(pinned_block), pinned_area, buf, &mut pinned_state);
+        }
+
+        // 6. Render the window, the table's rows numbered after the pinned ones
+        let mut rest_state =
+            TableState::default().with_selected(selected_row.filter(|&sel| sel >= pinned).map(|sel| sel - offset));
+        StatefulWidget::render(table(rest).header(header).block(block), table_area, buf, &mut rest_state);
+        *state.table.offset_mut() = offset - pinned;
+    }
+
+    /// Strip of the active filters, each with the key removing it, so it's
+    /// clear why entries are hidden
+    fn render_filter_chips(&self, area: Rect, buf: &mut Buffer, state: &CacheEditorState) {
+        if area.is_empty() {
+            return;
+        }
+        let mut spans = Vec::new();
+        if state.show_advanced {
+            spans.extend(filter_chip("advanced shown".to_string(), 't'));
+        }
+        if state.show_internal {
+            spans.extend(filter_chip("internal shown".to_string(), 'i'));
+        }
+        if state.show_ignored {
+            spans.extend(filter_chip("ignored shown".to_string(), 'z'));
+        }
+        match state.filter {
+            Some(Filter::Modified) => spans.extend(filter_chip("modified only".to_string(), 'f')),
+            Some(Filter::Problems) => spans.extend(filter_chip("problems only".to_string(), 'f')),
+            Some(Filter::Packages) => spans.extend(filter_chip("vcpkg/conan only".to_string(), 'f')),
+            Some(Filter::New) => spans.extend(filter_chip("new since last session only".to_string(), 'f')),
+            Some(filter) => spans.extend(filter_chip(format!("type: {}", filter), 'f')),
+            None => {}
+        }
+        spans.push(
+            Span::raw(format!("{} of {} entries shown", state.visible_count(), state.vars.len()))
+                .fg(ROW_NUMBER_FG_COLOR),
+        );
+        Paragraph::new(Line::from(spans)).bg(NORMAL_ROW_BG).render(area, buf);
+    }
+
+    fn render_selected_var(&self, area: Rect, buf: &mut Buffer, state: &mut CacheEditorState) {
+
+        let (name, desc) = if let Some(var) = state.selected() {
+            let mut values: String = "".to_string();
+            if var.var.typ == VarType::Enum {
+                values = format!("\n\nPossible values: \n{}", var.var.values.join(", "));
+            }
+            if has_references(&var.new_val) {
+                match state.show_expanded {
+                    true => values.push_str(&format!("\n\nExpanded: {}", state.expand(&var.new_val))),
+                    false => values.push_str("\n\nReferences other variables, x expands them"),
+                }
+            }
+            if let Some(issue) = state.issue(var) {
+                values.push_str(&format!("\n\n{}", issue));
+            }
+            if let Some(default) = state.deviation(var) {
+                values.push_str(&format!(
+                    "\n\nThe project default is '{}' (declared in {}), R resets to it",
+                    default.value,
+                    default.file.display()
+                ));
+            }
+            if var.var.modified {
+                values.push_str("\n\nSet by hand in ccmake, cmake-gui or cmake-tui (MODIFIED property)");
+            }
+            if let Some(change) = state.last_changes.get(&var.var.name) {
+                values.push_str(&format!("\n\nLast changed {}, {}", timestamp::format(change.time), change.how));
+            }
+            // The project's own description comes before cmake's documentation
+            let desc = match (is_unhelpful(&var.var.desc), state.descriptions.get(&var.var.name)) {
+                (true, Some(description)) => description.clone(),
+                (true, None) => match state.docs.get(&var.var.name) {
+                    Docs::Found(docs) => format!("From the CMake documentation:\n\n{}", docs),
+                    Docs::Pending => "Looking up the CMake documentation...".to_string(),
+                    Docs::None => var.var.desc.clone(),
+                },
+                (false, _) => var.var.desc.clone(),
+            };
+            let mut text = Text::from(desc);
+            // The structure of values with generator expressions or references
+            if has_structure(&var.new_val) {
+                let mut value = Line::raw("Value: ");
+                value.spans.extend(highlight(&var.new_val));
+                text.lines.extend([Line::default(), value]);
+            }
+            // Each of them starts with a blank line, one of which the line break ending
+            // the text so far was when it was one string
+            if let Some(values) = values.strip_prefix('\n') {
+                text.extend(Text::from(values.to_string()));
+            }
+            (var.var.name.clone(), text)
+        } else {
+            ("No var".to_string(), Text::raw("Nothing selected..."))
+        };
+
+        let mut block = Block::new()
+            .title(Line::raw(name).left_aligned())
+            .borders(Borders::TOP)
+            .border_set(symbols::border::EMPTY)
+            .border_style(TODO_HEADER_STYLE)
+            .bg(NORMAL_ROW_BG)
+            .padding(Padding::horizontal(1));
+
+        let paragraph = Paragraph::new(desc).fg(TEXT_FG_COLOR).wrap(Wrap { trim: false });
+        let inner = block.inner(area);
+        let max_offset = (paragraph.line_count(inner.width) as u16).saturating_sub(inner.height);
+        let offset = state.description_offset().min(max_offset);
+        if let Some((_, stored)) = state.description_scroll.as_mut() {
+            *stored = offset;
+        }
+
+        // Tell there's more to read, and how to get to it
+        let more = match (offset > 0, offset < max_offset) {
+            (true, true) => Some(" ↑↓ J/K "),
+            (false, true) => Some(" ↓ more, J/K "),
+            (true, false) => Some(" ↑ K "),
+            (false, false) => None,
+        };
+        if let Some(more) = more {
+            block = block.title(Line::raw(more).right_aligned());
+        }
+
+        paragraph.block(block).scroll((offset, 0)).render(area, buf);
+    }
+}
+
+impl StatefulWidget for CacheEditorWidget {
+    type State = CacheEditorState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let [list_area, footer_area] = Self::layout(area);
+        let chips_height = if state.show_advanced || state.show_internal || state.show_ignored || state.filter.is_some() { 1 } else { 0 };
+        let [chips_area, list_area] =
+            Layout::vertical([Constraint::Length(chips_height), Constraint::Fill(1)]).areas(list_area);
+        self.render_filter_chips(chips_area, buf, state);
+        self.render_table(list_area, buf, state);
+        self.render_selected_var(footer_area, buf, state);
+    }
+}
+
+/// Value as shown in the table, with the project default when it differs
+fn value_cell(var: &CacheVarTui, state: &CacheEditorState) -> Line<'static> {
+    let mut line = match var.var.typ {
+        VarType::Bool => Line::raw(bool_cell(&var.new_val)),
+        VarType::Filepath | VarType::Dirpath => path_cell(&var.new_val, var.var.typ == VarType::Dirpath, &state.paths),
+        _ => Line::from(highlight(&var.new_val)),
+    };
+    if let Some(default) = state.deviation(var) {
+        line.push_span(Span::raw(format!("  (default: {})", default.value)).fg(ROW_NUMBER_FG_COLOR));
+    }
+    line
+}
+
+fn line_width(line: &Line) -> usize {
+    line.spans.iter().map(|span| span.content.chars().count()).sum()
+}
+
+/// Drop the first `offset` characters of a value scrolled sideways, with an
+/// ellipsis in place of the first one left to show there's more before
+fn scroll_line(line: Line<'static>, offset: usize) -> Line<'static> {
+    let mut skip = offset + 1;
+    let mut scrolled = Line::from(Span::raw("…").fg(ROW_NUMBER_FG_COLOR));
+    for span in line.spans {
+        let len = span.content.chars().count();
+        if skip >= len {
+            skip -= len;
+            continue;
+        }
+        let rest: String = span.content.chars().skip(skip).collect();
+        scrolled.push_span(Span::styled(rest, span.style));
+        skip = 0;
+    }
+    scrolled
+}
+
+/// Lay a value out in `width` columns, cut short with an ellipsis or
+/// continued on more lines
+fn fit_value(line: Line<'static>, width: usize, long_values: LongValues) -> Text<'static> {
+    if width == 0 || line_width(&line) <= width {
+        return Text::from(line);
+    }
+    let mut lines = vec![Line::default()];
+    let mut used = 0;
+    for span in line.spans {
+        let mut part = String::new();
+        for c in span.content.chars() {
+            if used == width {
+                if long_values == LongValues::Truncate {
+                    // Room for the ellipsis in place of the last character
+                    let last = lines.last_mut().unwrap();
+                    last.push_span(Span::styled(part, span.style));
+                    truncate_line(last);
+                    return Text::from(lines);
+                }
+                lines.last_mut().unwrap().push_span(Span::styled(std::mem::take(&mut part), span.style));
+                lines.push(Line::default());
+                used = 0;
+            }
+            part.push(c);
+            used += 1;
+        }
+        lines.last_mut().unwrap().push_span(Span::styled(part, span.style));
+    }
+    Text::from(lines)
+}
+
+/// Replace the last character of a full line with an ellipsis
+fn truncate_line(line: &mut Line<'static>) {
+    while let Some(span) = line.spans.last_mut() {
+        let mut content = span.content.to_string();
+        if content.pop().is_some() {
+            content.push('…');
+            span.content = content.into();
+            return;
+        }
+        line.spans.pop();
+    }
+}
+
+/// Value of a BOOL as it shows in the table, the checkbox for what CMake reads
+fn bool_cell(value: &str) -> String {
+    let checkbox = match BoolValue::parse(value).value() {
+        Some(true) => "[x]",
+        Some(false) => "[ ]",
+        None => "[?]",
+    };
+    format!("{} {}", checkbox, value)
+}
+
+/// Value of a PATH/FILEPATH with a marker for what it points at
+fn path_cell(value: &str, want_dir: bool, paths: &PathStatus) -> Line<'static> {
+    let marker = match paths.check(value, want_dir).map(|c| c.state) {
+        Some(PathState::Exists) => Span::raw("✓ ").fg(GREEN.c700),
+        Some(PathState::Missing) => Span::raw("✗ ").fg(RED.c500),
+        Some(PathState::WrongKind) => Span::raw("≠ ").fg(YELLOW.c500),
+        Some(PathState::Unknown) => Span::raw("… ").fg(ROW_NUMBER_FG_COLOR),
+        None => Span::raw("  "),
+    };
+    let mut line = Line::from(marker);
+    line.spans.extend(highlight(value));
+    line
+}
+
+fn filter_chip(label: String, key: char) -> [Span<'static>; 3] {
+    [
+        Span::raw(format!(" {} ", label)).fg(SLATE.c100).bg(BLUE.c700),
+        Span::raw(format!(" {} ✕ ", key)).fg(SLATE.c300).bg(BLUE.c900),
+        Span::raw(" "),
+    ]
+}
+
+const fn alternate_colors(i: usize) -> Color {
+    if i.is_multiple_of(2) {
+        NORMAL_ROW_BG
+    } else {
+        ALT_ROW_BG_COLOR
+    }
+}
@@ -0,0 +1,172 @@
+use std::{fs, process::Stdio};
+
+use crate::cache_editor::CacheEditorState;
+use crate::cmake;
+use crate::config::cache_dir;
+use crate::json::{self, JsonValue};
+
+// Output of `cmake -E capabilities` below the cache dir, after a line naming
+// the cmake and version it came from
+const CACHE_FILE: &str = "capabilities";
+
+/// A generator cmake can write build files for
+#[derive(Debug, Clone)]
+pub struct Generator {
+    pub name: String,
+    // Takes -A, with the platforms it knows of when it lists them
+    pub platform_support: bool,
+    pub platforms: Vec<String>,
+    // Takes -T
+    pub toolset_support: bool,
+}
+
+/// What the cmake in use supports, from `cmake -E capabilities`
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    pub version: String,
+    pub generators: Vec<Generator>,
+    // File-api object kinds with their major versions, e.g. "codemodel v2"
+    pub file_api: Vec<String>,
+    pub tls: bool,
+    pub debugger: bool,
+}
+
+impl Capabilities {
+    fn parse(json: &JsonValue) -> Option<Self> {
+        let strings = |value: Option<&JsonValue>| -> Vec<String> {
+            value
+                .and_then(|v| v.as_array())
+                .map(|items| items.iter().filter_map(|i| i.as_str()).map(str::to_string).collect())
+                .unwrap_or_default()
+        };
+
+        let mut generators = Vec::new();
+        for generator in json.get("generators")?.as_array()? {
+            let name = generator.get("name")?.as_str()?;
+            let base = Generator {
+                name: name.to_string(),
+                platform_support: generator.get("platformSupport").and_then(|v| v.as_bool()).unwrap_or(false),
+                platforms: strings(generator.get("supportedPlatforms")),
+                toolset_support: generator.get("toolsetSupport").and_then(|v| v.as_bool()).unwrap_or(false),
+            };
+            // Extra generators combine with the main one, like "CodeBlocks - Ninja"
+            let extras: Vec<Generator> = strings(generator.get("extraGenerators"))
+                .into_iter()
+                .map(|extra| Generator { name: format!("{} - {}", extra, name), ..base.clone() })
+                .collect();
+            generators.push(base);
+            generators.extend(extras);
+        }
+
+        let file_api = json
+            .get("fileApi")
+            .and_then(|f| f.get("requests"))
+            .and_then(|r| r.as_array())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|request| {
+                let kind = request.get("kind")?.as_str()?;
+                let majors: Vec<String> = request
+                    .get("version")?
+                    .as_array()?
+                    .iter()
+                    .filter_map(|v| v.get("major")?.as_u64())
+                    .map(|major| format!("v{}", major))
+                    .collect();
+                Some(format!("{} {}", kind, majors.join("/")))
+            })
+            .collect();
+
+        Some(Self {
+            version: json.get("version")?.get("string")?.as_str()?.to_string(),
+            generators,
+            file_api,
+            tls: json.get("tls").and_then(|v| v.as_bool()).unwrap_or(false),
+            debugger: json.get("debugger").and_then(|v| v.as_bool()).unwrap_or(false),
+        })
+    }
+
+    /// The capabilities of the cmake in use. Asking cmake takes a moment, so
+    /// the answer is kept in the cache dir until another cmake or version is
+    /// in use.
+    pub fn load() -> Result<Self, String> {
+        let key = format!("{}\t{}", cmake::program().display(), cmake::version().map(|v| v.to_string()).unwrap_or_default());
+        let cache_path = cache_dir().map(|dir| dir.join(CACHE_FILE));
+        let cached = cache_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| content.split_once('\n').filter(|(header, _)| *header == key).map(|(_, json)| json.to_string()));
+
+        let output = match cached {
+            Some(output) => output,
+            None => {
+                let output = cmake::command()
+                    .args(["-E", "capabilities"])
+                    .stdin(Stdio::null())
+                    .stderr(Stdio::null())
+                    .output()
+                    .map_err(|e| format!("Failed to run {}: {}", cmake::program().display(), e))?;
+                if !output.status.success() {
+                    return Err(format!("{} doesn't report its capabilities, it needs to be 3.7 or newer", cmake::program().display()));
+                }
+                let output = String::from_utf8_lossy(&output.stdout).into_owned();
+                // Only an optimization, asking again next time is fine
+                if let Some(path) = &cache_path
+                    && let Some(dir) = path.parent()
+                {
+                    let _ = fs::create_dir_all(dir).and_then(|()| fs::write(path, format!("{}\n{}", key, output)));
+                }
+                output
+            }
+        };
+        json::parse(&output)
+            .ok()
+            .and_then(|json| Self::parse(&json))
+            .ok_or_else(|| "cmake -E capabilities gave output that doesn't parse".to_string())
+    }
+}
+
+/// A generator with the platform and toolset to pass along with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorChoice {
+    pub generator: String,
+    pub platform: Option<String>,
+    pub toolset: Option<String>,
+}
+
+impl GeneratorChoice {
+    /// The generator a build dir was configured with, None without a cache
+    pub fn from_cache(editor: &CacheEditorState) -> Option<Self> {
+        let value = |name: &str| editor.get(name).map(|v| v.var.value.clone()).filter(|v| !v.is_empty());
+        Some(Self {
+            generator: value("CMAKE_GENERATOR")?,
+            platform: value("CMAKE_GENERATOR_PLATFORM"),
+            toolset: value("CMAKE_GENERATOR_TOOLSET"),
+        })
+    }
+
+    /// Arguments for a configure that creates the cache
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec!["-G".to_string(), self.generator.clone()];
+        if let Some(platform) = &self.platform {
+            args.extend(["-A".to_string(), platform.clone()]);
+        }
+        if let Some(toolset) = &self.toolset {
+            args.extend(["-T".to_string(), toolset.clone()]);
+        }
+        args
+    }
+}
+
+impl std::fmt::Display for GeneratorChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.generator)?;
+        if let Some(platform) = &self.platform {
+            write!(f, ", platform {}", platform)?;
+        }
+        if let Some(toolset) = &self.toolset {
+            write!(f, ", toolset {}", toolset)?;
+        }
+        Ok(())
+    }
+}
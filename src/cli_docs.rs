@@ -0,0 +1,363 @@
+//! Shell completions and the man page, generated from the clap definition of
+//! the command line so they follow it as options are added.
+
+use clap::{Arg, ArgAction, Command, ValueEnum, ValueHint};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Completion script for `shell`, to be sourced or put where the shell
+/// looks for completions
+pub fn completions(cmd: &Command, shell: Shell) -> String {
+    let mut cmd = cmd.clone();
+    cmd.build();
+    match shell {
+        Shell::Bash => bash(&cmd),
+        Shell::Zsh => zsh(&cmd),
+        Shell::Fish => fish(&cmd),
+    }
+}
+
+fn options(cmd: &Command) -> impl Iterator<Item = &Arg> {
+    cmd.get_arguments().filter(|a| !a.is_positional() && !a.is_hide_set())
+}
+
+fn subcommands(cmd: &Command) -> impl Iterator<Item = &Command> {
+    cmd.get_subcommands().filter(|c| !c.is_hide_set())
+}
+
+fn takes_value(arg: &Arg) -> bool {
+    matches!(arg.get_action(), ArgAction::Set | ArgAction::Append)
+}
+
+fn is_path(arg: &Arg) -> bool {
+    matches!(
+        arg.get_value_hint(),
+        ValueHint::AnyPath | ValueHint::FilePath | ValueHint::DirPath | ValueHint::ExecutablePath
+    )
+}
+
+fn possible_values(arg: &Arg) -> Vec<String> {
+    arg.get_possible_values().iter().filter(|v| !v.is_hide_set()).map(|v| v.get_name().to_string()).collect()
+}
+
+// First line of the help text, what fits a completion menu
+fn short_help(help: Option<&clap::builder::StyledStr>) -> String {
+    help.map(|h| h.to_string().lines().next().unwrap_or_default().to_string()).unwrap_or_default()
+}
+
+fn flags(arg: &Arg) -> Vec<String> {
+    let short = arg.get_short_and_visible_aliases().unwrap_or_default().into_iter().map(|s| format!("-{}", s));
+    let long = arg.get_long_and_visible_aliases().unwrap_or_default().into_iter().map(|l| format!("--{}", l));
+    short.chain(long).collect()
+}
+
+fn bash(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let function = format!("_{}", name.replace('-', "_"));
+    let words = |cmd: &Command| {
+        options(cmd).flat_map(flags).chain(subcommands(cmd).map(|c| c.get_name().to_string())).collect::<Vec<_>>().join(" ")
+    };
+    let mut value_cases = String::new();
+    let mut seen = Vec::new();
+    for arg in options(cmd).chain(subcommands(cmd).flat_map(options)).filter(|a| takes_value(a)) {
+        // Global options show up again in every subcommand
+        if seen.contains(&arg.get_id()) {
+            continue;
+        }
+        seen.push(arg.get_id());
+        let values = possible_values(arg);
+        let reply = match (values.is_empty(), is_path(arg)) {
+            (false, _) => format!("COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", values.join(" ")),
+            (true, true) => "COMPREPLY=($(compgen -f -- \"$cur\"))".to_string(),
+            (true, false) => "COMPREPLY=()".to_string(),
+        };
+        value_cases.push_str(&format!("        {})\n            {}\n            return ;;\n", flags(arg).join("|"), reply));
+    }
+    let mut subcommand_cases = String::new();
+    for sub in subcommands(cmd) {
+        let positional_values: Vec<String> = sub.get_positionals().flat_map(possible_values).collect();
+        subcommand_cases.push_str(&format!(
+            "        {})\n            opts=\"{}\"\n            values=\"{}\" ;;\n",
+            sub.get_name(),
+            words(sub),
+            positional_values.join(" ")
+        ));
+    }
+
+    format!(
+        r#"{function}() {{
+    local cur prev sub opts values
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    sub=""
+    for word in "${{COMP_WORDS[@]:1:COMP_CWORD-1}}"; do
+        case "$word" in
+            {subs}) sub="$word" ;;
+        esac
+    done
+
+    case "$prev" in
+{value_cases}    esac
+
+    opts="{top}"
+    values=""
+    case "$sub" in
+{subcommand_cases}    esac
+
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "$opts" -- "$cur"))
+    elif [[ -n "$values" ]]; then
+        COMPREPLY=($(compgen -W "$values" -- "$cur"))
+    else
+        COMPREPLY=($(compgen -W "$opts" -- "$cur") $(compgen -f -- "$cur"))
+    fi
+}}
+
+complete -o filenames -F {function} {name}
+"#,
+        subs = subcommands(cmd).map(|c| c.get_name()).collect::<Vec<_>>().join("|"),
+        top = words(cmd),
+    )
+}
+
+fn zsh_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\'', "'\\''").replace('[', "\\[").replace(']', "\\]")
+}
+
+// `_arguments` specs of the options of `cmd`
+fn zsh_option_specs(cmd: &Command) -> Vec<String> {
+    let mut specs = Vec::new();
+    for arg in options(cmd) {
+        let help = zsh_escape(&short_help(arg.get_help()));
+        let value = match (takes_value(arg), possible_values(arg)) {
+            (false, _) => String::new(),
+            (true, values) if !values.is_empty() => format!(":{}:({})", arg.get_id(), values.join(" ")),
+            (true, _) if is_path(arg) => format!(":{}:_files", arg.get_id()),
+            (true, _) => format!(":{}: ", arg.get_id()),
+        };
+        let repeat = if matches!(arg.get_action(), ArgAction::Append) { "*" } else { "" };
+        for flag in flags(arg) {
+            specs.push(format!("'{}{}[{}]{}'", repeat, flag, help, value));
+        }
+    }
+    specs
+}
+
+fn zsh_positional_specs(cmd: &Command) -> Vec<String> {
+    cmd.get_positionals()
+        .enumerate()
+        .map(|(i, arg)| {
+            let values = possible_values(arg);
+            let action = if values.is_empty() { "_files".to_string() } else { format!("({})", values.join(" ")) };
+            format!("'{}:{}:{}'", i + 1, arg.get_id(), action)
+        })
+        .collect()
+}
+
+fn zsh(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let function = format!("_{}", name.replace('-', "_"));
+    let join = |specs: Vec<String>, indent: &str| specs.join(&format!(" \\\n{}", indent));
+
+    let commands: Vec<String> = subcommands(cmd)
+        .map(|c| format!("'{}:{}'", c.get_name(), zsh_escape(&short_help(c.get_about()))))
+        .collect();
+    let mut subcommand_cases = String::new();
+    for sub in subcommands(cmd) {
+        subcommand_cases.push_str(&format!(
+            "                {})\n                    _arguments \\\n                        {}\n                    ;;\n",
+            sub.get_name(),
+            join([zsh_option_specs(sub), zsh_positional_specs(sub)].concat(), "                        ")
+        ));
+    }
+    // The positional PATH is completed together with the subcommands
+    let top = zsh_option_specs(cmd);
+
+    format!(
+        r#"#compdef {name}
+
+{function}() {{
+    local curcontext="$curcontext" state line
+    _arguments -C \
+        {top} \
+        '1: :->first' \
+        '*:: :->rest'
+
+    case $state in
+        first)
+            local -a commands
+            commands=({commands})
+            _describe -t commands 'command' commands
+            _files
+            ;;
+        rest)
+            case $line[1] in
+{subcommand_cases}                *)
+                    _files
+                    ;;
+            esac
+            ;;
+    esac
+}}
+
+{function} "$@"
+"#,
+        top = join(top, "        "),
+        commands = commands.join(" "),
+    )
+}
+
+fn fish_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn fish(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let subs: Vec<&str> = subcommands(cmd).map(|c| c.get_name()).collect();
+    let mut out = format!("complete -c {} -f\n", name);
+
+    let option_line = |arg: &Arg, condition: &str| {
+        let mut line = format!("complete -c {}{}", name, condition);
+        if let Some(short) = arg.get_short() {
+            line.push_str(&format!(" -s {}", short));
+        }
+        if let Some(long) = arg.get_long() {
+            line.push_str(&format!(" -l {}", long));
+        }
+        let values = possible_values(arg);
+        match (takes_value(arg), values.is_empty()) {
+            (true, true) if is_path(arg) => line.push_str(" -r -F"),
+            (true, true) => line.push_str(" -x"),
+            (true, false) => line.push_str(&format!(" -x -a '{}'", values.join(" "))),
+            (false, _) => {}
+        }
+        let help = short_help(arg.get_help());
+        if !help.is_empty() {
+            line.push_str(&format!(" -d '{}'", fish_escape(&help)));
+        }
+        line + "\n"
+    };
+
+    let top_condition = format!(" -n 'not __fish_seen_subcommand_from {}'", subs.join(" "));
+    for arg in options(cmd) {
+        out.push_str(&option_line(arg, &top_condition));
+    }
+    out.push_str(&format!("complete -c {}{} -F\n", name, top_condition));
+    for sub in subcommands(cmd) {
+        out.push_str(&format!(
+            "complete -c {} -n '__fish_use_subcommand' -a {} -d '{}'\n",
+            name,
+            sub.get_name(),
+            fish_escape(&short_help(sub.get_about()))
+        ));
+        let condition = format!(" -n '__fish_seen_subcommand_from {}'", sub.get_name());
+        for arg in options(sub) {
+            out.push_str(&option_line(arg, &condition));
+        }
+        for arg in sub.get_positionals() {
+            let values = possible_values(arg);
+            match values.is_empty() {
+                true => out.push_str(&format!("complete -c {}{} -F\n", name, condition)),
+                false => out.push_str(&format!("complete -c {}{} -a '{}'\n", name, condition, values.join(" "))),
+            }
+        }
+    }
+    out
+}
+
+fn roff_escape(s: &str) -> String {
+    let escaped = s.replace('\\', "\\e").replace('-', "\\-");
+    // A leading dot or quote would be read as a request
+    match escaped.starts_with('.') || escaped.starts_with('\'') {
+        true => format!("\\&{}", escaped),
+        false => escaped,
+    }
+}
+
+fn roff_options(out: &mut String, args: Vec<&Arg>) {
+    for arg in args {
+        let mut names = flags(arg).iter().map(|f| format!("\\fB{}\\fR", roff_escape(f))).collect::<Vec<_>>().join(", ");
+        let value = arg
+            .get_value_names()
+            .and_then(|v| v.first())
+            .map_or(arg.get_id().to_string().to_uppercase(), |v| v.to_string());
+        if arg.is_positional() {
+            names = format!("\\fI{}\\fR", roff_escape(&value));
+        } else if takes_value(arg) {
+            names.push_str(&format!(" \\fI{}\\fR", roff_escape(&value)));
+        }
+        out.push_str(&format!(".TP\n{}\n", names));
+        let help = arg.get_long_help().or(arg.get_help()).map(|h| h.to_string()).unwrap_or_default();
+        for line in help.lines() {
+            out.push_str(&format!("{}\n", roff_escape(line)));
+        }
+        let values = possible_values(arg);
+        if !values.is_empty() {
+            out.push_str(&format!("Possible values: {}\n", roff_escape(&values.join(", "))));
+        }
+        let defaults: Vec<String> = arg.get_default_values().iter().map(|v| v.to_string_lossy().into_owned()).collect();
+        if !defaults.is_empty() && takes_value(arg) {
+            out.push_str(&format!("Default: {}\n", roff_escape(&defaults.join(", "))));
+        }
+    }
+}
+
+fn roff_usage(cmd: &mut Command) -> String {
+    let usage = cmd.render_usage().to_string();
+    usage
+        .trim_start_matches("Usage:")
+        .lines()
+        .map(|l| format!("{}\n", roff_escape(l.trim())))
+        .collect::<Vec<_>>()
+        .join(".br\n")
+}
+
+/// The man page (section 1) in roff, for `man -l` or to install into man1/
+pub fn manpage(cmd: &Command) -> String {
+    let mut cmd = cmd.clone();
+    cmd.build();
+    let name = cmd.get_name().to_string();
+    let version = cmd.get_version().unwrap_or_default().to_string();
+
+    let mut out = format!(".TH {} 1 \"\" \"{} {}\"\n", roff_escape(&name.to_uppercase()), roff_escape(&name), version);
+    out.push_str(&format!(
+        ".SH NAME\n{} \\- {}\n",
+        roff_escape(&name),
+        roff_escape(&short_help(cmd.get_about()))
+    ));
+    out.push_str(&format!(".SH SYNOPSIS\n{}", roff_usage(&mut cmd)));
+    if let Some(about) = cmd.get_long_about() {
+        out.push_str(".SH DESCRIPTION\n");
+        for line in about.to_string().lines() {
+            out.push_str(&format!("{}\n", roff_escape(line)));
+        }
+    }
+
+    out.push_str(".SH OPTIONS\n");
+    roff_options(&mut out, cmd.get_positionals().chain(options(&cmd)).collect());
+
+    let subs: Vec<Command> = subcommands(&cmd).filter(|c| c.get_name() != "help").cloned().collect();
+    if !subs.is_empty() {
+        out.push_str(".SH COMMANDS\n");
+        for mut sub in subs {
+            out.push_str(&format!(".SS {}\n", roff_escape(sub.get_name())));
+            if let Some(about) = sub.get_long_about().or(sub.get_about()) {
+                out.push_str(&format!("{}\n", roff_escape(&about.to_string())));
+            }
+            let bin_name = format!("{} {}", name, sub.get_name());
+            sub = sub.bin_name(bin_name);
+            out.push_str(&format!(".PP\n{}", roff_usage(&mut sub)));
+            // Global options are listed once above
+            roff_options(&mut out, sub.get_positionals().chain(options(&sub).filter(|a| !a.is_global_set())).collect());
+        }
+    }
+    if !version.is_empty() {
+        out.push_str(&format!(".SH VERSION\n{}\n", roff_escape(&version)));
+    }
+    out
+}
@@ -0,0 +1,137 @@
+use std::{
+    env, fmt,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{Mutex, OnceLock},
+};
+
+use crate::config::Config;
+
+static PROGRAM: OnceLock<PathBuf> = OnceLock::new();
+static VERSION: OnceLock<Option<CmakeVersion>> = OnceLock::new();
+// Environment variables set (or unset, when None) for every cmake run
+static ENV_OVERRIDES: Mutex<Vec<(String, Option<String>)>> = Mutex::new(Vec::new());
+
+/// A cmake release, as reported by `cmake --version`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CmakeVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl CmakeVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// Parse the first line of `cmake --version`, e.g. "cmake version 3.28.1"
+    /// or "cmake version 3.30.0-rc2"
+    pub fn parse(output: &str) -> Option<Self> {
+        let version = output.lines().next()?.strip_prefix("cmake version ")?.trim();
+        let mut parts = version.split(['.', '-']).map(|p| p.parse::<u32>().ok());
+        let major = parts.next()??;
+        let minor = parts.next()??;
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some(Self { major, minor, patch })
+    }
+
+    pub fn at_least(&self, major: u32, minor: u32) -> bool {
+        *self >= Self::new(major, minor, 0)
+    }
+}
+
+impl fmt::Display for CmakeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The cmake to run when none was picked on the command line: $CMAKE, then
+/// `cmake` from the config file, then the one on PATH
+pub fn default_program() -> PathBuf {
+    env::var_os("CMAKE")
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| Config::load().cmake)
+        .unwrap_or_else(|| PathBuf::from("cmake"))
+}
+
+/// Use `program` for every cmake run, or the default one when None. Only the
+/// first call counts, it's meant to be made once at startup.
+pub fn select(program: Option<PathBuf>) {
+    let _ = PROGRAM.set(program.unwrap_or_else(default_program));
+}
+
+/// The cmake executable every subprocess is started with
+pub fn program() -> &'static Path {
+    PROGRAM.get_or_init(default_program)
+}
+
+/// Set (Some) or unset (None) environment variables for the cmake runs
+/// from now on, replacing the overrides set before
+pub fn set_env_overrides(overrides: Vec<(String, Option<String>)>) {
+    if let Ok(mut current) = ENV_OVERRIDES.lock() {
+        *current = overrides;
+    }
+}
+
+fn with_env_overrides(mut cmd: Command) -> Command {
+    if let Ok(overrides) = ENV_OVERRIDES.lock() {
+        for (name, value) in overrides.iter() {
+            match value {
+                Some(value) => cmd.env(name, value),
+                None => cmd.env_remove(name),
+            };
+        }
+    }
+    cmd
+}
+
+/// A command running the selected cmake
+pub fn command() -> Command {
+    with_env_overrides(Command::new(program()))
+}
+
+/// A command running `tool` (ctest, cpack) from the selected cmake's
+/// installation, so they match it. The one on PATH when cmake was given by
+/// name only.
+pub fn tool_command(tool: &str) -> Command {
+    let cmd = match program().parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        Some(dir) => Command::new(dir.join(format!("{}{}", tool, env::consts::EXE_SUFFIX))),
+        None => Command::new(tool),
+    };
+    with_env_overrides(cmd)
+}
+
+/// Run `program --version`, None when it can't be run or doesn't say
+pub fn detect_version(program: &Path) -> Option<CmakeVersion> {
+    let output = Command::new(program)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    CmakeVersion::parse(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Version of the selected cmake, detected on first use
+pub fn version() -> Option<CmakeVersion> {
+    *VERSION.get_or_init(|| detect_version(program()))
+}
+
+/// Refuse a feature needing cmake `major.minor` or newer. Passes when the
+/// version is unknown, cmake will then say itself what it doesn't support.
+pub fn require(feature: &str, major: u32, minor: u32) -> Result<(), String> {
+    match version() {
+        Some(version) if !version.at_least(major, minor) => Err(format!(
+            "{} needs cmake {}.{} or newer, {} is {}",
+            feature,
+            major,
+            minor,
+            program().display(),
+            version
+        )),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,45 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+pub const COMPILE_COMMANDS: &str = "compile_commands.json";
+
+/// Whether `source_dir` already has a compile_commands.json linking to the
+/// one of `build_dir`
+pub fn is_linked(build_dir: &Path, source_dir: &Path) -> bool {
+    let link = source_dir.join(COMPILE_COMMANDS);
+    match (fs::read_link(&link), build_dir.join(COMPILE_COMMANDS).canonicalize()) {
+        (Ok(_), Ok(target)) => link.canonicalize().is_ok_and(|l| l == target),
+        _ => false,
+    }
+}
+
+/// Point `<source_dir>/compile_commands.json` at the one cmake wrote into
+/// `build_dir`, where clangd and other tools look for it. A link left from
+/// another build dir is replaced, a regular file is left alone.
+pub fn link_into_source(build_dir: &Path, source_dir: &Path) -> io::Result<PathBuf> {
+    let target = build_dir.join(COMPILE_COMMANDS).canonicalize()?;
+    let link = source_dir.join(COMPILE_COMMANDS);
+    if let Ok(meta) = fs::symlink_metadata(&link) {
+        if !meta.file_type().is_symlink() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a link, left as is", link.display()),
+            ));
+        }
+        fs::remove_file(&link)?;
+    }
+    symlink(&target, &link)?;
+    Ok(link)
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
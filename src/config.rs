@@ -0,0 +1,210 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use ratatui::style::Color;
+use regex::Regex;
+
+use crate::ignore;
+use crate::action::{AutoSave, Column, LongValues};
+use crate::cache_editor::{RowNumbers, TypeColoring, TypeColors};
+use crate::toml::{self, TomlTable, TomlValue};
+
+/// Where tool state (snapshots, history, backups) is written
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SidecarLocation {
+    // `.cmake-tui/` inside the build directory
+    Build,
+    // The XDG state directory, outside any source or build tree
+    State,
+}
+
+/// What to do when sidecar files would end up in a VCS working tree
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum VcsIgnore {
+    Ask,
+    Always,
+    Never,
+}
+
+/// User settings from `$XDG_CONFIG_HOME/cmake-tui/config.toml`
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub sidecar_location: SidecarLocation,
+    pub vcs_ignore: VcsIgnore,
+    // cmake to run instead of the one on PATH, --cmake and $CMAKE take precedence
+    pub cmake: Option<PathBuf>,
+    // Entries hidden from the table until revealed, as globs or /regexes/
+    pub ignore: Vec<Regex>,
+    // Columns of the cache table, in order
+    pub columns: Vec<Column>,
+    pub long_values: LongValues,
+    // Width of the name column, None to fit the longest name
+    pub name_width: Option<u16>,
+    // Row numbers to start with, `#` cycles through them
+    pub row_numbers: RowNumbers,
+    pub type_colors: TypeColors,
+    // Render without colors, also when $NO_COLOR is set
+    pub monochrome: bool,
+    pub auto_save: AutoSave,
+    // Problem reading the config file, reported once the UI is up
+    pub error: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            sidecar_location: SidecarLocation::Build,
+            vcs_ignore: VcsIgnore::Ask,
+            cmake: None,
+            ignore: Vec::new(),
+            columns: Column::DEFAULT.to_vec(),
+            long_values: LongValues::Truncate,
+            name_width: None,
+            row_numbers: RowNumbers::Off,
+            type_colors: TypeColors::default(),
+            monochrome: false,
+            auto_save: AutoSave::Off,
+            error: None,
+        }
+    }
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+pub fn state_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_STATE_HOME", ".local/state")
+}
+
+pub fn cache_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CACHE_HOME", ".cache")
+}
+
+fn xdg_dir(var: &str, home_fallback: &str) -> Option<PathBuf> {
+    let base = env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(home_fallback)))?;
+    Some(base.join("cmake-tui"))
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let mut config = match config_dir() {
+            Some(dir) => Self::load_from(&dir.join("config.toml")),
+            None => Self::default(),
+        };
+        // https://no-color.org: set and not empty
+        config.monochrome |= env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        config
+    }
+
+    /// Read a config file. A missing file gives the defaults, a broken one
+    /// gives the defaults plus an error to show the user.
+    pub fn load_from(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return Self::default(),
+        };
+        match toml::parse(&content) {
+            Ok(table) => Self::from_table(&table),
+            Err(e) => Self {
+                error: Some(format!("{}: {}", path.display(), e)),
+                ..Self::default()
+            },
+        }
+    }
+
+    pub fn from_table(table: &TomlTable) -> Self {
+        let mut config = Self::default();
+
+        match table.get("sidecar.location").and_then(|v| v.as_str()) {
+            Some("build") | None => {}
+            Some("state") => config.sidecar_location = SidecarLocation::State,
+            Some(other) => config.error = Some(format!("sidecar.location: unknown value `{}`", other)),
+        }
+        match table.get("sidecar.vcs_ignore").and_then(|v| v.as_str()) {
+            Some("ask") | None => {}
+            Some("always") => config.vcs_ignore = VcsIgnore::Always,
+            Some("never") => config.vcs_ignore = VcsIgnore::Never,
+            Some(other) => config.error = Some(format!("sidecar.vcs_ignore: unknown value `{}`", other)),
+        }
+        match table.get("cmake").map(|v| v.as_str()) {
+            Some(Some(path)) if !path.is_empty() => config.cmake = Some(PathBuf::from(path)),
+            Some(Some(_)) | None => {}
+            Some(None) => config.error = Some("cmake: expected the path of a cmake executable".to_string()),
+        }
+        match table.get("save.auto").map(|v| v.as_str().map(str::parse::<AutoSave>)) {
+            Some(Some(Ok(auto_save))) => config.auto_save = auto_save,
+            Some(Some(Err(e))) => config.error = Some(format!("save.auto: {}", e)),
+            Some(None) => config.error = Some("save.auto: expected \"off\", \"configure\", \"exit\" or \"always\"".to_string()),
+            None => {}
+        }
+        match table.get("view.columns") {
+            Some(TomlValue::Array(items)) => {
+                let names: Option<Vec<&str>> = items.iter().map(|item| item.as_str()).collect();
+                match names.ok_or_else(|| "expected strings".to_string()).and_then(|n| Column::parse_list(&n.join(","))) {
+                    Ok(columns) => config.columns = columns,
+                    Err(e) => config.error = Some(format!("view.columns: {}", e)),
+                }
+            }
+            Some(_) => config.error = Some("view.columns: expected a list of column names".to_string()),
+            None => {}
+        }
+        match table.get("view.long_values").map(|v| v.as_str().map(str::parse::<LongValues>)) {
+            Some(Some(Ok(long_values))) => config.long_values = long_values,
+            Some(Some(Err(e))) => config.error = Some(format!("view.long_values: {}", e)),
+            Some(None) => config.error = Some("view.long_values: expected \"truncate\" or \"wrap\"".to_string()),
+            None => {}
+        }
+        match table.get("view.row_numbers").map(|v| v.as_str().map(str::parse::<RowNumbers>)) {
+            Some(Some(Ok(row_numbers))) => config.row_numbers = row_numbers,
+            Some(Some(Err(e))) => config.error = Some(format!("view.row_numbers: {}", e)),
+            Some(None) => config.error = Some("view.row_numbers: expected \"off\", \"absolute\" or \"relative\"".to_string()),
+            None => {}
+        }
+        match table.get("view.name_width") {
+            Some(TomlValue::Int(width)) if *width > 0 => config.name_width = u16::try_from(*width).ok(),
+            Some(TomlValue::String(fit)) if fit == "fit" => {}
+            Some(_) => config.error = Some("view.name_width: expected a width or \"fit\"".to_string()),
+            None => {}
+        }
+        match table.get("theme.type_colors").map(|v| v.as_str().map(str::parse::<TypeColoring>)) {
+            Some(Some(Ok(coloring))) => config.type_colors.coloring = coloring,
+            Some(Some(Err(e))) => config.error = Some(format!("theme.type_colors: {}", e)),
+            Some(None) => config.error = Some("theme.type_colors: expected \"off\", \"cell\" or \"row\"".to_string()),
+            None => {}
+        }
+        match table.get("theme.monochrome") {
+            Some(TomlValue::Bool(monochrome)) => config.monochrome = *monochrome,
+            Some(_) => config.error = Some("theme.monochrome: expected true or false".to_string()),
+            None => {}
+        }
+        let colors = &mut config.type_colors;
+        for (key, color) in [
+            ("theme.bool", &mut colors.bool),
+            ("theme.path", &mut colors.path),
+            ("theme.string", &mut colors.string),
+            ("theme.enum", &mut colors.enumeration),
+            ("theme.internal", &mut colors.internal),
+            ("theme.other", &mut colors.other),
+        ] {
+            match table.get(key).map(|v| v.as_str().map(Color::from_str)) {
+                Some(Some(Ok(value))) => *color = value,
+                Some(_) => config.error = Some(format!("{}: expected a color name like \"green\" or \"#22c55e\"", key)),
+                None => {}
+            }
+        }
+        if let Some(value) = table.get("view.ignore") {
+            match ignore::parse_list(value) {
+                Ok(patterns) => config.ignore = patterns,
+                Err(e) => config.error = Some(e),
+            }
+        }
+        config
+    }
+}
@@ -0,0 +1,72 @@
+//! Panic hook for the TUI. The terminal is put back first, so a bug doesn't
+//! leave the shell in raw mode under a half drawn screen, then a report with
+//! the backtrace and what the app was doing is written for the bug report.
+
+use std::{
+    backtrace::Backtrace,
+    env, fs, io,
+    panic::{self, PanicHookInfo},
+    path::PathBuf,
+    process,
+    sync::Mutex,
+    thread,
+};
+
+use crate::build_info::{BUILD_DATE, FEATURES, GIT_COMMIT, VERSION};
+use crate::config::state_dir;
+use crate::timestamp;
+
+// What the app was doing, refreshed by the UI loop after something changed
+static STATE: Mutex<String> = Mutex::new(String::new());
+
+pub fn set_state(summary: String) {
+    *STATE.lock().unwrap_or_else(|e| e.into_inner()) = summary;
+}
+
+/// Install the hook in front of the ones already there (ratatui's, color_eyre's),
+/// once the terminal has been taken over
+pub fn install() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        tracing::error!("{}", info);
+        let report = write_report(info);
+        // Only the UI thread owns the terminal, the UI keeps going after a
+        // worker thread panicked and the hooks behind would restore it
+        if thread::current().name() != Some("main") {
+            return;
+        }
+        ratatui::restore();
+        // The hooks behind print the panic, the report's path goes below it
+        previous(info);
+        match report {
+            Ok(path) => eprintln!("cmake-tui crashed, the report for the bug is in {}", path.display()),
+            Err(e) => eprintln!("cmake-tui crashed, and writing the report failed: {}", e),
+        }
+    }));
+}
+
+/// `$XDG_STATE_HOME/cmake-tui/crashes/crash-TIME-PID.txt`, or in the temp dir
+fn write_report(info: &PanicHookInfo) -> io::Result<PathBuf> {
+    let dir = state_dir().map_or_else(env::temp_dir, |dir| dir.join("crashes"));
+    fs::create_dir_all(&dir)?;
+    let now = timestamp::now();
+    let path = dir.join(format!("crash-{}-{}.txt", now, process::id()));
+    let state = STATE.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    let features = match FEATURES {
+        "" => String::new(),
+        features => format!(", features: {}", features),
+    };
+    let report = format!(
+        "cmake-tui {} ({} {}{}) crashed at {} UTC\n\n{}\n\nState:\n{}\nBacktrace:\n{}\n",
+        VERSION,
+        GIT_COMMIT,
+        BUILD_DATE,
+        features,
+        timestamp::format(now),
+        info,
+        state,
+        Backtrace::force_capture()
+    );
+    fs::write(&path, report)?;
+    Ok(path)
+}
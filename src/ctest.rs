@@ -0,0 +1,242 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    symbols,
+    text::Line,
+    widgets::{Block, Borders, Cell, HighlightSpacing, Paragraph, Row, StatefulWidget, Table, TableState, Widget},
+};
+
+use crate::source_scan::split_arguments;
+use crate::text_input::TextInput;
+
+const HEADER_STYLE: Style = Style::new().fg(SLATE.c100).bg(BLUE.c800);
+const NORMAL_ROW_BG: Color = SLATE.c950;
+const ALT_ROW_BG: Color = SLATE.c900;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+const TESTFILE: &str = "CTestTestfile.cmake";
+// subdirs() nesting followed, deeper than any real project
+const MAX_DEPTH: usize = 32;
+
+/// A test registered with add_test()
+pub struct TestCase {
+    pub name: String,
+    // Build subdir the test is registered in, relative to the build dir
+    pub dir: PathBuf,
+    pub command: String,
+}
+
+/// The tests of the build dir from the CTestTestfile.cmake files configure
+/// writes, None when testing isn't enabled
+pub fn find_tests(build_dir: &Path) -> Option<Vec<TestCase>> {
+    if !build_dir.join(TESTFILE).is_file() {
+        return None;
+    }
+    let mut tests = Vec::new();
+    read_testfile(build_dir, Path::new(""), 0, &mut tests);
+    Some(tests)
+}
+
+fn read_testfile(build_dir: &Path, dir: &Path, depth: usize, tests: &mut Vec<TestCase>) {
+    let Ok(content) = fs::read_to_string(build_dir.join(dir).join(TESTFILE)) else { return };
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(args) = line.strip_prefix("add_test(").and_then(|a| a.strip_suffix(')')) {
+            let args = split_arguments(args);
+            let Some(name) = args.first().map(|a| unbracket(a)) else { continue };
+            // Multi-config generators register a test once per configuration
+            if tests.iter().any(|t| t.name == name) {
+                continue;
+            }
+            tests.push(TestCase { name: name.to_string(), dir: dir.to_path_buf(), command: args[1..].join(" ") });
+        } else if let Some(args) = line.strip_prefix("subdirs(").and_then(|a| a.strip_suffix(')'))
+            && depth < MAX_DEPTH
+        {
+            for subdir in split_arguments(args) {
+                read_testfile(build_dir, &dir.join(subdir), depth + 1, tests);
+            }
+        }
+    }
+}
+
+/// The content of a bracket argument like `[=[name]=]`
+fn unbracket(arg: &str) -> &str {
+    arg.strip_prefix('[')
+        .map(|a| a.trim_start_matches('='))
+        .and_then(|a| a.strip_prefix('['))
+        .and_then(|a| a.strip_suffix(']'))
+        .map(|a| a.trim_end_matches('='))
+        .and_then(|a| a.strip_suffix(']'))
+        .unwrap_or(arg)
+}
+
+/// Tab with the tests of the project, to run one or all of them with ctest
+pub struct TestsView {
+    tests: Option<Vec<TestCase>>,
+    filter: TextInput,
+    filtering: bool,
+    // Indices into tests of the rows shown
+    rows: Vec<usize>,
+    state: TableState,
+}
+
+impl TestsView {
+    pub fn new() -> Self {
+        Self { tests: None, filter: TextInput::default(), filtering: false, rows: Vec::new(), state: TableState::default() }
+    }
+
+    pub fn load(&mut self, build_dir: &Path) {
+        let selected = self.selected().map(|t| t.name.clone());
+        self.tests = find_tests(build_dir);
+        self.update_rows();
+        // Stay on the same test across a reload
+        if let Some(row) = selected.and_then(|name| self.rows.iter().position(|&i| self.test(i).name == name)) {
+            self.state.select(Some(row));
+        }
+    }
+
+    fn test(&self, idx: usize) -> &TestCase {
+        &self.tests.as_deref().unwrap_or_default()[idx]
+    }
+
+    fn update_rows(&mut self) {
+        let filter = self.filter.text().to_lowercase();
+        self.rows = self
+            .tests
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.name.to_lowercase().contains(&filter))
+            .map(|(i, _)| i)
+            .collect();
+        self.state.select(if self.rows.is_empty() { None } else { Some(0) });
+    }
+
+    pub fn selected(&self) -> Option<&TestCase> {
+        self.state.selected().and_then(|row| self.rows.get(row)).map(|&i| self.test(i))
+    }
+
+    pub fn select_next(&mut self, count: usize) {
+        let last = self.rows.len().saturating_sub(1);
+        self.state.select(self.state.selected().map(|row| (row + count).min(last)));
+    }
+
+    pub fn select_previous(&mut self, count: usize) {
+        self.state.select(self.state.selected().map(|row| row.saturating_sub(count)));
+    }
+
+    pub fn select_first(&mut self) {
+        self.state.select_first();
+    }
+
+    pub fn select_last(&mut self) {
+        self.state.select_last();
+    }
+
+    /// Typing into the filter, after `/`
+    pub fn filtering(&self) -> bool {
+        self.filtering
+    }
+
+    pub fn start_filter(&mut self) {
+        self.filter.clear();
+        self.filtering = true;
+        self.update_rows();
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.update_rows();
+    }
+
+    pub fn handle_filter_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => self.filtering = false,
+            KeyCode::Esc => {
+                self.filtering = false;
+                self.clear_filter();
+            }
+            _ => {
+                if self.filter.handle_key(key) {
+                    self.update_rows();
+                }
+            }
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let [table_area, filter_area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(2)]).areas(area);
+
+        let count = match &self.tests {
+            Some(tests) if self.rows.len() != tests.len() => format!(" ({} of {})", self.rows.len(), tests.len()),
+            Some(tests) => format!(" ({})", tests.len()),
+            None => String::new(),
+        };
+        let block = Block::new()
+            .title(Line::raw(format!("Tests{}", count)).centered())
+            .borders(Borders::TOP)
+            .border_set(symbols::border::EMPTY)
+            .border_style(HEADER_STYLE)
+            .bg(NORMAL_ROW_BG);
+
+        let Some(tests) = &self.tests else {
+            let text = format!("No {} in the build dir, the project doesn't call enable_testing()", TESTFILE);
+            Paragraph::new(text).block(block).fg(TEXT_FG).centered().render(area, buf);
+            return;
+        };
+
+        let header = Row::new(["Name", "Directory", "Command"]).style(HEADER_STYLE).bottom_margin(1);
+        let rows: Vec<Row> = self
+            .rows
+            .iter()
+            .map(|&i| &tests[i])
+            .enumerate()
+            .map(|(i, t)| {
+                let bg = if i % 2 == 0 { NORMAL_ROW_BG } else { ALT_ROW_BG };
+                Row::new([
+                    Cell::from(t.name.as_str()),
+                    Cell::from(t.dir.display().to_string()),
+                    Cell::from(t.command.as_str()),
+                ])
+                .style(Style::new().bg(bg).fg(TEXT_FG))
+            })
+            .collect();
+        let longest_name = tests.iter().map(|t| t.name.len()).max().unwrap_or(0).max(4);
+        let longest_dir = tests.iter().map(|t| t.dir.as_os_str().len()).max().unwrap_or(0).max(9);
+        let widths = [
+            Constraint::Length(longest_name as u16 + 2),
+            Constraint::Length(longest_dir as u16 + 2),
+            Constraint::Min(10),
+        ];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .row_highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(table, table_area, buf, &mut self.state);
+
+        let filter_line = match (self.filtering, self.filter.text().is_empty()) {
+            (true, _) => self.filter.line("Filter: "),
+            (false, false) => Line::raw(format!("Filter: {} (f to clear)", self.filter.text())),
+            (false, true) => Line::default(),
+        };
+        Paragraph::new(filter_line)
+            .block(Block::new().borders(Borders::TOP).border_set(symbols::border::EMPTY).border_style(HEADER_STYLE))
+            .bg(NORMAL_ROW_BG)
+            .fg(TEXT_FG)
+            .render(filter_area, buf);
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{
+        Color, Style, Stylize,
+        palette::tailwind::{AMBER, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::cache_editor::CacheEditorState;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+
+// Frames the slowest one is taken from
+const FRAME_WINDOW: usize = 60;
+
+/// Small box in the top right corner (F11) with how long frames take, how
+/// many entries there are and the last thing that happened, for bug reports
+/// about a slow or stuck UI. It doesn't take keys, everything goes on
+/// working below it.
+pub struct DebugOverlay {
+    pub visible: bool,
+    frame_times: Vec<Duration>,
+    frames: u64,
+    last_event: String,
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        Self { visible: false, frame_times: Vec::new(), frames: 0, last_event: "-".to_string() }
+    }
+
+    pub fn record_frame(&mut self, time: Duration) {
+        if self.frame_times.len() == FRAME_WINDOW {
+            self.frame_times.remove(0);
+        }
+        self.frame_times.push(time);
+        self.frames += 1;
+    }
+
+    pub fn record_event(&mut self, event: String) {
+        self.last_event = event;
+    }
+
+    pub fn last_event(&self) -> &str {
+        &self.last_event
+    }
+
+    /// Draw in the top right corner of `area`
+    pub fn render(&self, area: Rect, buf: &mut Buffer, editor: &CacheEditorState) {
+        let width = 56.min(area.width);
+        let area = Rect { x: area.right() - width, y: area.y, width, height: 6.min(area.height) };
+        Clear.render(area, buf);
+
+        let ms = |d: &Duration| d.as_secs_f64() * 1000.0;
+        let last = self.frame_times.last().map_or(0.0, ms);
+        let slowest = self.frame_times.iter().map(ms).fold(0.0, f64::max);
+        let lines = vec![
+            Line::raw(format!("frame   {:.1} ms, slowest {:.1} ms", last, slowest)),
+            Line::raw(format!("frames  {}", self.frames)),
+            Line::raw(format!(
+                "entries {}, {} shown, {} modified",
+                editor.vars.len(),
+                editor.visible_count(),
+                editor.modified_count()
+            )),
+            Line::raw(format!("last    {}", self.last_event)),
+        ];
+        let block = Block::new()
+            .title(Line::raw(" Debug ").bold())
+            .title_bottom(Line::raw(" <F11> close ").right_aligned())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(AMBER.c500))
+            .bg(PANEL_BG);
+        Paragraph::new(lines).block(block).fg(TEXT_FG).render(area, buf);
+    }
+}
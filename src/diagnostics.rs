@@ -0,0 +1,72 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use cmake_cache::ParseWarning;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+/// Lines of the cache the parser skipped or read differently than written
+pub struct DiagnosticsPane {
+    pub visible: bool,
+    state: ListState,
+}
+
+impl DiagnosticsPane {
+    pub fn new() -> Self {
+        Self { visible: false, state: ListState::default().with_selected(Some(0)) }
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn render(&mut self, warnings: &[ParseWarning], area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(format!(" Cache diagnostics: {} ", warnings.len())).centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [list_area, detail_area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(4)]).areas(inner);
+
+        let items: Vec<ListItem> = warnings
+            .iter()
+            .map(|w| ListItem::new(format!("line {:<6} {}", w.line, w.reason)))
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        let details = match self.state.selected().and_then(|i| warnings.get(i)) {
+            Some(w) => w.content.clone(),
+            None => "Every line of the cache was read".to_string(),
+        };
+        Paragraph::new(details)
+            .block(Block::new().borders(Borders::TOP).border_style(Style::new().fg(BLUE.c500)))
+            .fg(TEXT_FG)
+            .wrap(Wrap { trim: false })
+            .render(detail_area, buf);
+    }
+}
@@ -0,0 +1,98 @@
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::Rect,
+    style::{
+        Color, Style, Stylize,
+        palette::tailwind::{BLUE, RED, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Widget, Wrap},
+};
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const DETAILS_FG: Color = SLATE.c400;
+
+pub enum DialogResult<T> {
+    Picked(T),
+    // <Esc>, or any key for a dialog without choices
+    Cancelled,
+    Open(Dialog<T>),
+}
+
+/// Modal popup with a message, optional details and the keys to answer it
+/// with, each standing for a `T` the caller acts on. Used to confirm
+/// destructive actions and to show errors that need more than the message line.
+pub struct Dialog<T> {
+    title: String,
+    message: String,
+    details: Option<String>,
+    choices: Vec<(char, String, T)>,
+    error: bool,
+}
+
+impl<T> Dialog<T> {
+    pub fn new(title: &str, message: impl Into<String>) -> Self {
+        Self { title: title.to_string(), message: message.into(), details: None, choices: Vec::new(), error: false }
+    }
+
+    /// A dialog for something that went wrong, closed with any key
+    pub fn error(title: &str, message: impl Into<String>) -> Self {
+        Self { error: true, ..Self::new(title, message) }
+    }
+
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    pub fn choice(mut self, key: char, label: &str, value: T) -> Self {
+        self.choices.push((key, label.to_string(), value));
+        self
+    }
+
+    pub fn handle_key(mut self, key: KeyEvent) -> DialogResult<T> {
+        if self.choices.is_empty() {
+            return DialogResult::Cancelled;
+        }
+        match key.code {
+            KeyCode::Esc => DialogResult::Cancelled,
+            KeyCode::Char(c) => match self.choices.iter().position(|(k, _, _)| *k == c) {
+                Some(idx) => DialogResult::Picked(self.choices.swap_remove(idx).2),
+                // Keep asking until one of the choices is picked
+                None => DialogResult::Open(self),
+            },
+            _ => DialogResult::Open(self),
+        }
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let border = if self.error { RED.c500 } else { BLUE.c500 };
+        let mut block = Block::new()
+            .title(Line::raw(format!(" {} ", self.title)).centered().bold())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(border))
+            .bg(PANEL_BG)
+            .padding(Padding::uniform(1));
+        if self.choices.is_empty() {
+            block = block.title_bottom(Line::raw(" press any key ").centered());
+        }
+
+        let mut content = vec![Line::raw(self.message.clone())];
+        if let Some(details) = &self.details {
+            content.push(Line::raw(""));
+            content.extend(details.lines().map(|l| Line::raw(l.to_string()).fg(DETAILS_FG)));
+        }
+        if !self.choices.is_empty() {
+            content.push(Line::raw(""));
+            content.extend(self.choices.iter().map(|(key, label, _)| Line::raw(format!("[{}] {}", key, label))));
+        }
+        Paragraph::new(content)
+            .block(block)
+            .fg(TEXT_FG)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
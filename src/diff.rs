@@ -0,0 +1,110 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, GREEN, RED, SLATE},
+    },
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
+};
+
+use crate::cache_editor::{CacheEditorState, CacheVarTui};
+
+const HEADER_STYLE: Style = Style::new().fg(SLATE.c100).bg(BLUE.c800);
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const OLD_FG: Color = RED.c400;
+const NEW_FG: Color = GREEN.c400;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+/// The pending edits, old value next to the new one, to review before
+/// saving or configuring
+pub struct DiffView {
+    state: ListState,
+}
+
+impl DiffView {
+    pub fn new() -> Self {
+        Self { state: ListState::default().with_selected(Some(0)) }
+    }
+
+    fn edits(editor: &CacheEditorState) -> impl Iterator<Item = &CacheVarTui> {
+        editor.vars.iter().filter(|v| v.is_modified())
+    }
+
+    /// Name of the selected edit
+    pub fn selected<'a>(&self, editor: &'a CacheEditorState) -> Option<&'a str> {
+        Self::edits(editor).nth(self.state.selected()?).map(|v| v.var.name.as_str())
+    }
+
+    pub fn select_next(&mut self, count: usize) {
+        self.state.scroll_down_by(count as u16);
+    }
+
+    pub fn select_previous(&mut self, count: usize) {
+        self.state.scroll_up_by(count as u16);
+    }
+
+    pub fn select_first(&mut self) {
+        self.state.select_first();
+    }
+
+    pub fn select_last(&mut self) {
+        self.state.select_last();
+    }
+
+    pub fn render(&mut self, editor: &CacheEditorState, area: Rect, buf: &mut Buffer) {
+        let edits: Vec<&CacheVarTui> = Self::edits(editor).collect();
+        let block = Block::new()
+            .title(Line::raw(format!("Pending edits ({})", edits.len())).centered())
+            .borders(Borders::TOP)
+            .border_set(symbols::border::EMPTY)
+            .border_style(HEADER_STYLE)
+            .bg(PANEL_BG);
+
+        if edits.is_empty() {
+            Paragraph::new("No pending edits").block(block).fg(TEXT_FG).centered().render(area, buf);
+            return;
+        }
+
+        let longest_name = edits.iter().map(|v| v.var.name.len()).max().unwrap_or(0);
+        let items: Vec<ListItem> = edits
+            .iter()
+            .map(|v| {
+                let name = Span::raw(format!("{:<w$}  ", v.var.name, w = longest_name));
+                // Only properties changed
+                if !v.is_value_modified() {
+                    let mut spans = vec![name];
+                    if v.are_values_modified() {
+                        spans.extend([
+                            Span::raw("allowed values ").dim(),
+                            Span::raw(v.saved_values.join(";")).fg(OLD_FG),
+                            Span::raw(" → "),
+                            Span::raw(v.var.values.join(";")).fg(NEW_FG),
+                            Span::raw("  "),
+                        ]);
+                    }
+                    if v.is_advanced_modified() {
+                        let state = if v.var.advanced { "marked advanced" } else { "no longer advanced" };
+                        spans.push(Span::raw(state).fg(NEW_FG));
+                    }
+                    return ListItem::new(Line::from(spans));
+                }
+                let old = match v.is_new {
+                    true => Span::raw("(new entry)").dim(),
+                    false => Span::raw(v.var.value.clone()).fg(OLD_FG),
+                };
+                ListItem::new(Line::from(vec![name, old, Span::raw(" → "), Span::raw(v.new_val.clone()).fg(NEW_FG)]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
+};
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const NOTE_FG: Color = SLATE.c400;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+// What the usual entries of a build dir hold
+const KNOWN_ENTRIES: &[(&str, &str)] = &[
+    ("CMakeFiles", "cmake's own files and the targets' object files"),
+    ("_deps", "FetchContent sources and builds"),
+    ("Testing", "ctest results"),
+    ("_CPack_Packages", "cpack staging"),
+    ("vcpkg_installed", "vcpkg packages"),
+    (".cmake", "file-api queries and replies"),
+    (".cmake-tui", "cmake-tui's snapshots and history"),
+];
+
+/// Size of one entry at the top of the build dir
+pub struct EntryUsage {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+    // For CMakeFiles/, the part in the `<target>.dir` object dirs
+    pub objects: Option<u64>,
+}
+
+/// Bytes used below `path`, symlinks counted as themselves rather than what
+/// they point to
+fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else { return 0 };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path).map_or(0, |entries| entries.flatten().map(|e| dir_size(&e.path())).sum())
+}
+
+fn measure(path: PathBuf) -> EntryUsage {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let is_dir = fs::symlink_metadata(&path).is_ok_and(|m| m.is_dir());
+    let objects = (name == "CMakeFiles").then(|| {
+        fs::read_dir(&path).map_or(0, |entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().ends_with(".dir"))
+                .map(|e| dir_size(&e.path()))
+                .sum()
+        })
+    });
+    EntryUsage { size: dir_size(&path), name, path, is_dir, objects }
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.1} {}", size, UNITS[unit]),
+    }
+}
+
+/// What takes up the space in the build dir, biggest first. Measured on a
+/// background thread, a build dir can hold a lot of files.
+pub struct DiskUsagePane {
+    pub visible: bool,
+    entries: Vec<EntryUsage>,
+    rx: Option<Receiver<EntryUsage>>,
+    state: ListState,
+    // Entry to select again once it's measured, after measuring again
+    reselect: Option<String>,
+}
+
+impl DiskUsagePane {
+    pub fn new() -> Self {
+        Self { visible: false, entries: Vec::new(), rx: None, state: ListState::default(), reselect: None }
+    }
+
+    /// Measure `build_dir` again and show the pane
+    pub fn open(&mut self, build_dir: &Path) {
+        self.reselect = self.selected().map(|e| e.name.clone());
+        self.entries.clear();
+        let (tx, rx) = mpsc::channel();
+        let paths: Vec<PathBuf> =
+            fs::read_dir(build_dir).map_or(Vec::new(), |entries| entries.flatten().map(|e| e.path()).collect());
+        thread::spawn(move || {
+            for path in paths {
+                if tx.send(measure(path)).is_err() {
+                    break;
+                }
+            }
+        });
+        self.rx = Some(rx);
+        self.state.select(Some(0));
+        self.visible = true;
+    }
+
+    fn poll(&mut self) {
+        let selected = self.reselect.clone().or_else(|| self.selected().map(|e| e.name.clone()));
+        let Some(rx) = self.rx.take() else { return };
+        loop {
+            match rx.try_recv() {
+                Ok(entry) => self.entries.push(entry),
+                Err(mpsc::TryRecvError::Empty) => {
+                    self.rx = Some(rx);
+                    break;
+                }
+                Err(mpsc::TryRecvError::Disconnected) => break,
+            }
+        }
+        // Biggest first, staying on the same entry as the order changes
+        self.entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+        if let Some(row) = selected.and_then(|name| self.entries.iter().position(|e| e.name == name)) {
+            self.state.select(Some(row));
+            self.reselect = None;
+        } else if self.state.selected().is_none() && !self.entries.is_empty() {
+            // Rendering the list while it was still empty unselected it
+            self.state.select(Some(0));
+        }
+    }
+
+    pub fn is_measuring(&self) -> bool {
+        self.rx.is_some()
+    }
+
+    pub fn selected(&self) -> Option<&EntryUsage> {
+        self.state.selected().and_then(|i| self.entries.get(i))
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.poll();
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(" Disk usage ").centered().bold())
+            .title_bottom(Line::raw(" j/k select, x remove the directory, c run the clean target, r measure again, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [total_area, list_area] = Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+        let total: u64 = self.entries.iter().map(|e| e.size).sum();
+        let status = if self.is_measuring() { ", measuring..." } else { "" };
+        Paragraph::new(Line::raw(format!("{} in the build directory{}", format_size(total), status)))
+            .fg(TEXT_FG)
+            .render(total_area, buf);
+
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let size = format_size(entry.size);
+                let name = if entry.is_dir { format!("{}/", entry.name) } else { entry.name.clone() };
+                let mut note = KNOWN_ENTRIES
+                    .iter()
+                    .find(|(known, _)| *known == entry.name)
+                    .map(|(_, note)| note.to_string())
+                    .unwrap_or_default();
+                if let Some(objects) = entry.objects {
+                    note.push_str(&format!(", {} in object dirs", format_size(objects)));
+                }
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{:>10}  {:<28}", size, name)),
+                    Span::raw(note).fg(NOTE_FG),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{
+        Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph,
+        StatefulWidget, Widget, Wrap,
+    },
+};
+
+use cmake_cache::VarType;
+use crate::path_status::is_windows_path;
+use crate::text_input::TextInput;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+/// Variables that all hold the same path
+pub struct DuplicateGroup {
+    pub value: String,
+    pub names: Vec<String>,
+}
+
+fn looks_like_path(typ: &VarType, value: &str) -> bool {
+    if value.is_empty() || value.ends_with("-NOTFOUND") {
+        return false;
+    }
+    if matches!(typ, VarType::Filepath | VarType::Dirpath) {
+        return true;
+    }
+    // Absolute unix path or a windows one like C:/ or C:\
+    value.starts_with('/') || is_windows_path(value)
+}
+
+/// What a path value is grouped by. Windows doesn't tell C:\Foo from
+/// c:/foo/, so neither is told apart here.
+fn group_key(value: &str) -> String {
+    match is_windows_path(value) {
+        true => value.replace('\\', "/").trim_end_matches('/').to_lowercase(),
+        false => value.to_string(),
+    }
+}
+
+/// Group path-like variables by value, keeping only values shared by more
+/// than one variable. Largest groups come first.
+pub fn find_duplicate_paths<'a>(
+    vars: impl Iterator<Item = (&'a str, &'a VarType, &'a str)>,
+) -> Vec<DuplicateGroup> {
+    // The value as the first entry spells it, and the entries
+    let mut by_value: HashMap<String, (&str, Vec<String>)> = HashMap::new();
+    for (name, typ, value) in vars {
+        if looks_like_path(typ, value) {
+            by_value.entry(group_key(value)).or_insert((value, Vec::new())).1.push(name.to_string());
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_value
+        .into_values()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(value, mut names)| {
+            names.sort();
+            DuplicateGroup { value: value.to_string(), names }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.names.len().cmp(&a.names.len()).then_with(|| a.value.cmp(&b.value)));
+    groups
+}
+
+pub struct DuplicatesPane {
+    pub groups: Vec<DuplicateGroup>,
+    pub visible: bool,
+    // Replacement value being typed for the selected group
+    pub replace: Option<TextInput>,
+    state: ListState,
+}
+
+impl DuplicatesPane {
+    pub fn new() -> Self {
+        Self { groups: Vec::new(), visible: false, replace: None, state: ListState::default() }
+    }
+
+    pub fn set_groups(&mut self, groups: Vec<DuplicateGroup>) {
+        let selected = self.state.selected().unwrap_or(0);
+        self.groups = groups;
+        self.state.select(if self.groups.is_empty() {
+            None
+        } else {
+            Some(selected.min(self.groups.len() - 1))
+        });
+    }
+
+    pub fn selected(&self) -> Option<&DuplicateGroup> {
+        self.groups.get(self.state.selected()?)
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn start_replace(&mut self) {
+        if let Some(group) = self.selected() {
+            self.replace = Some(TextInput::new(&group.value));
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let hint = if self.replace.is_some() {
+            " <Enter> replace in all, <Esc> cancel "
+        } else {
+            " j/k select, r replace value in all, <Enter> jump to variable, <Esc> close "
+        };
+        let block = Block::new()
+            .title(Line::raw(format!(" Duplicate path values: {} ", self.groups.len())).centered().bold())
+            .title_bottom(Line::raw(hint).centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [list_area, detail_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(inner);
+
+        let items: Vec<ListItem> = self
+            .groups
+            .iter()
+            .map(|g| ListItem::new(format!("{:>3} x {}", g.names.len(), g.value)))
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        let mut details: Vec<Line> = match self.selected() {
+            Some(g) => g.names.iter().map(|n| Line::raw(format!("  {}", n))).collect(),
+            None => vec![Line::raw("No path value is shared by several variables")],
+        };
+        if let Some(input) = &self.replace {
+            details.insert(0, input.line("Replace with: "));
+            details.insert(1, Line::raw(""));
+        }
+        Paragraph::new(details)
+            .block(Block::new().borders(Borders::TOP).border_style(Style::new().fg(BLUE.c500)))
+            .fg(TEXT_FG)
+            .wrap(Wrap { trim: false })
+            .render(detail_area, buf);
+    }
+}
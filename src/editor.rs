@@ -0,0 +1,67 @@
+use std::{env, fs, io, path::Path, process::Command};
+
+/// The user's preferred editor, following the usual VISUAL > EDITOR > vi order
+pub fn editor_command() -> String {
+    env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Open `value` in the external editor and return the edited text, or None
+/// when the editor exited with an error. Must be called with the terminal
+/// restored to normal mode.
+///
+/// Cache values can't contain newlines, so semicolon lists are edited one
+/// item per line and joined back, other values have their lines joined with
+/// spaces (handy for long flag strings).
+pub fn edit_value(name: &str, value: &str) -> io::Result<Option<String>> {
+    let is_list = value.contains(';');
+    let content = if is_list { value.replace(';', "\n") } else { value.to_string() };
+
+    let path = env::temp_dir().join(format!("cmake-tui-{}-{}.txt", std::process::id(), name));
+    fs::write(&path, format!("{}\n", content))?;
+
+    // The editor command may carry arguments, e.g. `code --wait`
+    let editor = editor_command();
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program).args(parts).arg(&path).status();
+
+    let edited = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+
+    if !status?.success() {
+        return Ok(None);
+    }
+
+    let edited = edited?;
+    let lines: Vec<&str> = edited
+        .lines()
+        .map(|l| l.trim_end_matches('\r'))
+        .filter(|l| !l.is_empty())
+        .collect();
+    let separator = if is_list { ";" } else { " " };
+    Ok(Some(lines.join(separator)))
+}
+
+/// Open `path` at `line` in the external editor, returning whether it exited
+/// successfully. Must be called with the terminal restored to normal mode.
+///
+/// Most editors take `+<line>` before the file, the ones that don't get the
+/// `file:line` form they understand.
+pub fn open_file(path: &Path, line: usize) -> io::Result<bool> {
+    let editor = editor_command();
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let mut cmd = Command::new(program);
+    cmd.args(parts);
+    let name = Path::new(program).file_stem().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    match name.as_str() {
+        "code" | "codium" | "cursor" => cmd.arg("--goto").arg(format!("{}:{}", path.display(), line)),
+        "hx" | "helix" | "subl" | "zed" => cmd.arg(format!("{}:{}", path.display(), line)),
+        _ => cmd.arg(format!("+{}", line)).arg(path),
+    };
+    Ok(cmd.status()?.success())
+}
@@ -0,0 +1,230 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{AMBER, BLUE, SLATE},
+    },
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::cache_editor::CacheEditorState;
+use crate::text_input::TextInput;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const LABEL_FG: Color = SLATE.c400;
+const WARNING_FG: Color = AMBER.c400;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+/// Environment variables cmake reads during a configure, and what for
+const CONFIGURE_ENV: &[(&str, &str)] = &[
+    ("CC", "C compiler, read by the first configure only"),
+    ("CXX", "C++ compiler, read by the first configure only"),
+    ("FC", "Fortran compiler, read by the first configure only"),
+    ("CUDACXX", "CUDA compiler, read by the first configure only"),
+    ("CFLAGS", "Initial CMAKE_C_FLAGS"),
+    ("CXXFLAGS", "Initial CMAKE_CXX_FLAGS"),
+    ("LDFLAGS", "Initial linker flags of executables and libraries"),
+    ("CMAKE_PREFIX_PATH", "Where find_package() and the find_* commands look, before the system paths"),
+    ("PKG_CONFIG_PATH", "Where pkg-config looks for .pc files"),
+    ("CMAKE_GENERATOR", "Generator of a new build dir, when no -G is given"),
+    ("CMAKE_TOOLCHAIN_FILE", "Toolchain file of a new build dir"),
+    ("CMAKE_BUILD_PARALLEL_LEVEL", "Parallel jobs of cmake --build"),
+    ("VCPKG_ROOT", "The vcpkg installation"),
+    ("PATH", "Where programs and compilers given by name are found"),
+];
+
+/// Compiler variables and the cache entries they end up in
+const COMPILER_ENV: &[(&str, &str)] = &[
+    ("CC", "CMAKE_C_COMPILER"),
+    ("CXX", "CMAKE_CXX_COMPILER"),
+    ("FC", "CMAKE_Fortran_COMPILER"),
+    ("CUDACXX", "CMAKE_CUDA_COMPILER"),
+];
+
+/// The environment cmake runs with and overrides of it for the next
+/// configures: set to another value or unset, until cmake-tui exits
+pub struct EnvPane {
+    pub visible: bool,
+    // None unsets the variable
+    pub overrides: BTreeMap<String, Option<String>>,
+    // New value being typed for the selected variable
+    pub edit: Option<TextInput>,
+    names: Vec<String>,
+    // Compilers of the cache the environment disagrees with
+    warnings: Vec<String>,
+    state: ListState,
+}
+
+/// Where `program` is found, as a path or by name on `path` (a PATH value)
+fn resolve_program(program: &str, path: Option<&str>) -> Option<PathBuf> {
+    if program.contains(['/', '\\']) {
+        return Some(PathBuf::from(program));
+    }
+    env::split_paths(path?)
+        .map(|dir| dir.join(format!("{}{}", program, env::consts::EXE_SUFFIX)))
+        .find(|candidate| candidate.is_file())
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+impl EnvPane {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            overrides: BTreeMap::new(),
+            edit: None,
+            names: Vec::new(),
+            warnings: Vec::new(),
+            state: ListState::default(),
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.names = CONFIGURE_ENV.iter().map(|(name, _)| name.to_string()).collect();
+        // Overrides of other variables, from :setenv
+        for name in self.overrides.keys() {
+            if !self.names.contains(name) {
+                self.names.push(name.clone());
+            }
+        }
+        self.edit = None;
+        if self.state.selected().is_none() {
+            self.state.select(Some(0));
+        }
+        self.visible = true;
+    }
+
+    /// The value cmake will see, with the overrides applied
+    pub fn effective(&self, name: &str) -> Option<String> {
+        match self.overrides.get(name) {
+            Some(value) => value.clone(),
+            None => env::var(name).ok(),
+        }
+    }
+
+    /// The overrides in the form [`crate::cmake::set_env_overrides`] takes
+    pub fn override_list(&self) -> Vec<(String, Option<String>)> {
+        self.overrides.iter().map(|(name, value)| (name.clone(), value.clone())).collect()
+    }
+
+    /// Compilers of the cache that CC, CXX and the others now point
+    /// elsewhere than. cmake only reads them for a new cache, so they
+    /// change nothing until a fresh configure.
+    pub fn compiler_mismatches(&self, editor: &CacheEditorState) -> Vec<String> {
+        let path = self.effective("PATH");
+        COMPILER_ENV
+            .iter()
+            .filter_map(|(name, entry)| {
+                let wanted = self.effective(name).filter(|v| !v.trim().is_empty())?;
+                let cached = editor.get(entry).map(|v| v.var.value.clone()).filter(|v| !v.is_empty())?;
+                // CC may carry arguments, e.g. `gcc -m32`
+                let program = wanted.split_whitespace().next()?;
+                let resolved = resolve_program(program, path.as_deref())?;
+                (!same_file(&resolved, Path::new(&cached))).then(|| {
+                    format!(
+                        "{} is {} but {} is {}, F configures from a fresh cache to switch",
+                        name, wanted, entry, cached
+                    )
+                })
+            })
+            .collect()
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.names.get(self.state.selected()?).map(String::as_str)
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn start_edit(&mut self) {
+        if let Some(name) = self.selected() {
+            self.edit = Some(TextInput::new(&self.effective(name).unwrap_or_default()));
+        }
+    }
+
+    pub fn render(&mut self, editor: &CacheEditorState, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        self.warnings = self.compiler_mismatches(editor);
+
+        let hint = match self.edit {
+            Some(_) => " <Enter> set for the next configures, <Esc> cancel ",
+            None => " j/k select, <Enter> override, u unset, r restore, <Esc> close ",
+        };
+        let block = Block::new()
+            .title(Line::raw(" Configure environment ").centered().bold())
+            .title_bottom(Line::raw(hint).centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [warning_area, list_area, detail_area] = Layout::vertical([
+            Constraint::Length(self.warnings.len() as u16),
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+        let warnings: Vec<Line> = self.warnings.iter().map(|w| Line::raw(format!(" ! {}", w))).collect();
+        Paragraph::new(warnings).fg(WARNING_FG).render(warning_area, buf);
+
+        let width = self.names.iter().map(|n| n.len()).max().unwrap_or(0);
+        let items: Vec<ListItem> = self
+            .names
+            .iter()
+            .map(|name| {
+                let marker = if self.overrides.contains_key(name) { "*" } else { " " };
+                let value = match self.effective(name) {
+                    Some(value) => Span::raw(value),
+                    None => Span::raw("(unset)").fg(LABEL_FG),
+                };
+                ListItem::new(Line::from(vec![Span::raw(format!("{}{:<w$}  ", marker, name, w = width)), value]))
+            })
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        let Some(name) = self.selected() else { return };
+        let what = CONFIGURE_ENV.iter().find(|(n, _)| *n == name).map_or("Set with :setenv", |(_, what)| what);
+        let mut details = vec![Line::raw(what).fg(LABEL_FG)];
+        match self.overrides.get(name) {
+            Some(Some(_)) => details.push(Line::raw("Overridden for the next configures").fg(LABEL_FG)),
+            Some(None) => details.push(Line::raw("Unset for the next configures").fg(LABEL_FG)),
+            None => {}
+        }
+        details.push(Line::raw(""));
+        match &self.edit {
+            Some(input) => details.push(input.line(&format!("{}=", name))),
+            None => details.push(Line::raw(self.effective(name).unwrap_or_default())),
+        }
+        Paragraph::new(details)
+            .block(Block::new().borders(Borders::TOP).border_style(Style::new().fg(BLUE.c500)))
+            .fg(TEXT_FG)
+            .wrap(Wrap { trim: false })
+            .render(detail_area, buf);
+    }
+}
@@ -0,0 +1,71 @@
+// Deep enough for real chains of references, shallow enough to stop a
+// variable referencing itself quickly
+const MAX_DEPTH: usize = 16;
+
+/// Whether `value` references other variables with `${}` or `$ENV{}`
+pub fn has_references(value: &str) -> bool {
+    value.contains("${") || value.contains("$ENV{")
+}
+
+/// `value` with `${NAME}` replaced by what `lookup` gives for NAME and
+/// `$ENV{NAME}` by the environment, the way CMake would see it. References
+/// that can't be resolved are kept as written, nested ones (`${A_${B}}`)
+/// are expanded from the inside out.
+pub fn expand(value: &str, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    expand_at_depth(value, lookup, 0)
+}
+
+fn expand_at_depth(value: &str, lookup: &dyn Fn(&str) -> Option<String>, depth: usize) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut i = 0;
+    while i < value.len() {
+        let rest = &value[i..];
+        let prefix = if rest.starts_with("$ENV{") {
+            "$ENV{"
+        } else if rest.starts_with("${") {
+            "${"
+        } else {
+            let c = rest.chars().next().unwrap_or_default();
+            out.push(c);
+            i += c.len_utf8();
+            continue;
+        };
+
+        let Some(len) = closing_brace(&rest[prefix.len()..]) else {
+            // Unterminated, nothing more to expand
+            out.push_str(rest);
+            break;
+        };
+        let name = expand_at_depth(&rest[prefix.len()..prefix.len() + len], lookup, depth);
+        let resolved = match prefix {
+            "$ENV{" => std::env::var(&name).ok(),
+            _ if depth < MAX_DEPTH => lookup(&name).map(|v| expand_at_depth(&v, lookup, depth + 1)),
+            _ => None,
+        };
+        match resolved {
+            Some(v) => out.push_str(&v),
+            None => {
+                out.push_str(prefix);
+                out.push_str(&name);
+                out.push('}');
+            }
+        }
+        i += prefix.len() + len + 1;
+    }
+    out
+}
+
+// Length up to the `}` closing the reference `s` starts in, skipping over
+// nested references
+fn closing_brace(s: &str) -> Option<usize> {
+    let mut open = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => open += 1,
+            '}' if open == 0 => return Some(i),
+            '}' => open -= 1,
+            _ => {}
+        }
+    }
+    None
+}
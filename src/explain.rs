@@ -0,0 +1,202 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, GREEN, SLATE},
+    },
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
+};
+
+use cmake_cache::VarType;
+
+use crate::cache_editor::CacheVarTui;
+use crate::json;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SET_FG: Color = GREEN.c400;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+// Commands whose first argument is the variable they change
+const SETTING_COMMANDS: &[&str] = &["set", "unset", "option", "cmake_dependent_option", "list", "string", "math"];
+
+/// Write the cache entries, pending edits included, as a `cmake -C` script so a
+/// scratch build dir starts with the same settings as the real one
+pub fn write_initial_cache(vars: &[CacheVarTui], path: &Path) -> io::Result<()> {
+    let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"").replace('$', "\\$");
+    let mut script = String::new();
+    for var in vars {
+        let typ = match var.var.typ {
+            // cmake's own bookkeeping, the scratch configure writes it anew
+            VarType::Internal | VarType::Static => continue,
+            VarType::Uninitialized => "STRING",
+            ref typ => typ.cache_name(),
+        };
+        script.push_str(&format!("set({} \"{}\" CACHE {} \"\")\n", var.var.name, quote(&var.new_val), typ));
+    }
+    fs::write(path, script)
+}
+
+/// A traced configure started to explain a variable
+pub struct ExplainRun {
+    pub name: String,
+    pub source_dir: PathBuf,
+    pub initial_cache: PathBuf,
+    pub trace: PathBuf,
+}
+
+/// A CMake command of the traced configure that touches the variable
+pub struct TraceHit {
+    pub file: PathBuf,
+    // 1-based
+    pub line: usize,
+    // The command sets the variable rather than reading it
+    pub sets: bool,
+    // The command with its arguments expanded
+    pub call: String,
+}
+
+/// Read a `--trace-format=json-v1` trace and keep the commands that set or
+/// read `name`. Arguments are expanded in the trace, so reads through
+/// `${name}` are found in the source lines the commands come from.
+pub fn parse_trace_hits(path: &Path, name: &str) -> io::Result<Vec<TraceHit>> {
+    let content = fs::read_to_string(path)?;
+    let word = regex::Regex::new(&format!(r"\b{}\b", regex::escape(name)))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+    let mut sources: HashMap<String, Vec<String>> = HashMap::new();
+
+    let mut hits = Vec::new();
+    for line in content.lines() {
+        let Ok(event) = json::parse(line) else { continue };
+        let (Some(file), Some(cmd), Some(start)) = (
+            event.get("file").and_then(|f| f.as_str()),
+            event.get("cmd").and_then(|c| c.as_str()),
+            event.get("line").and_then(|l| l.as_u64()),
+        ) else {
+            // The version header
+            continue;
+        };
+        let args: Vec<&str> =
+            event.get("args").and_then(|a| a.as_array()).unwrap_or_default().iter().filter_map(|a| a.as_str()).collect();
+        let end = event.get("line_end").and_then(|l| l.as_u64()).unwrap_or(start);
+
+        let source = sources
+            .entry(file.to_string())
+            .or_insert_with(|| fs::read_to_string(file).unwrap_or_default().lines().map(str::to_string).collect());
+        let in_source = source
+            .iter()
+            .skip(start.saturating_sub(1) as usize)
+            .take(end.saturating_sub(start) as usize + 1)
+            .any(|l| word.is_match(l));
+        let cmd = cmd.to_lowercase();
+        let sets = match cmd.as_str() {
+            "set_property" => args.contains(&"CACHE") && args.contains(&name),
+            _ => SETTING_COMMANDS.contains(&cmd.as_str()) && args.iter().take(2).any(|a| *a == name),
+        };
+        if sets || in_source || args.iter().any(|a| word.is_match(a)) {
+            hits.push(TraceHit {
+                file: PathBuf::from(file),
+                line: start as usize,
+                sets,
+                call: format!("{}({})", cmd, args.join(" ")),
+            });
+        }
+    }
+    Ok(hits)
+}
+
+/// Where a variable was set and read during a traced configure
+pub struct ExplainPane {
+    pub visible: bool,
+    name: String,
+    source_dir: PathBuf,
+    hits: Vec<TraceHit>,
+    state: ListState,
+}
+
+impl ExplainPane {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            name: String::new(),
+            source_dir: PathBuf::new(),
+            hits: Vec::new(),
+            state: ListState::default(),
+        }
+    }
+
+    pub fn set_hits(&mut self, name: &str, source_dir: &Path, hits: Vec<TraceHit>) {
+        self.name = name.to_string();
+        self.source_dir = source_dir.to_path_buf();
+        self.hits = hits;
+        self.state = ListState::default().with_selected(Some(0));
+    }
+
+    pub fn selected(&self) -> Option<&TraceHit> {
+        self.state.selected().and_then(|i| self.hits.get(i))
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let sets = self.hits.iter().filter(|h| h.sets).count();
+        let title = format!(
+            " {}: set {} times, read {} times during configure ",
+            self.name,
+            sets,
+            self.hits.len() - sets
+        );
+        let block = Block::new()
+            .title(Line::raw(title).centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Enter> open in $EDITOR, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+
+        if self.hits.is_empty() {
+            let text = format!("No command run during configure touches {}", self.name);
+            Paragraph::new(text).block(block).fg(TEXT_FG).render(area, buf);
+            return;
+        }
+
+        let items: Vec<ListItem> = self
+            .hits
+            .iter()
+            .map(|hit| {
+                let file = hit.file.strip_prefix(&self.source_dir).unwrap_or(&hit.file);
+                let kind = match hit.sets {
+                    true => Span::raw("set  ").fg(SET_FG),
+                    false => Span::raw("read ").dim(),
+                };
+                ListItem::new(Line::from(vec![
+                    kind,
+                    Span::raw(format!("{}:{}  ", file.display(), hit.line)),
+                    Span::raw(hit.call.clone()).dim(),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
@@ -0,0 +1,68 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::cmake;
+use crate::json::{self, JsonValue};
+
+// Our own query dir, so other clients' queries are left alone
+const CLIENT: &str = "client-cmake-tui";
+
+// First cmake release writing each object kind, older ones ignore the query
+const OBJECT_VERSIONS: &[(&str, u32, u32)] = &[
+    ("codemodel-v2", 3, 14),
+    ("cache-v2", 3, 14),
+    ("cmakeFiles-v1", 3, 14),
+    ("toolchains-v1", 3, 20),
+    ("configureLog-v1", 3, 26),
+];
+
+fn api_dir(build_dir: &Path) -> PathBuf {
+    build_dir.join(".cmake").join("api").join("v1")
+}
+
+/// Ask cmake to write the `object` (e.g. "toolchains-v1") on the next
+/// configure, through the file-based API. Fails when the cmake in use is
+/// too old to write it.
+pub fn request(build_dir: &Path, object: &str) -> io::Result<()> {
+    if let Some((_, major, minor)) = OBJECT_VERSIONS.iter().find(|(kind, _, _)| *kind == object) {
+        cmake::require(&format!("The file-api {} object", object), *major, *minor).map_err(io::Error::other)?;
+    }
+    let query_dir = api_dir(build_dir).join("query").join(CLIENT);
+    fs::create_dir_all(&query_dir)?;
+    let query = query_dir.join(object);
+    if !query.exists() {
+        fs::write(query, "")?;
+    }
+    Ok(())
+}
+
+/// The `kind` object (e.g. "toolchains") of the latest reply, None when
+/// cmake hasn't written one yet
+pub fn read_reply(build_dir: &Path, kind: &str) -> Option<JsonValue> {
+    let reply_dir = api_dir(build_dir).join("reply");
+    // Index files are named after their time of writing, the last is the latest
+    let index = fs::read_dir(&reply_dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with("index-") && name.ends_with(".json"))
+        .max()?;
+    let index = json::parse(&fs::read_to_string(reply_dir.join(index)).ok()?).ok()?;
+    let file = index
+        .get("objects")?
+        .as_array()?
+        .iter()
+        .find(|o| o.get("kind").and_then(|k| k.as_str()) == Some(kind))?
+        .get("jsonFile")?
+        .as_str()?;
+    read_reply_file(build_dir, file)
+}
+
+/// A file of the reply another object refers to, like the targets of the
+/// codemodel
+pub fn read_reply_file(build_dir: &Path, file: &str) -> Option<JsonValue> {
+    let path = api_dir(build_dir).join("reply").join(file);
+    json::parse(&fs::read_to_string(path).ok()?).ok()
+}
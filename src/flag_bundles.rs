@@ -0,0 +1,195 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, GREEN, RED, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::cache_editor::CacheEditorState;
+use crate::launcher;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+// Languages compiled with GCC/Clang style flags
+const GCC_LIKE_LANGUAGES: &[&str] = &["C", "CXX", "OBJC", "OBJCXX"];
+const LINKER_FLAG_VARS: &[&str] = &["CMAKE_EXE_LINKER_FLAGS", "CMAKE_SHARED_LINKER_FLAGS", "CMAKE_MODULE_LINKER_FLAGS"];
+
+/// Flags turning on one instrumentation, added to the compiler and linker
+/// flags of the current build type together
+pub struct FlagBundle {
+    pub name: &'static str,
+    pub description: &'static str,
+    compile: &'static [&'static str],
+    link: &'static [&'static str],
+}
+
+pub const BUNDLES: &[FlagBundle] = &[
+    FlagBundle {
+        name: "AddressSanitizer",
+        description: "Out of bounds accesses, use after free and leaks",
+        compile: &["-fsanitize=address", "-fno-omit-frame-pointer"],
+        link: &["-fsanitize=address"],
+    },
+    FlagBundle {
+        name: "UndefinedBehaviorSanitizer",
+        description: "Signed overflow, misaligned pointers, invalid shifts and other undefined behavior",
+        compile: &["-fsanitize=undefined"],
+        link: &["-fsanitize=undefined"],
+    },
+    FlagBundle {
+        name: "ThreadSanitizer",
+        description: "Data races, can't be combined with AddressSanitizer",
+        compile: &["-fsanitize=thread"],
+        link: &["-fsanitize=thread"],
+    },
+    FlagBundle {
+        name: "Coverage",
+        description: "gcov line coverage, for gcovr or lcov",
+        compile: &["--coverage"],
+        link: &["--coverage"],
+    },
+    FlagBundle {
+        name: "Hardening",
+        description: "Fortified libc calls, stack protector and read-only relocations",
+        compile: &["-D_FORTIFY_SOURCE=2", "-fstack-protector-strong"],
+        link: &["-Wl,-z,relro,-z,now"],
+    },
+];
+
+/// A flags entry as it is and as it would be
+pub struct FlagChange {
+    pub name: String,
+    pub before: String,
+    pub after: String,
+}
+
+impl FlagBundle {
+    /// The flag entries of the current build type with the flags they need,
+    /// e.g. CMAKE_CXX_FLAGS_DEBUG for a Debug build
+    fn targets(&self, editor: &CacheEditorState) -> Vec<(String, &'static [&'static str])> {
+        let config = editor.get("CMAKE_BUILD_TYPE").map(|v| v.new_val.to_uppercase()).unwrap_or_default();
+        let suffix = if config.is_empty() { String::new() } else { format!("_{}", config) };
+        let compile = launcher::languages(editor)
+            .into_iter()
+            .filter(|lang| GCC_LIKE_LANGUAGES.contains(lang))
+            .map(|lang| (format!("CMAKE_{}_FLAGS{}", lang, suffix), self.compile));
+        let link = LINKER_FLAG_VARS.iter().map(|var| (format!("{}{}", var, suffix), self.link));
+        compile.chain(link).collect()
+    }
+
+    pub fn is_enabled(&self, editor: &CacheEditorState) -> bool {
+        self.targets(editor).iter().all(|(name, flags)| {
+            let value = editor.get(name).map(|v| v.new_val.as_str()).unwrap_or_default();
+            flags.iter().all(|flag| value.split_whitespace().any(|f| f == *flag))
+        })
+    }
+
+    /// What turning the bundle on, or off when it's on, changes
+    pub fn toggle(&self, editor: &CacheEditorState) -> Vec<FlagChange> {
+        let enabled = self.is_enabled(editor);
+        self.targets(editor)
+            .into_iter()
+            .map(|(name, flags)| {
+                let before = editor.get(&name).map(|v| v.new_val.clone()).unwrap_or_default();
+                let after = match enabled {
+                    true => without_flags(&before, flags),
+                    false => with_flags(&before, flags),
+                };
+                FlagChange { name, before, after }
+            })
+            .filter(|change| change.before != change.after)
+            .collect()
+    }
+}
+
+fn with_flags(value: &str, flags: &[&str]) -> String {
+    let mut result = value.trim().to_string();
+    for flag in flags.iter().filter(|flag| !value.split_whitespace().any(|f| f == **flag)) {
+        if !result.is_empty() {
+            result.push(' ');
+        }
+        result.push_str(flag);
+    }
+    result
+}
+
+fn without_flags(value: &str, flags: &[&str]) -> String {
+    value.split_whitespace().filter(|f| !flags.contains(f)).collect::<Vec<_>>().join(" ")
+}
+
+/// Menu of the bundles, with what toggling the selected one changes
+pub struct FlagBundlesPane {
+    pub visible: bool,
+    state: ListState,
+}
+
+impl FlagBundlesPane {
+    pub fn new() -> Self {
+        Self { visible: false, state: ListState::default().with_selected(Some(0)) }
+    }
+
+    pub fn selected(&self) -> Option<&'static FlagBundle> {
+        self.state.selected().and_then(|i| BUNDLES.get(i))
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn render(&mut self, editor: &CacheEditorState, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(" Sanitizers and instrumentation ").centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Space>/<Enter> toggle, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [list_area, preview_area] =
+            Layout::vertical([Constraint::Length(BUNDLES.len() as u16), Constraint::Fill(1)]).areas(inner);
+
+        let items: Vec<ListItem> = BUNDLES
+            .iter()
+            .map(|bundle| {
+                let checkbox = if bundle.is_enabled(editor) { "[x]" } else { "[ ]" };
+                ListItem::new(format!("{} {:<28} {}", checkbox, bundle.name, bundle.description))
+            })
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        let mut preview = Vec::new();
+        if let Some(bundle) = self.selected() {
+            let action = if bundle.is_enabled(editor) { "Turning it off" } else { "Turning it on" };
+            preview.push(Line::raw(format!("{} changes:", action)));
+            for change in bundle.toggle(editor) {
+                preview.push(Line::raw(""));
+                preview.push(Line::raw(change.name).bold());
+                preview.push(Line::raw(format!("- {}", change.before)).fg(RED.c400));
+                preview.push(Line::raw(format!("+ {}", change.after)).fg(GREEN.c400));
+            }
+        }
+        Paragraph::new(preview)
+            .block(Block::new().borders(Borders::TOP).border_style(Style::new().fg(BLUE.c500)))
+            .fg(TEXT_FG)
+            .wrap(Wrap { trim: false })
+            .render(preview_area, buf);
+    }
+}
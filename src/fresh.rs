@@ -0,0 +1,97 @@
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+/// What the caller should do after the pane handled a key
+pub enum FreshResult {
+    Continue,
+    // The definitions to configure the fresh cache with
+    Pick(Vec<(String, String)>),
+    Cancel,
+}
+
+/// Choice of the pending edits to carry over into a fresh cache, all of
+/// them to begin with. Leaving one out drops a value that may be what
+/// broke the cache.
+pub struct FreshPane {
+    // -D definitions as passed to configure, and whether to keep each
+    defines: Vec<((String, String), bool)>,
+    state: ListState,
+}
+
+impl FreshPane {
+    pub fn new(defines: Vec<(String, String)>) -> Self {
+        Self {
+            defines: defines.into_iter().map(|define| (define, true)).collect(),
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> FreshResult {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return FreshResult::Cancel,
+            KeyCode::Char('j') | KeyCode::Down => self.state.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.state.select_previous(),
+            KeyCode::Char(' ') => {
+                if let Some((_, keep)) = self.state.selected().and_then(|i| self.defines.get_mut(i)) {
+                    *keep = !*keep;
+                }
+            }
+            KeyCode::Char('a') => {
+                let keep = !self.defines.iter().all(|(_, keep)| *keep);
+                self.defines.iter_mut().for_each(|(_, k)| *k = keep);
+            }
+            KeyCode::Enter => {
+                let kept = self.defines.iter().filter(|(_, keep)| *keep).map(|(define, _)| define.clone()).collect();
+                return FreshResult::Pick(kept);
+            }
+            _ => {}
+        }
+        FreshResult::Continue
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(" Fresh configure ").centered().bold())
+            .title_bottom(Line::raw(" <Space> keep/drop, a all, <Enter> continue, <Esc> cancel ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [intro_area, list_area] = Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(inner);
+        Paragraph::new("The cache starts over from the project's defaults. Pending edits to pass along to the fresh configure:")
+            .fg(TEXT_FG)
+            .wrap(Wrap { trim: false })
+            .render(intro_area, buf);
+
+        let items: Vec<ListItem> = self
+            .defines
+            .iter()
+            .map(|((name, value), keep)| {
+                ListItem::new(format!("[{}] -D{}={}", if *keep { "x" } else { " " }, name, value))
+            })
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+    }
+}
@@ -0,0 +1,208 @@
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
+};
+
+use crate::capabilities::{Capabilities, Generator, GeneratorChoice};
+use crate::text_input::TextInput;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const DETAILS_FG: Color = SLATE.c400;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+/// What the caller should do after the picker handled a key
+pub enum GeneratorPickerResult {
+    Continue,
+    // None to leave the choice to cmake
+    Pick(Option<GeneratorChoice>),
+    Cancel,
+}
+
+enum Step {
+    Generator,
+    // Picking the platform for the generator, the list is empty to type it
+    Platform(Generator, ListState),
+    Toolset(Generator, Option<String>, TextInput),
+}
+
+/// Choice of generator from the ones the cmake in use supports, followed by
+/// its platform and toolset for generators that take them, with what else
+/// cmake reports about itself shown on top
+pub struct GeneratorPicker {
+    capabilities: Capabilities,
+    step: Step,
+    state: ListState,
+}
+
+impl GeneratorPicker {
+    pub fn new(capabilities: Capabilities, current: Option<&str>) -> Self {
+        // The first row is cmake's default
+        let selected = current
+            .and_then(|current| capabilities.generators.iter().position(|g| g.name == current))
+            .map_or(0, |i| i + 1);
+        Self { capabilities, step: Step::Generator, state: ListState::default().with_selected(Some(selected)) }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> GeneratorPickerResult {
+        let step = std::mem::replace(&mut self.step, Step::Generator);
+        self.step = match step {
+            Step::Generator => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return GeneratorPickerResult::Cancel,
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.state.select_next();
+                    Step::Generator
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.state.select_previous();
+                    Step::Generator
+                }
+                KeyCode::Enter => match self.state.selected() {
+                    Some(0) => return GeneratorPickerResult::Pick(None),
+                    Some(i) => match self.capabilities.generators.get(i - 1) {
+                        Some(generator) => return self.platform_step(generator.clone()),
+                        None => Step::Generator,
+                    },
+                    None => Step::Generator,
+                },
+                _ => Step::Generator,
+            },
+            Step::Platform(generator, mut state) => match key.code {
+                KeyCode::Esc => Step::Generator,
+                KeyCode::Char('j') | KeyCode::Down => {
+                    state.select_next();
+                    Step::Platform(generator, state)
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    state.select_previous();
+                    Step::Platform(generator, state)
+                }
+                KeyCode::Enter => {
+                    // The first row is the generator's default platform
+                    let platform = state.selected().filter(|&i| i > 0).and_then(|i| generator.platforms.get(i - 1).cloned());
+                    return self.toolset_step(generator, platform);
+                }
+                _ => Step::Platform(generator, state),
+            },
+            Step::Toolset(generator, platform, mut input) => match key.code {
+                KeyCode::Esc => Step::Generator,
+                KeyCode::Enter => {
+                    let toolset = Some(input.text().trim().to_string()).filter(|t| !t.is_empty());
+                    return GeneratorPickerResult::Pick(Some(GeneratorChoice {
+                        generator: generator.name,
+                        platform,
+                        toolset,
+                    }));
+                }
+                _ => {
+                    input.handle_key(key);
+                    Step::Toolset(generator, platform, input)
+                }
+            },
+        };
+        GeneratorPickerResult::Continue
+    }
+
+    fn platform_step(&mut self, generator: Generator) -> GeneratorPickerResult {
+        if generator.platform_support && !generator.platforms.is_empty() {
+            self.step = Step::Platform(generator, ListState::default().with_selected(Some(0)));
+            return GeneratorPickerResult::Continue;
+        }
+        self.toolset_step(generator, None)
+    }
+
+    fn toolset_step(&mut self, generator: Generator, platform: Option<String>) -> GeneratorPickerResult {
+        if generator.toolset_support {
+            self.step = Step::Toolset(generator, platform, TextInput::default());
+            return GeneratorPickerResult::Continue;
+        }
+        GeneratorPickerResult::Pick(Some(GeneratorChoice { generator: generator.name, platform, toolset: None }))
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let (title, hint) = match &self.step {
+            Step::Generator => (" Generator ", " j/k select, <Enter> pick, <Esc> cancel "),
+            Step::Platform(..) => (" Platform ", " j/k select, <Enter> pick, <Esc> back "),
+            Step::Toolset(..) => (" Toolset ", " <Enter> pick, <Esc> back "),
+        };
+        let block = Block::new()
+            .title(Line::raw(title).centered().bold())
+            .title_bottom(Line::raw(hint).centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [info_area, list_area] = Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(inner);
+
+        let caps = &self.capabilities;
+        let mut features = Vec::new();
+        if caps.tls {
+            features.push("TLS");
+        }
+        if caps.debugger {
+            features.push("debugger");
+        }
+        Paragraph::new(vec![
+            Line::raw(format!(
+                "cmake {}{}",
+                caps.version,
+                if features.is_empty() { String::new() } else { format!(", with {}", features.join(", ")) }
+            )),
+            Line::raw(format!("file-api: {}", caps.file_api.join(", "))).fg(DETAILS_FG),
+        ])
+        .fg(TEXT_FG)
+        .render(info_area, buf);
+
+        let list = |items: Vec<ListItem<'static>>| {
+            List::new(items)
+                .fg(TEXT_FG)
+                .highlight_style(SELECTED_STYLE)
+                .highlight_symbol(">")
+                .highlight_spacing(HighlightSpacing::Always)
+        };
+        match &mut self.step {
+            Step::Generator => {
+                let mut items = vec![ListItem::new("Default, cmake's choice for this machine")];
+                items.extend(self.capabilities.generators.iter().map(|g| {
+                    let mut options = Vec::new();
+                    if g.platform_support {
+                        options.push("-A");
+                    }
+                    if g.toolset_support {
+                        options.push("-T");
+                    }
+                    match options.is_empty() {
+                        true => ListItem::new(g.name.clone()),
+                        false => ListItem::new(format!("{}  (takes {})", g.name, options.join(" "))),
+                    }
+                }));
+                StatefulWidget::render(list(items), list_area, buf, &mut self.state);
+            }
+            Step::Platform(generator, state) => {
+                let mut items = vec![ListItem::new("Default platform")];
+                items.extend(generator.platforms.iter().map(|p| ListItem::new(p.clone())));
+                StatefulWidget::render(list(items), list_area, buf, state);
+            }
+            Step::Toolset(generator, _, input) => {
+                Paragraph::new(vec![
+                    Line::raw(format!("{} takes a toolset, e.g. v143 or ClangCL. Empty for the default.", generator.name)),
+                    Line::raw(""),
+                    input.line("Toolset: "),
+                ])
+                .fg(TEXT_FG)
+                .render(list_area, buf);
+            }
+        }
+    }
+}
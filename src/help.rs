@@ -0,0 +1,72 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{
+        Color, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use crate::screen::Screen;
+
+const HEADER_STYLE: Style = Style::new().fg(SLATE.c100).bg(BLUE.c800);
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const KEY_FG: Color = BLUE.c300;
+
+/// Every key of a screen with what it does, from the screen's bindings
+pub struct HelpView {
+    screen: Screen,
+    scroll: u16,
+}
+
+impl HelpView {
+    pub fn new() -> Self {
+        Self { screen: Screen::Cache, scroll: 0 }
+    }
+
+    pub fn open(&mut self, screen: Screen) {
+        self.screen = screen;
+        self.scroll = 0;
+    }
+
+    pub fn scroll(&mut self, lines: i16) {
+        self.scroll = self.scroll.saturating_add_signed(lines);
+    }
+
+    pub fn scroll_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let screen = self.screen;
+        let block = Block::new()
+            .title(Line::raw(format!("Keys of the {} screen", screen)).centered())
+            .borders(Borders::TOP)
+            .border_set(symbols::border::EMPTY)
+            .border_style(HEADER_STYLE)
+            .bg(PANEL_BG);
+
+        let keys: Vec<(String, &str)> = screen
+            .all_bindings()
+            .map(|b| (b.keys_label(), b.help))
+            .chain(screen.chords().iter().map(|c| (c.keys_label(), c.help)))
+            .chain(screen.prefixes().iter().map(|p| (p.keys_label(), p.help)))
+            .collect();
+        let width = keys.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0);
+        let lines: Vec<Line> = keys
+            .into_iter()
+            .map(|(label, help)| {
+                Line::from(vec![
+                    Span::raw(format!("  {:<w$}  ", label, w = width)).fg(KEY_FG),
+                    Span::raw(help),
+                ])
+            })
+            .collect();
+
+        Paragraph::new(lines).block(block).fg(TEXT_FG).scroll((self.scroll, 0)).render(area, buf);
+    }
+}
@@ -0,0 +1,68 @@
+use ratatui::{
+    style::{Color, Style, palette::tailwind::{CYAN, PURPLE}},
+    text::Span,
+};
+
+// `$<...>` generator expressions, their delimiters and separators
+const GENEX_FG: Color = PURPLE.c400;
+// `${VAR}` and `$ENV{VAR}` references, all of them
+const REFERENCE_FG: Color = CYAN.c400;
+
+enum Nesting {
+    Genex,
+    Reference,
+}
+
+/// Whether `value` has generator expressions or variable references worth
+/// highlighting
+pub fn has_structure(value: &str) -> bool {
+    value.contains("$<") || value.contains("${") || value.contains("$ENV{")
+}
+
+/// `value` split into spans colored by what they are: generator expression
+/// syntax, variable references, or plain text in the default style.
+/// Unterminated expressions are highlighted up to the end.
+pub fn highlight(value: &str) -> Vec<Span<'static>> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut push = |text: &str, style: Style| match spans.last_mut() {
+        Some(last) if last.style == style => last.content.to_mut().push_str(text),
+        _ => spans.push(Span::styled(text.to_string(), style)),
+    };
+    let genex = Style::new().fg(GENEX_FG);
+    let reference = Style::new().fg(REFERENCE_FG);
+
+    let mut stack = Vec::new();
+    let mut i = 0;
+    while i < value.len() {
+        let rest = &value[i..];
+        if rest.starts_with("$<") {
+            stack.push(Nesting::Genex);
+            push("$<", genex);
+            i += 2;
+            continue;
+        }
+        if let Some(prefix) = ["${", "$ENV{"].into_iter().find(|p| rest.starts_with(p)) {
+            stack.push(Nesting::Reference);
+            push(prefix, reference);
+            i += prefix.len();
+            continue;
+        }
+        let c = rest.chars().next().unwrap_or_default();
+        let mut buf = [0; 4];
+        let text = c.encode_utf8(&mut buf);
+        match (stack.last(), c) {
+            (Some(Nesting::Genex), '>') | (Some(Nesting::Reference), '}') => {
+                let style = match stack.pop() {
+                    Some(Nesting::Genex) => genex,
+                    _ => reference,
+                };
+                push(text, style);
+            }
+            (Some(Nesting::Genex), ':' | ',') => push(text, genex),
+            (Some(Nesting::Reference), _) => push(text, reference),
+            _ => push(text, Style::new()),
+        }
+        i += c.len_utf8();
+    }
+    spans
+}
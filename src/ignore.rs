@@ -0,0 +1,34 @@
+use regex::Regex;
+
+use crate::toml::TomlValue;
+
+/// Turn an entry of the `view.ignore` list into a regex matching whole
+/// names. Entries between slashes are regexes (`/^Qt5.*_DIR$/`), the rest
+/// globs where `*` matches any run of characters and `?` one of them
+/// (`FETCHCONTENT_*`).
+pub fn compile(pattern: &str) -> Result<Regex, String> {
+    if let Some(regex) = pattern.strip_prefix('/').and_then(|p| p.strip_suffix('/')).filter(|p| !p.is_empty()) {
+        return Regex::new(regex).map_err(|e| format!("`{}`: {}", pattern, e));
+    }
+    let glob: String = pattern
+        .chars()
+        .map(|c| match c {
+            '*' => ".*".to_string(),
+            '?' => ".".to_string(),
+            c => regex::escape(&c.to_string()),
+        })
+        .collect();
+    Regex::new(&format!("^{}$", glob)).map_err(|e| format!("`{}`: {}", pattern, e))
+}
+
+/// The patterns of a `view.ignore` list, in the user config or a project's
+pub fn parse_list(value: &TomlValue) -> Result<Vec<Regex>, String> {
+    let TomlValue::Array(items) = value else {
+        return Err("view.ignore: expected a list of patterns".to_string());
+    };
+    items
+        .iter()
+        .map(|item| item.as_str().ok_or_else(|| "expected strings".to_string()).and_then(compile))
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("view.ignore: {}", e))
+}
@@ -0,0 +1,302 @@
+use std::{
+    collections::HashMap,
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, GREEN, RED, SLATE},
+    },
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph,
+        StatefulWidget, Widget,
+    },
+};
+
+use crate::cache_editor::{CacheEditorState, LastChange};
+use crate::json::{self, JsonValue};
+use crate::timestamp;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+// One JSON object per line, appended to and never rewritten
+const JOURNAL_FILE: &str = "journal.jsonl";
+
+#[derive(Clone)]
+pub struct JournalChange {
+    pub name: String,
+    // None for entries that didn't exist before
+    pub old: Option<String>,
+    pub new: String,
+}
+
+/// One batch of changes applied to the cache
+pub struct JournalEntry {
+    pub time: u64,
+    pub user: String,
+    pub host: String,
+    // How the changes were applied: "save" or "configure" from cmake-tui,
+    // or "cmake" for values cmake itself changed while configuring
+    pub action: String,
+    pub changes: Vec<JournalChange>,
+}
+
+fn current_user() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("LOGNAME"))
+        .or_else(|_| env::var("USERNAME"))
+        .ok()
+        .or_else(user_from_passwd)
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// For sessions without a login environment (cron, some containers)
+#[cfg(unix)]
+fn user_from_passwd() -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+    let uid = fs::metadata("/proc/self").ok()?.uid().to_string();
+    fs::read_to_string("/etc/passwd").ok()?.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let name = fields.next()?;
+        (fields.nth(1)? == uid).then(|| name.to_string())
+    })
+}
+
+#[cfg(not(unix))]
+fn user_from_passwd() -> Option<String> {
+    None
+}
+
+fn current_host() -> String {
+    env::var("HOSTNAME")
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+impl JournalEntry {
+    /// Entry stamped with the current time, user and host
+    pub fn new(action: &str, changes: Vec<JournalChange>) -> Self {
+        Self {
+            time: timestamp::now(),
+            user: current_user(),
+            host: current_host(),
+            action: action.to_string(),
+            changes,
+        }
+    }
+
+    fn to_json(&self) -> JsonValue {
+        let changes = self
+            .changes
+            .iter()
+            .map(|c| {
+                JsonValue::Object(vec![
+                    ("name".to_string(), JsonValue::String(c.name.clone())),
+                    ("old".to_string(), c.old.clone().map_or(JsonValue::Null, JsonValue::String)),
+                    ("new".to_string(), JsonValue::String(c.new.clone())),
+                ])
+            })
+            .collect();
+        JsonValue::Object(vec![
+            ("time".to_string(), JsonValue::Number(self.time as f64)),
+            ("user".to_string(), JsonValue::String(self.user.clone())),
+            ("host".to_string(), JsonValue::String(self.host.clone())),
+            ("action".to_string(), JsonValue::String(self.action.clone())),
+            ("changes".to_string(), JsonValue::Array(changes)),
+        ])
+    }
+
+    fn from_json(value: &JsonValue) -> Option<Self> {
+        let text = |v: &JsonValue, key: &str| v.get(key).and_then(|s| s.as_str()).map(str::to_string);
+        let changes = value
+            .get("changes")?
+            .as_array()?
+            .iter()
+            .filter_map(|c| {
+                Some(JournalChange {
+                    name: text(c, "name")?,
+                    old: text(c, "old"),
+                    new: text(c, "new")?,
+                })
+            })
+            .collect();
+        Some(Self {
+            time: value.get("time")?.as_u64()?,
+            user: text(value, "user").unwrap_or_default(),
+            host: text(value, "host").unwrap_or_default(),
+            action: text(value, "action").unwrap_or_default(),
+            changes,
+        })
+    }
+}
+
+/// The pending edits of `editor` with their values from before
+pub fn pending_changes(editor: &CacheEditorState) -> Vec<JournalChange> {
+    editor
+        .vars
+        .iter()
+        .filter(|v| v.is_value_modified())
+        .map(|v| JournalChange {
+            name: v.var.name.clone(),
+            old: (!v.is_new).then(|| v.var.value.clone()),
+            new: v.new_val.clone(),
+        })
+        .collect()
+}
+
+/// Fold `changes` into the ones `applied` so far, one per entry going from
+/// its first old value to its last new one. Entries set back to where they
+/// started drop out.
+pub fn merge_changes(applied: &mut Vec<JournalChange>, changes: &[JournalChange]) {
+    for change in changes {
+        match applied.iter().position(|c| c.name == change.name) {
+            Some(i) => applied[i].new = change.new.clone(),
+            None => applied.push(change.clone()),
+        }
+    }
+    applied.retain(|c| c.old.as_ref() != Some(&c.new));
+}
+
+/// The changes applied in a session for scripts wrapping cmake-tui: a
+/// header, then tab separated `changed NAME OLD NEW` and `added NAME NEW`
+/// lines
+pub fn format_applied(cache_path: &Path, changes: &[JournalChange]) -> String {
+    let mut out = format!("cmake-tui: {} change(s) applied to {}\n", changes.len(), cache_path.display());
+    for change in changes {
+        match &change.old {
+            Some(old) => out.push_str(&format!("changed\t{}\t{}\t{}\n", change.name, old, change.new)),
+            None => out.push_str(&format!("added\t{}\t{}\n", change.name, change.new)),
+        }
+    }
+    out
+}
+
+pub fn append(sidecar_dir: &Path, entry: &JournalEntry) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(sidecar_dir.join(JOURNAL_FILE))?;
+    writeln!(file, "{}", entry.to_json())
+}
+
+/// All journal entries, newest first. Lines that don't parse are skipped.
+pub fn read(sidecar_dir: &Path) -> Vec<JournalEntry> {
+    let Ok(content) = fs::read_to_string(sidecar_dir.join(JOURNAL_FILE)) else { return Vec::new() };
+    let mut entries: Vec<JournalEntry> = content
+        .lines()
+        .filter_map(|line| json::parse(line).ok())
+        .filter_map(|value| JournalEntry::from_json(&value))
+        .collect();
+    entries.reverse();
+    entries
+}
+
+/// The latest change of each variable in `entries` (newest first)
+pub fn last_changes(entries: &[JournalEntry]) -> HashMap<String, LastChange> {
+    let mut last = HashMap::new();
+    for entry in entries {
+        let how = match entry.action.as_str() {
+            "save" => format!("saved in cmake-tui by {}@{}", entry.user, entry.host),
+            "configure" => format!("passed to configure by {}@{}", entry.user, entry.host),
+            "cmake" => "set by cmake while configuring".to_string(),
+            "batch" => format!("set with --batch by {}@{}", entry.user, entry.host),
+            other => format!("{} by {}@{}", other, entry.user, entry.host),
+        };
+        for change in &entry.changes {
+            last.entry(change.name.clone())
+                .or_insert_with(|| LastChange { time: entry.time, how: how.clone() });
+        }
+    }
+    last
+}
+
+/// Popup browsing the journal
+pub struct JournalPane {
+    pub entries: Vec<JournalEntry>,
+    pub visible: bool,
+    state: ListState,
+}
+
+impl JournalPane {
+    pub fn new() -> Self {
+        Self { entries: Vec::new(), visible: false, state: ListState::default() }
+    }
+
+    pub fn set_entries(&mut self, entries: Vec<JournalEntry>) {
+        self.entries = entries;
+        self.state.select(if self.entries.is_empty() { None } else { Some(0) });
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(format!(" Change history: {} ", self.entries.len())).centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [list_area, detail_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(inner);
+
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|e| {
+                ListItem::new(format!(
+                    "{}  {:<10} {}@{}  {} change(s)",
+                    timestamp::format(e.time),
+                    e.action,
+                    e.user,
+                    e.host,
+                    e.changes.len()
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        let selected = self.state.selected().and_then(|i| self.entries.get(i));
+        let lines: Vec<Line> = match selected {
+            Some(entry) => entry.changes.iter().map(change_line).collect(),
+            None => vec![Line::raw("No changes recorded yet")],
+        };
+        Paragraph::new(lines)
+            .block(Block::new().borders(Borders::TOP).border_style(Style::new().fg(BLUE.c500)))
+            .fg(TEXT_FG)
+            .render(detail_area, buf);
+    }
+}
+
+fn change_line(change: &JournalChange) -> Line<'static> {
+    let old = Span::raw(change.old.clone().unwrap_or_else(|| "(new entry)".to_string())).fg(RED.c400);
+    let new = Span::raw(change.new.clone()).fg(GREEN.c400);
+    Line::from(vec![Span::raw(format!("  {}: ", change.name)), old, Span::raw(" -> "), new])
+}
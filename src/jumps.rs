@@ -0,0 +1,56 @@
+// Jumps kept, the oldest are dropped first
+const MAX_JUMPS: usize = 100;
+
+/// Entries the selection jumped away from, by search, marks, `gg`/`G` or
+/// the panes that select an entry, for Ctrl-o and Ctrl-i to walk back and
+/// forth like in vim. Names rather than rows, as rows move with the filters.
+#[derive(Default)]
+pub struct JumpList {
+    names: Vec<String>,
+    // Where Ctrl-o and Ctrl-i are in the list, its length when not walking it
+    pos: usize,
+}
+
+impl JumpList {
+    /// Remember `from` as the start of a jump. Jumping from the middle of
+    /// the list drops the newer jumps, like a browser's forward history.
+    pub fn record(&mut self, from: String) {
+        self.names.truncate(self.pos);
+        if self.names.last() != Some(&from) {
+            self.names.push(from);
+        }
+        if self.names.len() > MAX_JUMPS {
+            self.names.remove(0);
+        }
+        self.pos = self.names.len();
+    }
+
+    /// The entry of the jump before, `current` being where the selection is
+    /// now, which Ctrl-i comes back to
+    pub fn back(&mut self, current: Option<&str>) -> Option<String> {
+        let mut pos = self.pos;
+        if pos == self.names.len()
+            && let Some(current) = current
+            && self.names.last().map(String::as_str) != Some(current)
+        {
+            self.names.push(current.to_string());
+        }
+        // Passing over the entry that's selected already
+        while pos > 0 {
+            pos -= 1;
+            if Some(self.names[pos].as_str()) != current {
+                self.pos = pos;
+                return Some(self.names[pos].clone());
+            }
+        }
+        None
+    }
+
+    pub fn forward(&mut self) -> Option<String> {
+        if self.pos + 1 >= self.names.len() {
+            return None;
+        }
+        self.pos += 1;
+        self.names.get(self.pos).cloned()
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::cache_editor::CacheEditorState;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+// Compiler caches cmake can put in front of the compiler
+const KNOWN_LAUNCHERS: &[&str] = &["ccache", "sccache", "buildcache"];
+const LANGUAGES: &[&str] = &["C", "CXX", "CUDA", "HIP", "OBJC", "OBJCXX"];
+
+/// Languages the project compiles, the ones cmake found a compiler for.
+/// C and C++ before the first configure found any.
+pub fn languages(editor: &CacheEditorState) -> Vec<&'static str> {
+    let found: Vec<&str> = LANGUAGES
+        .iter()
+        .copied()
+        .filter(|lang| editor.get(&format!("CMAKE_{}_COMPILER", lang)).is_some())
+        .collect();
+    if found.is_empty() { vec!["C", "CXX"] } else { found }
+}
+
+pub fn launcher_var(lang: &str) -> String {
+    format!("CMAKE_{}_COMPILER_LAUNCHER", lang)
+}
+
+/// The launcher the project's compilers run under, by its program name
+pub fn current_launcher(editor: &CacheEditorState) -> Option<String> {
+    languages(editor)
+        .iter()
+        .filter_map(|lang| editor.get(&launcher_var(lang)))
+        .map(|v| v.new_val.trim())
+        .find(|v| !v.is_empty())
+        .map(|v| {
+            let program = v.split(';').next().unwrap_or(v);
+            Path::new(program).file_name().map_or(program.to_string(), |n| n.to_string_lossy().to_string())
+        })
+}
+
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path)
+        .map(|dir| dir.join(program).with_extension(env::consts::EXE_EXTENSION))
+        .find(|candidate| candidate.is_file())
+}
+
+pub enum LauncherChoice {
+    Use(PathBuf),
+    Remove,
+    // Back to the launcher values from before the pane changed them
+    Revert,
+}
+
+/// Sets up a compiler cache: lists the ones installed and puts the picked
+/// one in front of every compiler of the project
+pub struct LauncherPane {
+    pub visible: bool,
+    choices: Vec<LauncherChoice>,
+    // Launcher values before the first change made here, to revert to
+    pub previous: Option<Vec<(String, Option<String>)>>,
+    state: ListState,
+}
+
+impl LauncherPane {
+    pub fn new() -> Self {
+        Self { visible: false, choices: Vec::new(), previous: None, state: ListState::default() }
+    }
+
+    /// Look for installed launchers again and show them
+    pub fn open(&mut self) {
+        self.choices = KNOWN_LAUNCHERS
+            .iter()
+            .filter_map(|l| find_in_path(l))
+            .map(LauncherChoice::Use)
+            .chain([LauncherChoice::Remove])
+            .collect();
+        if self.previous.is_some() {
+            self.choices.push(LauncherChoice::Revert);
+        }
+        self.state = ListState::default().with_selected(Some(0));
+        self.visible = true;
+    }
+
+    pub fn selected(&self) -> Option<&LauncherChoice> {
+        self.state.selected().and_then(|i| self.choices.get(i))
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn render(&mut self, editor: &CacheEditorState, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(" Compiler launcher ").centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Enter> apply, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [status_area, list_area] = Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(inner);
+
+        let languages = languages(editor);
+        let status = match current_launcher(editor) {
+            Some(launcher) => format!("Compilers run under {} ({})", launcher, languages.join(", ")),
+            None => format!("No launcher set for {}", languages.join(", ")),
+        };
+        let found = self.choices.iter().filter(|c| matches!(c, LauncherChoice::Use(_))).count();
+        let hint = match found {
+            0 => format!("None of {} is installed", KNOWN_LAUNCHERS.join(", ")),
+            _ => "A launcher caches compilation results, rebuilds of unchanged files get much faster".to_string(),
+        };
+        Paragraph::new(vec![Line::raw(status), Line::raw(hint).dim()])
+            .block(Block::new().borders(Borders::BOTTOM).border_style(Style::new().fg(BLUE.c500)))
+            .fg(TEXT_FG)
+            .wrap(Wrap { trim: false })
+            .render(status_area, buf);
+
+        let items: Vec<ListItem> = self
+            .choices
+            .iter()
+            .map(|choice| match choice {
+                LauncherChoice::Use(path) => ListItem::new(format!("Use {}", path.display())),
+                LauncherChoice::Remove => ListItem::new("Don't use a launcher"),
+                LauncherChoice::Revert => ListItem::new("Revert to the launcher from before"),
+            })
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+    }
+}
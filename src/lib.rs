@@ -0,0 +1,86 @@
+//! cmake-tui as a library. [`cache_editor`] holds the cache table widget,
+//! which other ratatui applications can embed on its own. Reading and
+//! writing the cache itself is done by the [`cmake_cache`] crate.
+
+pub use cmake_cache;
+
+pub mod action;
+pub mod app;
+pub mod batch;
+pub mod build_dirs;
+pub mod build_info;
+mod build_type;
+mod busy;
+pub mod cache_diff;
+pub mod cache_editor;
+mod capabilities;
+pub mod cli_docs;
+pub mod cmake;
+mod compile_commands;
+mod config;
+pub mod crash;
+mod ctest;
+mod debug_overlay;
+mod diagnostics;
+mod dialog;
+mod diff;
+mod disk_usage;
+mod duplicates;
+mod editor;
+mod environment;
+mod expand;
+mod explain;
+mod file_api;
+mod flag_bundles;
+mod fresh;
+mod generator_picker;
+mod help;
+mod highlight;
+mod ignore;
+mod journal;
+mod jumps;
+mod json;
+mod launcher;
+mod list_editor;
+mod log_pane;
+pub mod logging;
+mod missing_cache;
+mod monochrome;
+mod new_entry;
+mod package_manager;
+mod packaging;
+mod path_status;
+mod pins;
+mod presets;
+mod problems;
+mod profiling;
+mod project_config;
+mod quick_settings;
+mod recording;
+pub mod remote;
+pub mod rpc;
+pub mod run;
+mod screen;
+mod search_index;
+mod session;
+mod shell;
+mod sidecar;
+mod snapshots;
+mod source_scan;
+mod stale;
+mod sub_builds;
+mod targets;
+pub mod task;
+mod text_input;
+#[cfg(feature = "time-travel")]
+mod time_travel;
+mod timestamp;
+mod toml;
+mod toolchain;
+mod toolchain_picker;
+mod usages;
+mod validation;
+mod value_pane;
+mod variable_docs;
+mod vcs;
+mod wsl;
@@ -0,0 +1,197 @@
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{
+        Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph,
+        StatefulWidget, Widget,
+    },
+};
+
+use crate::text_input::TextInput;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+/// Split a CMake list on `;`, keeping escaped `\;` inside items
+pub fn split_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&';') => {
+                current.push('\\');
+                current.push(';');
+                chars.next();
+            }
+            ';' => items.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    items.push(current);
+    items.retain(|i| !i.is_empty());
+    items
+}
+
+pub fn is_list(value: &str) -> bool {
+    split_list(value).len() > 1
+}
+
+/// What the caller should do after the editor handled a key
+pub enum ListEditorResult {
+    Continue,
+    Apply(String),
+    Cancel,
+}
+
+/// Popup for editing a semicolon separated value item by item
+pub struct ListEditor {
+    pub name: String,
+    items: Vec<String>,
+    state: ListState,
+    // Text field for the item being edited or added
+    input: Option<TextInput>,
+    // Whether the input adds a new item after the selection instead of
+    // replacing the selected one
+    adding: bool,
+}
+
+impl ListEditor {
+    pub fn new(name: &str, value: &str) -> Self {
+        let items = split_list(value);
+        let mut state = ListState::default();
+        if !items.is_empty() {
+            state.select(Some(0));
+        }
+        Self { name: name.to_string(), items, state, input: None, adding: false }
+    }
+
+    pub fn value(&self) -> String {
+        self.items.join(";")
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> ListEditorResult {
+        if let Some(input) = self.input.as_mut() {
+            match key.code {
+                KeyCode::Esc => self.input = None,
+                KeyCode::Enter => self.commit_input(),
+                _ => { input.handle_key(key); }
+            }
+            return ListEditorResult::Continue;
+        }
+
+        let selected = self.state.selected();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return ListEditorResult::Cancel,
+            KeyCode::Char('w') => return ListEditorResult::Apply(self.value()),
+            KeyCode::Char('j') | KeyCode::Down => self.state.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.state.select_previous(),
+            KeyCode::Enter | KeyCode::Char('e') => {
+                if let Some(item) = selected.and_then(|i| self.items.get(i)) {
+                    self.input = Some(TextInput::new(item));
+                    self.adding = false;
+                }
+            }
+            KeyCode::Char('a') => {
+                self.input = Some(TextInput::default());
+                self.adding = true;
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if let Some(i) = selected.filter(|&i| i < self.items.len()) {
+                    self.items.remove(i);
+                    if self.items.is_empty() {
+                        self.state.select(None);
+                    } else {
+                        self.state.select(Some(i.min(self.items.len() - 1)));
+                    }
+                }
+            }
+            KeyCode::Char('J') => {
+                if let Some(i) = selected.filter(|&i| i + 1 < self.items.len()) {
+                    self.items.swap(i, i + 1);
+                    self.state.select(Some(i + 1));
+                }
+            }
+            KeyCode::Char('K') => {
+                if let Some(i) = selected.filter(|&i| i > 0 && i < self.items.len()) {
+                    self.items.swap(i, i - 1);
+                    self.state.select(Some(i - 1));
+                }
+            }
+            _ => {}
+        }
+        ListEditorResult::Continue
+    }
+
+    fn commit_input(&mut self) {
+        let Some(input) = self.input.take() else { return };
+        // A `;` typed into an item splits it into several
+        let new_items = split_list(input.text());
+
+        let selected = self.state.selected().filter(|&i| i < self.items.len());
+        let at = match (self.adding, selected) {
+            (true, Some(i)) => i + 1,
+            (true, None) => self.items.len(),
+            (false, Some(i)) => {
+                self.items.remove(i);
+                i
+            }
+            (false, None) => return,
+        };
+
+        let count = new_items.len();
+        self.items.splice(at..at, new_items);
+        if count > 0 {
+            self.state.select(Some(at + count - 1));
+        } else if !self.items.is_empty() {
+            self.state.select(Some(at.min(self.items.len() - 1)));
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let hint = if self.input.is_some() {
+            " <Enter> confirm, <Esc> cancel "
+        } else {
+            " a add, e edit, d delete, J/K move, w apply, <Esc> discard "
+        };
+        let block = Block::new()
+            .title(Line::raw(format!(" {} ({} items) ", self.name, self.items.len())).centered().bold())
+            .title_bottom(Line::raw(hint).centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [list_area, input_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+
+        let items: Vec<ListItem> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| ListItem::new(format!("{:>3}. {}", i + 1, item)))
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        if let Some(input) = &self.input {
+            let prefix = if self.adding { "New item: " } else { "Edit item: " };
+            Paragraph::new(input.line(prefix)).fg(TEXT_FG).render(input_area, buf);
+        }
+    }
+}
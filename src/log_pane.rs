@@ -0,0 +1,361 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use crate::task::Stream;
+
+// Oldest lines are dropped once the scrollback grows past this
+const MAX_LOG_LINES: usize = 20_000;
+
+const LOG_HEADER_STYLE: Style = Style::new().fg(SLATE.c100).bg(BLUE.c800);
+const LOG_BG: Color = SLATE.c950;
+const LOG_FG: Color = SLATE.c200;
+const CURSOR_STYLE: Style = Style::new().bg(SLATE.c700);
+const INFO_STYLE: Style = Style::new().fg(BLUE.c300).add_modifier(Modifier::BOLD);
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum LogView {
+    Hidden,
+    Pane,
+}
+
+struct LogLine {
+    line: Line<'static>,
+    // Text without escape codes, used for searching and error detection
+    plain: String,
+    is_error: bool,
+}
+
+pub struct LogPane {
+    lines: Vec<LogLine>,
+    pub view: LogView,
+    pub title: String,
+    // Index of the first visible line
+    scroll: usize,
+    // Keep the newest output in view while a task is streaming
+    follow: bool,
+    // Line picked by a search or an error jump
+    cursor: Option<usize>,
+    search: String,
+    // Height of the last render, used for paging
+    height: usize,
+}
+
+impl LogPane {
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            view: LogView::Hidden,
+            title: String::new(),
+            scroll: 0,
+            follow: true,
+            cursor: None,
+            search: String::new(),
+            height: 0,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.view != LogView::Hidden
+    }
+
+    pub fn toggle(&mut self) {
+        self.view = match self.view {
+            LogView::Hidden => LogView::Pane,
+            _ => LogView::Hidden,
+        };
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.scroll = 0;
+        self.cursor = None;
+        self.follow = true;
+    }
+
+    /// Append a line of tool output, keeping its ANSI colors
+    pub fn push(&mut self, stream: Stream, text: &str) {
+        let (mut line, plain) = parse_ansi(text);
+        if stream == Stream::Stderr && line.spans.iter().all(|s| s.style == Style::default()) {
+            line = line.fg(Color::Yellow);
+        }
+        let is_error = is_error_line(&plain);
+        self.push_line(LogLine { line, plain, is_error });
+    }
+
+    /// Append a message of our own, e.g. the command being run
+    pub fn push_info(&mut self, text: &str) {
+        let line = Line::styled(text.to_string(), INFO_STYLE);
+        self.push_line(LogLine { line, plain: text.to_string(), is_error: false });
+    }
+
+    fn push_line(&mut self, line: LogLine) {
+        self.lines.push(line);
+        if self.lines.len() > MAX_LOG_LINES {
+            let excess = self.lines.len() - MAX_LOG_LINES;
+            self.lines.drain(..excess);
+            self.scroll = self.scroll.saturating_sub(excess);
+            self.cursor = self.cursor.and_then(|c| c.checked_sub(excess));
+        }
+    }
+
+    fn last_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.height.max(1))
+    }
+
+    pub fn scroll_down(&mut self, amount: usize) {
+        self.scroll = (self.scroll + amount).min(self.last_scroll());
+        self.follow = self.scroll == self.last_scroll();
+    }
+
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.scroll = self.scroll.saturating_sub(amount);
+        self.follow = false;
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_down(self.height.max(1));
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_up(self.height.max(1));
+    }
+
+    pub fn scroll_top(&mut self) {
+        self.scroll = 0;
+        self.follow = false;
+    }
+
+    pub fn scroll_bottom(&mut self) {
+        self.scroll = self.last_scroll();
+        self.follow = true;
+    }
+
+    fn jump_to(&mut self, idx: usize) {
+        self.cursor = Some(idx);
+        self.follow = false;
+        // Keep a little context above the line we jumped to
+        self.scroll = idx.saturating_sub(2).min(self.last_scroll());
+    }
+
+    fn find_forward(&self, pred: impl Fn(&LogLine) -> bool) -> Option<usize> {
+        let start = self.cursor.map_or(self.scroll, |c| c + 1);
+        (start..self.lines.len())
+            .chain(0..start.min(self.lines.len()))
+            .find(|&i| pred(&self.lines[i]))
+    }
+
+    fn find_backward(&self, pred: impl Fn(&LogLine) -> bool) -> Option<usize> {
+        let start = self.cursor.unwrap_or(self.scroll);
+        (0..start)
+            .rev()
+            .chain((start..self.lines.len()).rev())
+            .find(|&i| pred(&self.lines[i]))
+    }
+
+    pub fn next_error(&mut self) {
+        if let Some(idx) = self.find_forward(|l| l.is_error) {
+            self.jump_to(idx);
+        }
+    }
+
+    pub fn previous_error(&mut self) {
+        if let Some(idx) = self.find_backward(|l| l.is_error) {
+            self.jump_to(idx);
+        }
+    }
+
+    /// Output text without escape codes, oldest line first
+    pub fn plain_lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(|l| l.plain.as_str())
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.lines.iter().filter(|l| l.is_error).count()
+    }
+
+    pub fn search(&mut self, query: &str) {
+        self.search = query.to_lowercase();
+        self.next_match();
+    }
+
+    pub fn next_match(&mut self) {
+        if self.search.is_empty() { return; }
+        let query = self.search.clone();
+        if let Some(idx) = self.find_forward(|l| l.plain.to_lowercase().contains(&query)) {
+            self.jump_to(idx);
+        }
+    }
+
+    pub fn previous_match(&mut self) {
+        if self.search.is_empty() { return; }
+        let query = self.search.clone();
+        if let Some(idx) = self.find_backward(|l| l.plain.to_lowercase().contains(&query)) {
+            self.jump_to(idx);
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer, focused: bool) {
+        let marker = if focused { "▶" } else { " " };
+        let errors = self.error_count();
+        let title = if errors > 0 {
+            format!("{} Log {} ({} errors) ", marker, self.title, errors)
+        } else {
+            format!("{} Log {} ", marker, self.title)
+        };
+
+        let block = Block::new()
+            .title(Line::raw(title).left_aligned())
+            .title(Line::raw(" Tab focus, j/k scroll, / search, ]/[ errors, z fullscreen ").right_aligned())
+            .borders(Borders::TOP)
+            .border_set(symbols::border::EMPTY)
+            .border_style(LOG_HEADER_STYLE)
+            .bg(LOG_BG);
+
+        let inner = block.inner(area);
+        self.height = inner.height as usize;
+        if self.follow {
+            self.scroll = self.last_scroll();
+        }
+
+        let end = (self.scroll + self.height).min(self.lines.len());
+        let visible: Vec<Line> = self.lines[self.scroll.min(end)..end]
+            .iter()
+            .enumerate()
+            .map(|(i, l)| {
+                if Some(self.scroll + i) == self.cursor {
+                    l.line.clone().patch_style(CURSOR_STYLE)
+                } else {
+                    l.line.clone()
+                }
+            })
+            .collect();
+
+        Paragraph::new(visible)
+            .block(block)
+            .fg(LOG_FG)
+            .render(area, buf);
+    }
+}
+
+fn is_error_line(plain: &str) -> bool {
+    let lower = plain.to_lowercase();
+    plain.starts_with("CMake Error")
+        || plain.starts_with("FAILED:")
+        || lower.contains("error:")
+        || (lower.starts_with("make") && lower.contains("*** ["))
+}
+
+/// Convert a line containing ANSI SGR escape sequences into styled spans.
+/// Other escape sequences (cursor movement etc.) are dropped.
+pub fn parse_ansi(text: &str) -> (Line<'static>, String) {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut current = String::new();
+    let mut style = Style::default();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            if c == '\t' {
+                current.push_str("    ");
+                plain.push_str("    ");
+            } else if !c.is_control() {
+                current.push(c);
+                plain.push(c);
+            }
+            continue;
+        }
+
+        if chars.peek() != Some(&'[') {
+            // Lone escape or a non-CSI sequence, skip the next char
+            chars.next();
+            continue;
+        }
+        chars.next();
+
+        let mut params = String::new();
+        let mut final_byte = None;
+        for c in chars.by_ref() {
+            if ('\x40'..='\x7e').contains(&c) {
+                final_byte = Some(c);
+                break;
+            }
+            params.push(c);
+        }
+
+        if final_byte == Some('m') {
+            if !current.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut current), style));
+            }
+            style = apply_sgr(style, &params);
+        }
+    }
+
+    if !current.is_empty() {
+        spans.push(Span::styled(current, style));
+    }
+    (Line::from(spans), plain)
+}
+
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+    let codes: Vec<u16> = if params.is_empty() {
+        vec![0]
+    } else {
+        params.split(';').map(|p| p.parse().unwrap_or(0)).collect()
+    };
+
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            0 => style = Style::default(),
+            1 => style = style.add_modifier(Modifier::BOLD),
+            2 => style = style.add_modifier(Modifier::DIM),
+            3 => style = style.add_modifier(Modifier::ITALIC),
+            4 => style = style.add_modifier(Modifier::UNDERLINED),
+            7 => style = style.add_modifier(Modifier::REVERSED),
+            22 => style = style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style = style.remove_modifier(Modifier::ITALIC),
+            24 => style = style.remove_modifier(Modifier::UNDERLINED),
+            27 => style = style.remove_modifier(Modifier::REVERSED),
+            n @ 30..=37 => style = style.fg(Color::Indexed((n - 30) as u8)),
+            n @ 90..=97 => style = style.fg(Color::Indexed((n - 90 + 8) as u8)),
+            n @ 40..=47 => style = style.bg(Color::Indexed((n - 40) as u8)),
+            n @ 100..=107 => style = style.bg(Color::Indexed((n - 100 + 8) as u8)),
+            39 => style.fg = None,
+            49 => style.bg = None,
+            n @ (38 | 48) => {
+                let color = match codes.get(i + 1) {
+                    Some(5) => {
+                        let c = codes.get(i + 2).map(|&c| Color::Indexed(c as u8));
+                        i += 2;
+                        c
+                    }
+                    Some(2) => {
+                        let rgb = (codes.get(i + 2), codes.get(i + 3), codes.get(i + 4));
+                        i += 4;
+                        match rgb {
+                            (Some(&r), Some(&g), Some(&b)) => Some(Color::Rgb(r as u8, g as u8, b as u8)),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+                if let Some(color) = color {
+                    style = if n == 38 { style.fg(color) } else { style.bg(color) };
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    style
+}
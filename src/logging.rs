@@ -0,0 +1,19 @@
+//! `--log-file`: key presses, the commands run and what went wrong, written
+//! with `tracing` to a file for bug reports. Without the option nothing is
+//! recorded.
+
+use std::{fs::OpenOptions, io, path::Path, sync::Mutex};
+
+use tracing::Level;
+
+/// Send the events of the session to `path`, appended to what's there
+pub fn init(path: &Path) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    tracing_subscriber::fmt()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_target(false)
+        .with_max_level(Level::DEBUG)
+        .try_init()
+        .map_err(io::Error::other)
+}
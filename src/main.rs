@@ -1,19 +1,126 @@
-mod cache_parser;
-mod app;
-
-use app::App;
+use cmake_tui::{app::App, batch::{self, Definition}, build_dirs::resolve_build_dir, cache_diff::{self, DiffFormat}, cli_docs::{self, Shell}, build_info, crash, cmake, logging, remote::{self, Remote}, rpc::RpcServer, run, task::configure_in_terminal};
 use std::path::PathBuf;
-use clap::{Parser};
-use color_eyre::Result;
+use clap::{CommandFactory, Parser, Subcommand};
+use color_eyre::{Result, eyre::eyre};
 
 #[derive(Parser, Debug)]
 #[command(
-    version = "0.1",
+    version = build_info::VERSION,
     about = "Modify CMake cache variables",
+    // Handled by `version` below, the long version runs cmake to find out its
+    // version and only the one picked with --cmake is worth reporting
+    disable_version_flag = true,
 )]
 struct Cli {
-    #[arg(short, long, default_value = ".")]
+    /// Print version, with the commit, build date and cmake in use
+    #[arg(short = 'V', long)]
+    version: bool,
+
+    /// Build directory, its CMakeCache.txt, ssh://host/path/to/build for one on another machine,
+    /// or a source directory to look for build directories in
+    #[arg(value_name = "PATH", conflicts_with = "path")]
+    target: Option<PathBuf>,
+
+    /// Same as PATH
+    #[arg(short, long, default_value = ".", global = true)]
     path: PathBuf,
+
+    /// cmake executable to run, instead of $CMAKE, the config file's or the one on PATH.
+    /// ctest and cpack are taken from the same directory.
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::ExecutablePath, global = true)]
+    cmake: Option<PathBuf>,
+
+    /// Open the build dir PATH inside this running container, cmake runs there through `docker exec`
+    #[arg(long, value_name = "NAME", global = true)]
+    container: Option<String>,
+
+    /// With --container: docker or podman, docker unless only podman is installed
+    #[arg(long, value_name = "PROGRAM", requires = "container", global = true)]
+    container_engine: Option<String>,
+
+    /// Accept JSON-RPC requests (list, get, set, save, configure, quit) on this unix socket
+    #[arg(long, value_name = "SOCKET", conflicts_with = "embed")]
+    listen: Option<PathBuf>,
+
+    /// Run without a terminal UI, taking the same JSON-RPC requests on stdin
+    /// and writing responses and state updates as JSON lines to stdout
+    #[arg(long)]
+    embed: bool,
+
+    /// Log key presses, the commands run and parse warnings to FILE
+    #[arg(long, value_name = "FILE", global = true)]
+    log_file: Option<PathBuf>,
+
+    /// Record key presses and state checksums to FILE, for bug reports
+    #[arg(long, value_name = "FILE", conflicts_with = "embed")]
+    record: Option<PathBuf>,
+
+    /// Replay a recording made with --record against the same cache, any key stops it
+    #[arg(long, value_name = "FILE", conflicts_with = "embed")]
+    replay: Option<PathBuf>,
+
+    /// Run a configure before opening, so options added since the last one show up
+    #[arg(long, conflicts_with = "embed")]
+    configure_first: bool,
+
+    /// After the TUI exits, also print the cmake command that applies the same changes
+    #[arg(long, conflicts_with_all = ["embed", "batch"])]
+    print_command: bool,
+
+    /// Apply the -D definitions to the cache without opening the TUI, print what changed and exit
+    #[arg(long, requires = "define", conflicts_with_all = ["embed", "listen", "record", "replay", "configure_first"])]
+    batch: bool,
+
+    /// With --batch: set an entry, NAME:TYPE=VALUE adds one the cache doesn't have yet
+    #[arg(short = 'D', value_name = "NAME[:TYPE]=VALUE", requires = "batch")]
+    define: Vec<Definition>,
+
+    /// With --batch: configure with the definitions instead of only writing them to the cache
+    #[arg(long, requires = "batch")]
+    configure: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Apply a changes file to the cache without opening the TUI, like --batch
+    Apply {
+        /// TOML file of `NAME = VALUE` lines, `"NAME:TYPE" = VALUE` to add entries or check their type
+        file: PathBuf,
+
+        /// Configure with the changes instead of only writing them to the cache
+        #[arg(long)]
+        configure: bool,
+    },
+    /// Run COMMAND in the build dir with the cache entries in its environment,
+    /// e.g. `cmake-tui run -- '${CMAKE_CXX_COMPILER}' --version`
+    Run {
+        /// Only export the entries matching PATTERN, a glob or a /regex/, all of them without
+        #[arg(long, value_name = "PATTERN")]
+        export: Vec<String>,
+
+        /// The command and its arguments, `${NAME}` in them is replaced by the entry's value
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// Print how the cache differs from a reference, exit with 1 when it does and 2 on errors
+    Diff {
+        /// A CMakeCache.txt or build dir, a JSON file of entries, or a configure preset of the project
+        #[arg(long, value_name = "REFERENCE")]
+        against: String,
+
+        #[arg(long, value_enum, default_value = "unified")]
+        format: DiffFormat,
+    },
+    /// Print a completion script for SHELL, e.g. `cmake-tui completions bash > /etc/bash_completion.d/cmake-tui`
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// Print the man page, e.g. `cmake-tui manpage | man -l -`
+    Manpage,
 }
 
 
@@ -24,11 +131,132 @@ fn main() -> Result<()> {
     //     std::process::exit(1);
     // }
 
-    println!("Using directory: {}", cli.path.display());
-
     color_eyre::install()?;
+    if let Some(path) = &cli.log_file {
+        logging::init(path).map_err(|e| eyre!("Failed to open {}: {}", path.display(), e))?;
+        tracing::info!("cmake-tui {} started with {:?}", build_info::VERSION, std::env::args().collect::<Vec<_>>());
+    }
+    cmake::select(cli.cmake.clone());
+    if cli.version {
+        println!("cmake-tui {}", build_info::long_version());
+        return Ok(());
+    }
+    match &cli.command {
+        Some(Command::Completions { shell }) => {
+            print!("{}", cli_docs::completions(&Cli::command(), *shell));
+            return Ok(());
+        }
+        Some(Command::Manpage) => {
+            print!("{}", cli_docs::manpage(&Cli::command()));
+            return Ok(());
+        }
+        _ => {}
+    }
+    // A source dir instead of a build dir, look for the build dirs below it
+    let pick = !cli.embed && !cli.batch && cli.command.is_none();
+    let path = cli.target.unwrap_or(cli.path);
+    // A build dir on another machine or in a container is edited through a local copy of its cache
+    let remote = match &cli.container {
+        Some(name) => Some(Ok(Remote::container(cli.container_engine.clone(), name, &path.to_string_lossy()))),
+        None => path.to_str().and_then(Remote::parse),
+    };
+    let path = match remote {
+        Some(remote) => {
+            let remote = remote.map_err(|e| eyre!(e))?;
+            remote.fetch().map_err(|e| eyre!(e))?;
+            let mirror = remote.mirror.clone();
+            remote::set(remote);
+            mirror
+        }
+        None => path,
+    };
+    let resolved = resolve_build_dir(path, pick);
+    // diff's exit code 1 means the caches differ
+    if let (Some(Command::Diff { .. }), Err(e)) = (&cli.command, &resolved) {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    }
+    let Some(path) = resolved? else { return Ok(()) };
+
+    if let Some(Command::Run { export, command }) = &cli.command {
+        match run::run_command(&path, export, command) {
+            Ok(code) => std::process::exit(code),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(Command::Diff { against, format }) = &cli.command {
+        match cache_diff::run(&path, against, *format) {
+            Ok(true) => return Ok(()),
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    // stdout carries the protocol in embed mode, nothing else may go there
+    if cli.embed {
+        let mut app = App::new(path);
+        app.serve_rpc(RpcServer::stdio());
+        return app.run_embedded();
+    }
+
+    let batch = match &cli.command {
+        Some(Command::Apply { file, configure }) => Some((batch::read_changes_file(file), *configure)),
+        _ if cli.batch => Some((Ok(cli.define), cli.configure)),
+        _ => None,
+    };
+    if let Some((definitions, configure)) = batch {
+        // Plain messages for scripts, without the report color_eyre adds
+        match definitions.and_then(|definitions| batch::run(&path, &definitions, configure)) {
+            Ok(true) => return Ok(()),
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // stdout is kept for the exit summary, for scripts to read
+    eprintln!("Using directory: {}", path.display());
+
+    if cli.configure_first {
+        let status = configure_in_terminal(&path, &[]).map_err(|e| eyre!("Failed to run cmake: {}", e))?;
+        // The cache from before is still worth editing, e.g. to fix what broke
+        // the configure, but give a chance to read the errors first
+        if !status.success() {
+            eprintln!("Configure failed ({}), press <Enter> to open the cache as it is", status);
+            std::io::stdin().read_line(&mut String::new())?;
+        }
+    }
+
+    // Bind before taking over the terminal, so errors are readable
+    let rpc = cli.listen.as_deref().map(RpcServer::listen).transpose()?;
+
+    let mut app = App::new(path);
+    if let Some(rpc) = rpc {
+        app.serve_rpc(rpc);
+    }
+    if let Some(path) = &cli.replay {
+        app.replay_from(path).map_err(|e| eyre!("Failed to load recording: {}", e))?;
+    }
+    if let Some(path) = &cli.record {
+        app.record_to(path).map_err(|e| eyre!("Failed to create {}: {}", path.display(), e))?;
+    }
+
     let terminal = ratatui::init();
-    let app_result = App::new(cli.path).run(terminal);
+    crash::install();
+    let app_result = app.run(terminal);
     ratatui::restore();
+
+    if let Some(summary) = app.exit_summary(cli.print_command) {
+        print!("{}", summary);
+    }
     app_result
 }
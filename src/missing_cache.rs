@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{
+        Color, Stylize,
+        palette::tailwind::{RED, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Padding, Paragraph, Widget, Wrap},
+};
+
+use crate::text_input::TextInput;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum MissingCachePrompt {
+    // Another build dir, cache file or source dir to open
+    OpenPath,
+    // Source dir for an initial configure
+    SourceDir,
+}
+
+/// Shown in place of the table while there's no cache to edit, with the
+/// ways out: open something else or configure the build dir for the first time
+pub struct MissingCacheScreen {
+    pub error: String,
+    pub prompt: Option<(MissingCachePrompt, TextInput)>,
+    // Why the last choice didn't work out
+    pub problem: Option<String>,
+}
+
+impl MissingCacheScreen {
+    pub fn new(error: String) -> Self {
+        Self { error, prompt: None, problem: None }
+    }
+
+    pub fn render(&self, build_dir: &Path, configuring: bool, area: Rect, buf: &mut Buffer) {
+        let block = Block::new()
+            .title(Line::raw(" No cache to edit ").centered().bold())
+            .borders(Borders::ALL)
+            .border_style(RED.c500)
+            .bg(PANEL_BG)
+            .padding(Padding::uniform(1));
+
+        let mut content = vec![
+            Line::raw(format!("Could not read a CMake cache from {}:", build_dir.display())),
+            Line::raw(format!("  {}", self.error)),
+            Line::raw(""),
+            Line::raw("cmake-tui edits the CMakeCache.txt that cmake writes into a build directory when configuring."),
+            Line::raw("Pass a build directory, a CMakeCache.txt or a source directory with build directories below it."),
+            Line::raw(""),
+        ];
+        match &self.prompt {
+            _ if configuring => content.push(Line::raw("Configuring, the cache opens once cmake is done...")),
+            Some((MissingCachePrompt::OpenPath, input)) => content.push(input.line("Open: ")),
+            Some((MissingCachePrompt::SourceDir, input)) => content.push(input.line("Source directory to configure: ")),
+            None => {
+                content.push(Line::raw("[o] open another directory"));
+                content.push(Line::raw("[c] run an initial configure"));
+                content.push(Line::raw("[q] quit"));
+                content.push(Line::raw("<Esc> continue with an empty cache"));
+            }
+        }
+        if let Some(problem) = &self.problem {
+            content.push(Line::raw(""));
+            content.push(Line::raw(problem.clone()).fg(RED.c400));
+        }
+        Paragraph::new(content)
+            .block(block)
+            .fg(TEXT_FG)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+/// Likely source dir for configuring `build_dir`: the dir itself when cmake-tui
+/// was started in a source tree, else its parent for the usual `<src>/build`
+pub fn guess_source_dir(build_dir: &Path) -> PathBuf {
+    let build_dir = build_dir.canonicalize().unwrap_or_else(|_| build_dir.to_path_buf());
+    if build_dir.join("CMakeLists.txt").is_file() {
+        return build_dir;
+    }
+    match build_dir.parent() {
+        Some(parent) if parent.join("CMakeLists.txt").is_file() => parent.to_path_buf(),
+        _ => build_dir,
+    }
+}
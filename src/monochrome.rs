@@ -0,0 +1,24 @@
+use ratatui::{
+    buffer::Buffer,
+    style::{
+        Color, Modifier,
+        palette::tailwind::{BLUE, SLATE},
+    },
+};
+
+// Backgrounds that mark something rather than fill a panel: the selection,
+// the filter chips and the table header
+const EMPHASIS_BGS: &[Color] = &[SLATE.c800, BLUE.c700, BLUE.c800, BLUE.c900];
+
+/// Drop the colors of a rendered frame, for NO_COLOR and the monochrome
+/// theme. What a background color marked is shown in reverse video instead,
+/// everything else is left to the symbols and modifiers already there.
+pub fn strip_colors(buf: &mut Buffer) {
+    for cell in buf.content.iter_mut() {
+        if EMPHASIS_BGS.contains(&cell.bg) {
+            cell.modifier.insert(Modifier::REVERSED);
+        }
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+    }
+}
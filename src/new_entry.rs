@@ -0,0 +1,191 @@
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Style, Stylize,
+        palette::tailwind::{BLUE, RED, SLATE},
+    },
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use cmake_cache::{CacheVar, VarType};
+use crate::text_input::TextInput;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const LABEL_FG: Color = SLATE.c500;
+
+// Types a new entry can be created with
+const TYPES: [VarType; 4] = [VarType::Bool, VarType::Str, VarType::Filepath, VarType::Dirpath];
+
+#[derive(PartialEq, Clone, Copy)]
+enum Field {
+    Name,
+    Type,
+    Value,
+    Description,
+}
+
+impl Field {
+    const ALL: [Field; 4] = [Field::Name, Field::Type, Field::Value, Field::Description];
+
+    fn label(self) -> &'static str {
+        match self {
+            Field::Name => "Name",
+            Field::Type => "Type",
+            Field::Value => "Value",
+            Field::Description => "Description",
+        }
+    }
+}
+
+/// What the caller should do after the form handled a key
+pub enum NewEntryResult {
+    Continue,
+    Create(CacheVar),
+    Cancel,
+}
+
+/// Popup form for adding an entry that isn't in the cache yet
+pub struct NewEntryForm {
+    name: TextInput,
+    type_idx: usize,
+    value: TextInput,
+    desc: TextInput,
+    field: Field,
+    // Names already in the cache, which can't be created again
+    existing: Vec<String>,
+    error: Option<String>,
+}
+
+impl NewEntryForm {
+    pub fn new(existing: Vec<String>) -> Self {
+        Self {
+            name: TextInput::default(),
+            type_idx: 1,
+            value: TextInput::default(),
+            desc: TextInput::default(),
+            field: Field::Name,
+            existing,
+            error: None,
+        }
+    }
+
+    /// Start from a copy of `var` with `value`. The name cursor is put after
+    /// the first `_`, so e.g. `MYLIB_INCLUDE_DIR` only needs `DEBUG_` typed
+    /// to become `MYLIB_DEBUG_INCLUDE_DIR`.
+    pub fn duplicate(var: &CacheVar, value: &str, existing: Vec<String>) -> Self {
+        let cursor = var.name.find('_').map_or(var.name.len(), |i| i + 1);
+        let typ = if var.typ == VarType::Enum { VarType::Str } else { var.typ.clone() };
+        Self {
+            name: TextInput::with_cursor(&var.name, cursor),
+            type_idx: TYPES.iter().position(|t| *t == typ).unwrap_or(1),
+            value: TextInput::new(value),
+            desc: TextInput::new(var.desc.trim()),
+            field: Field::Name,
+            existing,
+            error: None,
+        }
+    }
+
+    fn input_mut(&mut self) -> Option<&mut TextInput> {
+        match self.field {
+            Field::Name => Some(&mut self.name),
+            Field::Type => None,
+            Field::Value => Some(&mut self.value),
+            Field::Description => Some(&mut self.desc),
+        }
+    }
+
+    fn move_field(&mut self, forward: bool) {
+        let idx = Field::ALL.iter().position(|f| *f == self.field).unwrap_or(0);
+        let len = Field::ALL.len();
+        let next = if forward { (idx + 1) % len } else { (idx + len - 1) % len };
+        self.field = Field::ALL[next];
+    }
+
+    fn validate(&self) -> Result<CacheVar, String> {
+        let name = self.name.text().trim();
+        let valid_ident = name
+            .chars()
+            .enumerate()
+            .all(|(i, c)| c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()));
+        if name.is_empty() || !valid_ident {
+            return Err("Name must be letters, digits and _, not starting with a digit".to_string());
+        }
+        if self.existing.iter().any(|n| n == name) {
+            return Err(format!("{} is already in the cache", name));
+        }
+        Ok(CacheVar::new(
+            name.to_string(),
+            TYPES[self.type_idx].clone(),
+            self.desc.text().to_string(),
+            self.value.text().to_string(),
+        ))
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> NewEntryResult {
+        self.error = None;
+        match key.code {
+            KeyCode::Esc => return NewEntryResult::Cancel,
+            KeyCode::Enter => match self.validate() {
+                Ok(var) => return NewEntryResult::Create(var),
+                Err(e) => self.error = Some(e),
+            },
+            KeyCode::Tab | KeyCode::Down => self.move_field(true),
+            KeyCode::BackTab | KeyCode::Up => self.move_field(false),
+            KeyCode::Char(' ') | KeyCode::Right if self.field == Field::Type => {
+                self.type_idx = (self.type_idx + 1) % TYPES.len();
+            }
+            KeyCode::Left if self.field == Field::Type => {
+                self.type_idx = (self.type_idx + TYPES.len() - 1) % TYPES.len();
+            }
+            _ => {
+                if let Some(input) = self.input_mut() {
+                    input.handle_key(key);
+                }
+            }
+        }
+        NewEntryResult::Continue
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(" New cache entry ").centered().bold())
+            .title_bottom(Line::raw(" <Tab> next field, <Space> change type, <Enter> create, <Esc> cancel ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let rows = Layout::vertical([Constraint::Length(1); 6]).split(inner);
+        for (i, field) in Field::ALL.iter().enumerate() {
+            let prefix = format!("{:>12}: ", field.label());
+            let active = *field == self.field;
+            let line = match field {
+                Field::Type => {
+                    let typ = Span::raw(format!("< {} >", TYPES[self.type_idx].cache_name()));
+                    let typ = if active { typ.reversed() } else { typ };
+                    Line::from(vec![Span::raw(prefix), typ])
+                }
+                Field::Name if active => self.name.line(&prefix),
+                Field::Value if active => self.value.line(&prefix),
+                Field::Description if active => self.desc.line(&prefix),
+                Field::Name => Line::raw(format!("{}{}", prefix, self.name.text())),
+                Field::Value => Line::raw(format!("{}{}", prefix, self.value.text())),
+                Field::Description => Line::raw(format!("{}{}", prefix, self.desc.text())),
+            };
+            let fg = if active { TEXT_FG } else { LABEL_FG };
+            Paragraph::new(line).fg(fg).render(rows[i], buf);
+        }
+
+        if let Some(error) = &self.error {
+            Paragraph::new(error.as_str()).fg(RED.c400).render(rows[5], buf);
+        }
+    }
+}
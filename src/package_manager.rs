@@ -0,0 +1,170 @@
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::cache_editor::CacheEditorState;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+pub const TRIPLET_VAR: &str = "VCPKG_TARGET_TRIPLET";
+
+const VCPKG_PREFIXES: &[&str] = &["VCPKG_", "_VCPKG_", "Z_VCPKG_", "X_VCPKG_"];
+const CONAN_PREFIXES: &[&str] = &["CONAN_", "_CONAN_"];
+
+/// Package manager feeding dependencies to the project through its
+/// toolchain file or dependency provider
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PackageManager {
+    Vcpkg,
+    Conan,
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            PackageManager::Vcpkg => "vcpkg",
+            PackageManager::Conan => "conan",
+        })
+    }
+}
+
+/// The package manager a cache entry belongs to, by its name
+pub fn owner(name: &str) -> Option<PackageManager> {
+    if VCPKG_PREFIXES.iter().any(|p| name.starts_with(p)) {
+        Some(PackageManager::Vcpkg)
+    } else if CONAN_PREFIXES.iter().any(|p| name.starts_with(p)) {
+        Some(PackageManager::Conan)
+    } else {
+        None
+    }
+}
+
+/// The package manager the build dir was configured with: from the
+/// toolchain file or the dependency provider, else from its variables
+pub fn detect(editor: &CacheEditorState) -> Option<PackageManager> {
+    let value = |name: &str| editor.get(name).map(|v| v.new_val.to_lowercase()).unwrap_or_default();
+    let toolchain = value("CMAKE_TOOLCHAIN_FILE");
+    let includes = value("CMAKE_PROJECT_TOP_LEVEL_INCLUDES");
+    if toolchain.ends_with("vcpkg.cmake") {
+        Some(PackageManager::Vcpkg)
+    } else if toolchain.ends_with("conan_toolchain.cmake") || includes.contains("conan_provider.cmake") {
+        Some(PackageManager::Conan)
+    } else {
+        editor.vars.iter().find_map(|v| owner(&v.var.name))
+    }
+}
+
+/// The vcpkg checkout: the one the cache names, the one holding the
+/// toolchain file (`<root>/scripts/buildsystems/vcpkg.cmake`), else $VCPKG_ROOT
+fn vcpkg_root(editor: &CacheEditorState) -> Option<PathBuf> {
+    let from_cache = editor.get("Z_VCPKG_ROOT_DIR").map(|v| PathBuf::from(&v.new_val));
+    let from_toolchain = editor
+        .get("CMAKE_TOOLCHAIN_FILE")
+        .map(|v| PathBuf::from(&v.new_val))
+        .filter(|p| p.ends_with("scripts/buildsystems/vcpkg.cmake"))
+        .and_then(|p| p.ancestors().nth(3).map(Path::to_path_buf));
+    let from_env = env::var_os("VCPKG_ROOT").map(PathBuf::from);
+    [from_cache, from_toolchain, from_env].into_iter().flatten().find(|root| root.join("triplets").is_dir())
+}
+
+/// Triplets the vcpkg checkout ships, the community ones after the official
+/// ones, plus those of the overlay dirs the project adds
+pub fn installed_triplets(editor: &CacheEditorState) -> Vec<String> {
+    let mut dirs = Vec::new();
+    if let Some(root) = vcpkg_root(editor) {
+        dirs.push(root.join("triplets"));
+        dirs.push(root.join("triplets").join("community"));
+    }
+    if let Some(overlays) = editor.get("VCPKG_OVERLAY_TRIPLETS") {
+        dirs.extend(overlays.new_val.split(';').filter(|d| !d.is_empty()).map(PathBuf::from));
+    }
+
+    let mut triplets = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(&dir) else { continue };
+        let mut found: Vec<String> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "cmake"))
+            .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .filter(|t| !triplets.contains(t))
+            .collect();
+        found.sort();
+        triplets.extend(found);
+    }
+    triplets
+}
+
+/// Pick for VCPKG_TARGET_TRIPLET among the installed triplets, like the
+/// values of an enum entry
+pub struct TripletPane {
+    pub visible: bool,
+    triplets: Vec<String>,
+    state: ListState,
+}
+
+impl TripletPane {
+    pub fn new() -> Self {
+        Self { visible: false, triplets: Vec::new(), state: ListState::default() }
+    }
+
+    /// Show the installed triplets with the current one selected, false
+    /// when no vcpkg checkout with triplets was found
+    pub fn open(&mut self, editor: &CacheEditorState) -> bool {
+        self.triplets = installed_triplets(editor);
+        if self.triplets.is_empty() {
+            return false;
+        }
+        let current = editor.get(TRIPLET_VAR).map(|v| v.new_val.as_str());
+        let selected = self.triplets.iter().position(|t| Some(t.as_str()) == current);
+        self.state = ListState::default().with_selected(Some(selected.unwrap_or(0)));
+        self.visible = true;
+        true
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.state.selected().and_then(|i| self.triplets.get(i)).map(String::as_str)
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(" vcpkg triplet ").centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Enter> set, c set and configure, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+
+        let items: Vec<ListItem> = self.triplets.iter().map(|t| ListItem::new(t.as_str())).collect();
+        let list = List::new(items)
+            .block(block)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
@@ -0,0 +1,110 @@
+use std::{fs, path::Path};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::source_scan::split_arguments;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+/// Written by `include(CPack)` into the build dir
+pub const CPACK_CONFIG: &str = "CPackConfig.cmake";
+
+// CPack generators with a short description, the portable archives first
+const GENERATORS: &[(&str, &str)] = &[
+    ("TGZ", "tar.gz archive"),
+    ("TXZ", "tar.xz archive"),
+    ("ZIP", "zip archive"),
+    ("7Z", "7-Zip archive"),
+    ("STGZ", "self extracting shell script"),
+    ("DEB", "Debian package"),
+    ("RPM", "RPM package"),
+    ("NSIS", "Windows installer, needs NSIS"),
+    ("WIX", "Windows MSI, needs the WiX toolset"),
+    ("DragNDrop", "macOS disk image"),
+    ("productbuild", "macOS installer package"),
+];
+
+/// The generators CPACK_GENERATOR names in the build dir's CPack config,
+/// None when the project doesn't use CPack
+pub fn configured_generators(build_dir: &Path) -> Option<Vec<String>> {
+    let content = fs::read_to_string(build_dir.join(CPACK_CONFIG)).ok()?;
+    let generators = content
+        .lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix("set(CPACK_GENERATOR")?.strip_suffix(')'))
+        .map(|args| split_arguments(args).iter().flat_map(|a| a.split(';')).map(str::to_string).collect())
+        .unwrap_or_default();
+    Some(generators)
+}
+
+/// Pick of the CPack generator to package with, the ones the project
+/// configured first
+pub struct PackagePane {
+    pub visible: bool,
+    // None for the generators of CPACK_GENERATOR
+    choices: Vec<(Option<&'static str>, String)>,
+    state: ListState,
+}
+
+impl PackagePane {
+    pub fn new() -> Self {
+        Self { visible: false, choices: Vec::new(), state: ListState::default() }
+    }
+
+    /// Show the generators, false when the build dir has no CPack config
+    pub fn open(&mut self, build_dir: &Path) -> bool {
+        let Some(configured) = configured_generators(build_dir) else { return false };
+        self.choices.clear();
+        if !configured.is_empty() {
+            self.choices.push((None, format!("As configured ({})", configured.join(", "))));
+        }
+        self.choices.extend(GENERATORS.iter().map(|(name, desc)| (Some(*name), format!("{:<14} {}", name, desc))));
+        self.state = ListState::default().with_selected(Some(0));
+        self.visible = true;
+        true
+    }
+
+    /// The generator picked, Some(None) for the configured ones
+    pub fn selected(&self) -> Option<Option<&'static str>> {
+        self.state.selected().and_then(|i| self.choices.get(i)).map(|(generator, _)| *generator)
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(" Package with cpack ").centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Enter> package, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+
+        let items: Vec<ListItem> = self.choices.iter().map(|(_, label)| ListItem::new(label.as_str())).collect();
+        let list = List::new(items)
+            .block(block)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
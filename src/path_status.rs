@@ -0,0 +1,148 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Sender},
+    },
+    thread,
+};
+
+use crate::wsl::Wsl;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum PathKind {
+    File,
+    Dir,
+    Missing,
+}
+
+/// Whether `value` is a Windows path, `C:/...`, `C:\...` or a `\\server\share`
+/// one, whose backslashes are separators rather than escapes
+pub fn is_windows_path(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    (bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && matches!(bytes[2], b'/' | b'\\'))
+        || value.starts_with("\\\\")
+}
+
+/// What the paths of a PATH/FILEPATH value point at
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PathState {
+    Exists,
+    Missing,
+    // A file where a directory is expected or the other way around
+    WrongKind,
+    // Not stat'ed yet
+    Unknown,
+}
+
+/// The paths in a value that aren't what the entry expects
+pub struct PathCheck {
+    pub state: PathState,
+    pub missing: Vec<String>,
+    pub wrong_kind: Vec<String>,
+}
+
+/// Stats the paths of path values on a background thread, so slow file
+/// systems (network mounts, disks spinning up) don't hold up drawing the
+/// table. Values show as unknown until their result is in.
+#[derive(Clone)]
+pub struct PathStatus {
+    // None while the stat is in flight
+    known: Arc<Mutex<HashMap<String, Option<PathKind>>>>,
+    requests: Sender<String>,
+    // Bumped with each result that comes in, for the cells showing them
+    generation: Arc<AtomicU64>,
+    // Paths of a cache from the other side of WSL are looked up translated
+    pub wsl: Option<Wsl>,
+}
+
+impl PathStatus {
+    pub fn new() -> Self {
+        let known: Arc<Mutex<HashMap<String, Option<PathKind>>>> = Arc::default();
+        let (requests, pending) = mpsc::channel::<String>();
+        let results = Arc::clone(&known);
+        let generation = Arc::new(AtomicU64::new(0));
+        let bump = Arc::clone(&generation);
+        // Ends once the last sender is dropped with the editor state
+        thread::spawn(move || {
+            for path in pending {
+                let kind = match fs::metadata(&path) {
+                    Ok(meta) if meta.is_dir() => PathKind::Dir,
+                    Ok(_) => PathKind::File,
+                    Err(_) => PathKind::Missing,
+                };
+                if let Ok(mut known) = results.lock() {
+                    known.insert(path, Some(kind));
+                }
+                bump.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Self { known, requests, generation, wsl: None }
+    }
+
+    /// Forget what was found, after a configure may have created or removed paths
+    pub fn clear(&self) {
+        if let Ok(mut known) = self.known.lock() {
+            known.clear();
+        }
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Changes whenever what's known about the paths does
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    fn kind(&self, path: &str) -> Option<PathKind> {
+        let mut known = self.known.lock().ok()?;
+        match known.get(path) {
+            Some(kind) => *kind,
+            None => {
+                known.insert(path.to_string(), None);
+                let _ = self.requests.send(path.to_string());
+                None
+            }
+        }
+    }
+
+    /// Check the `;` separated paths of `value`, None when there's none to check.
+    /// Relative paths and ones with references are resolved by the project,
+    /// only absolute ones are looked at.
+    pub fn check(&self, value: &str, want_dir: bool) -> Option<PathCheck> {
+        // As written in the cache, and where to look
+        let paths: Vec<(&str, String)> = value
+            .split(';')
+            .filter(|p| !p.contains("${") && !p.contains("$<"))
+            .map(|p| (p, self.wsl.as_ref().and_then(|wsl| wsl.translate(p)).unwrap_or_else(|| p.to_string())))
+            .filter(|(_, local)| Path::new(local).is_absolute())
+            .collect();
+        if paths.is_empty() {
+            return None;
+        }
+
+        let mut check = PathCheck { state: PathState::Exists, missing: Vec::new(), wrong_kind: Vec::new() };
+        let mut unknown = false;
+        for (path, local) in paths {
+            match self.kind(&local) {
+                None => unknown = true,
+                Some(PathKind::Missing) => check.missing.push(path.to_string()),
+                Some(PathKind::Dir) if !want_dir => check.wrong_kind.push(path.to_string()),
+                Some(PathKind::File) if want_dir => check.wrong_kind.push(path.to_string()),
+                Some(_) => {}
+            }
+        }
+        check.state = if !check.missing.is_empty() {
+            PathState::Missing
+        } else if !check.wrong_kind.is_empty() {
+            PathState::WrongKind
+        } else if unknown {
+            PathState::Unknown
+        } else {
+            PathState::Exists
+        };
+        Some(check)
+    }
+}
@@ -0,0 +1,37 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::sidecar::state_dir_for;
+
+const PINS_FILE: &str = "pins";
+
+/// Where the pins of a project are kept, under the state dir so every build
+/// dir of the project shares them. Keyed by the source dir, or the build dir
+/// when the cache doesn't name one.
+fn pins_path(project_dir: &Path) -> Option<PathBuf> {
+    Some(state_dir_for(project_dir)?.join(PINS_FILE))
+}
+
+/// Names of the pinned variables, in the order they were pinned. A missing
+/// file just means nothing is pinned yet.
+pub fn load(project_dir: &Path) -> Vec<String> {
+    let Some(content) = pins_path(project_dir).and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    content.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_string).collect()
+}
+
+/// Write the pins of the project, one name per line
+pub fn save(project_dir: &Path, pins: &[String]) -> io::Result<()> {
+    let Some(path) = pins_path(project_dir) else {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "no state directory ($XDG_STATE_HOME or $HOME) available"));
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut content = pins.join("\n");
+    content.push('\n');
+    fs::write(path, content)
+}
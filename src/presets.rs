@@ -0,0 +1,116 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::cmake;
+use crate::json::{self, JsonValue};
+
+const USER_PRESETS: &str = "CMakeUserPresets.json";
+
+/// The presets schema version to write into a new file, the newest the cmake
+/// in use reads up to 3, the first without a required generator. An error
+/// for a cmake that predates presets (3.19).
+fn presets_version() -> Result<u32, String> {
+    cmake::require("CMakeUserPresets.json", 3, 19)?;
+    Ok(match cmake::version() {
+        Some(v) if !v.at_least(3, 20) => 1,
+        Some(v) if !v.at_least(3, 21) => 2,
+        _ => 3,
+    })
+}
+
+fn user_presets_path(source_dir: &Path) -> PathBuf {
+    source_dir.join(USER_PRESETS)
+}
+
+fn read_user_presets(path: &Path) -> Result<Option<JsonValue>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => json::parse(&content)
+            .map(Some)
+            .map_err(|e| format!("{} doesn't parse, not touching it: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+/// Whether the source dir's CMakeUserPresets.json has a configure preset named `name`
+pub fn has_configure_preset(source_dir: &Path, name: &str) -> bool {
+    let Ok(Some(presets)) = read_user_presets(&user_presets_path(source_dir)) else { return false };
+    presets
+        .get("configurePresets")
+        .and_then(|p| p.as_array())
+        .is_some_and(|p| p.iter().any(|preset| preset.get("name").and_then(|n| n.as_str()) == Some(name)))
+}
+
+/// Write a configure preset named `name` for `build_dir` into the source
+/// dir's CMakeUserPresets.json, with `defines` (as passed with -D, NAME or
+/// NAME:TYPE) as its cacheVariables. A preset of the same name is replaced,
+/// everything else in the file is kept. `generator` is written for schema
+/// versions before 3, which require one. Returns the file written.
+pub fn export_configure_preset(
+    source_dir: &Path,
+    build_dir: &Path,
+    name: &str,
+    generator: Option<&str>,
+    defines: &[(String, String)],
+) -> Result<PathBuf, String> {
+    let version = presets_version()?;
+    let path = user_presets_path(source_dir);
+    let mut root = read_user_presets(&path)?
+        .unwrap_or_else(|| JsonValue::Object(vec![("version".to_string(), JsonValue::Number(version as f64))]));
+    // An existing file's version decides, cmake reads older ones too
+    let version = root.get("version").and_then(|v| v.as_u64()).map_or(version, |v| v as u32);
+    let JsonValue::Object(entries) = &mut root else {
+        return Err(format!("{} doesn't hold a JSON object, not touching it", path.display()));
+    };
+
+    let cache_variables = defines
+        .iter()
+        .map(|(define, value)| match define.split_once(':') {
+            Some((name, typ)) => (
+                name.to_string(),
+                JsonValue::Object(vec![
+                    ("type".to_string(), JsonValue::String(typ.to_string())),
+                    ("value".to_string(), JsonValue::String(value.clone())),
+                ]),
+            ),
+            None => (define.clone(), JsonValue::String(value.clone())),
+        })
+        .collect();
+    let mut fields = vec![("name".to_string(), JsonValue::String(name.to_string()))];
+    if version < 3 {
+        let Some(generator) = generator else {
+            return Err(format!("Presets version {} needs a generator, the cache doesn't name one", version));
+        };
+        fields.push(("generator".to_string(), JsonValue::String(generator.to_string())));
+    }
+    fields.push(("binaryDir".to_string(), JsonValue::String(binary_dir(source_dir, build_dir))));
+    fields.push(("cacheVariables".to_string(), JsonValue::Object(cache_variables)));
+    let preset = JsonValue::Object(fields);
+
+    if !entries.iter().any(|(key, _)| key == "configurePresets") {
+        entries.push(("configurePresets".to_string(), JsonValue::Array(Vec::new())));
+    }
+    let Some((_, JsonValue::Array(presets))) = entries.iter_mut().find(|(key, _)| key == "configurePresets") else {
+        return Err(format!("configurePresets in {} is not an array, not touching it", path.display()));
+    };
+    match presets.iter_mut().find(|p| p.get("name").and_then(|n| n.as_str()) == Some(name)) {
+        Some(existing) => *existing = preset,
+        None => presets.push(preset),
+    }
+
+    fs::write(&path, root.pretty() + "\n").map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+// Relative to the source dir when the build dir is inside it, so the preset
+// works in other checkouts too
+fn binary_dir(source_dir: &Path, build_dir: &Path) -> String {
+    let build_dir = fs::canonicalize(build_dir).unwrap_or_else(|_| build_dir.to_path_buf());
+    let source_dir = fs::canonicalize(source_dir).unwrap_or_else(|_| source_dir.to_path_buf());
+    match build_dir.strip_prefix(&source_dir) {
+        Ok(rel) => format!("${{sourceDir}}/{}", rel.to_string_lossy().replace('\\', "/")),
+        Err(_) => build_dir.to_string_lossy().into_owned(),
+    }
+}
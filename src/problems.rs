@@ -0,0 +1,222 @@
+use std::fmt;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, RED, SLATE, YELLOW},
+    },
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph,
+        StatefulWidget, Widget, Wrap,
+    },
+};
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => f.pad("error"),
+            Severity::Warning => f.pad("warning"),
+        }
+    }
+}
+
+/// One `CMake Error ...` / `CMake Warning ...` block from configure output
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub severity: Severity,
+    // First line of the block, e.g. `CMake Error at CMakeLists.txt:12 (message):`
+    pub header: String,
+    // `file:line` when cmake reported one
+    pub location: Option<String>,
+    pub message: Vec<String>,
+}
+
+impl Problem {
+    pub fn summary(&self) -> String {
+        let first = self.message.iter().map(|l| l.trim()).find(|l| !l.is_empty());
+        match (&self.location, first) {
+            (Some(loc), Some(msg)) => format!("{}: {}", loc, msg),
+            (None, Some(msg)) => msg.to_string(),
+            (Some(loc), None) => loc.clone(),
+            (None, None) => self.header.clone(),
+        }
+    }
+
+    /// Identifiers in the message, in order of appearance. Used to find cache
+    /// variables the problem is about.
+    pub fn identifiers(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.header.as_str())
+            .chain(self.message.iter().map(|l| l.as_str()))
+            .flat_map(|l| l.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_')))
+            .filter(|w| w.len() > 1 && !w.starts_with(|c: char| c.is_ascii_digit()))
+    }
+}
+
+/// Split configure output into error and warning blocks. A block starts at a
+/// line beginning with `CMake Error`/`CMake Warning` and continues over the
+/// indented (or empty) lines following it.
+pub fn parse_problems<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut current: Option<Problem> = None;
+
+    for line in lines {
+        if let Some(problem) = parse_header(line) {
+            if let Some(done) = current.take() {
+                problems.push(done);
+            }
+            current = Some(problem);
+            continue;
+        }
+
+        let Some(problem) = current.as_mut() else { continue };
+        if line.is_empty() || line.starts_with(' ') || line.starts_with('\t') {
+            problem.message.push(line.to_string());
+        } else if let Some(done) = current.take() {
+            problems.push(done);
+        }
+    }
+    if let Some(done) = current {
+        problems.push(done);
+    }
+
+    for problem in problems.iter_mut() {
+        while problem.message.last().is_some_and(|l| l.trim().is_empty()) {
+            problem.message.pop();
+        }
+    }
+    problems
+}
+
+fn parse_header(line: &str) -> Option<Problem> {
+    let (severity, rest) = if let Some(rest) = line.strip_prefix("CMake Error") {
+        (Severity::Error, rest)
+    } else if let Some(rest) = line.strip_prefix("CMake Warning") {
+        (Severity::Warning, rest)
+    } else if let Some(rest) = line.strip_prefix("CMake Deprecation Warning") {
+        (Severity::Warning, rest)
+    } else {
+        return None;
+    };
+    let rest = rest.strip_prefix(" (dev)").unwrap_or(rest);
+
+    let mut location = None;
+    let mut message = Vec::new();
+    if let Some(at) = rest.strip_prefix(" at ") {
+        // `CMake Error at path/CMakeLists.txt:12 (message):`
+        let at = at.trim_end_matches(':');
+        let loc = at.rsplit_once(" (").map_or(at, |(loc, _)| loc);
+        location = Some(loc.to_string());
+    } else if let Some(msg) = rest.strip_prefix(':') {
+        // `CMake Error: message on the same line`
+        let msg = msg.trim();
+        if !msg.is_empty() {
+            message.push(msg.to_string());
+        }
+    }
+
+    Some(Problem { severity, header: line.to_string(), location, message })
+}
+
+pub struct ProblemsPane {
+    pub problems: Vec<Problem>,
+    pub visible: bool,
+    state: ListState,
+}
+
+impl ProblemsPane {
+    pub fn new() -> Self {
+        Self { problems: Vec::new(), visible: false, state: ListState::default() }
+    }
+
+    pub fn set_problems(&mut self, problems: Vec<Problem>) {
+        self.problems = problems;
+        self.state = ListState::default();
+        if !self.problems.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
+    pub fn selected(&self) -> Option<&Problem> {
+        self.problems.get(self.state.selected()?)
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.problems.iter().filter(|p| p.severity == severity).count()
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let title = format!(
+            " Problems: {} errors, {} warnings ",
+            self.count(Severity::Error),
+            self.count(Severity::Warning)
+        );
+        let block = Block::new()
+            .title(Line::raw(title).centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Enter> jump to variable, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [list_area, detail_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(inner);
+
+        let items: Vec<ListItem> = self
+            .problems
+            .iter()
+            .map(|p| {
+                let color = match p.severity {
+                    Severity::Error => RED.c400,
+                    Severity::Warning => YELLOW.c400,
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<8}", p.severity), Style::new().fg(color).bold()),
+                    Span::raw(p.summary()),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        let details: Vec<Line> = match self.selected() {
+            Some(p) => std::iter::once(Line::raw(p.header.clone()).bold())
+                .chain(p.message.iter().map(|l| Line::raw(l.clone())))
+                .collect(),
+            None => vec![Line::raw("No problems reported by the last configure")],
+        };
+        Paragraph::new(details)
+            .block(Block::new().borders(Borders::TOP).border_style(Style::new().fg(BLUE.c500)))
+            .fg(TEXT_FG)
+            .wrap(Wrap { trim: false })
+            .render(detail_area, buf);
+    }
+}
@@ -0,0 +1,201 @@
+use std::{collections::HashMap, fs, io, path::Path, time::Duration};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::json::{self, JsonValue};
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+/// Time spent in one CMake function or file during a profiled configure
+pub struct ProfileEntry {
+    pub name: String,
+    pub calls: usize,
+    // Excluding the time of the calls made from it, so the entries add up
+    // to the whole configure and the top ones are where the time went
+    pub self_time: Duration,
+}
+
+pub struct Profile {
+    pub functions: Vec<ProfileEntry>,
+    pub files: Vec<ProfileEntry>,
+    pub total: Duration,
+}
+
+// One complete ("ph": "X") event of the trace, times in microseconds
+struct TraceEvent {
+    name: String,
+    file: Option<String>,
+    start: u64,
+    duration: u64,
+}
+
+/// Read the trace cmake writes with `--profiling-format=google-trace`
+pub fn parse_trace(path: &Path) -> io::Result<Profile> {
+    let content = fs::read_to_string(path)?;
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), message));
+    let trace = json::parse(&content).map_err(|e| invalid(e.to_string()))?;
+    // Both the bare array cmake writes and the object form of the format
+    let events = trace
+        .as_array()
+        .or_else(|| trace.get("traceEvents")?.as_array())
+        .ok_or_else(|| invalid("not a google-trace profile".to_string()))?;
+
+    let number = |value: Option<&JsonValue>| match value {
+        Some(JsonValue::Number(n)) if *n >= 0.0 => Some(*n as u64),
+        _ => None,
+    };
+    let mut events: Vec<TraceEvent> = events
+        .iter()
+        .filter(|e| e.get("ph").and_then(|p| p.as_str()) == Some("X"))
+        .filter_map(|e| {
+            // `location` is `path/CMakeLists.txt:12`
+            let location = e.get("args").and_then(|a| a.get("location")).and_then(|l| l.as_str());
+            Some(TraceEvent {
+                name: e.get("name")?.as_str()?.to_string(),
+                file: location.map(|l| l.rsplit_once(':').map_or(l, |(file, _)| file).to_string()),
+                start: number(e.get("ts"))?,
+                duration: number(e.get("dur"))?,
+            })
+        })
+        .collect();
+    Ok(summarize(&mut events))
+}
+
+fn summarize(events: &mut [TraceEvent]) -> Profile {
+    // Parents before their children: by start, the longer one first
+    events.sort_by(|a, b| a.start.cmp(&b.start).then(b.duration.cmp(&a.duration)));
+
+    let mut self_times: Vec<u64> = events.iter().map(|e| e.duration).collect();
+    // Events enclosing the current one, as (end, index)
+    let mut open: Vec<(u64, usize)> = Vec::new();
+    let mut total = 0;
+    for (i, event) in events.iter().enumerate() {
+        while open.last().is_some_and(|&(end, _)| end <= event.start) {
+            open.pop();
+        }
+        match open.last() {
+            Some(&(_, parent)) => self_times[parent] = self_times[parent].saturating_sub(event.duration),
+            None => total += event.duration,
+        }
+        open.push((event.start + event.duration, i));
+    }
+
+    let mut functions: HashMap<&str, ProfileEntry> = HashMap::new();
+    let mut files: HashMap<&str, ProfileEntry> = HashMap::new();
+    for (event, &self_time) in events.iter().zip(&self_times) {
+        add_call(&mut functions, &event.name, self_time);
+        if let Some(file) = &event.file {
+            add_call(&mut files, file, self_time);
+        }
+    }
+
+    let slowest_first = |map: HashMap<&str, ProfileEntry>| {
+        let mut entries: Vec<ProfileEntry> = map.into_values().collect();
+        entries.sort_by(|a, b| b.self_time.cmp(&a.self_time).then_with(|| a.name.cmp(&b.name)));
+        entries
+    };
+    Profile {
+        functions: slowest_first(functions),
+        files: slowest_first(files),
+        total: Duration::from_micros(total),
+    }
+}
+
+fn add_call<'a>(entries: &mut HashMap<&'a str, ProfileEntry>, key: &'a str, self_time: u64) {
+    let entry = entries.entry(key).or_insert_with(|| ProfileEntry {
+        name: key.to_string(),
+        calls: 0,
+        self_time: Duration::ZERO,
+    });
+    entry.calls += 1;
+    entry.self_time += Duration::from_micros(self_time);
+}
+
+/// Slowest functions and files of the last profiled configure
+pub struct ProfilePane {
+    profile: Option<Profile>,
+    pub visible: bool,
+    // Listing files instead of functions
+    by_file: bool,
+    state: ListState,
+}
+
+impl ProfilePane {
+    pub fn new() -> Self {
+        Self { profile: None, visible: false, by_file: false, state: ListState::default() }
+    }
+
+    pub fn has_profile(&self) -> bool {
+        self.profile.is_some()
+    }
+
+    pub fn set_profile(&mut self, profile: Profile) {
+        self.profile = Some(profile);
+        self.state = ListState::default().with_selected(Some(0));
+    }
+
+    pub fn toggle_grouping(&mut self) {
+        self.by_file = !self.by_file;
+        self.state = ListState::default().with_selected(Some(0));
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let grouping = if self.by_file { "files" } else { "functions" };
+        let title = match &self.profile {
+            Some(p) => format!(" Slowest {}, configure took {:.2}s ", grouping, p.total.as_secs_f64()),
+            None => " Profile ".to_string(),
+        };
+        let block = Block::new()
+            .title(Line::raw(title).centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Tab> functions/files, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [header_area, list_area] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+        Line::raw(format!("  {:>9} {:>6} {:>7}  {}", "self", "%", "calls", grouping))
+            .fg(TEXT_FG)
+            .bold()
+            .render(header_area, buf);
+
+        let Some(profile) = &self.profile else { return };
+        let entries = if self.by_file { &profile.files } else { &profile.functions };
+        let total = profile.total.as_secs_f64().max(f64::EPSILON);
+        let items: Vec<ListItem> = entries
+            .iter()
+            .map(|e| {
+                let secs = e.self_time.as_secs_f64();
+                ListItem::new(format!("{:>8.3}s {:>5.1}% {:>7}  {}", secs, secs / total * 100.0, e.calls, e.name))
+            })
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+    }
+}
@@ -0,0 +1,103 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use regex::Regex;
+
+use crate::ignore;
+use crate::toml::{self, TomlValue};
+
+/// Checked into a project next to its top level CMakeLists.txt, or dropped
+/// into a build dir for settings of that build alone
+pub const PROJECT_CONFIG_FILE: &str = ".cmake-tui.toml";
+
+/// Settings a project keeps for everyone working on it, merged over the
+/// user config:
+///
+/// ```toml
+/// pinned = ["BUILD_TESTING", "MYLIB_BACKEND"]
+///
+/// [view]
+/// ignore = ["MYLIB_INTERNAL_*"]
+///
+/// # Values to pick from for STRING options the project doesn't give STRINGS
+/// [enums]
+/// MYLIB_BACKEND = ["cpu", "cuda", "rocm"]
+///
+/// # Shown for options declared without a help string
+/// [descriptions]
+/// MYLIB_BACKEND = "Where the kernels run"
+/// ```
+#[derive(Debug, Default)]
+pub struct ProjectConfig {
+    pub pinned: Vec<String>,
+    pub ignore: Vec<Regex>,
+    pub enums: HashMap<String, Vec<String>>,
+    pub descriptions: HashMap<String, String>,
+    // Problem reading one of the files, reported once the UI is up
+    pub error: Option<String>,
+}
+
+fn strings(value: &TomlValue) -> Option<Vec<String>> {
+    match value {
+        TomlValue::Array(items) => items.iter().map(|item| item.as_str().map(str::to_string)).collect(),
+        _ => None,
+    }
+}
+
+impl ProjectConfig {
+    /// The source dir's file with the build dir's merged over it. Without
+    /// either file, nothing changes from the user config.
+    pub fn load(source_dir: Option<&Path>, build_dir: &Path) -> Self {
+        let mut config = Self::default();
+        let mut dirs = vec![build_dir];
+        if let Some(source_dir) = source_dir.filter(|dir| *dir != build_dir) {
+            dirs.insert(0, source_dir);
+        }
+        for dir in dirs {
+            let path = dir.join(PROJECT_CONFIG_FILE);
+            let Ok(content) = fs::read_to_string(&path) else { continue };
+            if let Err(e) = config.merge(&content) {
+                config.error = Some(format!("{}: {}", path.display(), e));
+            }
+        }
+        config
+    }
+
+    fn merge(&mut self, content: &str) -> Result<(), String> {
+        let table = toml::parse(content).map_err(|e| e.to_string())?;
+        let mut error = None;
+        for (key, value) in table.iter() {
+            match key {
+                "pinned" => match strings(value) {
+                    Some(names) => {
+                        for name in names {
+                            if !self.pinned.contains(&name) {
+                                self.pinned.push(name);
+                            }
+                        }
+                    }
+                    None => error = Some("pinned: expected a list of variable names".to_string()),
+                },
+                "view.ignore" => match ignore::parse_list(value) {
+                    Ok(patterns) => self.ignore.extend(patterns),
+                    Err(e) => error = Some(e),
+                },
+                _ => {
+                    if let Some(name) = key.strip_prefix("enums.") {
+                        match strings(value) {
+                            Some(values) => _ = self.enums.insert(name.to_string(), values),
+                            None => error = Some(format!("{}: expected a list of values", key)),
+                        }
+                    } else if let Some(name) = key.strip_prefix("descriptions.") {
+                        match value.as_str() {
+                            Some(description) => _ = self.descriptions.insert(name.to_string(), description.to_string()),
+                            None => error = Some(format!("{}: expected a string", key)),
+                        }
+                    } else {
+                        error = Some(format!("unknown key `{}`", key));
+                    }
+                }
+            }
+        }
+        error.map_or(Ok(()), Err)
+    }
+}
@@ -0,0 +1,244 @@
+use std::{fs, path::Path};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::cache_editor::CacheEditorState;
+use crate::json::{self, JsonValue};
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+/// Written by project maintainers next to the top level CMakeLists.txt
+pub const QUICK_SETTINGS_FILE: &str = "cmake-tui.json";
+
+pub struct QuickSetting {
+    pub label: String,
+    pub variable: String,
+    pub description: String,
+}
+
+pub struct QuickGroup {
+    pub name: String,
+    pub settings: Vec<QuickSetting>,
+}
+
+/// A combination of values the project recommends, applied all at once
+pub struct Recommendation {
+    pub name: String,
+    pub description: String,
+    pub values: Vec<(String, String)>,
+}
+
+/// The curated settings of a project's cmake-tui.json:
+///
+/// ```json
+/// {
+///   "groups": [
+///     { "name": "Build", "settings": [
+///       { "label": "Build type", "variable": "CMAKE_BUILD_TYPE", "description": "..." }
+///     ] }
+///   ],
+///   "recommended": [
+///     { "name": "Fast debug", "description": "...", "values": { "CMAKE_BUILD_TYPE": "Debug" } }
+///   ]
+/// }
+/// ```
+pub struct QuickSettings {
+    pub groups: Vec<QuickGroup>,
+    pub recommended: Vec<Recommendation>,
+}
+
+impl QuickSettings {
+    /// The quick settings the project in `source_dir` ships, None when it
+    /// has none
+    pub fn load(source_dir: &Path) -> Option<Result<Self, String>> {
+        let path = source_dir.join(QUICK_SETTINGS_FILE);
+        let content = fs::read_to_string(&path).ok()?;
+        Some(Self::parse(&content).map_err(|e| format!("{}: {}", path.display(), e)))
+    }
+
+    fn parse(content: &str) -> Result<Self, String> {
+        let root = json::parse(content).map_err(|e| e.to_string())?;
+        let string = |value: &JsonValue, key: &str| value.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+        let mut groups = Vec::new();
+        for group in root.get("groups").and_then(|g| g.as_array()).unwrap_or_default() {
+            let name = string(group, "name").ok_or("a group without \"name\"")?;
+            let mut settings = Vec::new();
+            for setting in group.get("settings").and_then(|s| s.as_array()).unwrap_or_default() {
+                let variable = string(setting, "variable")
+                    .ok_or_else(|| format!("a setting without \"variable\" in group \"{}\"", name))?;
+                settings.push(QuickSetting {
+                    label: string(setting, "label").unwrap_or_else(|| variable.clone()),
+                    description: string(setting, "description").unwrap_or_default(),
+                    variable,
+                });
+            }
+            groups.push(QuickGroup { name, settings });
+        }
+
+        let mut recommended = Vec::new();
+        for combination in root.get("recommended").and_then(|r| r.as_array()).unwrap_or_default() {
+            let name = string(combination, "name").ok_or("a recommendation without \"name\"")?;
+            let Some(JsonValue::Object(values)) = combination.get("values") else {
+                return Err(format!("recommendation \"{}\" has no \"values\" object", name));
+            };
+            let values = values
+                .iter()
+                .map(|(variable, value)| match value {
+                    JsonValue::String(s) => Ok((variable.clone(), s.clone())),
+                    JsonValue::Bool(b) => Ok((variable.clone(), if *b { "ON" } else { "OFF" }.to_string())),
+                    JsonValue::Number(n) => Ok((variable.clone(), n.to_string())),
+                    _ => Err(format!("value of {} in \"{}\" is not a string", variable, name)),
+                })
+                .collect::<Result<_, _>>()?;
+            recommended.push(Recommendation {
+                description: string(combination, "description").unwrap_or_default(),
+                name,
+                values,
+            });
+        }
+        Ok(Self { groups, recommended })
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum QuickRow {
+    Header(usize),
+    Setting(usize, usize),
+    RecommendedHeader,
+    Recommendation(usize),
+}
+
+/// Simplified menu over the project's quick settings, for users who don't
+/// want to go through the whole cache
+pub struct QuickSettingsPane {
+    pub settings: QuickSettings,
+    pub visible: bool,
+    rows: Vec<QuickRow>,
+    state: ListState,
+}
+
+impl QuickSettingsPane {
+    pub fn new(settings: QuickSettings) -> Self {
+        let mut rows = Vec::new();
+        for (g, group) in settings.groups.iter().enumerate() {
+            rows.push(QuickRow::Header(g));
+            rows.extend((0..group.settings.len()).map(|s| QuickRow::Setting(g, s)));
+        }
+        if !settings.recommended.is_empty() {
+            rows.push(QuickRow::RecommendedHeader);
+            rows.extend((0..settings.recommended.len()).map(QuickRow::Recommendation));
+        }
+        let mut pane = Self { settings, visible: false, rows, state: ListState::default() };
+        pane.select_next();
+        pane
+    }
+
+    pub fn selected(&self) -> Option<QuickRow> {
+        self.state.selected().and_then(|i| self.rows.get(i).copied())
+    }
+
+    fn is_header(row: &QuickRow) -> bool {
+        matches!(row, QuickRow::Header(_) | QuickRow::RecommendedHeader)
+    }
+
+    /// Move to the next setting or recommendation, skipping group headers
+    pub fn select_next(&mut self) {
+        let start = self.state.selected().map_or(0, |i| i + 1);
+        if let Some(i) = (start..self.rows.len()).find(|&i| !Self::is_header(&self.rows[i])) {
+            self.state.select(Some(i));
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        let end = self.state.selected().unwrap_or(0);
+        if let Some(i) = (0..end).rev().find(|&i| !Self::is_header(&self.rows[i])) {
+            self.state.select(Some(i));
+        }
+    }
+
+    pub fn setting(&self, group: usize, setting: usize) -> &QuickSetting {
+        &self.settings.groups[group].settings[setting]
+    }
+
+    pub fn render(&mut self, editor: &CacheEditorState, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(" Quick settings ").centered().bold())
+            .title_bottom(
+                Line::raw(" j/k select, <Space> change, <Enter> edit in table / apply, <Esc> full table ").centered(),
+            )
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [list_area, detail_area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(4)]).areas(inner);
+
+        let label_width = self
+            .settings
+            .groups
+            .iter()
+            .flat_map(|g| g.settings.iter().map(|s| s.label.chars().count()))
+            .max()
+            .unwrap_or(0);
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|row| match *row {
+                QuickRow::Header(g) => ListItem::new(Line::raw(self.settings.groups[g].name.clone()).bold()),
+                QuickRow::RecommendedHeader => ListItem::new(Line::raw("Recommended").bold()),
+                QuickRow::Setting(g, s) => {
+                    let setting = self.setting(g, s);
+                    let value = match editor.get(&setting.variable) {
+                        Some(var) if var.is_modified() => Span::raw(format!("{} *", var.new_val)).bold(),
+                        Some(var) => Span::raw(var.new_val.clone()),
+                        None => Span::raw("(not in the cache)").dim(),
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::raw(format!("  {:<width$}  ", setting.label, width = label_width)),
+                        value,
+                    ]))
+                }
+                QuickRow::Recommendation(r) => ListItem::new(format!("  {}", self.settings.recommended[r].name)),
+            })
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        let details = match self.selected() {
+            Some(QuickRow::Setting(g, s)) => {
+                let setting = self.setting(g, s);
+                format!("{}: {}", setting.variable, setting.description)
+            }
+            Some(QuickRow::Recommendation(r)) => {
+                let recommendation = &self.settings.recommended[r];
+                let values: Vec<String> = recommendation.values.iter().map(|(n, v)| format!("{}={}", n, v)).collect();
+                format!("{}\nSets {}", recommendation.description, values.join(" "))
+            }
+            _ => String::new(),
+        };
+        Paragraph::new(details)
+            .block(Block::new().borders(Borders::TOP).border_style(Style::new().fg(BLUE.c500)))
+            .fg(TEXT_FG)
+            .wrap(Wrap { trim: false })
+            .render(detail_area, buf);
+    }
+}
@@ -0,0 +1,211 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{self, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::json::{self, JsonValue};
+
+const FORMAT_VERSION: u64 = 1;
+
+// Long pauses in a recording aren't worth waiting for when replaying
+const MAX_REPLAY_GAP: Duration = Duration::from_secs(1);
+
+/// FNV-1a hash, unlike std's hasher it stays the same across builds and
+/// platforms, so recordings made by users can be checked by maintainers
+pub fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+// u64 doesn't fit into a JSON number losslessly
+fn hex(value: u64) -> JsonValue {
+    JsonValue::String(format!("{:016x}", value))
+}
+
+fn from_hex(value: &JsonValue) -> Option<u64> {
+    u64::from_str_radix(value.as_str()?, 16).ok()
+}
+
+/// Writes the key presses of a session as JSON lines: a header with the
+/// checksum of the cache the session started from, then one line per key
+/// with its time and the checksum of the app state after handling it.
+pub struct Recorder {
+    file: File,
+    started: Instant,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, cache_checksum: u64) -> io::Result<Self> {
+        let mut file = File::create(path)?;
+        let header = JsonValue::Object(vec![
+            ("version".to_string(), JsonValue::Number(FORMAT_VERSION as f64)),
+            ("cache".to_string(), hex(cache_checksum)),
+        ]);
+        writeln!(file, "{}", header)?;
+        Ok(Self { file, started: Instant::now() })
+    }
+
+    pub fn record(&mut self, key: &KeyEvent, state: u64) -> io::Result<()> {
+        // Keys that can't be written back wouldn't replay anyway
+        let Some(key) = key_to_json(key) else { return Ok(()) };
+        let event = JsonValue::Object(vec![
+            ("ms".to_string(), JsonValue::Number(self.started.elapsed().as_millis() as f64)),
+            ("key".to_string(), key),
+            ("state".to_string(), hex(state)),
+        ]);
+        // Flushed line by line, the interesting recordings end in a crash
+        writeln!(self.file, "{}", event)?;
+        self.file.flush()
+    }
+}
+
+pub struct RecordedKey {
+    pub key: KeyEvent,
+    // State checksum after the key was handled while recording
+    pub state: u64,
+    ms: u64,
+}
+
+/// A recording being played back
+pub struct Replay {
+    pub cache_checksum: u64,
+    // Set when the cache differs from the one recorded against, which
+    // explains most divergences
+    pub other_cache: bool,
+    keys: VecDeque<RecordedKey>,
+    // Number of keys handed out so far
+    pub position: usize,
+    last_ms: u64,
+    last_at: Instant,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let invalid = |line: usize, message: &str| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{}:{}: {}", path.display(), line, message))
+        };
+
+        let mut lines = content.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        let (_, header) = lines.next().ok_or_else(|| invalid(1, "empty recording"))?;
+        let header = json::parse(header).map_err(|e| invalid(1, &e.to_string()))?;
+        match header.get("version").and_then(|v| v.as_u64()) {
+            Some(FORMAT_VERSION) => {}
+            Some(v) => return Err(invalid(1, &format!("unsupported recording version {}", v))),
+            None => return Err(invalid(1, "not a cmake-tui recording")),
+        }
+        let cache_checksum = header.get("cache").and_then(from_hex).unwrap_or(0);
+
+        let mut keys = VecDeque::new();
+        for (i, line) in lines {
+            let value = json::parse(line).map_err(|e| invalid(i + 1, &e.to_string()))?;
+            let key = (|| {
+                Some(RecordedKey {
+                    key: key_from_json(value.get("key")?)?,
+                    state: from_hex(value.get("state")?)?,
+                    ms: value.get("ms")?.as_u64()?,
+                })
+            })()
+            .ok_or_else(|| invalid(i + 1, "invalid key event"))?;
+            keys.push_back(key);
+        }
+        Ok(Self { cache_checksum, other_cache: false, keys, position: 0, last_ms: 0, last_at: Instant::now() })
+    }
+
+    pub fn total(&self) -> usize {
+        self.position + self.keys.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The next key once as much time passed as between the recorded keys
+    pub fn next_due(&mut self) -> Option<RecordedKey> {
+        let gap = Duration::from_millis(self.keys.front()?.ms.saturating_sub(self.last_ms));
+        if self.last_at.elapsed() < gap.min(MAX_REPLAY_GAP) {
+            return None;
+        }
+        let key = self.keys.pop_front()?;
+        self.position += 1;
+        self.last_ms = key.ms;
+        self.last_at = Instant::now();
+        Some(key)
+    }
+}
+
+fn key_to_json(key: &KeyEvent) -> Option<JsonValue> {
+    let code = match key.code {
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Delete => "Delete".to_string(),
+        KeyCode::Insert => "Insert".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::BackTab => "BackTab".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        _ => return None,
+    };
+    let modifiers = [
+        (KeyModifiers::CONTROL, "ctrl"),
+        (KeyModifiers::ALT, "alt"),
+        (KeyModifiers::SHIFT, "shift"),
+    ]
+    .into_iter()
+    .filter(|(m, _)| key.modifiers.contains(*m))
+    .map(|(_, name)| JsonValue::String(name.to_string()))
+    .collect();
+    Some(JsonValue::Object(vec![
+        ("code".to_string(), JsonValue::String(code)),
+        ("modifiers".to_string(), JsonValue::Array(modifiers)),
+    ]))
+}
+
+fn key_from_json(value: &JsonValue) -> Option<KeyEvent> {
+    let code = value.get("code")?.as_str()?;
+    let mut chars = code.chars();
+    let code = match (chars.next()?, chars.next()) {
+        (c, None) => KeyCode::Char(c),
+        _ => match code {
+            "Enter" => KeyCode::Enter,
+            "Esc" => KeyCode::Esc,
+            "Backspace" => KeyCode::Backspace,
+            "Delete" => KeyCode::Delete,
+            "Insert" => KeyCode::Insert,
+            "Tab" => KeyCode::Tab,
+            "BackTab" => KeyCode::BackTab,
+            "Left" => KeyCode::Left,
+            "Right" => KeyCode::Right,
+            "Up" => KeyCode::Up,
+            "Down" => KeyCode::Down,
+            "Home" => KeyCode::Home,
+            "End" => KeyCode::End,
+            "PageUp" => KeyCode::PageUp,
+            "PageDown" => KeyCode::PageDown,
+            f => KeyCode::F(f.strip_prefix('F')?.parse().ok()?),
+        },
+    };
+    let mut modifiers = KeyModifiers::NONE;
+    for name in value.get("modifiers")?.as_array()? {
+        modifiers |= match name.as_str()? {
+            "ctrl" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+    }
+    Some(KeyEvent::new(code, modifiers))
+}
@@ -0,0 +1,218 @@
+use std::{
+    env::consts::EXE_SUFFIX,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::OnceLock,
+};
+
+use crate::sidecar::state_dir_for;
+
+static REMOTE: OnceLock<Remote> = OnceLock::new();
+
+const CACHE_FILE: &str = "CMakeCache.txt";
+
+/// How commands get to the machine the build dir is on
+#[derive(Debug, Clone)]
+pub enum Transport {
+    Ssh {
+        // `host` or `user@host`, as ssh takes it
+        host: String,
+        port: Option<u16>,
+    },
+    // A running container, through `docker exec` or `podman exec`
+    Container { engine: String, name: String },
+}
+
+/// A build dir on another machine, given as `ssh://[user@]host[:port]/path`,
+/// or in a container, given with --container. Its cache is copied into a
+/// local mirror dir to be edited there, written back on save, and cmake
+/// runs where the build dir is.
+#[derive(Debug, Clone)]
+pub struct Remote {
+    pub transport: Transport,
+    // The build dir on the remote machine, relative to the home dir (the
+    // container's working dir) when not absolute
+    pub dir: String,
+    // Where the cache is kept locally
+    pub mirror: PathBuf,
+}
+
+/// `arg` quoted for a POSIX shell
+pub fn quote(arg: &str) -> String {
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c)) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+impl Remote {
+    /// The remote build dir of an `ssh://` URL, None for any other path
+    pub fn parse(url: &str) -> Option<Result<Self, String>> {
+        let rest = url.strip_prefix("ssh://")?;
+        let Some((authority, dir)) = rest.split_once('/') else {
+            return Some(Err(format!("{}: no build dir, expected ssh://host/path/to/build", url)));
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => match port.parse() {
+                Ok(port) => (host, Some(port)),
+                Err(_) => return Some(Err(format!("{}: bad port `{}`", url, port))),
+            },
+            None => (authority, None),
+        };
+        if host.is_empty() || dir.is_empty() {
+            return Some(Err(format!("{}: expected ssh://host/path/to/build", url)));
+        }
+        let dir = match dir.strip_prefix("~/") {
+            Some(home_relative) => home_relative.trim_end_matches('/').to_string(),
+            None => format!("/{}", dir.trim_end_matches('/')),
+        };
+        Some(Ok(Self::new(Transport::Ssh { host: host.to_string(), port }, dir)))
+    }
+
+    /// The build dir `dir` inside the running container `name`, with
+    /// `engine` or else docker, or podman when there's no docker
+    pub fn container(engine: Option<String>, name: &str, dir: &str) -> Self {
+        let engine = engine.unwrap_or_else(|| {
+            let path = std::env::var("PATH").unwrap_or_default();
+            let on_path =
+                |program: &str| std::env::split_paths(&path).any(|d| d.join(format!("{}{}", program, EXE_SUFFIX)).is_file());
+            if !on_path("docker") && on_path("podman") { "podman" } else { "docker" }.to_string()
+        });
+        let dir = match dir.trim_end_matches('/') {
+            "" => "/".to_string(),
+            dir => dir.to_string(),
+        };
+        Self::new(Transport::Container { engine, name: name.to_string() }, dir)
+    }
+
+    fn new(transport: Transport, dir: String) -> Self {
+        let place = match &transport {
+            Transport::Ssh { host, .. } => format!("ssh/{}", host),
+            Transport::Container { engine, name } => format!("{}/{}", engine, name),
+        };
+        let mirror = state_dir_for(Path::new(&format!("{}/{}", place, dir)))
+            .unwrap_or_else(|| std::env::temp_dir().join(format!("cmake-tui-{}", place.replace(['@', '/'], "_"))));
+        Self { transport, dir, mirror }
+    }
+
+    /// Where the build dir is, for the title
+    pub fn url(&self) -> String {
+        match &self.transport {
+            Transport::Ssh { host, port } => {
+                let port = port.map(|p| format!(":{}", p)).unwrap_or_default();
+                let dir = if self.dir.starts_with('/') { self.dir.clone() } else { format!("/~/{}", self.dir) };
+                format!("ssh://{}{}{}", host, port, dir)
+            }
+            Transport::Container { engine, name } => format!("{} {}:{}", engine, name, self.dir),
+        }
+    }
+
+    /// Run `line` with a shell where the build dir is. ssh never asks for a
+    /// password, the TUI owns the terminal.
+    fn shell(&self, line: &str) -> Command {
+        tracing::info!("running {:?} on {}", line, self.url());
+        match &self.transport {
+            Transport::Ssh { host, port } => {
+                let mut cmd = Command::new("ssh");
+                cmd.arg("-o").arg("BatchMode=yes");
+                if let Some(port) = port {
+                    cmd.arg("-p").arg(port.to_string());
+                }
+                cmd.arg(host).arg("--").arg(line);
+                cmd
+            }
+            Transport::Container { engine, name } => {
+                let mut cmd = Command::new(engine);
+                // -i passes stdin on, for writing the cache
+                cmd.arg("exec").arg("-i").arg(name).arg("sh").arg("-c").arg(line);
+                cmd
+            }
+        }
+    }
+
+    fn program(&self) -> &str {
+        match &self.transport {
+            Transport::Ssh { .. } => "ssh",
+            Transport::Container { engine, .. } => engine,
+        }
+    }
+
+    fn remote_cache(&self) -> String {
+        format!("{}/{}", self.dir, CACHE_FILE)
+    }
+
+    /// Copy the remote cache into the mirror dir
+    pub fn fetch(&self) -> Result<(), String> {
+        let output = self
+            .shell(&format!("cat {}", quote(&self.remote_cache())))
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("Failed to run {}: {}", self.program(), e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Failed to read {}: {}", self.url(), stderr.trim()));
+        }
+        fs::create_dir_all(&self.mirror).map_err(|e| format!("Failed to create {}: {}", self.mirror.display(), e))?;
+        fs::write(self.mirror.join(CACHE_FILE), output.stdout)
+            .map_err(|e| format!("Failed to write {}: {}", self.mirror.display(), e))
+    }
+
+    /// Write the mirrored cache back to the remote build dir, through a
+    /// temporary file so a dropped connection can't leave half a cache
+    pub fn upload(&self) -> Result<(), String> {
+        let local = fs::File::open(self.mirror.join(CACHE_FILE)).map_err(|e| format!("Failed to read the cache: {}", e))?;
+        let target = quote(&self.remote_cache());
+        let temp = quote(&format!("{}.cmake-tui", self.remote_cache()));
+        let output = self
+            .shell(&format!("cat > {} && mv {} {}", temp, temp, target))
+            .stdin(local)
+            .output()
+            .map_err(|e| format!("Failed to run {}: {}", self.program(), e))?;
+        match output.status.success() {
+            true => Ok(()),
+            false => Err(format!("Failed to write {}: {}", self.url(), String::from_utf8_lossy(&output.stderr).trim())),
+        }
+    }
+
+    /// `cmd`, made to run in the remote build dir: the mirror dir in its
+    /// arguments is replaced by the remote one, its environment is passed
+    /// along, and the cmake tools are looked up on the remote PATH
+    fn wrap(&self, cmd: &Command) -> Command {
+        let mirror = self.mirror.to_string_lossy();
+        let local_to_remote = |arg: &str| arg.replace(mirror.as_ref(), &self.dir);
+
+        let mut line = format!("cd {} && env", quote(&self.dir));
+        for (name, value) in cmd.get_envs() {
+            let name = name.to_string_lossy();
+            match value {
+                Some(value) => line.push_str(&format!(" {}", quote(&format!("{}={}", name, value.to_string_lossy())))),
+                None => line.push_str(&format!(" -u {}", quote(&name))),
+            }
+        }
+        let program = Path::new(cmd.get_program()).file_name().unwrap_or(cmd.get_program()).to_string_lossy();
+        line.push_str(&format!(" {}", quote(&program)));
+        for arg in cmd.get_args() {
+            line.push_str(&format!(" {}", quote(&local_to_remote(&arg.to_string_lossy()))));
+        }
+        self.shell(&line)
+    }
+}
+
+/// Work on `remote` instead of a local build dir, for the whole run
+pub fn set(remote: Remote) {
+    let _ = REMOTE.set(remote);
+}
+
+pub fn current() -> Option<&'static Remote> {
+    REMOTE.get()
+}
+
+/// `cmd` as it has to run for the build dir: through ssh for a remote one,
+/// as is otherwise
+pub fn wrap(cmd: Command) -> Command {
+    match current() {
+        Some(remote) => remote.wrap(&cmd),
+        None => cmd,
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::cache_editor::CacheVarTui;
+use crate::json::{self, JsonValue};
+
+// JSON-RPC 2.0 error codes
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+// Application errors, e.g. saving while cmake runs
+pub const SERVER_ERROR: i64 = -32000;
+
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+/// A request received from a client, answered by the UI thread
+pub struct RpcCall {
+    pub method: String,
+    pub params: JsonValue,
+    // None for notifications, which get no response
+    id: Option<JsonValue>,
+    reply: Sender<String>,
+}
+
+impl RpcCall {
+    pub fn respond(self, result: Result<JsonValue, RpcError>) {
+        let Some(id) = self.id else { return };
+        // The client may have disconnected meanwhile, nothing to do then
+        let _ = self.reply.send(response(id, result));
+    }
+
+    /// String parameter `key` from a `{"key": ...}` params object
+    pub fn param_str(&self, key: &str) -> Result<&str, RpcError> {
+        self.params
+            .get(key)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("missing string parameter '{}'", key)))
+    }
+}
+
+/// How a cache variable is presented to clients
+pub fn var_json(var: &CacheVarTui) -> JsonValue {
+    JsonValue::Object(vec![
+        ("name".to_string(), JsonValue::String(var.var.name.clone())),
+        ("type".to_string(), JsonValue::String(var.var.typ.cache_name().to_string())),
+        ("value".to_string(), JsonValue::String(var.new_val.clone())),
+        ("saved_value".to_string(), JsonValue::String(var.var.value.clone())),
+        ("modified".to_string(), JsonValue::Bool(var.is_modified())),
+        ("advanced".to_string(), JsonValue::Bool(var.var.advanced)),
+        ("user_modified".to_string(), JsonValue::Bool(var.var.modified)),
+        ("description".to_string(), JsonValue::String(var.var.desc.clone())),
+    ])
+}
+
+fn response(id: JsonValue, result: Result<JsonValue, RpcError>) -> String {
+    let mut fields = vec![
+        ("jsonrpc".to_string(), JsonValue::String("2.0".to_string())),
+        ("id".to_string(), id),
+    ];
+    match result {
+        Ok(value) => fields.push(("result".to_string(), value)),
+        Err(e) => fields.push((
+            "error".to_string(),
+            JsonValue::Object(vec![
+                ("code".to_string(), JsonValue::Number(e.code as f64)),
+                ("message".to_string(), JsonValue::String(e.message)),
+            ]),
+        )),
+    }
+    JsonValue::Object(fields).to_string()
+}
+
+// Reply channels of the connected clients, for notifications
+type Clients = Arc<Mutex<Vec<Sender<String>>>>;
+
+/// Server speaking newline delimited JSON-RPC 2.0, on a unix socket or on
+/// stdin/stdout. Connections are read on background threads, calls are
+/// handed to the UI through `poll` so they act on the same session the user
+/// sees.
+pub struct RpcServer {
+    // Socket to remove once done, None when serving stdio
+    path: Option<PathBuf>,
+    rx: Receiver<RpcCall>,
+    clients: Clients,
+    // stdout writer of --embed, drained before exiting
+    stdout: Option<(Sender<String>, JoinHandle<()>)>,
+}
+
+impl RpcServer {
+    #[cfg(unix)]
+    pub fn listen(path: &Path) -> io::Result<Self> {
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        // A socket left behind by a previous run that nobody listens on anymore
+        if path.exists() && UnixStream::connect(path).is_err() {
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        let (tx, rx) = mpsc::channel();
+        let clients = Clients::default();
+
+        let server_clients = clients.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+                let clients = server_clients.clone();
+                thread::spawn(move || {
+                    let Ok(writer) = stream.try_clone() else { return };
+                    let (reply, _) = connect(writer, &clients);
+                    serve_connection(BufReader::new(stream), reply, tx);
+                });
+            }
+        });
+        Ok(Self { path: Some(path.to_path_buf()), rx, clients, stdout: None })
+    }
+
+    #[cfg(not(unix))]
+    pub fn listen(_path: &Path) -> io::Result<Self> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "--listen needs unix domain sockets"))
+    }
+
+    /// Serve a single client on stdin/stdout. Once stdin is closed,
+    /// `recv_timeout` reports the server as disconnected.
+    pub fn stdio() -> Self {
+        let (tx, rx) = mpsc::channel();
+        let clients = Clients::default();
+        // Registered right away so notifications sent before the first
+        // request still reach the client
+        let (reply, writer) = connect(io::stdout(), &clients);
+        let reader_reply = reply.clone();
+        thread::spawn(move || serve_connection(io::stdin().lock(), reader_reply, tx));
+        Self { path: None, rx, clients, stdout: Some((reply, writer)) }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Calls received since the last poll
+    pub fn poll(&self) -> Vec<RpcCall> {
+        self.rx.try_iter().collect()
+    }
+
+    /// Wait up to `timeout` for the next call
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<RpcCall, RecvTimeoutError> {
+        self.rx.recv_timeout(timeout)
+    }
+
+    /// Send a notification (a request without id) to every connected client
+    pub fn notify(&self, method: &str, params: JsonValue) {
+        let message = JsonValue::Object(vec![
+            ("jsonrpc".to_string(), JsonValue::String("2.0".to_string())),
+            ("method".to_string(), JsonValue::String(method.to_string())),
+            ("params".to_string(), params),
+        ])
+        .to_string();
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.retain(|client| client.send(message.clone()).is_ok());
+        }
+    }
+}
+
+impl Drop for RpcServer {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+        // The process exits right after, make sure the last responses are out
+        if let Some((reply, writer)) = self.stdout.take() {
+            let _ = reply.send(String::new());
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Start writing a client's responses and notifications as the UI produces
+/// them, returns the channel to send them through. An empty line stops the
+/// writer.
+fn connect(mut writer: impl Write + Send + 'static, clients: &Clients) -> (Sender<String>, JoinHandle<()>) {
+    let (reply_tx, reply_rx) = mpsc::channel::<String>();
+    if let Ok(mut clients) = clients.lock() {
+        clients.push(reply_tx.clone());
+    }
+    let handle = thread::spawn(move || {
+        for line in reply_rx {
+            if line.is_empty() || writeln!(writer, "{}", line).and_then(|_| writer.flush()).is_err() {
+                break;
+            }
+        }
+    });
+    (reply_tx, handle)
+}
+
+fn serve_connection(reader: impl BufRead, reply_tx: Sender<String>, calls: Sender<RpcCall>) {
+    for line in reader.lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request = match json::parse(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let _ = reply_tx.send(response(JsonValue::Null, Err(RpcError::new(PARSE_ERROR, e.to_string()))));
+                continue;
+            }
+        };
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(|m| m.as_str()) else {
+            let error = RpcError::new(INVALID_REQUEST, "missing method");
+            let _ = reply_tx.send(response(id.unwrap_or(JsonValue::Null), Err(error)));
+            continue;
+        };
+        let call = RpcCall {
+            method: method.to_string(),
+            params: request.get("params").cloned().unwrap_or(JsonValue::Null),
+            id,
+            reply: reply_tx.clone(),
+        };
+        if calls.send(call).is_err() {
+            // The UI is gone
+            break;
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    io::{self, BufRead, Write},
+    path::Path,
+    process::{Command, ExitStatus},
+};
+
+use cmake_cache::{Cache, CacheVar, VarType};
+use regex::Regex;
+
+use crate::expand::expand;
+use crate::ignore;
+use crate::shell::shell_command;
+
+/// The entries to put into a command's environment, each under its own
+/// name: the ones matching `export`, or all of them when it's empty.
+/// cmake's internal entries and names the environment can't hold are left out.
+pub fn cache_env(vars: &[CacheVar], export: &[Regex]) -> Vec<(String, String)> {
+    vars.iter()
+        .filter(|v| !matches!(v.typ, VarType::Internal | VarType::Static))
+        .filter(|v| !v.name.is_empty() && v.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|v| export.is_empty() || export.iter().any(|p| p.is_match(&v.name)))
+        .map(|v| (v.name.clone(), v.value.clone()))
+        .collect()
+}
+
+/// `arg` with `${NAME}` replaced by the value of the cache entry NAME, the
+/// way CMake would expand it. References to other names are kept.
+pub fn substitute(arg: &str, vars: &[CacheVar]) -> String {
+    expand(arg, &|name| vars.iter().find(|v| v.name == name).map(|v| v.value.clone()))
+}
+
+/// Run `line` with the user's shell in `build_dir`, the cache substituted
+/// and exported, then wait for <Enter> so the output can be read before the
+/// TUI takes the terminal back. Must be called with the terminal restored.
+pub fn run_in_shell(build_dir: &Path, vars: &[CacheVar], line: &str) -> io::Result<ExitStatus> {
+    let line = substitute(line, vars);
+    println!("$ {}", line);
+    let flag = if cfg!(windows) { "/C" } else { "-c" };
+    tracing::info!("running {} {} {:?}", shell_command(), flag, line);
+    let status = Command::new(shell_command())
+        .arg(flag)
+        .arg(&line)
+        .current_dir(build_dir)
+        .envs(cache_env(vars, &[]))
+        .status()?;
+
+    print!("\n{}, press <Enter> to go back to cmake-tui", status);
+    io::stdout().flush()?;
+    io::stdin().lock().read_line(&mut String::new())?;
+    Ok(status)
+}
+
+/// `cmake-tui run`: the command in the build dir with the cache exported
+/// and substituted, as it's on disk. Returns the command's exit code.
+pub fn run_command(build_dir: &Path, export: &[String], command: &[String]) -> Result<i32, String> {
+    let cache = Cache::load(build_dir).map_err(|e| format!("Failed to read the cache of {}: {}", build_dir.display(), e))?;
+    let vars = cache.into_entries();
+    let export = export.iter().map(|p| ignore::compile(p)).collect::<Result<Vec<_>, _>>()?;
+
+    let Some((program, args)) = command.split_first() else {
+        return Err("no command to run".to_string());
+    };
+    let program = substitute(program, &vars);
+    let status = Command::new(&program)
+        .args(args.iter().map(|arg| substitute(arg, &vars)))
+        .current_dir(build_dir)
+        .envs(cache_env(&vars, &export))
+        .status()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    // Killed by a signal, like a shell reports it
+    Ok(status.code().unwrap_or(128))
+}
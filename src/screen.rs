@@ -0,0 +1,338 @@
+use std::{fmt, str::FromStr};
+
+use ratatui::crossterm::event::KeyCode;
+
+use crate::action::Action;
+use crate::cache_editor::VALUE_SCROLL_STEP;
+
+/// What fills the main area. Tabs are switched between, the other screens
+/// open on top of the current one and close again with <Esc>.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Screen {
+    Cache,
+    Targets,
+    Tests,
+    // The log of the last task over the whole main area
+    Log,
+    // Pending edits, old and new value
+    Diff,
+    // Every key of the screen it was opened from
+    Help,
+}
+
+impl Screen {
+    pub const TABS: [Screen; 3] = [Screen::Cache, Screen::Targets, Screen::Tests];
+
+    pub fn is_tab(self) -> bool {
+        Screen::TABS.contains(&self)
+    }
+
+    /// Keys of the screen, without the global ones
+    pub fn bindings(self) -> &'static [Binding] {
+        match self {
+            Screen::Cache => CACHE_KEYS,
+            Screen::Targets => TARGETS_KEYS,
+            Screen::Tests => TESTS_KEYS,
+            Screen::Log => LOG_KEYS,
+            Screen::Diff => DIFF_KEYS,
+            Screen::Help => HELP_KEYS,
+        }
+    }
+
+    /// The binding of `key` on this screen, the screen's own before the global ones
+    pub fn binding(self, key: KeyCode) -> Option<&'static Binding> {
+        self.bindings().iter().chain(GLOBAL_KEYS).find(|b| b.keys.contains(&key))
+    }
+
+    /// Two key sequences of the screen
+    pub fn chords(self) -> &'static [Chord] {
+        match self {
+            Screen::Cache => CACHE_CHORDS,
+            _ => LIST_CHORDS,
+        }
+    }
+
+    /// Keys taking the next one as their argument
+    pub fn prefixes(self) -> &'static [Prefix] {
+        match self {
+            Screen::Cache => CACHE_PREFIXES,
+            _ => &[],
+        }
+    }
+
+    pub fn prefix(self, key: KeyCode) -> Option<&'static Prefix> {
+        self.prefixes().iter().find(|p| p.key == key)
+    }
+
+    /// Whether `key` is the first of a chord or a prefix, and has to wait
+    /// for the next
+    pub fn starts_chord(self, key: KeyCode) -> bool {
+        self.chords().iter().any(|c| c.keys[0] == key) || self.prefix(key).is_some()
+    }
+
+    pub fn chord(self, first: KeyCode, second: KeyCode) -> Option<&'static Chord> {
+        self.chords().iter().find(|c| c.keys == [first, second])
+    }
+
+    /// Every binding of the screen, for the help screen.
+    /// Global keys the screen binds itself are left out.
+    pub fn all_bindings(self) -> impl Iterator<Item = &'static Binding> {
+        let own = self.bindings();
+        own.iter().chain(GLOBAL_KEYS.iter().filter(|g| !g.keys.iter().any(|k| own.iter().any(|b| b.keys.contains(k)))))
+    }
+}
+
+impl FromStr for Screen {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cache" => Ok(Screen::Cache),
+            "targets" => Ok(Screen::Targets),
+            "tests" => Ok(Screen::Tests),
+            "log" => Ok(Screen::Log),
+            "diff" => Ok(Screen::Diff),
+            "help" => Ok(Screen::Help),
+            _ => Err(format!("unknown screen '{}', expected cache, targets, tests, log, diff or help", s)),
+        }
+    }
+}
+
+impl fmt::Display for Screen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Screen::Cache => "cache",
+            Screen::Targets => "targets",
+            Screen::Tests => "tests",
+            Screen::Log => "log",
+            Screen::Diff => "diff",
+            Screen::Help => "help",
+        };
+        f.pad(s)
+    }
+}
+
+/// Keys resolving to an action on a screen
+pub struct Binding {
+    pub keys: &'static [KeyCode],
+    pub action: Action,
+    pub help: &'static str,
+}
+
+impl Binding {
+    pub fn keys_label(&self) -> String {
+        self.keys.iter().map(key_label).collect::<Vec<_>>().join("/")
+    }
+}
+
+/// Two keys pressed one after the other, like vim's `gg`. The first key
+/// alone still does what it's bound to, once no second key follows.
+pub struct Chord {
+    pub keys: [KeyCode; 2],
+    pub action: Action,
+    pub help: &'static str,
+}
+
+impl Chord {
+    pub fn keys_label(&self) -> String {
+        self.keys.iter().map(key_label).collect()
+    }
+}
+
+/// A key taking the letter typed after it, like vim's `ma`
+pub struct Prefix {
+    pub key: KeyCode,
+    pub action: fn(char) -> Action,
+    pub help: &'static str,
+}
+
+impl Prefix {
+    pub fn keys_label(&self) -> String {
+        format!("{}{{a-z}}", key_label(&self.key))
+    }
+}
+
+pub fn key_label(key: &KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "<Space>".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::PageDown => "<PgDn>".to_string(),
+        KeyCode::PageUp => "<PgUp>".to_string(),
+        key => format!("<{:?}>", key),
+    }
+}
+
+/// The screens open, the tab at the bottom
+pub struct ScreenStack {
+    screens: Vec<Screen>,
+}
+
+impl ScreenStack {
+    pub fn new() -> Self {
+        Self { screens: vec![Screen::Cache] }
+    }
+
+    pub fn current(&self) -> Screen {
+        self.screens.last().copied().unwrap_or(Screen::Cache)
+    }
+
+    pub fn tab(&self) -> Screen {
+        self.screens.first().copied().unwrap_or(Screen::Cache)
+    }
+
+    /// Switch to a tab, closing the screens on top, or open a screen on top
+    pub fn open(&mut self, screen: Screen) {
+        if screen.is_tab() {
+            self.screens = vec![screen];
+        } else if self.current() != screen {
+            self.screens.retain(|s| *s != screen);
+            self.screens.push(screen);
+        }
+    }
+
+    /// Close the current screen, false when it's the tab
+    pub fn close(&mut self) -> bool {
+        match self.screens.len() > 1 {
+            true => self.screens.pop().is_some(),
+            false => false,
+        }
+    }
+
+    pub fn next_tab(&self) -> Screen {
+        let idx = Screen::TABS.iter().position(|s| *s == self.tab()).unwrap_or(0);
+        Screen::TABS[(idx + 1) % Screen::TABS.len()]
+    }
+}
+
+// Keys working the same on every screen unless the screen binds them itself
+const GLOBAL_KEYS: &[Binding] = &[
+    Binding { keys: &[KeyCode::Char(':')], action: Action::StartCommand, help: "enter a command" },
+    Binding { keys: &[KeyCode::Char('c')], action: Action::Configure, help: "configure" },
+    Binding { keys: &[KeyCode::Char('b')], action: Action::Build, help: "build" },
+    Binding { keys: &[KeyCode::Char('L')], action: Action::ToggleLog, help: "toggle the log" },
+    Binding { keys: &[KeyCode::Tab], action: Action::FocusLog, help: "focus the log" },
+    Binding { keys: &[KeyCode::Char('P')], action: Action::ShowProblems, help: "list problems" },
+    Binding { keys: &[KeyCode::Char('v')], action: Action::NextTab, help: "switch tabs" },
+    Binding { keys: &[KeyCode::Char('?')], action: Action::ShowScreen(Screen::Help), help: "list all keys" },
+    Binding { keys: &[KeyCode::F(11)], action: Action::ToggleDebugOverlay, help: "toggle the debug overlay" },
+    Binding { keys: &[KeyCode::Char('!')], action: Action::OpenShell, help: "open a shell in the build dir" },
+    Binding { keys: &[KeyCode::Char('w')], action: Action::Save, help: "save" },
+    Binding { keys: &[KeyCode::Char('q')], action: Action::Quit, help: "quit" },
+];
+
+const LIST_CHORDS: &[Chord] = &[Chord { keys: [KeyCode::Char('g'), KeyCode::Char('g')], action: Action::SelectFirst, help: "go to the top, [count]gg to that row" }];
+
+const CACHE_CHORDS: &[Chord] = &[
+    Chord { keys: [KeyCode::Char('g'), KeyCode::Char('g')], action: Action::SelectFirst, help: "go to the top, [count]gg to that row" },
+    Chord { keys: [KeyCode::Char('d'), KeyCode::Char('d')], action: Action::DeleteEntry, help: "delete the entry" },
+    Chord { keys: [KeyCode::Char('y'), KeyCode::Char('y')], action: Action::DuplicateEntry, help: "duplicate the entry" },
+];
+
+const CACHE_PREFIXES: &[Prefix] = &[
+    Prefix { key: KeyCode::Char('m'), action: Action::SetMark, help: "mark the entry" },
+    Prefix { key: KeyCode::Char('\''), action: Action::JumpToMark, help: "go to the marked entry, '' back from a jump (Ctrl-o back, Ctrl-i forward)" },
+];
+
+const CACHE_KEYS: &[Binding] = &[
+    Binding { keys: &[KeyCode::Char('j'), KeyCode::Down], action: Action::SelectNext(1), help: "move down, [count]j to jump" },
+    Binding { keys: &[KeyCode::Char('k'), KeyCode::Up], action: Action::SelectPrevious(1), help: "move up" },
+    Binding { keys: &[KeyCode::Char('g'), KeyCode::Home], action: Action::SelectFirst, help: "go to the top, [count]g to that row" },
+    Binding { keys: &[KeyCode::Char('G'), KeyCode::End], action: Action::SelectLast, help: "go to the bottom" },
+    Binding { keys: &[KeyCode::Char(' ')], action: Action::CycleValue, help: "cycle the value" },
+    Binding { keys: &[KeyCode::Enter], action: Action::EditValue, help: "edit the value" },
+    Binding { keys: &[KeyCode::Char('R')], action: Action::ResetToDefault, help: "reset to the project default" },
+    Binding { keys: &[KeyCode::Char('.')], action: Action::Repeat, help: "repeat the last change on the entry" },
+    Binding { keys: &[KeyCode::Char('/')], action: Action::StartSearch, help: "search (↑↓ for earlier searches)" },
+    Binding { keys: &[KeyCode::Char('n')], action: Action::NextSearchResult, help: "go to the next match" },
+    Binding { keys: &[KeyCode::Char('N')], action: Action::PreviousSearchResult, help: "go to the previous match" },
+    Binding { keys: &[KeyCode::Char('J')], action: Action::ScrollDescription(1), help: "scroll the description down" },
+    Binding { keys: &[KeyCode::Char('K')], action: Action::ScrollDescription(-1), help: "scroll the description up" },
+    Binding { keys: &[KeyCode::Char('<')], action: Action::ScrollValue(-VALUE_SCROLL_STEP), help: "scroll the value left (also Shift+←)" },
+    Binding { keys: &[KeyCode::Char('>')], action: Action::ScrollValue(VALUE_SCROLL_STEP), help: "scroll the value right (also Shift+→)" },
+    Binding { keys: &[KeyCode::Char('o')], action: Action::ShowValue, help: "inspect the whole value" },
+    Binding { keys: &[KeyCode::Char('t')], action: Action::ToggleAdvanced, help: "toggle advanced" },
+    Binding { keys: &[KeyCode::Char('i')], action: Action::ToggleInternal, help: "toggle internal" },
+    Binding { keys: &[KeyCode::Char('z')], action: Action::ToggleIgnored, help: "toggle ignored" },
+    Binding { keys: &[KeyCode::Char('x')], action: Action::ToggleExpanded, help: "expand references" },
+    Binding { keys: &[KeyCode::Char('f')], action: Action::SetFilter(None), help: "clear the filter" },
+    Binding { keys: &[KeyCode::Char('#')], action: Action::CycleRowNumbers, help: "toggle row numbers" },
+    Binding { keys: &[KeyCode::Char('I')], action: Action::Install, help: "install" },
+    Binding { keys: &[KeyCode::Char('B')], action: Action::ShowBuildTypes, help: "pick the build type" },
+    Binding { keys: &[KeyCode::Char('C')], action: Action::ToggleCompileCommands, help: "export compile_commands.json" },
+    Binding { keys: &[KeyCode::Char('T')], action: Action::ShowToolchain, help: "show the toolchain" },
+    Binding { keys: &[KeyCode::Char('$')], action: Action::ShowEnv, help: "show and override the configure environment" },
+    Binding { keys: &[KeyCode::Char('F')], action: Action::FreshConfigure, help: "configure from a fresh cache" },
+    Binding { keys: &[KeyCode::Char('U')], action: Action::ShowDiskUsage, help: "show the build dir's disk usage" },
+    Binding { keys: &[KeyCode::Char('D')], action: Action::ShowDuplicates, help: "list duplicate paths" },
+    Binding { keys: &[KeyCode::Char('d')], action: Action::ShowScreen(Screen::Diff), help: "review pending edits" },
+    Binding { keys: &[KeyCode::Char('e')], action: Action::OpenEditor, help: "edit in $EDITOR" },
+    Binding { keys: &[KeyCode::Char('u')], action: Action::ShowUsages, help: "show where it's used" },
+    Binding { keys: &[KeyCode::Char('E')], action: Action::Explain, help: "trace where configure sets it" },
+    Binding { keys: &[KeyCode::Char('l')], action: Action::OpenListEditor, help: "edit as a list" },
+    Binding { keys: &[KeyCode::Char('a')], action: Action::NewEntry, help: "add an entry" },
+    Binding { keys: &[KeyCode::Char('y')], action: Action::DuplicateEntry, help: "duplicate the entry" },
+    Binding { keys: &[KeyCode::Char('X')], action: Action::DeleteEntry, help: "delete the entry" },
+    Binding { keys: &[KeyCode::Char('p')], action: Action::TogglePin, help: "pin the entry to the top" },
+    Binding { keys: &[KeyCode::Char('S')], action: Action::ShowSnapshots, help: "list snapshots" },
+    Binding { keys: &[KeyCode::Char('H')], action: Action::ShowHistory, help: "show the change history" },
+    Binding { keys: &[KeyCode::Char('Q')], action: Action::ShowQuickSettings, help: "show quick settings" },
+    Binding { keys: &[KeyCode::Char('V')], action: Action::ShowAbout, help: "show version info" },
+    Binding { keys: &[KeyCode::Char('q'), KeyCode::Esc], action: Action::Quit, help: "quit" },
+];
+
+const TARGETS_KEYS: &[Binding] = &[
+    Binding { keys: &[KeyCode::Char('j'), KeyCode::Down], action: Action::SelectNext(1), help: "move down" },
+    Binding { keys: &[KeyCode::Char('k'), KeyCode::Up], action: Action::SelectPrevious(1), help: "move up" },
+    Binding { keys: &[KeyCode::Char('g'), KeyCode::Home], action: Action::SelectFirst, help: "go to the top" },
+    Binding { keys: &[KeyCode::Char('G'), KeyCode::End], action: Action::SelectLast, help: "go to the bottom" },
+    Binding { keys: &[KeyCode::Enter], action: Action::BuildSelectedTarget, help: "build the target" },
+    Binding { keys: &[KeyCode::Char('/')], action: Action::StartSearch, help: "filter" },
+    Binding { keys: &[KeyCode::Char('f')], action: Action::SetFilter(None), help: "clear the filter" },
+    Binding { keys: &[KeyCode::Char('q'), KeyCode::Esc], action: Action::Quit, help: "quit" },
+];
+
+const TESTS_KEYS: &[Binding] = &[
+    Binding { keys: &[KeyCode::Char('j'), KeyCode::Down], action: Action::SelectNext(1), help: "move down" },
+    Binding { keys: &[KeyCode::Char('k'), KeyCode::Up], action: Action::SelectPrevious(1), help: "move up" },
+    Binding { keys: &[KeyCode::Char('g'), KeyCode::Home], action: Action::SelectFirst, help: "go to the top" },
+    Binding { keys: &[KeyCode::Char('G'), KeyCode::End], action: Action::SelectLast, help: "go to the bottom" },
+    Binding { keys: &[KeyCode::Enter], action: Action::RunSelectedTest, help: "run the test" },
+    Binding { keys: &[KeyCode::Char('r')], action: Action::RunTests, help: "run all tests" },
+    Binding { keys: &[KeyCode::Char('/')], action: Action::StartSearch, help: "filter" },
+    Binding { keys: &[KeyCode::Char('f')], action: Action::SetFilter(None), help: "clear the filter" },
+    Binding { keys: &[KeyCode::Char('q'), KeyCode::Esc], action: Action::Quit, help: "quit" },
+];
+
+const LOG_KEYS: &[Binding] = &[
+    Binding { keys: &[KeyCode::Char('j'), KeyCode::Down], action: Action::SelectNext(1), help: "scroll down" },
+    Binding { keys: &[KeyCode::Char('k'), KeyCode::Up], action: Action::SelectPrevious(1), help: "scroll up" },
+    Binding { keys: &[KeyCode::PageDown, KeyCode::Char(' ')], action: Action::PageDown, help: "page down" },
+    Binding { keys: &[KeyCode::PageUp], action: Action::PageUp, help: "page up" },
+    Binding { keys: &[KeyCode::Char('g'), KeyCode::Home], action: Action::SelectFirst, help: "go to the top" },
+    Binding { keys: &[KeyCode::Char('G'), KeyCode::End], action: Action::SelectLast, help: "go to the bottom" },
+    Binding { keys: &[KeyCode::Char(']')], action: Action::NextError, help: "go to the next error" },
+    Binding { keys: &[KeyCode::Char('[')], action: Action::PreviousError, help: "go to the previous error" },
+    Binding { keys: &[KeyCode::Char('/')], action: Action::StartSearch, help: "search" },
+    Binding { keys: &[KeyCode::Char('n')], action: Action::NextSearchResult, help: "go to the next match" },
+    Binding { keys: &[KeyCode::Char('N')], action: Action::PreviousSearchResult, help: "go to the previous match" },
+    Binding { keys: &[KeyCode::Char('z')], action: Action::ShowScreen(Screen::Log), help: "show it fullscreen" },
+    Binding { keys: &[KeyCode::Tab, KeyCode::Esc], action: Action::LeaveLog, help: "go back" },
+];
+
+const DIFF_KEYS: &[Binding] = &[
+    Binding { keys: &[KeyCode::Char('j'), KeyCode::Down], action: Action::SelectNext(1), help: "move down" },
+    Binding { keys: &[KeyCode::Char('k'), KeyCode::Up], action: Action::SelectPrevious(1), help: "move up" },
+    Binding { keys: &[KeyCode::Char('g'), KeyCode::Home], action: Action::SelectFirst, help: "go to the top" },
+    Binding { keys: &[KeyCode::Char('G'), KeyCode::End], action: Action::SelectLast, help: "go to the bottom" },
+    Binding { keys: &[KeyCode::Enter], action: Action::ShowEntry, help: "show the entry in the cache" },
+    Binding { keys: &[KeyCode::Char('q'), KeyCode::Esc], action: Action::CloseScreen, help: "close" },
+];
+
+const HELP_KEYS: &[Binding] = &[
+    Binding { keys: &[KeyCode::Char('j'), KeyCode::Down], action: Action::SelectNext(1), help: "scroll down" },
+    Binding { keys: &[KeyCode::Char('k'), KeyCode::Up], action: Action::SelectPrevious(1), help: "scroll up" },
+    Binding { keys: &[KeyCode::Char('g'), KeyCode::Home], action: Action::SelectFirst, help: "go to the top" },
+    Binding { keys: &[KeyCode::Char('q'), KeyCode::Esc], action: Action::CloseScreen, help: "close" },
+];
@@ -0,0 +1,42 @@
+/// The names of the cache entries lowercased and sorted, so the ones
+/// starting with what's typed into the search are found by binary search
+/// instead of lowercasing every name on each key press
+#[derive(Clone, Default)]
+pub struct NameIndex {
+    // Lowercase name and the entry's index
+    names: Vec<(String, usize)>,
+}
+
+impl NameIndex {
+    /// Index `names`, the entry indices being their positions
+    pub fn new<'a>(names: impl Iterator<Item = &'a str>) -> Self {
+        let mut names: Vec<(String, usize)> = names.enumerate().map(|(idx, name)| (name.to_lowercase(), idx)).collect();
+        names.sort();
+        Self { names }
+    }
+
+    /// Add the entry `name` inserted at `idx`, the ones after it moving up
+    pub fn insert(&mut self, name: &str, idx: usize) {
+        for (_, i) in self.names.iter_mut().filter(|(_, i)| *i >= idx) {
+            *i += 1;
+        }
+        let entry = (name.to_lowercase(), idx);
+        let pos = self.names.partition_point(|e| *e < entry);
+        self.names.insert(pos, entry);
+    }
+
+    /// Drop the entry at `idx`, the ones after it moving down
+    pub fn remove(&mut self, idx: usize) {
+        self.names.retain(|(_, i)| *i != idx);
+        for (_, i) in self.names.iter_mut().filter(|(_, i)| *i > idx) {
+            *i -= 1;
+        }
+    }
+
+    /// Indices of the entries whose name starts with `prefix`, ignoring case
+    pub fn with_prefix(&self, prefix: &str) -> impl Iterator<Item = usize> + '_ {
+        let prefix = prefix.to_lowercase();
+        let start = self.names.partition_point(|(name, _)| name.as_str() < prefix.as_str());
+        self.names[start..].iter().take_while(move |(name, _)| name.starts_with(&prefix)).map(|(_, idx)| *idx)
+    }
+}
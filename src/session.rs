@@ -0,0 +1,110 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use crate::action::{Filter, SortOrder};
+use crate::json::{self, JsonValue};
+
+const SESSION_FILE: &str = "session.json";
+
+/// View state restored when the same build dir is opened again
+#[derive(Debug, PartialEq, Clone)]
+pub struct Session {
+    pub selected: Option<String>,
+    pub filter: Option<Filter>,
+    pub sort: SortOrder,
+    pub show_advanced: bool,
+    pub show_internal: bool,
+    // Previous `/` queries, oldest first
+    pub search_history: Vec<String>,
+    // Names of the cache entries, to tell what a configure since added or
+    // removed. None for a session saved before they were kept.
+    pub entries: Option<Vec<String>>,
+    // `m{a-z}` marks, by their letter
+    pub marks: BTreeMap<char, String>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            selected: None,
+            filter: None,
+            sort: SortOrder::Name,
+            show_advanced: false,
+            show_internal: false,
+            search_history: Vec::new(),
+            entries: None,
+            marks: BTreeMap::new(),
+        }
+    }
+}
+
+impl Session {
+    /// Read the session saved in `sidecar_dir`. Missing or unreadable state
+    /// just means starting fresh, so errors aren't reported.
+    pub fn load(sidecar_dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(sidecar_dir.join(SESSION_FILE)).ok()?;
+        let value = json::parse(&content).ok()?;
+        let defaults = Self::default();
+
+        Some(Self {
+            selected: value.get("selected").and_then(|v| v.as_str()).map(str::to_string),
+            filter: value.get("filter").and_then(|v| v.as_str()).and_then(|s| s.parse().ok()),
+            sort: value
+                .get("sort")
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.sort),
+            show_advanced: value
+                .get("show_advanced")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.show_advanced),
+            show_internal: value
+                .get("show_internal")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.show_internal),
+            search_history: value
+                .get("search_history")
+                .and_then(|v| v.as_array())
+                .map(|queries| queries.iter().filter_map(|q| q.as_str()).map(str::to_string).collect())
+                .unwrap_or_default(),
+            entries: value
+                .get("entries")
+                .and_then(|v| v.as_array())
+                .map(|names| names.iter().filter_map(|n| n.as_str()).map(str::to_string).collect()),
+            marks: match value.get("marks") {
+                Some(JsonValue::Object(marks)) => marks
+                    .iter()
+                    .filter_map(|(letter, name)| Some((letter.chars().next()?, name.as_str()?.to_string())))
+                    .collect(),
+                _ => BTreeMap::new(),
+            },
+        })
+    }
+
+    pub fn save(&self, sidecar_dir: &Path) -> io::Result<()> {
+        let opt_str = |s: Option<String>| s.map(JsonValue::String).unwrap_or(JsonValue::Null);
+        let value = JsonValue::Object(vec![
+            ("selected".to_string(), opt_str(self.selected.clone())),
+            ("filter".to_string(), opt_str(self.filter.map(|f| f.to_string()))),
+            ("sort".to_string(), JsonValue::String(self.sort.to_string())),
+            ("show_advanced".to_string(), JsonValue::Bool(self.show_advanced)),
+            ("show_internal".to_string(), JsonValue::Bool(self.show_internal)),
+            (
+                "search_history".to_string(),
+                JsonValue::Array(self.search_history.iter().cloned().map(JsonValue::String).collect()),
+            ),
+            (
+                "entries".to_string(),
+                self.entries
+                    .as_ref()
+                    .map_or(JsonValue::Null, |names| JsonValue::Array(names.iter().cloned().map(JsonValue::String).collect())),
+            ),
+            (
+                "marks".to_string(),
+                JsonValue::Object(
+                    self.marks.iter().map(|(letter, name)| (letter.to_string(), JsonValue::String(name.clone()))).collect(),
+                ),
+            ),
+        ]);
+        fs::write(sidecar_dir.join(SESSION_FILE), format!("{}\n", value))
+    }
+}
@@ -0,0 +1,43 @@
+use std::{
+    env, io,
+    path::Path,
+    process::{Command, ExitStatus},
+};
+
+/// The user's interactive shell: $SHELL, or what the platform falls back to
+pub fn shell_command() -> String {
+    env::var("SHELL")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .or_else(|| cfg!(windows).then(|| env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string())))
+        .unwrap_or_else(|| "sh".to_string())
+}
+
+/// Run the shell in `dir` until it exits. Must be called with the terminal
+/// restored to normal mode.
+///
+/// CMAKE_TUI_BUILD_DIR is set for prompts and scripts that want to know
+/// they're inside cmake-tui.
+pub fn run_shell(dir: &Path) -> io::Result<ExitStatus> {
+    println!("cmake-tui is suspended, exit the shell to get back to it");
+    let absolute = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    tracing::info!("running {} in {}", shell_command(), dir.display());
+    Command::new(shell_command()).current_dir(dir).env("CMAKE_TUI_BUILD_DIR", absolute).status()
+}
+
+/// Stop the process the way Ctrl-z does outside raw mode, returning once
+/// it's resumed with `fg`. Must be called with the terminal restored.
+#[cfg(unix)]
+pub fn suspend() -> io::Result<()> {
+    // Raw mode turned off the terminal's own Ctrl-z, so the signal is sent by hand
+    let status = Command::new("kill").arg("-TSTP").arg(std::process::id().to_string()).status()?;
+    match status.success() {
+        true => Ok(()),
+        false => Err(io::Error::other("kill -TSTP failed")),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn suspend() -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "no job control on this platform"))
+}
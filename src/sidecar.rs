@@ -0,0 +1,92 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::config::{Config, SidecarLocation, state_dir};
+use crate::vcs::{self, Vcs};
+
+const SIDECAR_DIR_NAME: &str = ".cmake-tui";
+
+/// Directory holding the files cmake-tui keeps for a build dir (session
+/// state, snapshots, history, backups).
+pub struct Sidecar {
+    pub dir: PathBuf,
+    // Whether `dir` lives inside the build tree
+    pub in_tree: bool,
+}
+
+/// Per directory folder under the state dir, for a build dir or a project's
+/// source dir. The path is flattened into the name so it stays
+/// recognizable, with a hash to keep it unique.
+pub(crate) fn state_dir_for(dir: &Path) -> Option<PathBuf> {
+    let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    let path = dir.to_string_lossy();
+
+    // FNV-1a, stable across runs and Rust versions unlike DefaultHasher
+    let hash = path.bytes().fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+    let readable: String = path
+        .chars()
+        .rev()
+        .take(40)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect();
+
+    Some(state_dir()?.join(format!("{}-{:08x}", readable.trim_matches('_'), hash as u32)))
+}
+
+impl Sidecar {
+    pub fn resolve(build_dir: &Path, config: &Config) -> Self {
+        let state = state_dir_for(build_dir);
+        let in_tree = Self { dir: build_dir.join(SIDECAR_DIR_NAME), in_tree: true };
+
+        match (config.sidecar_location, state) {
+            (SidecarLocation::State, Some(dir)) => Self { dir, in_tree: false },
+            // Data moved out of the tree earlier keeps being used from there
+            (SidecarLocation::Build, Some(dir)) if dir.exists() && !in_tree.dir.exists() => {
+                Self { dir, in_tree: false }
+            }
+            _ => in_tree,
+        }
+    }
+
+    /// Create the directory. Returns the VCS whose working tree it was
+    /// created in when that tree doesn't ignore it yet, so the caller can
+    /// offer to fix that.
+    pub fn create(&self) -> io::Result<Option<Box<dyn Vcs>>> {
+        if self.dir.exists() {
+            return Ok(None);
+        }
+        fs::create_dir_all(&self.dir)?;
+
+        if !self.in_tree {
+            return Ok(None);
+        }
+        let dir = fs::canonicalize(&self.dir)?;
+        Ok(vcs::detect(&dir).filter(|vcs| !vcs.is_ignored(&dir)))
+    }
+
+    /// Move everything to the state directory, out of any working tree
+    pub fn move_out_of_tree(&mut self, build_dir: &Path) -> io::Result<()> {
+        let Some(target) = state_dir_for(build_dir) else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "no state directory ($XDG_STATE_HOME or $HOME) available"));
+        };
+        fs::create_dir_all(&target)?;
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let dest = target.join(entry.file_name());
+            if fs::rename(entry.path(), &dest).is_err() {
+                // Different filesystem, fall back to copying
+                fs::copy(entry.path(), &dest)?;
+                fs::remove_file(entry.path())?;
+            }
+        }
+        fs::remove_dir(&self.dir)?;
+        self.dir = target;
+        self.in_tree = false;
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, GREEN, RED, SLATE},
+    },
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph,
+        StatefulWidget, Widget,
+    },
+};
+
+use cmake_cache::CacheLock;
+use cmake_cache::parse_cache_content;
+use crate::json::{self, JsonValue};
+use crate::text_input::TextInput;
+use crate::timestamp;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+const SNAPSHOTS_DIR: &str = "snapshots";
+const CACHE_FILE: &str = "CMakeCache.txt";
+const META_FILE: &str = "meta.json";
+
+/// Full copy of CMakeCache.txt taken at some point, kept in the sidecar dir
+pub struct Snapshot {
+    pub name: String,
+    // Unix time the snapshot was taken
+    pub created: u64,
+    dir: PathBuf,
+}
+
+/// A variable whose value differs between a snapshot and the current cache.
+/// `None` means the variable doesn't exist on that side.
+pub struct SnapshotChange {
+    pub name: String,
+    pub snapshot: Option<String>,
+    pub current: Option<String>,
+}
+
+impl Snapshot {
+    /// Copy the build dir's current cache into a new snapshot
+    pub fn create(sidecar_dir: &Path, build_dir: &Path, name: &str) -> io::Result<Self> {
+        let content = fs::read_to_string(build_dir.join(CACHE_FILE))?;
+        let created = timestamp::now();
+
+        // Several snapshots may be taken within the same second
+        let root = sidecar_dir.join(SNAPSHOTS_DIR);
+        let mut id = created;
+        while root.join(id.to_string()).exists() {
+            id += 1;
+        }
+        let dir = root.join(id.to_string());
+        fs::create_dir_all(&dir)?;
+
+        fs::write(dir.join(CACHE_FILE), content)?;
+        let meta = JsonValue::Object(vec![
+            ("name".to_string(), JsonValue::String(name.to_string())),
+            ("created".to_string(), JsonValue::Number(created as f64)),
+        ]);
+        fs::write(dir.join(META_FILE), format!("{}\n", meta))?;
+        Ok(Self { name: name.to_string(), created, dir })
+    }
+
+    /// Snapshots in `sidecar_dir`, newest first
+    pub fn list(sidecar_dir: &Path) -> Vec<Self> {
+        let Ok(entries) = fs::read_dir(sidecar_dir.join(SNAPSHOTS_DIR)) else { return Vec::new() };
+        let mut snapshots: Vec<Self> = entries
+            .flatten()
+            .filter_map(|entry| Self::open(entry.path()))
+            .collect();
+        snapshots.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| b.dir.cmp(&a.dir)));
+        snapshots
+    }
+
+    fn open(dir: PathBuf) -> Option<Self> {
+        if !dir.join(CACHE_FILE).is_file() {
+            return None;
+        }
+        let meta = fs::read_to_string(dir.join(META_FILE)).ok().and_then(|m| json::parse(&m).ok());
+        let id = dir.file_name()?.to_str()?.parse::<u64>().ok();
+        let created = meta
+            .as_ref()
+            .and_then(|m| m.get("created"))
+            .and_then(|c| c.as_u64())
+            .or(id)
+            .unwrap_or(0);
+        let name = meta
+            .as_ref()
+            .and_then(|m| m.get("name"))
+            .and_then(|n| n.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| "(unnamed)".to_string());
+        Some(Self { name, created, dir })
+    }
+
+    pub fn content(&self) -> io::Result<String> {
+        fs::read_to_string(self.dir.join(CACHE_FILE))
+    }
+
+    /// Put the snapshot's cache back in place of the build dir's one
+    pub fn restore(&self, build_dir: &Path) -> io::Result<()> {
+        let _lock = CacheLock::acquire(build_dir)?;
+        let tmp_path = build_dir.join("CMakeCache.txt.tmp");
+        fs::write(&tmp_path, self.content()?)?;
+        fs::rename(&tmp_path, build_dir.join(CACHE_FILE))
+    }
+
+    pub fn delete(&self) -> io::Result<()> {
+        fs::remove_dir_all(&self.dir)
+    }
+
+    /// Variables whose value differs from the build dir's current cache
+    pub fn diff(&self, build_dir: &Path) -> io::Result<Vec<SnapshotChange>> {
+        let values = |content: &str| -> io::Result<BTreeMap<String, String>> {
+            Ok(parse_cache_content(content).into_iter().map(|v| (v.name, v.value)).collect())
+        };
+        let snapshot = values(&self.content()?)?;
+        let mut current = values(&fs::read_to_string(build_dir.join(CACHE_FILE))?)?;
+
+        let mut changes = Vec::new();
+        for (name, old) in snapshot {
+            match current.remove(&name) {
+                Some(new) if new == old => {}
+                new => changes.push(SnapshotChange { name, snapshot: Some(old), current: new }),
+            }
+        }
+        changes.extend(current.into_iter().map(|(name, new)| SnapshotChange {
+            name,
+            snapshot: None,
+            current: Some(new),
+        }));
+        changes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(changes)
+    }
+}
+
+/// Popup listing the snapshots of the build dir
+pub struct SnapshotsPane {
+    pub snapshots: Vec<Snapshot>,
+    pub visible: bool,
+    // Name being typed for a new snapshot
+    pub name_input: Option<TextInput>,
+    // Differences of the selected snapshot to the current cache, once asked for
+    pub diff: Option<Vec<SnapshotChange>>,
+    state: ListState,
+}
+
+impl SnapshotsPane {
+    pub fn new() -> Self {
+        Self {
+            snapshots: Vec::new(),
+            visible: false,
+            name_input: None,
+            diff: None,
+            state: ListState::default(),
+        }
+    }
+
+    pub fn set_snapshots(&mut self, snapshots: Vec<Snapshot>) {
+        let selected = self.state.selected().unwrap_or(0);
+        self.snapshots = snapshots;
+        self.diff = None;
+        self.state.select(if self.snapshots.is_empty() {
+            None
+        } else {
+            Some(selected.min(self.snapshots.len() - 1))
+        });
+    }
+
+    pub fn selected(&self) -> Option<&Snapshot> {
+        self.snapshots.get(self.state.selected()?)
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+        self.diff = None;
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+        self.diff = None;
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let hint = if self.name_input.is_some() {
+            " <Enter> take snapshot, <Esc> cancel "
+        } else {
+            " j/k select, s new snapshot, d diff, r restore, x delete, <Esc> close "
+        };
+        let block = Block::new()
+            .title(Line::raw(format!(" Snapshots: {} ", self.snapshots.len())).centered().bold())
+            .title_bottom(Line::raw(hint).centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [input_area, list_area, diff_area] = Layout::vertical([
+            Constraint::Length(if self.name_input.is_some() { 2 } else { 0 }),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+
+        if let Some(input) = &self.name_input {
+            Paragraph::new(input.line("Snapshot name: ")).fg(TEXT_FG).render(input_area, buf);
+        }
+
+        let items: Vec<ListItem> = self
+            .snapshots
+            .iter()
+            .map(|s| ListItem::new(format!("{}  {}", timestamp::format(s.created), s.name)))
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        let lines: Vec<Line> = match &self.diff {
+            None if self.snapshots.is_empty() => vec![Line::raw("No snapshots yet, press s to take one")],
+            None => vec![Line::raw("Press d to compare the selected snapshot with the current cache")],
+            Some(changes) if changes.is_empty() => vec![Line::raw("Same values as the current cache")],
+            Some(changes) => changes.iter().map(change_line).collect(),
+        };
+        Paragraph::new(lines)
+            .block(Block::new().borders(Borders::TOP).border_style(Style::new().fg(BLUE.c500)))
+            .fg(TEXT_FG)
+            .render(diff_area, buf);
+    }
+}
+
+fn change_line(change: &SnapshotChange) -> Line<'static> {
+    let old = Span::raw(change.snapshot.clone().unwrap_or_else(|| "(not set)".to_string())).fg(RED.c400);
+    let new = Span::raw(change.current.clone().unwrap_or_else(|| "(not set)".to_string())).fg(GREEN.c400);
+    Line::from(vec![Span::raw(format!("  {}: ", change.name)), old, Span::raw(" -> "), new])
+}
@@ -0,0 +1,215 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+// Stop walking huge source trees after this many CMake files
+const MAX_SCANNED_FILES: usize = 5_000;
+
+/// Collect all CMakeLists.txt and *.cmake files below `source_dir`, skipping
+/// VCS metadata, hidden directories and anything that looks like a build tree.
+pub fn find_cmake_files(source_dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![source_dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        if dir != source_dir && dir.join("CMakeCache.txt").exists() {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&dir) else { continue };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            let Ok(file_type) = entry.file_type() else { continue };
+
+            if file_type.is_dir() {
+                if !name.starts_with('.') {
+                    dirs.push(path);
+                }
+            } else if name == "CMakeLists.txt" || name.ends_with(".cmake") {
+                files.push(path);
+                if files.len() >= MAX_SCANNED_FILES {
+                    return files;
+                }
+            }
+        }
+    }
+    files
+}
+
+pub struct EnumScanner {
+    strings_regex: regex::Regex,
+}
+
+impl EnumScanner {
+    pub fn new() -> Result<Self, regex::Error> {
+        Ok(Self {
+            strings_regex: regex::Regex::new(
+                r"(?is)\bset_property\s*\(\s*CACHE\s+([A-Za-z_][A-Za-z0-9_]*)\s+(APPEND\s+)?PROPERTY\s+STRINGS\b([^)]*)\)",
+            )?,
+        })
+    }
+
+    /// Find `set_property(CACHE <var> PROPERTY STRINGS ...)` calls in the
+    /// project's CMake files and return the allowed values for each variable.
+    pub fn scan(&self, source_dir: &Path) -> HashMap<String, Vec<String>> {
+        let mut enums: HashMap<String, Vec<String>> = HashMap::new();
+
+        for file in find_cmake_files(source_dir) {
+            let Ok(content) = fs::read_to_string(&file) else { continue };
+            let content = strip_comments(&content);
+
+            for caps in self.strings_regex.captures_iter(&content) {
+                let name = caps[1].to_string();
+                let append = caps.get(2).is_some();
+                let Some(values) = parse_values(&caps[3]) else { continue };
+
+                let entry = enums.entry(name).or_default();
+                if !append {
+                    entry.clear();
+                }
+                for value in values {
+                    if !entry.contains(&value) {
+                        entry.push(value);
+                    }
+                }
+            }
+        }
+
+        enums.retain(|_, values| !values.is_empty());
+        enums
+    }
+}
+
+/// Value a variable gets from the project when it's not in the cache yet
+#[derive(Clone)]
+pub struct ProjectDefault {
+    pub value: String,
+    // Where it's declared, relative to the source dir
+    pub file: PathBuf,
+}
+
+pub struct DefaultScanner {
+    option_regex: regex::Regex,
+    set_cache_regex: regex::Regex,
+}
+
+impl DefaultScanner {
+    pub fn new() -> Result<Self, regex::Error> {
+        Ok(Self {
+            option_regex: regex::Regex::new(
+                r#"(?is)\boption\s*\(\s*([A-Za-z_][A-Za-z0-9_]*)\s+"(?:[^"\\]|\\.)*"\s*([^\s()]*)\s*\)"#,
+            )?,
+            set_cache_regex: regex::Regex::new(
+                r#"(?is)\bset\s*\(\s*([A-Za-z_][A-Za-z0-9_]*)\s+((?:"(?:[^"\\]|\\.)*"|[^\s()"]+)(?:\s+(?:"(?:[^"\\]|\\.)*"|[^\s()"]+))*?)\s+CACHE\s+(?:BOOL|STRING|PATH|FILEPATH)\b"#,
+            )?,
+        })
+    }
+
+    /// Find the `option(<var> "<help>" [value])` and `set(<var> <value> CACHE
+    /// <type> ...)` declarations in the project's CMake files and return the
+    /// default of each variable. Defaults computed from other variables are
+    /// left out, and the first declaration found wins.
+    pub fn scan(&self, source_dir: &Path) -> HashMap<String, ProjectDefault> {
+        let mut defaults: HashMap<String, ProjectDefault> = HashMap::new();
+
+        let mut files = find_cmake_files(source_dir);
+        // The top level CMakeLists.txt first, it runs before the others
+        files.sort_by_key(|f| (f.parent() != Some(source_dir), f.clone()));
+        for file in files {
+            let Ok(content) = fs::read_to_string(&file) else { continue };
+            let content = strip_comments(&content);
+
+            let options = self.option_regex.captures_iter(&content).filter_map(|caps| {
+                let value = match &caps[2] {
+                    "" => "OFF".to_string(),
+                    value => parse_values(value)?.join(";"),
+                };
+                Some((caps[1].to_string(), value))
+            });
+            let cache_sets = self
+                .set_cache_regex
+                .captures_iter(&content)
+                .filter_map(|caps| Some((caps[1].to_string(), parse_values(&caps[2])?.join(";"))));
+
+            let relative = file.strip_prefix(source_dir).unwrap_or(&file);
+            for (name, value) in options.chain(cache_sets) {
+                defaults.entry(name).or_insert_with(|| ProjectDefault { value, file: relative.to_path_buf() });
+            }
+        }
+        defaults
+    }
+}
+
+/// Split the arguments of a STRINGS property into values. Returns None when
+/// the list references variables, as those can't be resolved without cmake.
+fn parse_values(args: &str) -> Option<Vec<String>> {
+    if args.contains("${") {
+        return None;
+    }
+
+    let mut values = Vec::new();
+    for arg in split_arguments(args) {
+        values.extend(arg.split(';').filter(|v| !v.is_empty()).map(|v| v.to_string()));
+    }
+    Some(values)
+}
+
+/// Split CMake command arguments on whitespace, honoring double quotes
+pub fn split_arguments(args: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = args.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                if in_quotes {
+                    out.push(std::mem::take(&mut current));
+                }
+                in_quotes = !in_quotes;
+            }
+            '\\' if in_quotes => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    out.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        out.push(current);
+    }
+    out
+}
+
+/// Remove `#` line comments, leaving quoted strings untouched
+pub fn strip_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    for line in content.lines() {
+        let mut in_quotes = false;
+        let mut end = line.len();
+        for (i, c) in line.char_indices() {
+            match c {
+                '"' => in_quotes = !in_quotes,
+                '#' if !in_quotes => {
+                    end = i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        out.push_str(&line[..end]);
+        out.push('\n');
+    }
+    out
+}
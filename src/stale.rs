@@ -0,0 +1,46 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::file_api;
+
+/// Project files changed since the last configure: the CMakeLists.txt and
+/// *.cmake files the configure read, per the file-api cmakeFiles reply,
+/// that are newer than CMakeCache.txt. Files generated by the configure and
+/// cmake's own modules are left out. Files in the source dir are given
+/// relative to it, ready to be shown. Empty when nothing changed or there's
+/// no reply yet, in which case one is requested for the next configure.
+pub fn changed_project_files(build_dir: &Path) -> Vec<PathBuf> {
+    let Some(reply) = file_api::read_reply(build_dir, "cmakeFiles") else {
+        // Not worth an error, the check just isn't possible without it
+        let _ = file_api::request(build_dir, "cmakeFiles-v1");
+        return Vec::new();
+    };
+    let Some(cache_time) = fs::metadata(build_dir.join("CMakeCache.txt")).and_then(|m| m.modified()).ok() else {
+        return Vec::new();
+    };
+    let source_dir = reply.get("paths").and_then(|p| p.get("source")).and_then(|s| s.as_str()).map(PathBuf::from);
+    let flag = |input: &crate::json::JsonValue, name: &str| input.get(name).and_then(|v| v.as_bool()).unwrap_or(false);
+
+    let mut changed: Vec<PathBuf> = reply
+        .get("inputs")
+        .and_then(|i| i.as_array())
+        .unwrap_or_default()
+        .iter()
+        .filter(|input| !flag(input, "isGenerated") && !flag(input, "isCMake"))
+        .filter_map(|input| input.get("path")?.as_str())
+        .map(|path| match &source_dir {
+            // Relative to the source dir when inside it
+            Some(source_dir) => source_dir.join(path),
+            None => PathBuf::from(path),
+        })
+        .filter(|path| fs::metadata(path).and_then(|m| m.modified()).is_ok_and(|time| time > cache_time))
+        .map(|path| match source_dir.as_deref().and_then(|dir| path.strip_prefix(dir).ok()) {
+            Some(relative) => relative.to_path_buf(),
+            None => path,
+        })
+        .collect();
+    changed.sort();
+    changed
+}
@@ -0,0 +1,150 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+// Deep enough for <name>-prefix/src/<name>-build of ExternalProject
+const MAX_DEPTH: usize = 5;
+
+/// A build with its own cache inside the build dir of the project
+pub struct SubBuild {
+    pub path: PathBuf,
+    pub name: String,
+    // "FetchContent" or "ExternalProject"
+    pub kind: &'static str,
+}
+
+/// The caches of FetchContent sub-builds (`_deps/<name>-subbuild`) and
+/// ExternalProject builds below `build_dir`
+pub fn find_sub_builds(build_dir: &Path) -> Vec<SubBuild> {
+    let mut found = Vec::new();
+    scan(build_dir, 0, &mut found);
+    found
+}
+
+fn scan(dir: &Path, depth: usize, found: &mut Vec<SubBuild>) {
+    if depth >= MAX_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let mut subdirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|e| {
+            let name = e.file_name();
+            let name = name.to_string_lossy();
+            // CMakeFiles holds try_compile caches, nobody edits those
+            !name.starts_with('.') && name != "CMakeFiles"
+        })
+        .map(|e| e.path())
+        .collect();
+    subdirs.sort();
+
+    for subdir in subdirs {
+        if subdir.join("CMakeCache.txt").is_file() {
+            found.push(sub_build(subdir));
+        } else {
+            scan(&subdir, depth + 1, found);
+        }
+    }
+}
+
+fn sub_build(path: PathBuf) -> SubBuild {
+    let dir_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let in_deps = path.parent().and_then(Path::file_name).is_some_and(|n| n == "_deps");
+    match dir_name.strip_suffix("-subbuild") {
+        Some(name) if in_deps => SubBuild { name: name.to_string(), kind: "FetchContent", path },
+        _ => {
+            let name = dir_name.strip_suffix("-build").unwrap_or(&dir_name).to_string();
+            SubBuild { name, kind: "ExternalProject", path }
+        }
+    }
+}
+
+pub enum SubBuildChoice {
+    Open(PathBuf),
+    // Back to the build the current one was opened from
+    Back(PathBuf),
+}
+
+/// Dependency builds of the project with a cache of their own, to open
+/// in place of the main one
+pub struct SubBuildsPane {
+    pub visible: bool,
+    choices: Vec<(SubBuildChoice, String)>,
+    state: ListState,
+}
+
+impl SubBuildsPane {
+    pub fn new() -> Self {
+        Self { visible: false, choices: Vec::new(), state: ListState::default() }
+    }
+
+    /// List the sub-builds of `build_dir`, and `parent` to go back to when
+    /// `build_dir` is itself one
+    pub fn open(&mut self, build_dir: &Path, parent: Option<&Path>) {
+        self.choices.clear();
+        if let Some(parent) = parent {
+            self.choices
+                .push((SubBuildChoice::Back(parent.to_path_buf()), format!("Back to {}", parent.display())));
+        }
+        for sub in find_sub_builds(build_dir) {
+            let path = sub.path.strip_prefix(build_dir).unwrap_or(&sub.path);
+            let label = format!("{:<24} {:<16} {}", sub.name, sub.kind, path.display());
+            self.choices.push((SubBuildChoice::Open(sub.path), label));
+        }
+        self.state = ListState::default().with_selected(Some(0));
+        self.visible = true;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.choices.is_empty()
+    }
+
+    pub fn selected(&self) -> Option<&SubBuildChoice> {
+        self.state.selected().and_then(|i| self.choices.get(i)).map(|(choice, _)| choice)
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(" Dependency builds ").centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Enter> open, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+
+        let items: Vec<ListItem> = self.choices.iter().map(|(_, label)| ListItem::new(label.as_str())).collect();
+        let list = List::new(items)
+            .block(block)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
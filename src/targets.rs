@@ -0,0 +1,267 @@
+use std::path::Path;
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    symbols,
+    text::Line,
+    widgets::{
+        Block, Borders, Cell, HighlightSpacing, Paragraph, Row, StatefulWidget, Table, TableState, Widget,
+    },
+};
+
+use crate::file_api;
+use crate::json::JsonValue;
+use crate::text_input::TextInput;
+
+const HEADER_STYLE: Style = Style::new().fg(SLATE.c100).bg(BLUE.c800);
+const NORMAL_ROW_BG: Color = SLATE.c950;
+const ALT_ROW_BG: Color = SLATE.c900;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+/// A target of the project, from the codemodel of the file API
+pub struct Target {
+    pub name: String,
+    // EXECUTABLE, STATIC_LIBRARY, UTILITY, ...
+    pub typ: String,
+    pub sources: usize,
+    pub link_libraries: Vec<String>,
+    // Source dir of the target relative to the top level one
+    pub dir: String,
+}
+
+/// The targets of the last configure for `build_type` (the first
+/// configuration when no configuration has that name), None when cmake
+/// didn't write a codemodel
+pub fn read_targets(build_dir: &Path, build_type: &str) -> Option<Vec<Target>> {
+    let reply = file_api::read_reply(build_dir, "codemodel")?;
+    let configurations = reply.get("configurations")?.as_array()?;
+    let configuration = configurations
+        .iter()
+        .find(|c| c.get("name").and_then(|n| n.as_str()) == Some(build_type))
+        .or_else(|| configurations.first())?;
+
+    let mut targets: Vec<Target> = configuration
+        .get("targets")?
+        .as_array()?
+        .iter()
+        .filter_map(|t| {
+            let name = t.get("name")?.as_str()?.to_string();
+            // The summary has the name even when the target's own file is missing
+            let details = t.get("jsonFile").and_then(|f| f.as_str()).and_then(|f| file_api::read_reply_file(build_dir, f));
+            Some(match details {
+                Some(details) => target(name, &details),
+                None => Target { name, typ: String::new(), sources: 0, link_libraries: Vec::new(), dir: String::new() },
+            })
+        })
+        .collect();
+    targets.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(targets)
+}
+
+fn target(name: String, details: &JsonValue) -> Target {
+    let str_field = |v: Option<&JsonValue>| v.and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let link_libraries = details
+        .get("link")
+        .and_then(|l| l.get("commandFragments"))
+        .and_then(|f| f.as_array())
+        .unwrap_or_default()
+        .iter()
+        .filter(|f| f.get("role").and_then(|r| r.as_str()) == Some("libraries"))
+        .filter_map(|f| f.get("fragment").and_then(|f| f.as_str()))
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .collect();
+    Target {
+        name,
+        typ: str_field(details.get("type")),
+        sources: details.get("sources").and_then(|s| s.as_array()).map_or(0, |s| s.len()),
+        link_libraries,
+        dir: str_field(details.get("paths").and_then(|p| p.get("source"))),
+    }
+}
+
+/// Second top level view next to the cache table, the targets of the
+/// project with their type, source count and link libraries
+pub struct TargetsView {
+    targets: Option<Vec<Target>>,
+    // Why the file API query couldn't be written
+    query_error: Option<String>,
+    filter: TextInput,
+    filtering: bool,
+    // Indices into targets of the rows shown
+    rows: Vec<usize>,
+    state: TableState,
+}
+
+impl TargetsView {
+    pub fn new() -> Self {
+        Self {
+            targets: None,
+            query_error: None,
+            filter: TextInput::default(),
+            filtering: false,
+            rows: Vec::new(),
+            state: TableState::default(),
+        }
+    }
+
+    /// Ask cmake for the codemodel and read the one of the last configure
+    pub fn load(&mut self, build_dir: &Path, build_type: &str) {
+        self.query_error = file_api::request(build_dir, "codemodel-v2").err().map(|e| e.to_string());
+        let selected = self.selected().map(|t| t.name.clone());
+        self.targets = read_targets(build_dir, build_type);
+        self.update_rows();
+        // Stay on the same target across a reload
+        if let Some(row) = selected.and_then(|name| self.rows.iter().position(|&i| self.target(i).name == name)) {
+            self.state.select(Some(row));
+        }
+    }
+
+    fn target(&self, idx: usize) -> &Target {
+        &self.targets.as_deref().unwrap_or_default()[idx]
+    }
+
+    fn update_rows(&mut self) {
+        let filter = self.filter.text().to_lowercase();
+        self.rows = self
+            .targets
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.name.to_lowercase().contains(&filter) || t.typ.to_lowercase().contains(&filter))
+            .map(|(i, _)| i)
+            .collect();
+        self.state.select(if self.rows.is_empty() { None } else { Some(0) });
+    }
+
+    pub fn selected(&self) -> Option<&Target> {
+        self.state.selected().and_then(|row| self.rows.get(row)).map(|&i| self.target(i))
+    }
+
+    pub fn select_next(&mut self, count: usize) {
+        let last = self.rows.len().saturating_sub(1);
+        self.state.select(self.state.selected().map(|row| (row + count).min(last)));
+    }
+
+    pub fn select_previous(&mut self, count: usize) {
+        self.state.select(self.state.selected().map(|row| row.saturating_sub(count)));
+    }
+
+    pub fn select_first(&mut self) {
+        self.state.select_first();
+    }
+
+    pub fn select_last(&mut self) {
+        self.state.select_last();
+    }
+
+    /// Typing into the filter, after `/`
+    pub fn filtering(&self) -> bool {
+        self.filtering
+    }
+
+    pub fn start_filter(&mut self) {
+        self.filter.clear();
+        self.filtering = true;
+        self.update_rows();
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.update_rows();
+    }
+
+    pub fn handle_filter_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => self.filtering = false,
+            KeyCode::Esc => {
+                self.filtering = false;
+                self.clear_filter();
+            }
+            _ => {
+                if self.filter.handle_key(key) {
+                    self.update_rows();
+                }
+            }
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let [table_area, filter_area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(2)]).areas(area);
+
+        let count = match &self.targets {
+            Some(targets) if self.rows.len() != targets.len() => format!(" ({} of {})", self.rows.len(), targets.len()),
+            Some(targets) => format!(" ({})", targets.len()),
+            None => String::new(),
+        };
+        let block = Block::new()
+            .title(Line::raw(format!("Targets{}", count)).centered())
+            .borders(Borders::TOP)
+            .border_set(symbols::border::EMPTY)
+            .border_style(HEADER_STYLE)
+            .bg(NORMAL_ROW_BG);
+
+        let Some(targets) = &self.targets else {
+            let text = match &self.query_error {
+                Some(e) => format!("Couldn't ask cmake for the targets: {}", e),
+                None => "Targets show after the next configure (c)".to_string(),
+            };
+            Paragraph::new(text).block(block).fg(TEXT_FG).centered().render(area, buf);
+            return;
+        };
+
+        let header = Row::new(["Name", "Type", "Sources", "Link libraries"]).style(HEADER_STYLE).bottom_margin(1);
+        let rows: Vec<Row> = self
+            .rows
+            .iter()
+            .map(|&i| &targets[i])
+            .enumerate()
+            .map(|(i, t)| {
+                let bg = if i % 2 == 0 { NORMAL_ROW_BG } else { ALT_ROW_BG };
+                Row::new([
+                    Cell::from(t.name.as_str()),
+                    Cell::from(t.typ.to_lowercase().replace('_', " ")),
+                    Cell::from(t.sources.to_string()),
+                    Cell::from(t.link_libraries.join(" ")),
+                ])
+                .style(Style::new().bg(bg).fg(TEXT_FG))
+            })
+            .collect();
+        let longest_name = targets.iter().map(|t| t.name.len()).max().unwrap_or(0).max(4);
+        let widths = [
+            Constraint::Length(longest_name as u16 + 2),
+            Constraint::Length(16),
+            Constraint::Length(8),
+            Constraint::Min(10),
+        ];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .row_highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(table, table_area, buf, &mut self.state);
+
+        let filter_line = match (self.filtering, self.filter.text().is_empty()) {
+            (true, _) => self.filter.line("Filter: "),
+            (false, false) => Line::raw(format!("Filter: {} (f to clear)", self.filter.text())),
+            (false, true) => match self.selected() {
+                Some(t) if !t.dir.is_empty() => Line::raw(format!("{} in {}", t.name, t.dir)),
+                _ => Line::default(),
+            },
+        };
+        Paragraph::new(filter_line)
+            .block(Block::new().borders(Borders::TOP).border_set(symbols::border::EMPTY).border_style(HEADER_STYLE))
+            .bg(NORMAL_ROW_BG)
+            .fg(TEXT_FG)
+            .render(filter_area, buf);
+    }
+}
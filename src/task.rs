@@ -0,0 +1,327 @@
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread,
+    time::Duration,
+};
+
+use crate::capabilities::GeneratorChoice;
+use crate::cmake;
+use crate::remote;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TaskKind {
+    Configure,
+    Build,
+    // Traced configure of a scratch build dir, to explain a variable
+    Explain,
+    Install,
+    Package,
+    Test,
+}
+
+impl fmt::Display for TaskKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskKind::Configure => write!(f, "configure"),
+            TaskKind::Build => write!(f, "build"),
+            TaskKind::Explain => write!(f, "explain"),
+            TaskKind::Install => write!(f, "install"),
+            TaskKind::Package => write!(f, "package"),
+            TaskKind::Test => write!(f, "test"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+pub enum TaskEvent {
+    Line(Stream, String),
+    Finished(Option<ExitStatus>),
+}
+
+/// A finished task, kept for the summary printed on exit
+pub struct TaskRecord {
+    pub kind: TaskKind,
+    pub duration: Duration,
+    // None when the process was killed by a signal or couldn't be waited on
+    pub exit_code: Option<i32>,
+    // Cache edits passed along to a configure run
+    pub changes: usize,
+}
+
+impl TaskRecord {
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Plain text summary of the tasks run during a session
+pub fn format_summary(build_dir: &Path, records: &[TaskRecord]) -> String {
+    let mut out = format!("cmake-tui: {} task(s) run in {}\n", records.len(), build_dir.display());
+    for record in records {
+        let code = record.exit_code.map_or("killed".to_string(), |c| c.to_string());
+        out.push_str(&format!(
+            "  {:<10} exit {:<6} {:>8.1}s",
+            record.kind.to_string(),
+            code,
+            record.duration.as_secs_f32()
+        ));
+        if record.changes > 0 {
+            out.push_str(&format!("  ({} cache changes)", record.changes));
+        }
+        out.push('\n');
+    }
+
+    let saved: usize = records
+        .iter()
+        .filter(|r| r.kind == TaskKind::Configure && r.succeeded())
+        .map(|r| r.changes)
+        .sum();
+    out.push_str(&format!("{} cache change(s) saved\n", saved));
+    out
+}
+
+/// A cmake subprocess whose output is read on background threads and
+/// forwarded line by line, so the UI can keep drawing while it runs.
+pub struct Task {
+    pub kind: TaskKind,
+    child: Child,
+    rx: Receiver<TaskEvent>,
+    // Set once finished, to None inside when the exit status couldn't be had
+    status: Option<Option<ExitStatus>>,
+}
+
+fn configure_command(build_dir: &Path, defines: &[(String, String)]) -> Command {
+    let mut cmd = cmake::command();
+    for (name, value) in defines {
+        cmd.arg(format!("-D{}={}", name, value));
+    }
+    cmd.arg(build_dir);
+    cmd
+}
+
+// A configure creating the cache of `build_dir`
+fn create_command(
+    source_dir: &Path,
+    build_dir: &Path,
+    generator: Option<&GeneratorChoice>,
+    defines: &[(String, String)],
+) -> Command {
+    let mut cmd = cmake::command();
+    cmd.arg("-S").arg(source_dir).arg("-B").arg(build_dir);
+    if let Some(generator) = generator {
+        cmd.args(generator.args());
+    }
+    for (name, value) in defines {
+        cmd.arg(format!("-D{}={}", name, value));
+    }
+    cmd
+}
+
+/// Configure `build_dir` in the foreground with cmake's output going straight
+/// to the terminal, for --configure-first before the editor takes over and
+/// for --batch
+pub fn configure_in_terminal(build_dir: &Path, defines: &[(String, String)]) -> io::Result<ExitStatus> {
+    let mut cmd = remote::wrap(configure_command(build_dir, defines));
+    tracing::info!("running {:?}", cmd);
+    cmd.stdin(Stdio::null()).status()
+}
+
+impl Task {
+    pub fn configure(build_dir: &Path, defines: &[(String, String)]) -> io::Result<Self> {
+        Self::spawn(TaskKind::Configure, configure_command(build_dir, defines))
+    }
+
+    /// Configure writing a trace of every CMake command run to `output`
+    pub fn profiled_configure(build_dir: &Path, defines: &[(String, String)], output: &Path) -> io::Result<Self> {
+        let mut cmd = configure_command(build_dir, defines);
+        cmd.arg("--profiling-format=google-trace")
+            .arg(format!("--profiling-output={}", output.display()));
+        Self::spawn(TaskKind::Configure, cmd)
+    }
+
+    /// Configure a build dir that has no cache yet, with `generator` or the
+    /// one cmake picks
+    pub fn initial_configure(
+        source_dir: &Path,
+        build_dir: &Path,
+        generator: Option<&GeneratorChoice>,
+        defines: &[(String, String)],
+    ) -> io::Result<Self> {
+        Self::spawn(TaskKind::Configure, create_command(source_dir, build_dir, generator, defines))
+    }
+
+    /// Configure with `--fresh`, which has cmake discard the cache and
+    /// CMakeFiles/ of `build_dir` first (cmake 3.24 and newer)
+    pub fn fresh_configure(
+        source_dir: &Path,
+        build_dir: &Path,
+        generator: Option<&GeneratorChoice>,
+        defines: &[(String, String)],
+    ) -> io::Result<Self> {
+        let mut cmd = create_command(source_dir, build_dir, generator, defines);
+        cmd.arg("--fresh");
+        Self::spawn(TaskKind::Configure, cmd)
+    }
+
+    /// Configure `source_dir` from scratch in `scratch_dir`, starting from the
+    /// settings in `initial_cache` and writing a trace of every command run,
+    /// with its arguments expanded, to `output`
+    pub fn traced_configure(
+        source_dir: &Path,
+        scratch_dir: &Path,
+        initial_cache: &Path,
+        generator: Option<&str>,
+        output: &Path,
+    ) -> io::Result<Self> {
+        let mut cmd = cmake::command();
+        cmd.arg("-S").arg(source_dir).arg("-B").arg(scratch_dir).arg("-C").arg(initial_cache);
+        if let Some(generator) = generator {
+            cmd.arg("-G").arg(generator);
+        }
+        cmd.arg("--trace-expand")
+            .arg("--trace-format=json-v1")
+            .arg(format!("--trace-redirect={}", output.display()));
+        Self::spawn(TaskKind::Explain, cmd)
+    }
+
+    /// Build everything, the configuration `config` of a multi-config
+    /// generator when given
+    pub fn build(build_dir: &Path, config: Option<&str>) -> io::Result<Self> {
+        let mut cmd = cmake::command();
+        cmd.arg("--build").arg(build_dir);
+        if let Some(config) = config {
+            cmd.arg("--config").arg(config);
+        }
+        Self::spawn(TaskKind::Build, cmd)
+    }
+
+    /// Build only `target` and what it depends on
+    pub fn build_target(build_dir: &Path, target: &str, config: Option<&str>) -> io::Result<Self> {
+        let mut cmd = cmake::command();
+        cmd.arg("--build").arg(build_dir).arg("--target").arg(target);
+        if let Some(config) = config {
+            cmd.arg("--config").arg(config);
+        }
+        Self::spawn(TaskKind::Build, cmd)
+    }
+
+    /// Run the tests with ctest, only `name` when given
+    pub fn test(build_dir: &Path, name: Option<&str>, config: Option<&str>) -> io::Result<Self> {
+        let mut cmd = cmake::tool_command("ctest");
+        cmd.arg("--test-dir").arg(build_dir).arg("--output-on-failure");
+        if let Some(config) = config {
+            cmd.arg("-C").arg(config);
+        }
+        if let Some(name) = name {
+            cmd.arg("-R").arg(format!("^{}$", regex::escape(name)));
+        }
+        Self::spawn(TaskKind::Test, cmd)
+    }
+
+    /// Install the built project, to `prefix` instead of CMAKE_INSTALL_PREFIX when given
+    pub fn install(build_dir: &Path, prefix: Option<&str>, config: Option<&str>) -> io::Result<Self> {
+        let mut cmd = cmake::command();
+        cmd.arg("--install").arg(build_dir);
+        if let Some(config) = config {
+            cmd.arg("--config").arg(config);
+        }
+        if let Some(prefix) = prefix {
+            cmd.arg("--prefix").arg(prefix);
+        }
+        Self::spawn(TaskKind::Install, cmd)
+    }
+
+    /// Run cpack in the build dir, which is where it puts the packages, with
+    /// `generator` instead of the configured CPACK_GENERATOR when given.
+    /// `config` is relative to the build dir.
+    pub fn package(build_dir: &Path, config: &Path, generator: Option<&str>) -> io::Result<Self> {
+        let mut cmd = cmake::tool_command("cpack");
+        cmd.arg("--config").arg(config).current_dir(build_dir);
+        if let Some(generator) = generator {
+            cmd.arg("-G").arg(generator);
+        }
+        Self::spawn(TaskKind::Package, cmd)
+    }
+
+    fn spawn(kind: TaskKind, mut cmd: Command) -> io::Result<Self> {
+        // Output goes to a pipe, so ask cmake and the build tools to keep their colors
+        cmd.env("CLICOLOR_FORCE", "1");
+        let mut cmd = remote::wrap(cmd);
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        tracing::info!("running {:?}", cmd);
+        let mut child = cmd.spawn()?;
+        let (tx, rx) = mpsc::channel();
+
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, Stream::Stdout, tx.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, Stream::Stderr, tx);
+        }
+
+        Ok(Self { kind, child, rx, status: None })
+    }
+
+    /// Drain all output produced since the last call. Once both streams are
+    /// closed the exit status is collected and reported as the last event.
+    pub fn poll(&mut self) -> Vec<TaskEvent> {
+        let mut events = Vec::new();
+        if self.status.is_some() {
+            return events;
+        }
+
+        loop {
+            match self.rx.try_recv() {
+                Ok(event) => events.push(event),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    let status = self.child.wait().ok();
+                    self.status = Some(status);
+                    events.push(TaskEvent::Finished(status));
+                    break;
+                }
+            }
+        }
+        events
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status.is_none()
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+}
+
+fn forward_lines<R: Read + Send + 'static>(reader: R, stream: Stream, tx: Sender<TaskEvent>) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buf);
+                    let line = line.trim_end_matches(['\n', '\r']).to_string();
+                    if tx.send(TaskEvent::Line(stream, line)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
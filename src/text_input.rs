@@ -0,0 +1,100 @@
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Single line text field with a cursor, used for the search and command
+/// lines, the value editor and popups that ask for a value
+#[derive(Default, Clone)]
+pub struct TextInput {
+    text: String,
+    // Cursor position in graphemes (what the user sees as one character,
+    // e.g. a letter with combining accents or an emoji sequence), not bytes
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new(text: &str) -> Self {
+        Self { text: text.to_string(), cursor: text.graphemes(true).count() }
+    }
+
+    /// Like `new`, with the cursor at grapheme position `cursor` instead of the end
+    pub fn with_cursor(text: &str, cursor: usize) -> Self {
+        Self { text: text.to_string(), cursor: cursor.min(text.graphemes(true).count()) }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    fn byte_pos(&self, grapheme_pos: usize) -> usize {
+        self.text.grapheme_indices(true).nth(grapheme_pos).map_or(self.text.len(), |(i, _)| i)
+    }
+
+    fn grapheme_count(&self) -> usize {
+        self.text.graphemes(true).count()
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let pos = self.byte_pos(self.cursor);
+        self.text.insert(pos, c);
+        // A combining mark joins the grapheme before it instead of adding one
+        self.cursor = self.text[..pos + c.len_utf8()].graphemes(true).count();
+    }
+
+    pub fn backspace(&mut self) {
+        if self.cursor == 0 { return; }
+        self.cursor -= 1;
+        let start = self.byte_pos(self.cursor);
+        let end = self.byte_pos(self.cursor + 1);
+        self.text.replace_range(start..end, "");
+    }
+
+    pub fn delete(&mut self) {
+        if self.cursor >= self.grapheme_count() { return; }
+        let start = self.byte_pos(self.cursor);
+        let end = self.byte_pos(self.cursor + 1);
+        self.text.replace_range(start..end, "");
+    }
+
+    /// Handle an editing key. Returns false for keys the input doesn't use,
+    /// like Enter and Esc, so the caller can act on them.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char(c) => self.insert(c),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.grapheme_count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.grapheme_count(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Render the text after `prefix`, with the cursor shown as a reversed cell
+    pub fn line(&self, prefix: &str) -> Line<'static> {
+        let pos = self.byte_pos(self.cursor);
+        let (before, rest) = self.text.split_at(pos);
+        let under_cursor = rest.graphemes(true).next().unwrap_or("");
+        let after = &rest[under_cursor.len()..];
+
+        Line::from(vec![
+            Span::raw(prefix.to_string()),
+            Span::raw(before.to_string()),
+            Span::styled(
+                if under_cursor.is_empty() { " " } else { under_cursor }.to_string(),
+                Style::new().add_modifier(Modifier::REVERSED),
+            ),
+            Span::raw(after.to_string()),
+        ])
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::cache_editor::CacheEditorState;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+// Each step keeps a full copy of the editor state
+const MAX_STEPS: usize = 200;
+
+/// Debug overlay (feature `time-travel`) recording the editor state after
+/// each dispatched action, so the state can be stepped back and forth
+pub struct TimeTravel {
+    steps: VecDeque<(String, CacheEditorState)>,
+    // Step whose state is the current one
+    cursor: usize,
+    pub visible: bool,
+    state: ListState,
+}
+
+impl TimeTravel {
+    pub fn new(initial: &CacheEditorState) -> Self {
+        let mut steps = VecDeque::new();
+        steps.push_back(("(start)".to_string(), initial.clone()));
+        Self { steps, cursor: 0, visible: false, state: ListState::default() }
+    }
+
+    /// Add the state `action` led to. Steps undone before are dropped, like
+    /// the redo history of an editor.
+    pub fn record(&mut self, action: String, editor: &CacheEditorState) {
+        self.steps.truncate(self.cursor + 1);
+        self.steps.push_back((action, editor.clone()));
+        if self.steps.len() > MAX_STEPS {
+            self.steps.pop_front();
+        }
+        self.cursor = self.steps.len() - 1;
+    }
+
+    /// State before the current step, to be put in place of the editor's
+    pub fn step_back(&mut self) -> Option<CacheEditorState> {
+        self.cursor = self.cursor.checked_sub(1)?;
+        Some(self.steps[self.cursor].1.clone())
+    }
+
+    pub fn step_forward(&mut self) -> Option<CacheEditorState> {
+        if self.cursor + 1 >= self.steps.len() {
+            return None;
+        }
+        self.cursor += 1;
+        Some(self.steps[self.cursor].1.clone())
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(format!(" Time travel: step {} of {} ", self.cursor + 1, self.steps.len())).centered().bold())
+            .title_bottom(Line::raw(" h/l step back/forward, <F12> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+
+        let items: Vec<ListItem> = self
+            .steps
+            .iter()
+            .map(|(action, editor)| {
+                let selected = editor.selected().map_or("-", |v| v.var.name.as_str());
+                ListItem::new(format!(
+                    "{:<36} {:>4} rows  {:>3} modified  {}",
+                    action,
+                    editor.visible_count(),
+                    editor.modified_count(),
+                    selected
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        self.state.select(Some(self.cursor));
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
@@ -0,0 +1,206 @@
+use std::{collections::HashMap, fmt};
+
+/// Minimal TOML reader covering what cmake-tui's config files use: tables,
+/// dotted keys, strings, integers, booleans and arrays of those.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TomlValue {
+    String(String),
+    Int(i64),
+    Bool(bool),
+    Array(Vec<TomlValue>),
+}
+
+impl TomlValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            TomlValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TomlError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TomlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Parsed document, keys are fully qualified (`section.key`)
+#[derive(Debug, Default, Clone)]
+pub struct TomlTable {
+    values: HashMap<String, TomlValue>,
+}
+
+impl TomlTable {
+    pub fn get(&self, key: &str) -> Option<&TomlValue> {
+        self.values.get(key)
+    }
+
+    /// Every key with its value, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &TomlValue)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+pub fn parse(content: &str) -> Result<TomlTable, TomlError> {
+    let mut table = TomlTable::default();
+    let mut section = String::new();
+    let mut lines = content.lines().enumerate();
+
+    while let Some((idx, raw)) = lines.next() {
+        let err = |message: &str| TomlError { line: idx + 1, message: message.to_string() };
+        let line = strip_comment(raw).trim().to_string();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let Some(name) = name.strip_suffix(']') else {
+                return Err(err("unterminated table header"));
+            };
+            section = name.trim().trim_matches('"').to_string();
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(err("expected `key = value`"));
+        };
+        let key = key.trim().trim_matches('"');
+        if key.is_empty() {
+            return Err(err("empty key"));
+        }
+
+        // Arrays may span several lines
+        let mut value = value.trim().to_string();
+        if value.starts_with('[') {
+            while !brackets_balanced(&value) {
+                let Some((_, next)) = lines.next() else {
+                    return Err(err("unterminated array"));
+                };
+                value.push(' ');
+                value.push_str(strip_comment(next).trim());
+            }
+        }
+
+        let parsed = parse_value(&value).map_err(|m| err(&m))?;
+        let full_key = if section.is_empty() { key.to_string() } else { format!("{}.{}", section, key) };
+        table.values.insert(full_key, parsed);
+    }
+    Ok(table)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '\\' if in_string => escaped = !escaped,
+            '"' if !escaped => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => escaped = false,
+        }
+    }
+    line
+}
+
+fn brackets_balanced(value: &str) -> bool {
+    let mut depth = 0i32;
+    let mut in_string = false;
+    for c in value.chars() {
+        match c {
+            '"' => in_string = !in_string,
+            '[' if !in_string => depth += 1,
+            ']' if !in_string => depth -= 1,
+            _ => {}
+        }
+    }
+    depth <= 0
+}
+
+fn parse_value(value: &str) -> Result<TomlValue, String> {
+    let value = value.trim();
+    if let Some(inner) = value.strip_prefix('"') {
+        let Some(inner) = inner.strip_suffix('"') else {
+            return Err("unterminated string".to_string());
+        };
+        return Ok(TomlValue::String(unescape(inner)));
+    }
+    if let Some(inner) = value.strip_prefix('\'') {
+        // Literal string, no escapes
+        let Some(inner) = inner.strip_suffix('\'') else {
+            return Err("unterminated string".to_string());
+        };
+        return Ok(TomlValue::String(inner.to_string()));
+    }
+    if let Some(inner) = value.strip_prefix('[') {
+        let Some(inner) = inner.strip_suffix(']') else {
+            return Err("unterminated array".to_string());
+        };
+        return split_array(inner)
+            .iter()
+            .map(|item| parse_value(item))
+            .collect::<Result<Vec<_>, _>>()
+            .map(TomlValue::Array);
+    }
+    match value {
+        "true" => return Ok(TomlValue::Bool(true)),
+        "false" => return Ok(TomlValue::Bool(false)),
+        _ => {}
+    }
+    value
+        .replace('_', "")
+        .parse::<i64>()
+        .map(TomlValue::Int)
+        .map_err(|_| format!("unsupported value `{}`", value))
+}
+
+fn split_array(inner: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut in_string = false;
+    let mut depth = 0;
+    for c in inner.chars() {
+        match c {
+            '"' | '\'' => {
+                in_string = !in_string;
+                current.push(c);
+            }
+            '[' if !in_string => {
+                depth += 1;
+                current.push(c);
+            }
+            ']' if !in_string => {
+                depth -= 1;
+                current.push(c);
+            }
+            ',' if !in_string && depth == 0 => items.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    items.push(current);
+    items.into_iter().map(|i| i.trim().to_string()).filter(|i| !i.is_empty()).collect()
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
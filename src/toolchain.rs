@@ -0,0 +1,163 @@
+use std::path::Path;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{
+        Color, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Widget, Wrap},
+};
+
+use crate::cache_editor::CacheEditorState;
+use crate::file_api;
+use crate::launcher;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const LABEL_FG: Color = SLATE.c400;
+
+const TOOLCHAIN_VARS: &[&str] = &[
+    "CMAKE_TOOLCHAIN_FILE",
+    "CMAKE_SYSROOT",
+    "CMAKE_OSX_SYSROOT",
+    "CMAKE_OSX_ARCHITECTURES",
+    "CMAKE_C_COMPILER_TARGET",
+    "CMAKE_CXX_COMPILER_TARGET",
+    "CMAKE_FIND_ROOT_PATH",
+    "CMAKE_CROSSCOMPILING_EMULATOR",
+];
+const LINKER_VARS: &[&str] = &[
+    "CMAKE_LINKER",
+    "CMAKE_LINKER_TYPE",
+    "CMAKE_AR",
+    "CMAKE_RANLIB",
+    "CMAKE_STRIP",
+    "CMAKE_OBJCOPY",
+    "CMAKE_EXE_LINKER_FLAGS",
+];
+
+/// A compiler as cmake identified it, from the file API
+pub struct Toolchain {
+    pub language: String,
+    pub path: Option<String>,
+    pub id: Option<String>,
+    pub version: Option<String>,
+    pub target: Option<String>,
+}
+
+/// The toolchains of the last configure, None when cmake didn't write
+/// them (the query goes in with the first time the dashboard is opened)
+fn read_toolchains(build_dir: &Path) -> Option<Vec<Toolchain>> {
+    let reply = file_api::read_reply(build_dir, "toolchains")?;
+    let toolchains = reply
+        .get("toolchains")?
+        .as_array()?
+        .iter()
+        .filter_map(|t| {
+            let compiler = t.get("compiler");
+            let field = |key: &str| compiler.and_then(|c| c.get(key)).and_then(|v| v.as_str()).map(str::to_string);
+            Some(Toolchain {
+                language: t.get("language")?.as_str()?.to_string(),
+                path: field("path"),
+                id: field("id"),
+                version: field("version"),
+                target: field("target"),
+            })
+        })
+        .collect();
+    Some(toolchains)
+}
+
+/// Compilers, toolchain file, sysroot and binutils on one screen, to check
+/// a cross-compile setup at a glance
+pub struct ToolchainPane {
+    pub visible: bool,
+    toolchains: Option<Vec<Toolchain>>,
+    // Why the file API query couldn't be written
+    query_error: Option<String>,
+    scroll: u16,
+}
+
+impl ToolchainPane {
+    pub fn new() -> Self {
+        Self { visible: false, toolchains: None, query_error: None, scroll: 0 }
+    }
+
+    pub fn open(&mut self, build_dir: &Path) {
+        self.query_error = file_api::request(build_dir, "toolchains-v1").err().map(|e| e.to_string());
+        self.toolchains = read_toolchains(build_dir);
+        self.scroll = 0;
+        self.visible = true;
+    }
+
+    pub fn scroll(&mut self, lines: i16) {
+        self.scroll = self.scroll.saturating_add_signed(lines);
+    }
+
+    pub fn render(&self, editor: &CacheEditorState, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(" Toolchain ").centered().bold())
+            .title_bottom(Line::raw(" j/k scroll, p pick a toolchain file, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG)
+            .padding(Padding::horizontal(1));
+
+        let value = |name: &str| editor.get(name).map(|v| v.new_val.clone()).filter(|v| !v.is_empty());
+        let field = |label: &str, value: String| {
+            Line::from(vec![Span::raw(format!("{:<32}", label)).fg(LABEL_FG), Span::raw(value)])
+        };
+
+        let mut lines = vec![Line::raw("Compilers").bold()];
+        for lang in launcher::languages(editor) {
+            let identified = self.toolchains.as_ref().and_then(|t| t.iter().find(|t| t.language == lang));
+            let path = value(&format!("CMAKE_{}_COMPILER", lang))
+                .or_else(|| identified.and_then(|t| t.path.clone()))
+                .unwrap_or_else(|| "(not found)".to_string());
+            let mut details = Vec::new();
+            if let Some(t) = identified {
+                details.extend([t.id.clone(), t.version.clone()].into_iter().flatten());
+                if let Some(target) = &t.target {
+                    details.push(format!("for {}", target));
+                }
+            }
+            let description = match details.is_empty() {
+                true => path,
+                false => format!("{}  ({})", path, details.join(" ")),
+            };
+            lines.push(field(lang, description));
+        }
+        match (&self.toolchains, &self.query_error) {
+            (Some(_), _) => {}
+            (None, Some(e)) => lines.push(Line::raw(format!("Couldn't ask cmake for compiler versions: {}", e)).dim()),
+            (None, None) => lines.push(Line::raw("Compiler versions show after the next configure (c)").dim()),
+        }
+
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Toolchain").bold());
+        if value("CMAKE_TOOLCHAIN_FILE").is_none() {
+            lines.push(field("CMAKE_TOOLCHAIN_FILE", "(none, building for this machine)".to_string()));
+        }
+        lines.extend(TOOLCHAIN_VARS.iter().filter_map(|name| Some(field(name, value(name)?))));
+
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Linker and binutils").bold());
+        let linker: Vec<Line> = LINKER_VARS.iter().filter_map(|name| Some(field(name, value(name)?))).collect();
+        if linker.is_empty() {
+            lines.push(Line::raw("None set, cmake picks the defaults of the compiler").dim());
+        }
+        lines.extend(linker);
+
+        Paragraph::new(lines)
+            .block(block)
+            .fg(TEXT_FG)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,193 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
+};
+
+use crate::config::config_dir;
+use crate::text_input::TextInput;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+// Kept next to config.toml, one path per line, the last used first
+const RECENT_FILE: &str = "recent_toolchains";
+const MAX_RECENT: usize = 10;
+// How deep the source tree is searched for toolchain files
+const MAX_SEARCH_DEPTH: usize = 4;
+
+/// Toolchain files used before, most recent first, those that still exist
+pub fn recent_toolchains() -> Vec<PathBuf> {
+    let Some(dir) = config_dir() else { return Vec::new() };
+    fs::read_to_string(dir.join(RECENT_FILE))
+        .unwrap_or_default()
+        .lines()
+        .map(PathBuf::from)
+        .filter(|p| p.is_file())
+        .collect()
+}
+
+pub fn remember_toolchain(path: &Path) -> io::Result<()> {
+    let Some(dir) = config_dir() else { return Ok(()) };
+    let mut recent = recent_toolchains();
+    recent.retain(|p| p != path);
+    recent.insert(0, path.to_path_buf());
+    recent.truncate(MAX_RECENT);
+    fs::create_dir_all(&dir)?;
+    let content: String = recent.iter().map(|p| format!("{}\n", p.display())).collect();
+    fs::write(dir.join(RECENT_FILE), content)
+}
+
+/// `*.cmake` files in the source tree named like toolchain files, skipping
+/// hidden and build directories
+fn find_toolchain_files(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let mut entries: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+    entries.sort();
+    for path in entries {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+        if path.is_dir() {
+            if depth < MAX_SEARCH_DEPTH && !name.starts_with('.') && !path.join("CMakeCache.txt").exists() {
+                find_toolchain_files(&path, depth + 1, found);
+            }
+        } else if name.ends_with(".cmake") && name.contains("toolchain") {
+            found.push(path);
+        }
+    }
+}
+
+enum Choice {
+    // No toolchain file, build for this machine
+    Native,
+    File(PathBuf, &'static str),
+    TypePath,
+}
+
+/// What the caller should do after the picker handled a key
+pub enum PickerResult {
+    Continue,
+    // The toolchain file to switch to, None for a native build
+    Pick(Option<PathBuf>),
+    Cancel,
+}
+
+/// Choice of toolchain file: the ones used recently, the ones the project
+/// ships, or any other path
+pub struct ToolchainPicker {
+    choices: Vec<Choice>,
+    state: ListState,
+    // Typing a path after picking "Other file..."
+    input: Option<TextInput>,
+    error: Option<String>,
+}
+
+impl ToolchainPicker {
+    pub fn new(source_dir: Option<&Path>, current: Option<&str>) -> Self {
+        let recent = recent_toolchains();
+        let mut project = Vec::new();
+        if let Some(source_dir) = source_dir {
+            find_toolchain_files(source_dir, 0, &mut project);
+        }
+        project.retain(|p| !recent.contains(p));
+
+        let mut choices = vec![Choice::Native];
+        choices.extend(recent.into_iter().map(|p| Choice::File(p, "recent")));
+        choices.extend(project.into_iter().map(|p| Choice::File(p, "in the project")));
+        choices.push(Choice::TypePath);
+
+        let selected = choices
+            .iter()
+            .position(|c| match (c, current) {
+                (Choice::File(path, _), Some(current)) => path == Path::new(current),
+                (Choice::Native, None) => true,
+                _ => false,
+            })
+            .unwrap_or(0);
+        Self { choices, state: ListState::default().with_selected(Some(selected)), input: None, error: None }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> PickerResult {
+        if let Some(input) = self.input.as_mut() {
+            match key.code {
+                KeyCode::Esc => self.input = None,
+                KeyCode::Enter => {
+                    let path = PathBuf::from(input.text());
+                    match path.canonicalize() {
+                        Ok(path) if path.is_file() => return PickerResult::Pick(Some(path)),
+                        _ => self.error = Some(format!("{} is not a file", path.display())),
+                    }
+                }
+                _ => {
+                    input.handle_key(key);
+                }
+            }
+            return PickerResult::Continue;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return PickerResult::Cancel,
+            KeyCode::Char('j') | KeyCode::Down => self.state.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.state.select_previous(),
+            KeyCode::Enter => match self.state.selected().and_then(|i| self.choices.get(i)) {
+                Some(Choice::Native) => return PickerResult::Pick(None),
+                Some(Choice::File(path, _)) => return PickerResult::Pick(Some(path.clone())),
+                Some(Choice::TypePath) => self.input = Some(TextInput::default()),
+                None => {}
+            },
+            _ => {}
+        }
+        PickerResult::Continue
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(" Toolchain file ").centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Enter> switch, <Esc> cancel ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [list_area, input_area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(2)]).areas(inner);
+
+        let items: Vec<ListItem> = self
+            .choices
+            .iter()
+            .map(|choice| match choice {
+                Choice::Native => ListItem::new("None, build for this machine"),
+                Choice::File(path, origin) => ListItem::new(format!("{}  ({})", path.display(), origin)),
+                Choice::TypePath => ListItem::new("Other file..."),
+            })
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        let mut lines = Vec::new();
+        if let Some(input) = &self.input {
+            lines.push(input.line("Path: "));
+        }
+        if let Some(error) = &self.error {
+            lines.push(Line::raw(error.clone()).dim());
+        }
+        Paragraph::new(lines).fg(TEXT_FG).render(input_area, buf);
+    }
+}
@@ -0,0 +1,164 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Modifier, Style, Stylize,
+        palette::tailwind::{BLUE, SLATE},
+    },
+    text::Line,
+    widgets::{Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
+};
+
+use crate::source_scan::find_cmake_files;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+const CONTEXT_FG: Color = SLATE.c400;
+
+const PRESET_FILES: &[&str] = &["CMakePresets.json", "CMakeUserPresets.json"];
+// Lines shown above and below a hit
+const CONTEXT_LINES: usize = 3;
+
+/// A line of the project mentioning a variable
+pub struct Usage {
+    pub file: PathBuf,
+    // 1-based
+    pub line: usize,
+    pub text: String,
+    // The hit with the lines around it, by line number
+    context: Vec<(usize, String)>,
+}
+
+/// Lines of the project's CMake files and presets that mention `name` as a
+/// whole word, the top level CMakeLists.txt first
+pub fn find_usages(source_dir: &Path, name: &str) -> Vec<Usage> {
+    let Ok(regex) = regex::Regex::new(&format!(r"\b{}\b", regex::escape(name))) else { return Vec::new() };
+
+    let mut files = find_cmake_files(source_dir);
+    files.extend(PRESET_FILES.iter().map(|f| source_dir.join(f)).filter(|f| f.is_file()));
+    files.sort_by_key(|f| (f.parent() != Some(source_dir), f.clone()));
+
+    let mut usages = Vec::new();
+    for file in files {
+        let Ok(content) = fs::read_to_string(&file) else { continue };
+        let lines: Vec<&str> = content.lines().collect();
+        for (idx, line) in lines.iter().enumerate().filter(|(_, line)| regex.is_match(line)) {
+            let context = lines
+                .iter()
+                .enumerate()
+                .skip(idx.saturating_sub(CONTEXT_LINES))
+                .take(CONTEXT_LINES * 2 + 1)
+                .map(|(i, l)| (i + 1, l.to_string()))
+                .collect();
+            usages.push(Usage { file: file.clone(), line: idx + 1, text: line.trim().to_string(), context });
+        }
+    }
+    usages
+}
+
+/// Where the selected variable is used in the project, with the code around
+/// each place
+pub struct UsagesPane {
+    pub visible: bool,
+    name: String,
+    source_dir: PathBuf,
+    usages: Vec<Usage>,
+    state: ListState,
+}
+
+impl UsagesPane {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            name: String::new(),
+            source_dir: PathBuf::new(),
+            usages: Vec::new(),
+            state: ListState::default(),
+        }
+    }
+
+    pub fn open(&mut self, source_dir: &Path, name: &str) {
+        self.usages = find_usages(source_dir, name);
+        self.name = name.to_string();
+        self.source_dir = source_dir.to_path_buf();
+        self.state = ListState::default().with_selected(Some(0));
+        self.visible = true;
+    }
+
+    pub fn selected(&self) -> Option<&Usage> {
+        self.state.selected().and_then(|i| self.usages.get(i))
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.source_dir).unwrap_or(path)
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let title = format!(" {} is used in {} places ", self.name, self.usages.len());
+        let block = Block::new()
+            .title(Line::raw(title).centered().bold())
+            .title_bottom(Line::raw(" j/k select, <Enter> open in $EDITOR, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.usages.is_empty() {
+            let text = format!("No CMake file or preset in {} mentions {}", self.source_dir.display(), self.name);
+            Paragraph::new(text).fg(TEXT_FG).render(inner, buf);
+            return;
+        }
+
+        let [list_area, context_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(CONTEXT_LINES as u16 * 2 + 2),
+        ])
+        .areas(inner);
+
+        let items: Vec<ListItem> = self
+            .usages
+            .iter()
+            .map(|u| ListItem::new(format!("{}:{}  {}", self.relative(&u.file).display(), u.line, u.text)))
+            .collect();
+        let list = List::new(items)
+            .fg(TEXT_FG)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        let context: Vec<Line> = self
+            .selected()
+            .map(|usage| {
+                usage
+                    .context
+                    .iter()
+                    .map(|(number, text)| {
+                        let line = Line::raw(format!("{:>5}  {}", number, text));
+                        if *number == usage.line { line.fg(TEXT_FG).bold() } else { line.fg(CONTEXT_FG) }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Paragraph::new(context)
+            .block(Block::new().borders(Borders::TOP).border_style(Style::new().fg(BLUE.c500)))
+            .render(context_area, buf);
+    }
+}
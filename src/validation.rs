@@ -0,0 +1,89 @@
+use cmake_cache::{BoolValue, CacheVar, VarType};
+
+use crate::path_status::PathStatus;
+
+// Variables cmake reads as a number, an empty value leaves them unset
+const NUMERIC_VARS: &[&str] = &[
+    "CMAKE_C_STANDARD",
+    "CMAKE_CXX_STANDARD",
+    "CMAKE_CUDA_STANDARD",
+    "CMAKE_HIP_STANDARD",
+    "CMAKE_OBJC_STANDARD",
+    "CMAKE_OBJCXX_STANDARD",
+    "CMAKE_BUILD_PARALLEL_LEVEL",
+];
+
+/// What's wrong with `value` as the value of `var`, if anything
+pub fn validate(var: &CacheVar, value: &str, paths: &PathStatus) -> Option<String> {
+    if let Some(lang) = compiler_language(&var.name)
+        && (value.is_empty() || is_notfound(value))
+    {
+        return Some(format!("No {} compiler, cmake can't build {} sources without one", lang, lang));
+    }
+    if NUMERIC_VARS.contains(&var.name.as_str()) && !value.is_empty() && value.parse::<u32>().is_err() {
+        return Some(format!("\"{}\" is not a number", value));
+    }
+
+    match var.typ {
+        VarType::Bool => match check_bool(value) {
+            Ok(warning) => warning,
+            Err(problem) => Some(problem),
+        },
+        VarType::Enum if !value.is_empty() && !var.values.iter().any(|v| v == value) => {
+            Some(format!("\"{}\" is not one of {}", value, var.values.join(", ")))
+        }
+        VarType::Filepath | VarType::Dirpath => path_problem(value, var.typ == VarType::Dirpath, paths),
+        _ => None,
+    }
+}
+
+/// Problem with a typed BOOL value: Err when CMake wouldn't read it as a
+/// boolean at all, a warning when it reads it other than it looks
+pub fn check_bool(value: &str) -> Result<Option<String>, String> {
+    match BoolValue::parse(value) {
+        BoolValue::Plain(_) => Ok(None),
+        BoolValue::Unusual(b) => Ok(Some(format!(
+            "CMake reads \"{}\" as {}, prefer ON/OFF",
+            value,
+            if b { "true" } else { "false" }
+        ))),
+        BoolValue::Unknown => Err(format!(
+            "\"{}\" is not a boolean, use ON/OFF, TRUE/FALSE, YES/NO, Y/N or 1/0",
+            value
+        )),
+    }
+}
+
+// `CMAKE_<LANG>_COMPILER`, but not e.g. CMAKE_CXX_COMPILER_LAUNCHER or
+// CMAKE_CUDA_HOST_COMPILER
+fn compiler_language(name: &str) -> Option<&str> {
+    let lang = name.strip_prefix("CMAKE_")?.strip_suffix("_COMPILER")?;
+    (!lang.is_empty() && !lang.contains('_')).then_some(lang)
+}
+
+fn is_notfound(value: &str) -> bool {
+    value == "NOTFOUND" || value.ends_with("-NOTFOUND")
+}
+
+fn path_problem(value: &str, want_dir: bool, paths: &PathStatus) -> Option<String> {
+    if is_notfound(value) {
+        return Some("Not found by the last configure".to_string());
+    }
+    let check = paths.check(value, want_dir)?;
+    let expected = if want_dir { "not a directory" } else { "a directory, not a file" };
+    match (check.missing.as_slice(), check.wrong_kind.as_slice()) {
+        ([], []) => None,
+        ([path], []) => Some(format!("{} does not exist", path)),
+        ([], [path]) => Some(format!("{} is {}", path, expected)),
+        (missing, wrong_kind) => {
+            let mut problems = Vec::new();
+            if !missing.is_empty() {
+                problems.push(format!("missing: {}", missing.join(", ")));
+            }
+            if !wrong_kind.is_empty() {
+                problems.push(format!("{}: {}", expected, wrong_kind.join(", ")));
+            }
+            Some(format!("Paths {}", problems.join("; ")))
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{
+        Color, Style, Stylize,
+        palette::tailwind::{AMBER, BLUE, SLATE},
+    },
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Widget, Wrap},
+};
+
+use crate::list_editor::split_list;
+use crate::path_status::is_windows_path;
+
+const PANEL_BG: Color = SLATE.c950;
+const TEXT_FG: Color = SLATE.c200;
+const SUMMARY_FG: Color = SLATE.c400;
+// Escapes and control characters, which are easy to miss in the value
+const ESCAPE_FG: Color = AMBER.c400;
+
+/// The whole value of an entry, for values too long for the table. Lists
+/// are shown one item per line, and escapes and control characters are
+/// spelled out.
+pub struct ValuePane {
+    pub visible: bool,
+    name: String,
+    value: String,
+    // The value as is, without splitting lists or spelling out escapes
+    raw: bool,
+    scroll: u16,
+}
+
+/// The value with backslash escapes highlighted and control characters
+/// written the way they'd be typed, e.g. a tab as \t. The backslashes of
+/// Windows paths are left alone.
+fn show_escapes(value: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let windows_path = is_windows_path(value);
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        let escape = match c {
+            '\\' if !windows_path => match chars.next() {
+                Some(next) => format!("\\{}", next),
+                None => "\\".to_string(),
+            },
+            '\t' => "\\t".to_string(),
+            '\n' => "\\n".to_string(),
+            '\r' => "\\r".to_string(),
+            c if c.is_control() => format!("\\x{:02x}", c as u32),
+            c => {
+                plain.push(c);
+                continue;
+            }
+        };
+        if !plain.is_empty() {
+            spans.push(Span::raw(std::mem::take(&mut plain)));
+        }
+        spans.push(Span::raw(escape).fg(ESCAPE_FG));
+    }
+    if !plain.is_empty() {
+        spans.push(Span::raw(plain));
+    }
+    spans
+}
+
+impl ValuePane {
+    pub fn new() -> Self {
+        Self { visible: false, name: String::new(), value: String::new(), raw: false, scroll: 0 }
+    }
+
+    pub fn open(&mut self, name: &str, value: &str) {
+        self.name = name.to_string();
+        self.value = value.to_string();
+        self.scroll = 0;
+        self.visible = true;
+    }
+
+    pub fn scroll(&mut self, lines: i16) {
+        self.scroll = self.scroll.saturating_add_signed(lines);
+    }
+
+    pub fn toggle_raw(&mut self) {
+        self.raw = !self.raw;
+        self.scroll = 0;
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::raw(format!(" {} ", self.name)).centered().bold())
+            .title_bottom(Line::raw(" j/k scroll, r raw/pretty, s allowed values, a advanced, <Esc> close ").centered())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(BLUE.c500))
+            .bg(PANEL_BG)
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let items = split_list(&self.value);
+        let length = self.value.chars().count();
+        let mut summary = match items.len() {
+            0 | 1 => format!("{} characters", length),
+            n => format!("{} items, {} characters", n, length),
+        };
+        if self.raw {
+            summary.push_str(", shown as is");
+        }
+
+        let lines: Vec<Line> = if self.raw {
+            self.value.lines().map(|line| Line::raw(line.to_string())).collect()
+        } else if items.len() > 1 {
+            items
+                .iter()
+                .map(|item| {
+                    let mut spans = vec![Span::raw("• ").fg(SUMMARY_FG)];
+                    spans.extend(show_escapes(item));
+                    Line::from(spans)
+                })
+                .collect()
+        } else {
+            vec![Line::from(show_escapes(&self.value))]
+        };
+
+        let [summary_area, value_area] = Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+        Paragraph::new(summary).fg(SUMMARY_FG).render(summary_area, buf);
+
+        let paragraph = Paragraph::new(lines).fg(TEXT_FG).wrap(Wrap { trim: false });
+        // Stop at the last line rather than scrolling into nothing
+        let max_scroll = (paragraph.line_count(value_area.width) as u16).saturating_sub(value_area.height);
+        self.scroll = self.scroll.min(max_scroll);
+        paragraph.scroll((self.scroll, 0)).render(value_area, buf);
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    collections::HashMap,
+    process::Stdio,
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Sender},
+    },
+    thread,
+};
+
+use crate::cmake;
+
+// Help strings cmake writes for entries it knows nothing about
+const GENERIC_HELP: &[&str] = &[
+    "No help, variable specified on the command line.",
+    "Path to a program.",
+    "Path to a file.",
+    "Path to a library.",
+    "Value Computed by CMake",
+];
+
+const LANGUAGES: &[&str] = &["C", "CXX", "CUDA", "HIP", "OBJC", "OBJCXX", "Fortran", "ASM", "Swift", "ISPC"];
+const CONFIGS: &[&str] = &["DEBUG", "RELEASE", "RELWITHDEBINFO", "MINSIZEREL"];
+
+/// Whether the help string of an entry explains nothing, so the CMake
+/// documentation is worth showing instead
+pub fn is_unhelpful(desc: &str) -> bool {
+    let desc = desc.trim();
+    desc.is_empty() || GENERIC_HELP.contains(&desc)
+}
+
+/// Documentation of CMake's own variables, from `cmake --help-variable`.
+/// Looked up on a background thread the first time a variable is shown,
+/// as cmake takes a moment to start.
+#[derive(Clone)]
+pub struct VariableDocs {
+    // None while the lookup runs, Some("") when cmake doesn't know the variable
+    known: Arc<Mutex<HashMap<String, Option<String>>>>,
+    requests: Sender<String>,
+}
+
+/// The documentation of a variable, as far as it's known
+pub enum Docs {
+    Found(String),
+    Pending,
+    None,
+}
+
+impl VariableDocs {
+    pub fn new() -> Self {
+        let known: Arc<Mutex<HashMap<String, Option<String>>>> = Arc::default();
+        let (requests, pending) = mpsc::channel::<String>();
+        let results = Arc::clone(&known);
+        // Ends once the last sender is dropped with the editor state
+        thread::spawn(move || {
+            for name in pending {
+                let docs = documented_names(&name).iter().find_map(|n| help_variable(n)).unwrap_or_default();
+                if let Ok(mut known) = results.lock() {
+                    known.insert(name, Some(docs));
+                }
+            }
+        });
+        Self { known, requests }
+    }
+
+    pub fn get(&self, name: &str) -> Docs {
+        if !name.starts_with("CMAKE_") {
+            return Docs::None;
+        }
+        let Ok(mut known) = self.known.lock() else { return Docs::None };
+        match known.get(name) {
+            Some(Some(docs)) if docs.is_empty() => Docs::None,
+            Some(Some(docs)) => Docs::Found(docs.clone()),
+            Some(None) => Docs::Pending,
+            None => {
+                known.insert(name.to_string(), None);
+                let _ = self.requests.send(name.to_string());
+                Docs::Pending
+            }
+        }
+    }
+}
+
+/// Names the docs may list `name` under: itself, then with the language and
+/// build configuration replaced by placeholders, e.g. CMAKE_CXX_FLAGS_DEBUG
+/// is documented as CMAKE_<LANG>_FLAGS_<CONFIG>
+fn documented_names(name: &str) -> Vec<String> {
+    let mut names = vec![name.to_string()];
+    let generic_config = CONFIGS
+        .iter()
+        .find_map(|c| name.strip_suffix(c)?.strip_suffix('_'))
+        .map(|base| format!("{}_<CONFIG>", base));
+    if let Some(generic) = &generic_config {
+        names.push(generic.clone());
+    }
+    for candidate in std::iter::once(name.to_string()).chain(generic_config) {
+        let rest = &candidate["CMAKE_".len()..];
+        if let Some(lang) = LANGUAGES.iter().find(|l| rest.starts_with(&format!("{}_", l))) {
+            names.push(format!("CMAKE_<LANG>{}", &rest[lang.len()..]));
+        }
+    }
+    names
+}
+
+fn help_variable(name: &str) -> Option<String> {
+    let output = cmake::command()
+        .arg("--help-variable")
+        .arg(name)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = from_rst(&String::from_utf8_lossy(&output.stdout));
+    (!text.is_empty()).then_some(text)
+}
+
+/// The docs are reStructuredText, drop the markup that doesn't read well
+/// as plain text: the title, roles like :variable:`X` and ``literals``
+fn from_rst(rst: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut rst_lines = rst.lines().peekable();
+    while let Some(line) = rst_lines.next() {
+        // Titles are underlined with a row of the same punctuation
+        if rst_lines.peek().is_some_and(|next| is_underline(next)) {
+            rst_lines.next();
+            if lines.is_empty() {
+                continue;
+            }
+        }
+        if let Some(version) = line.trim().strip_prefix(".. versionadded::") {
+            lines.push(format!("New in version {}.", version.trim()));
+            continue;
+        }
+        if line.trim_start().starts_with("..") {
+            continue;
+        }
+        lines.push(strip_roles(line).replace("``", ""));
+    }
+    lines.join("\n").trim().to_string()
+}
+
+fn is_underline(line: &str) -> bool {
+    let mut chars = line.chars();
+    chars.next().is_some_and(|c| "-=^~*".contains(c) && line.len() > 2 && line.chars().all(|d| d == c))
+}
+
+// `:variable:`CMAKE_BUILD_TYPE`` becomes `CMAKE_BUILD_TYPE`, the target
+// of `:prop_tgt:`text <target>`` becomes `text`
+fn strip_roles(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find(':') {
+        let after = &rest[start + 1..];
+        let role = after.find(":`").filter(|&end| after[..end].chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+        let Some(role_end) = role else {
+            out.push_str(&rest[..=start]);
+            rest = after;
+            continue;
+        };
+        let target = &after[role_end + 2..];
+        let Some(target_end) = target.find('`') else {
+            out.push_str(&rest[..=start]);
+            rest = after;
+            continue;
+        };
+        let text = &target[..target_end];
+        out.push_str(&rest[..start]);
+        out.push_str(text.split_once(" <").map_or(text, |(text, _)| text));
+        rest = &target[target_end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
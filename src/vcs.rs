@@ -0,0 +1,121 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+/// A version control system whose ignore file we know how to maintain
+pub trait Vcs {
+    fn name(&self) -> &'static str;
+
+    /// Root of the working tree
+    fn root(&self) -> &Path;
+
+    fn ignore_file(&self) -> PathBuf;
+
+    fn is_ignored(&self, path: &Path) -> bool;
+
+    /// Append an entry ignoring the directory `path` to the ignore file
+    fn add_ignore(&self, path: &Path) -> io::Result<()>;
+}
+
+/// Find the working tree containing `path`, if any
+pub fn detect(path: &Path) -> Option<Box<dyn Vcs>> {
+    let mut dir = Some(path);
+    while let Some(d) = dir {
+        if d.join(".git").exists() {
+            return Some(Box::new(Git { root: d.to_path_buf() }));
+        }
+        if d.join(".hg").is_dir() {
+            return Some(Box::new(Mercurial { root: d.to_path_buf() }));
+        }
+        dir = d.parent();
+    }
+    None
+}
+
+fn relative_to(root: &Path, path: &Path) -> String {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    path.strip_prefix(root)
+        .unwrap_or(&path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn append_lines(file: &Path, lines: &[String]) -> io::Result<()> {
+    // Don't glue the new entry onto a last line lacking its newline
+    let needs_newline = fs::read(file).is_ok_and(|c| !c.is_empty() && !c.ends_with(b"\n"));
+    let mut f = OpenOptions::new().create(true).append(true).open(file)?;
+    if needs_newline {
+        writeln!(f)?;
+    }
+    for line in lines {
+        writeln!(f, "{}", line)?;
+    }
+    Ok(())
+}
+
+pub struct Git {
+    root: PathBuf,
+}
+
+impl Vcs for Git {
+    fn name(&self) -> &'static str {
+        "git"
+    }
+
+    fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn ignore_file(&self) -> PathBuf {
+        self.root.join(".gitignore")
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        // Let git answer, it knows about nested and global ignore files
+        Command::new("git")
+            .arg("-C")
+            .arg(&self.root)
+            .args(["check-ignore", "-q"])
+            .arg(path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+    }
+
+    fn add_ignore(&self, path: &Path) -> io::Result<()> {
+        append_lines(&self.ignore_file(), &[format!("/{}/", relative_to(&self.root, path))])
+    }
+}
+
+pub struct Mercurial {
+    root: PathBuf,
+}
+
+impl Vcs for Mercurial {
+    fn name(&self) -> &'static str {
+        "hg"
+    }
+
+    fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn ignore_file(&self) -> PathBuf {
+        self.root.join(".hgignore")
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        let rel = relative_to(&self.root, path);
+        fs::read_to_string(self.ignore_file())
+            .is_ok_and(|content| content.lines().any(|l| l.trim().trim_end_matches('/') == rel))
+    }
+
+    fn add_ignore(&self, path: &Path) -> io::Result<()> {
+        // The syntax line only affects the patterns following it
+        append_lines(&self.ignore_file(), &["syntax: glob".to_string(), relative_to(&self.root, path)])
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use cmake_cache::parse_source_dir;
+
+/// A build dir on the other side of WSL than cmake-tui, whose cache has
+/// paths as they're seen from there
+#[derive(Debug, Clone, PartialEq)]
+pub enum Wsl {
+    // cmake-tui runs inside WSL, on a cache written by Windows cmake:
+    // C:\ is /mnt/c here
+    Inside,
+    // cmake-tui runs on Windows, on a cache written inside this distro:
+    // its / is \\wsl$\<distro>\ here
+    Distro(String),
+}
+
+fn running_in_wsl() -> bool {
+    cfg!(target_os = "linux")
+        && (env::var_os("WSL_DISTRO_NAME").is_some() || Path::new("/proc/sys/fs/binfmt_misc/WSLInterop").exists())
+}
+
+/// The distro of a `\\wsl$\<distro>\...` or `\\wsl.localhost\<distro>\...`
+/// path and the rest of it, either slash
+fn distro_of(path: &str) -> Option<(String, String)> {
+    let path = path.replace('/', r"\");
+    let path = path.strip_prefix(r"\\?\UNC\").or_else(|| path.strip_prefix(r"\\"))?;
+    let rest = path.strip_prefix(r"wsl$\").or_else(|| path.strip_prefix(r"wsl.localhost\"))?;
+    let (distro, rest) = rest.split_once('\\').unwrap_or((rest, ""));
+    Some((distro.to_string(), rest.to_string()))
+}
+
+impl Wsl {
+    /// How the paths in the cache of `build_dir` are reached from here,
+    /// None when they need no translation
+    pub fn detect(build_dir: &Path) -> Option<Self> {
+        if running_in_wsl() {
+            return Some(Self::Inside);
+        }
+        if !cfg!(windows) {
+            return None;
+        }
+        let dir = fs::canonicalize(build_dir).unwrap_or_else(|_| build_dir.to_path_buf());
+        distro_of(&dir.to_string_lossy()).map(|(distro, _)| Self::Distro(distro))
+    }
+
+    /// `path` from the cache as it's reached from here, None when it's
+    /// not one from the other side
+    pub fn translate(&self, path: &str) -> Option<String> {
+        match self {
+            Self::Inside => {
+                if let Some((_, rest)) = distro_of(path) {
+                    return Some(format!("/{}", rest.replace('\\', "/")));
+                }
+                let bytes = path.as_bytes();
+                let drive_path = bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+                (drive_path && matches!(bytes[2], b'/' | b'\\')).then(|| {
+                    format!("/mnt/{}/{}", path[..1].to_lowercase(), path[3..].replace('\\', "/"))
+                })
+            }
+            Self::Distro(distro) => {
+                if !path.starts_with('/') {
+                    return None;
+                }
+                // /mnt/c/... is the Windows drive itself
+                let mut parts = path[1..].splitn(3, '/');
+                if let (Some("mnt"), Some(drive)) = (parts.next(), parts.next())
+                    && drive.len() == 1
+                    && drive.chars().all(|c| c.is_ascii_alphabetic())
+                {
+                    let rest = parts.next().unwrap_or_default().replace('/', r"\");
+                    return Some(format!(r"{}:\{}", drive.to_uppercase(), rest));
+                }
+                Some(format!(r"\\wsl$\{}{}", distro, path.replace('/', r"\")))
+            }
+        }
+    }
+}
+
+/// The project's source directory (CMAKE_HOME_DIRECTORY) as it's reached
+/// from here, for reading and writing the project's files. cmake itself
+/// takes the path as it's in the cache.
+pub fn local_source_dir(build_dir: &Path) -> Option<PathBuf> {
+    let source_dir = parse_source_dir(build_dir)?;
+    let translated = Wsl::detect(build_dir).and_then(|wsl| wsl.translate(&source_dir.to_string_lossy()));
+    Some(translated.map_or(source_dir, PathBuf::from))
+}